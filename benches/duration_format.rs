@@ -0,0 +1,34 @@
+#[macro_use]
+extern crate bencher;
+
+use std::io::Write;
+use std::time::Duration;
+
+use bencher::Bencher;
+use humantime::format_duration;
+
+fn format_seconds(b: &mut Bencher) {
+    let duration = Duration::from_secs(5400);
+    let mut buf = Vec::with_capacity(100);
+    b.iter(|| {
+        buf.clear();
+        write!(&mut buf, "{}", format_duration(duration)).unwrap()
+    });
+}
+
+fn format_all_units(b: &mut Bencher) {
+    let duration = Duration::new(2 * 31_557_600 + 3 * 2_630_016 + 93_784, 5_006_007);
+    let mut buf = Vec::with_capacity(100);
+    b.iter(|| {
+        buf.clear();
+        write!(&mut buf, "{}", format_duration(duration)).unwrap()
+    });
+}
+
+fn format_to_string(b: &mut Bencher) {
+    let duration = Duration::new(93_784, 250_000_000);
+    b.iter(|| format_duration(duration).to_string());
+}
+
+benchmark_group!(benches, format_seconds, format_all_units, format_to_string);
+benchmark_main!(benches);
@@ -0,0 +1,48 @@
+/*
+ * C API of the humantime duration parser and formatter
+ *
+ * All functions return HUMANTIME_OK on success or one of the error codes
+ * below, the message of the last error on the calling thread is available
+ * from humantime_last_error_message().
+ */
+#ifndef HUMANTIME_H
+#define HUMANTIME_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define HUMANTIME_OK 0
+#define HUMANTIME_INVALID_CHARACTER 1
+#define HUMANTIME_NUMBER_EXPECTED 2
+#define HUMANTIME_UNKNOWN_UNIT 3
+#define HUMANTIME_NUMBER_OVERFLOW 4
+#define HUMANTIME_EMPTY 5
+#define HUMANTIME_WHITESPACE_EXPECTED 6
+#define HUMANTIME_MISSING_NUMBER 7
+#define HUMANTIME_NULL_POINTER (-1)
+#define HUMANTIME_INVALID_UTF8 (-2)
+#define HUMANTIME_BUFFER_TOO_SMALL (-3)
+#define HUMANTIME_INVALID_ARGUMENT (-4)
+
+/* Parses a duration like "1h 30m", the result is stored in secs and nanos */
+int humantime_parse_duration(const char *s, uint64_t *secs, uint32_t *nanos);
+
+/* Formats a duration like "1h 30m" into buf, 64 bytes are always enough */
+int humantime_format_duration(uint64_t secs, uint32_t nanos, char *buf, size_t len);
+
+/*
+ * Copies the message of the last error into buf, truncating it to len
+ * bytes including the NUL. Returns the full length of the message, zero if
+ * the last call succeeded.
+ */
+size_t humantime_last_error_message(char *buf, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* HUMANTIME_H */
@@ -0,0 +1,282 @@
+//! C API of the humantime duration parser and formatter
+//!
+//! The functions use exactly the same grammar as
+//! [`humantime::parse_duration`] and [`humantime::format_duration`] in its
+//! default style, so services written in other languages accept and print
+//! the same values.
+//! See `include/humantime.h` for the declarations.
+//!
+//! Every function returns `HUMANTIME_OK` (zero) on success or one of the
+//! error codes below. A description of the last error on the current
+//! thread is available from [`humantime_last_error_message`].
+
+#![warn(missing_debug_implementations, missing_docs, unreachable_pub)]
+
+use std::cell::RefCell;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::time::Duration;
+
+use humantime::{parse_duration, DurationError, DurationErrorKind, FormatOptions, Style};
+
+/// The call succeeded
+pub const HUMANTIME_OK: c_int = 0;
+/// Invalid character in the duration
+pub const HUMANTIME_INVALID_CHARACTER: c_int = 1;
+/// A number is expected in the duration
+pub const HUMANTIME_NUMBER_EXPECTED: c_int = 2;
+/// The time unit is missing or unknown
+pub const HUMANTIME_UNKNOWN_UNIT: c_int = 3;
+/// The value is too large or too precise
+pub const HUMANTIME_NUMBER_OVERFLOW: c_int = 4;
+/// The duration is empty
+pub const HUMANTIME_EMPTY: c_int = 5;
+/// Whitespace is expected between the components of the duration
+pub const HUMANTIME_WHITESPACE_EXPECTED: c_int = 6;
+/// A time unit is not preceded by a number
+pub const HUMANTIME_MISSING_NUMBER: c_int = 7;
+/// A required pointer is null
+pub const HUMANTIME_NULL_POINTER: c_int = -1;
+/// The string is not valid UTF-8
+pub const HUMANTIME_INVALID_UTF8: c_int = -2;
+/// The output buffer is too small
+pub const HUMANTIME_BUFFER_TOO_SMALL: c_int = -3;
+/// An argument is out of range, e.g. nanoseconds above 999 999 999
+pub const HUMANTIME_INVALID_ARGUMENT: c_int = -4;
+
+thread_local! {
+    static LAST_ERROR: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+fn fail(code: c_int, message: String) -> c_int {
+    LAST_ERROR.with(|e| *e.borrow_mut() = message);
+    code
+}
+
+fn succeed() -> c_int {
+    LAST_ERROR.with(|e| e.borrow_mut().clear());
+    HUMANTIME_OK
+}
+
+fn error_code(err: &DurationError) -> c_int {
+    match err.kind() {
+        DurationErrorKind::InvalidCharacter => HUMANTIME_INVALID_CHARACTER,
+        DurationErrorKind::NumberExpected => HUMANTIME_NUMBER_EXPECTED,
+        DurationErrorKind::WhitespaceExpected => HUMANTIME_WHITESPACE_EXPECTED,
+        DurationErrorKind::MissingNumber => HUMANTIME_MISSING_NUMBER,
+        DurationErrorKind::MissingUnit | DurationErrorKind::UnknownUnit => HUMANTIME_UNKNOWN_UNIT,
+        DurationErrorKind::NumberOverflow => HUMANTIME_NUMBER_OVERFLOW,
+        DurationErrorKind::Empty => HUMANTIME_EMPTY,
+        // variants added in later releases of humantime
+        _ => HUMANTIME_INVALID_CHARACTER,
+    }
+}
+
+/// Copies `src` with a terminating NUL into a buffer of `len` bytes
+///
+/// Writes nothing if it doesn't fit.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `len` bytes.
+unsafe fn copy_out(src: &str, buf: *mut c_char, len: usize) -> bool {
+    if src.len() >= len {
+        return false;
+    }
+    std::ptr::copy_nonoverlapping(src.as_ptr() as *const c_char, buf, src.len());
+    *buf.add(src.len()) = 0;
+    true
+}
+
+/// Parses a NUL-terminated duration like `1h 30m`
+///
+/// On success the whole seconds are stored in `secs` and the remaining
+/// nanoseconds in `nanos`, on failure they are left untouched.
+///
+/// # Safety
+///
+/// `s` must be a NUL-terminated string, `secs` and `nanos` must be valid
+/// for writes.
+#[no_mangle]
+pub unsafe extern "C" fn humantime_parse_duration(
+    s: *const c_char,
+    secs: *mut u64,
+    nanos: *mut u32,
+) -> c_int {
+    if s.is_null() || secs.is_null() || nanos.is_null() {
+        return fail(HUMANTIME_NULL_POINTER, "null pointer passed".to_owned());
+    }
+    let s = match CStr::from_ptr(s).to_str() {
+        Ok(s) => s,
+        Err(e) => return fail(HUMANTIME_INVALID_UTF8, e.to_string()),
+    };
+    match parse_duration(s) {
+        Ok(d) => {
+            *secs = d.as_secs();
+            *nanos = d.subsec_nanos();
+            succeed()
+        }
+        Err(e) => fail(error_code(&e), e.to_string()),
+    }
+}
+
+/// Formats a duration like `1h 30m` into `buf` of `len` bytes
+///
+/// The result is NUL-terminated. Returns `HUMANTIME_BUFFER_TOO_SMALL`
+/// and writes nothing if the buffer is too small, 64 bytes are enough for
+/// any duration.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn humantime_format_duration(
+    secs: u64,
+    nanos: u32,
+    buf: *mut c_char,
+    len: usize,
+) -> c_int {
+    if buf.is_null() {
+        return fail(HUMANTIME_NULL_POINTER, "null pointer passed".to_owned());
+    }
+    if nanos >= 1_000_000_000 {
+        return fail(
+            HUMANTIME_INVALID_ARGUMENT,
+            format!("nanoseconds {} are out of range", nanos),
+        );
+    }
+    // not `format_duration`, as the default style may write longer text
+    let formatted = FormatOptions::new()
+        .with_style(Style::Human)
+        .format(Duration::new(secs, nanos))
+        .to_string();
+    if copy_out(&formatted, buf, len) {
+        succeed()
+    } else {
+        fail(
+            HUMANTIME_BUFFER_TOO_SMALL,
+            format!(
+                "buffer of {} bytes is too small, {} bytes needed",
+                len,
+                formatted.len() + 1
+            ),
+        )
+    }
+}
+
+/// Copies the message of the last error on this thread into `buf`
+///
+/// The message is truncated to fit into `len` bytes, including the
+/// terminating NUL. Returns the length of the whole message without the
+/// NUL, zero if the last call succeeded.
+///
+/// # Safety
+///
+/// `buf` must be valid for writes of `len` bytes, or null if `len` is zero.
+#[no_mangle]
+pub unsafe extern "C" fn humantime_last_error_message(buf: *mut c_char, len: usize) -> usize {
+    LAST_ERROR.with(|e| {
+        let message = e.borrow();
+        if !buf.is_null() && len > 0 {
+            let mut end = message.len().min(len - 1);
+            while !message.is_char_boundary(end) {
+                end -= 1;
+            }
+            copy_out(&message[..end], buf, len);
+        }
+        message.len()
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::CStr;
+    use std::os::raw::c_char;
+    use std::ptr;
+
+    use super::*;
+
+    fn parse(s: &[u8]) -> (i32, u64, u32) {
+        let (mut secs, mut nanos) = (0, 0);
+        let code =
+            unsafe { humantime_parse_duration(s.as_ptr() as *const c_char, &mut secs, &mut nanos) };
+        (code, secs, nanos)
+    }
+
+    fn last_error() -> String {
+        let mut buf = [0 as c_char; 128];
+        unsafe { humantime_last_error_message(buf.as_mut_ptr(), buf.len()) };
+        unsafe { CStr::from_ptr(buf.as_ptr()) }
+            .to_str()
+            .unwrap()
+            .to_owned()
+    }
+
+    #[test]
+    fn parse_duration() {
+        assert_eq!(parse(b"1h 30m 1.5s\0"), (HUMANTIME_OK, 5401, 500_000_000));
+        assert_eq!(last_error(), "");
+        assert_eq!(parse(b"5\0").0, HUMANTIME_UNKNOWN_UNIT);
+        assert_eq!(last_error(), "time unit needed, for example 5sec or 5ms");
+        assert_eq!(parse(b"\0").0, HUMANTIME_EMPTY);
+        assert_eq!(parse(b"1s!\0").0, HUMANTIME_INVALID_CHARACTER);
+        assert_eq!(parse(b"3 fortnights\0").0, HUMANTIME_UNKNOWN_UNIT);
+        assert_eq!(parse(b"m\0").0, HUMANTIME_MISSING_NUMBER);
+        assert_eq!(last_error(), "number needed, for example 2m");
+        assert_eq!(parse(b"\xff\0").0, HUMANTIME_INVALID_UTF8);
+        let code =
+            unsafe { humantime_parse_duration(ptr::null(), ptr::null_mut(), ptr::null_mut()) };
+        assert_eq!(code, HUMANTIME_NULL_POINTER);
+    }
+
+    #[test]
+    fn error_codes() {
+        let strict = humantime::ParseOptions::new().with_concatenation(false);
+        let err = strict.parse("1h30m").unwrap_err();
+        assert_eq!(error_code(&err), HUMANTIME_WHITESPACE_EXPECTED);
+        assert_eq!(
+            error_code(&humantime::parse_duration("1h x").unwrap_err()),
+            HUMANTIME_NUMBER_EXPECTED
+        );
+    }
+
+    #[test]
+    fn format_duration() {
+        let mut buf = [0 as c_char; 64];
+        let code = unsafe { humantime_format_duration(5401, 500_000_000, buf.as_mut_ptr(), 64) };
+        assert_eq!(code, HUMANTIME_OK);
+        let s = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        assert_eq!(s.to_str().unwrap(), "1h 30m 1s 500ms");
+
+        let code = unsafe { humantime_format_duration(5401, 0, buf.as_mut_ptr(), 6) };
+        assert_eq!(code, HUMANTIME_BUFFER_TOO_SMALL);
+        assert_eq!(
+            last_error(),
+            "buffer of 6 bytes is too small, 10 bytes needed"
+        );
+        let code =
+            unsafe { humantime_format_duration(u64::MAX, 999_999_999, buf.as_mut_ptr(), 64) };
+        assert_eq!(code, HUMANTIME_OK);
+        // the style of `format_duration` doesn't apply
+        let code = humantime::with_format_style(humantime::Style::Verbose, || unsafe {
+            humantime_format_duration(u64::MAX, 999_999_999, buf.as_mut_ptr(), 64)
+        });
+        assert_eq!(code, HUMANTIME_OK);
+        let code = unsafe { humantime_format_duration(0, 1_000_000_000, buf.as_mut_ptr(), 64) };
+        assert_eq!(code, HUMANTIME_INVALID_ARGUMENT);
+    }
+
+    #[test]
+    fn truncated_message() {
+        assert_eq!(parse(b"5\0").0, HUMANTIME_UNKNOWN_UNIT);
+        let mut buf = [0 as c_char; 5];
+        let len = unsafe { humantime_last_error_message(buf.as_mut_ptr(), buf.len()) };
+        assert_eq!(len, 41);
+        let s = unsafe { CStr::from_ptr(buf.as_ptr()) };
+        assert_eq!(s.to_str().unwrap(), "time");
+        assert_eq!(
+            unsafe { humantime_last_error_message(ptr::null_mut(), 0) },
+            41
+        );
+    }
+}
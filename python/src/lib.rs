@@ -0,0 +1,104 @@
+//! Python bindings of the humantime parser and formatter
+//!
+//! Built with [maturin], the extension module is importable as
+//! `humantime`:
+//!
+//! ```python
+//! >>> import datetime, humantime
+//! >>> humantime.parse_duration("1h 30m")
+//! datetime.timedelta(seconds=5400)
+//! >>> humantime.format_duration(datetime.timedelta(seconds=5400.25))
+//! '1h 30m 250ms'
+//! >>> humantime.parse_rfc3339("2018-02-14T00:28:07Z")
+//! datetime.datetime(2018, 2, 14, 0, 28, 7, tzinfo=datetime.timezone.utc)
+//! ```
+//!
+//! Parse errors are raised as `ValueError` with the same message as the
+//! Rust error. Python's `timedelta` is limited to microseconds, smaller
+//! fractions are truncated.
+//!
+//! [maturin]: https://www.maturin.rs
+
+#![warn(missing_debug_implementations, missing_docs, unreachable_pub)]
+
+use std::time::{Duration, SystemTime};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// Parses a duration like `1h 30m` into a `timedelta`
+#[pyfunction]
+fn parse_duration(s: &str) -> PyResult<Duration> {
+    humantime::parse_duration(s).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Formats a `timedelta` like `1h 30m`
+#[pyfunction]
+fn format_duration(value: Duration) -> String {
+    humantime::format_duration(value).to_string()
+}
+
+/// Parses an RFC3339 timestamp like `2018-02-14T00:28:07Z` into a UTC
+/// `datetime`
+#[pyfunction]
+fn parse_rfc3339(s: &str) -> PyResult<SystemTime> {
+    humantime::parse_rfc3339(s).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// The `humantime` Python module
+#[pymodule]
+#[pyo3(name = "humantime")]
+fn humantime_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(parse_duration, m)?)?;
+    m.add_function(wrap_pyfunction!(format_duration, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_rfc3339, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use pyo3::prelude::*;
+    use pyo3::types::PyModule;
+
+    fn eval(code: &str) -> String {
+        Python::attach(|py| {
+            let m = PyModule::new(py, "humantime").unwrap();
+            super::humantime_module(&m).unwrap();
+            let globals = pyo3::types::PyDict::new(py);
+            globals.set_item("humantime", m).unwrap();
+            let code = std::ffi::CString::new(code).unwrap();
+            match py.eval(&code, Some(&globals), None) {
+                Ok(value) => value.repr().unwrap().to_string(),
+                Err(e) => e.to_string(),
+            }
+        })
+    }
+
+    #[test]
+    fn duration() {
+        assert_eq!(
+            eval("humantime.parse_duration('1h 30m 1.5s')"),
+            "datetime.timedelta(seconds=5401, microseconds=500000)"
+        );
+        assert_eq!(
+            eval("humantime.parse_duration('5')"),
+            "ValueError: time unit needed, for example 5sec or 5ms"
+        );
+        assert_eq!(
+            eval("humantime.format_duration(humantime.parse_duration('2days 1ms'))"),
+            "'2days 1ms'"
+        );
+    }
+
+    #[test]
+    fn timestamp() {
+        assert_eq!(
+            eval("humantime.parse_rfc3339('2018-02-14T00:28:07Z')"),
+            "datetime.datetime(2018, 2, 14, 0, 28, 7, tzinfo=datetime.timezone.utc)"
+        );
+        assert_eq!(
+            eval("humantime.parse_rfc3339('2018-02-14')"),
+            "ValueError: timestamp format is invalid"
+        );
+    }
+}
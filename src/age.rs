@@ -0,0 +1,209 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+use crate::duration::{self, format_duration, parse_duration_in};
+
+/// Error parsing an age filter
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// Filter doesn't start with `older than`, `newer than` or an operator
+    InvalidFormat,
+    /// The age is not a valid duration
+    Duration(duration::Error),
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Duration(e) => Some(e),
+            Error::InvalidFormat => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidFormat => write!(
+                f,
+                "age filter must start with `older than`, `newer than`, \
+                `>`, `>=`, `<` or `<=`"
+            ),
+            Error::Duration(e) => write!(f, "invalid age: {}", e),
+        }
+    }
+}
+
+impl From<duration::Error> for Error {
+    fn from(e: duration::Error) -> Error {
+        Error::Duration(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Op {
+    Older,
+    OlderOrEqual,
+    Newer,
+    NewerOrEqual,
+}
+
+/// A filter by age, like `older than 7d` or `<2h`
+///
+/// Supported spellings:
+///
+/// * `older than <age>`, `> <age>`
+/// * `newer than <age>`, `< <age>`
+/// * `>= <age>`, `<= <age>`
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use humantime::parse_age_filter;
+///
+/// let filter = parse_age_filter("older than 7d").unwrap();
+/// let now = SystemTime::now();
+/// assert!(filter.matches(now - Duration::from_secs(8 * 86400), now));
+/// assert!(!filter.matches(now - Duration::from_secs(3600), now));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AgeFilter {
+    op: Op,
+    age: Duration,
+}
+
+/// Parse an age filter, see [`AgeFilter`] for the syntax
+pub fn parse_age_filter(input: &str) -> Result<AgeFilter, Error> {
+    let s = input.trim();
+    let prefixes = [
+        ("older than ", Op::Older),
+        ("newer than ", Op::Newer),
+        (">=", Op::OlderOrEqual),
+        ("<=", Op::NewerOrEqual),
+        (">", Op::Older),
+        ("<", Op::Newer),
+    ];
+    for &(prefix, op) in &prefixes {
+        if let Some(age) = s.strip_prefix(prefix) {
+            return Ok(AgeFilter {
+                op,
+                age: parse_duration_in(input, age)?,
+            });
+        }
+    }
+    Err(Error::InvalidFormat)
+}
+
+impl AgeFilter {
+    /// The age threshold of the filter
+    pub fn age(&self) -> Duration {
+        self.age
+    }
+
+    /// Checks whether a file modified (or created) at `time` passes
+    ///
+    /// Times in the future relative to `now` are considered to have zero
+    /// age.
+    pub fn matches(&self, time: SystemTime, now: SystemTime) -> bool {
+        let age = now.duration_since(time).unwrap_or(Duration::ZERO);
+        match self.op {
+            Op::Older => age > self.age,
+            Op::OlderOrEqual => age >= self.age,
+            Op::Newer => age < self.age,
+            Op::NewerOrEqual => age <= self.age,
+        }
+    }
+}
+
+impl FromStr for AgeFilter {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<AgeFilter, Error> {
+        parse_age_filter(s)
+    }
+}
+
+impl fmt::Display for AgeFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let op = match self.op {
+            Op::Older => "older than",
+            Op::OlderOrEqual => ">=",
+            Op::Newer => "newer than",
+            Op::NewerOrEqual => "<=",
+        };
+        write!(f, "{} {}", op, format_duration(self.age))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{parse_age_filter, Error};
+    use crate::duration::Error as DurationError;
+
+    fn check(filter: &str, age: u64) -> bool {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        parse_age_filter(filter)
+            .unwrap()
+            .matches(now - Duration::from_secs(age), now)
+    }
+
+    #[test]
+    fn older() {
+        assert!(check("older than 1h", 3601));
+        assert!(!check("older than 1h", 3600));
+        assert!(check(">1h", 3601));
+        assert!(!check("> 1h", 3600));
+        assert!(check(">= 1h", 3600));
+        assert!(!check(">=1h", 3599));
+    }
+
+    #[test]
+    fn newer() {
+        assert!(check("newer than 2h", 7199));
+        assert!(!check("newer than 2h", 7200));
+        assert!(check("<2h", 0));
+        assert!(check("<= 2h", 7200));
+        assert!(!check("<=2h", 7201));
+    }
+
+    #[test]
+    fn future() {
+        let now = UNIX_EPOCH + Duration::from_secs(1000);
+        let future = now + Duration::from_secs(10);
+        assert!(parse_age_filter("<1s").unwrap().matches(future, now));
+        assert!(!parse_age_filter(">0s").unwrap().matches(future, now));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_age_filter("7d"), Err(Error::InvalidFormat));
+        assert_eq!(parse_age_filter("older 7d"), Err(Error::InvalidFormat));
+        assert_eq!(parse_age_filter("older than"), Err(Error::InvalidFormat));
+        assert_eq!(parse_age_filter("older than "), Err(Error::InvalidFormat));
+        assert_eq!(
+            parse_age_filter(">"),
+            Err(Error::Duration(DurationError::Empty))
+        );
+        // offsets are in the whole input
+        match parse_age_filter(" older than 7x") {
+            Err(Error::Duration(e)) => assert_eq!(e.span(), Some(13..14)),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn display() {
+        for input in &["older than 7days", "newer than 2h", ">= 1m", "<= 5s"] {
+            assert_eq!(parse_age_filter(input).unwrap().to_string(), *input);
+        }
+        assert_eq!(
+            parse_age_filter(">1h").unwrap().to_string(),
+            "older than 1h"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::time::{Duration as StdDuration, UNIX_EPOCH};
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::wrapper::{Duration, Timeout, Timestamp};
+
+/// First second of year 10000, the last one `format_rfc3339` can write
+const MAX_TIMESTAMP_SECS: u64 = 253_402_300_800;
+
+fn std_duration(u: &mut Unstructured<'_>, max_secs: u64) -> Result<StdDuration> {
+    let secs = u.int_in_range(0..=max_secs)?;
+    let nanos = u.int_in_range(0..=999_999_999)?;
+    Ok(StdDuration::new(secs, nanos))
+}
+
+impl<'a> Arbitrary<'a> for Duration {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Duration> {
+        std_duration(u, u64::MAX).map(Duration::from)
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, Some(12))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Timestamp {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Timestamp> {
+        let since_epoch = std_duration(u, MAX_TIMESTAMP_SECS - 1)?;
+        Ok(Timestamp::from(UNIX_EPOCH + since_epoch))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, Some(12))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Timeout {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Timeout> {
+        let value = if u.arbitrary()? {
+            Some(std_duration(u, u64::MAX)?)
+        } else {
+            None
+        };
+        Ok(Timeout::new(value))
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (1, Some(13))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use crate::wrapper::{Duration, Timeout, Timestamp};
+
+    fn values<'a, T: Arbitrary<'a>>(data: &'a [u8]) -> Vec<T> {
+        let mut u = Unstructured::new(data);
+        (0..100).map(|_| T::arbitrary(&mut u).unwrap()).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let data: Vec<u8> = (0..2000u32).map(|i| (i * 7919 % 251) as u8).collect();
+        for d in values::<Duration>(&data) {
+            assert_eq!(d.to_string().parse::<Duration>(), Ok(d));
+        }
+        for ts in values::<Timestamp>(&data) {
+            assert_eq!(ts.to_string().parse::<Timestamp>(), Ok(ts));
+        }
+        for t in values::<Timeout>(&data) {
+            assert_eq!(t.to_string().parse::<Timeout>(), Ok(t));
+        }
+    }
+
+    #[test]
+    fn extremes() {
+        let max = [0xff; 64];
+        let ts: Timestamp = values(&max)[0];
+        assert_eq!(ts.to_string().parse::<Timestamp>(), Ok(ts));
+        let d: Duration = values(&max)[0];
+        assert_eq!(d.to_string().parse::<Duration>(), Ok(d));
+        let zero: Duration = values(&[])[0];
+        assert_eq!(zero.to_string(), "0s");
+    }
+}
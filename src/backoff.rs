@@ -0,0 +1,354 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::duration::{self, format_duration, parse_duration_in};
+
+/// Error parsing a backoff policy
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// Policy is neither `fixed` nor `exponential`
+    UnknownPolicy(String),
+    /// The expression doesn't match the syntax of the policy
+    InvalidFormat,
+    /// One of the delays is not a valid duration
+    Duration(duration::Error),
+    /// The maximum delay is shorter than the initial one
+    InvalidRange,
+    /// The multiplier is not a decimal number from `1` to `1000`
+    ///
+    /// At most three fractional digits are supported.
+    InvalidFactor,
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Duration(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownPolicy(name) => write!(
+                f,
+                "unknown backoff policy {:?}, supported policies: fixed, exponential",
+                name
+            ),
+            Error::InvalidFormat => write!(
+                f,
+                "backoff format is invalid, expected `fixed 5s` or `exponential 100ms..30s x2`"
+            ),
+            Error::Duration(e) => write!(f, "invalid delay: {}", e),
+            Error::InvalidRange => write!(f, "maximum delay is shorter than the initial delay"),
+            Error::InvalidFactor => write!(f, "multiplier must be a number from 1 to 1000"),
+        }
+    }
+}
+
+impl From<duration::Error> for Error {
+    fn from(e: duration::Error) -> Error {
+        Error::Duration(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Fixed(Duration),
+    Exponential {
+        initial: Duration,
+        max: Duration,
+        /// Multiplier in thousandths
+        factor: u64,
+    },
+}
+
+/// Retry delay policy, like `fixed 5s` or `exponential 100ms..30s x2`
+///
+/// Supported policies:
+///
+/// * `fixed <delay>` -- the same delay before every attempt
+/// * `exponential <initial>..<max> [x<factor>]` -- the delay is multiplied
+///   by the factor (`2` by default) after every attempt, until it reaches
+///   the maximum
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::Backoff;
+///
+/// let policy: Backoff = "exponential 100ms..1s x3".parse().unwrap();
+/// let delays: Vec<Duration> = policy.delays().take(5).collect();
+/// assert_eq!(delays, vec![
+///     Duration::from_millis(100),
+///     Duration::from_millis(300),
+///     Duration::from_millis(900),
+///     Duration::from_millis(1000),
+///     Duration::from_millis(1000),
+/// ]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Backoff(Kind);
+
+/// An endless iterator over delays of a [`Backoff`] policy
+#[derive(Debug, Clone)]
+pub struct Delays {
+    next: Duration,
+    kind: Kind,
+}
+
+/// Largest multiplier, in thousandths
+const MAX_FACTOR: u64 = 1_000_000;
+
+fn parse_factor(s: &str) -> Result<u64, Error> {
+    let (int, frac) = match s.find('.') {
+        Some(idx) => (&s[..idx], &s[idx + 1..]),
+        None => (s, "0"),
+    };
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !digits(int) || !digits(frac) || frac.len() > 3 {
+        return Err(Error::InvalidFactor);
+    }
+    let int: u64 = int.parse().map_err(|_| Error::InvalidFactor)?;
+    let frac: u64 = frac.parse::<u64>().expect("digits") * 10u64.pow(3 - frac.len() as u32);
+    let factor = int
+        .checked_mul(1000)
+        .and_then(|v| v.checked_add(frac))
+        .ok_or(Error::InvalidFactor)?;
+    if !(1000..=MAX_FACTOR).contains(&factor) {
+        return Err(Error::InvalidFactor);
+    }
+    Ok(factor)
+}
+
+impl Backoff {
+    /// Policy which waits the same time before every attempt
+    pub const fn fixed(delay: Duration) -> Backoff {
+        Backoff(Kind::Fixed(delay))
+    }
+
+    /// Policy which doubles the delay until it reaches `max`
+    ///
+    /// The `max` is raised to `initial` if it's shorter.
+    pub fn exponential(initial: Duration, max: Duration) -> Backoff {
+        Backoff(Kind::Exponential {
+            initial,
+            max: max.max(initial),
+            factor: 2000,
+        })
+    }
+
+    /// Returns an endless iterator over delays before each attempt
+    pub fn delays(&self) -> Delays {
+        let next = match self.0 {
+            Kind::Fixed(delay) => delay,
+            Kind::Exponential { initial, .. } => initial,
+        };
+        Delays { next, kind: self.0 }
+    }
+}
+
+impl Iterator for Delays {
+    type Item = Duration;
+    fn next(&mut self) -> Option<Duration> {
+        let current = self.next;
+        if let Kind::Exponential { max, factor, .. } = self.kind {
+            let nanos = current.as_nanos().checked_mul(u128::from(factor));
+            self.next = match nanos.map(|n| n / 1000) {
+                Some(nanos) if nanos < max.as_nanos() => {
+                    // less than `max`, so it fits
+                    Duration::new(
+                        (nanos / 1_000_000_000) as u64,
+                        (nanos % 1_000_000_000) as u32,
+                    )
+                }
+                _ => max,
+            };
+        }
+        Some(current)
+    }
+}
+
+impl FromStr for Backoff {
+    type Err = Error;
+    fn from_str(input: &str) -> Result<Backoff, Error> {
+        let s = input.trim();
+        let (name, rest) = match s.find(char::is_whitespace) {
+            Some(idx) => (&s[..idx], s[idx..].trim()),
+            None => (s, &s[s.len()..]),
+        };
+        match name {
+            "fixed" => Ok(Backoff::fixed(parse_duration_in(input, rest)?)),
+            "exponential" => {
+                let (range, factor) = match rest.rfind(char::is_whitespace) {
+                    Some(idx) if rest[idx..].trim_start().starts_with('x') => {
+                        (&rest[..idx], parse_factor(&rest[idx..].trim_start()[1..])?)
+                    }
+                    _ => (rest, 2000),
+                };
+                let idx = range.find("..").ok_or(Error::InvalidFormat)?;
+                let initial = parse_duration_in(input, &range[..idx])?;
+                let max = parse_duration_in(input, &range[idx + 2..])?;
+                if max < initial {
+                    return Err(Error::InvalidRange);
+                }
+                Ok(Backoff(Kind::Exponential {
+                    initial,
+                    max,
+                    factor,
+                }))
+            }
+            "" => Err(Error::InvalidFormat),
+            _ => Err(Error::UnknownPolicy(name.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Backoff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Kind::Fixed(delay) => write!(f, "fixed {}", format_duration(delay)),
+            Kind::Exponential {
+                initial,
+                max,
+                factor,
+            } => {
+                write!(
+                    f,
+                    "exponential {}..{} x{}",
+                    format_duration(initial),
+                    format_duration(max),
+                    factor / 1000
+                )?;
+                let mut frac = factor % 1000;
+                if frac != 0 {
+                    let mut width = 3;
+                    while frac % 10 == 0 {
+                        frac /= 10;
+                        width -= 1;
+                    }
+                    write!(f, ".{:0width$}", frac, width = width)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{Backoff, Error};
+    use crate::duration::{parse_duration, Error as DurationError};
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    fn delays(s: &str, n: usize) -> Vec<Duration> {
+        s.parse::<Backoff>().unwrap().delays().take(n).collect()
+    }
+
+    #[test]
+    fn fixed() {
+        assert_eq!(delays("fixed 5s", 3), vec![ms(5000); 3]);
+        assert_eq!(delays(" fixed  1m 30s ", 1), vec![ms(90_000)]);
+        assert_eq!("fixed 5s".parse::<Backoff>(), Ok(Backoff::fixed(ms(5000))));
+    }
+
+    #[test]
+    fn exponential() {
+        assert_eq!(
+            delays("exponential 100ms..30s", 4),
+            vec![ms(100), ms(200), ms(400), ms(800)]
+        );
+        assert_eq!(
+            delays("exponential 1s .. 2s x1.5", 4),
+            vec![ms(1000), ms(1500), ms(2000), ms(2000)]
+        );
+        assert_eq!(
+            delays("exponential 1s..1s x10", 2),
+            vec![ms(1000), ms(1000)]
+        );
+        assert_eq!(delays("exponential 0s..1s", 2), vec![ms(0), ms(0)]);
+        assert_eq!(
+            "exponential 100ms..30s x2".parse::<Backoff>(),
+            Ok(Backoff::exponential(ms(100), ms(30_000)))
+        );
+    }
+
+    #[test]
+    fn big_values() {
+        let max = Duration::MAX;
+        let s = Backoff::exponential(Duration::from_secs(u64::MAX / 2), max);
+        assert_eq!(s.delays().nth(2), Some(max));
+        let s: Backoff = "exponential 1s..500000000000y x1000".parse().unwrap();
+        let max = parse_duration("500000000000y").unwrap();
+        assert_eq!(s.delays().nth(7), Some(max));
+        assert_eq!(s.delays().nth(100), Some(max));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            "linear 1s".parse::<Backoff>(),
+            Err(Error::UnknownPolicy("linear".to_owned()))
+        );
+        assert_eq!("".parse::<Backoff>(), Err(Error::InvalidFormat));
+        assert_eq!(
+            "exponential 1s".parse::<Backoff>(),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            "exponential 2s..1s".parse::<Backoff>(),
+            Err(Error::InvalidRange)
+        );
+        assert_eq!(
+            "fixed".parse::<Backoff>(),
+            Err(Error::Duration(DurationError::Empty))
+        );
+        // offsets are in the whole input
+        let offset = |s: &str| match s.parse::<Backoff>() {
+            Err(Error::Duration(e)) => e.span(),
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(offset("fixed 5x"), Some(7..8));
+        assert_eq!(offset(" exponential 1s..5x x1.5"), Some(18..19));
+        for factor in &[
+            "x0.5",
+            "x",
+            "x1.2345",
+            "x-2",
+            "x2.",
+            "xx",
+            "x1000.001",
+            "x18446744073709551",
+        ] {
+            assert_eq!(
+                format!("exponential 1s..2s {}", factor).parse::<Backoff>(),
+                Err(Error::InvalidFactor),
+                "{}",
+                factor
+            );
+        }
+    }
+
+    #[test]
+    fn display() {
+        for input in &[
+            "fixed 5s",
+            "exponential 100ms..30s x2",
+            "exponential 1s..1h x1.25",
+        ] {
+            let policy: Backoff = input.parse().unwrap();
+            assert_eq!(policy.to_string(), *input);
+        }
+    }
+}
@@ -0,0 +1,424 @@
+//! Command line interface to the humantime parser and formatter
+//!
+//! Built with `cargo install humantime --features cli`, run
+//! `humantime --help` for the list of commands.
+
+use std::env;
+use std::process;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use humantime::{
+    format_duration, format_duration_ffmpeg, format_duration_iso8601, format_duration_kubernetes,
+    format_duration_postgres, format_duration_prometheus, format_rfc3339, format_rfc3339_micros,
+    format_rfc3339_millis, format_rfc3339_nanos, format_rfc3339_seconds, grammar, parse_duration,
+    parse_rfc3339_weak, DurationError, DurationErrorKind, FormatOptions, Style,
+};
+
+const USAGE: &str = "\
+Usage:
+    humantime parse <duration> [--to human|secs|millis|micros|nanos]
+    humantime format <number> [--from secs|millis|micros|nanos] [--style <style>]
+    humantime ts parse <timestamp> [--to secs|millis|rfc3339]
+    humantime ts format <epoch-secs> [--precision smart|secs|millis|micros|nanos]
+    humantime check <duration>
+
+Styles: human, verbose, abbreviated, prometheus, kubernetes, go, postgres, iso8601, ffmpeg, machine
+
+Examples:
+    humantime parse \"1h 30m\" --to secs       prints 5400
+    humantime format 5400 --style verbose    prints 1 hour 30 minutes
+    humantime ts format 1518568087           prints 2018-02-14T00:28:07Z
+
+The check command prints a JSON object with `valid`, and either
+`seconds` or `diagnostics` with `offset`, `end`, `message` and
+`suggestion` fields, the exit code is 1 if the value is invalid.";
+
+#[derive(Debug, PartialEq)]
+enum Error {
+    /// Wrong command line, exit code 2
+    Usage(String),
+    /// Invalid value, exit code 1
+    Value(String),
+    /// Output of a failed `check`, printed to stdout with exit code 1
+    Invalid(String),
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(output) => println!("{}", output),
+        Err(Error::Usage(message)) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+        Err(Error::Value(message)) => {
+            eprintln!("error: {}", message);
+            process::exit(1);
+        }
+        Err(Error::Invalid(output)) => {
+            println!("{}", output);
+            process::exit(1);
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<String, Error> {
+    let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+    match args.as_slice() {
+        [] | ["help"] | ["--help"] | ["-h"] => Ok(USAGE.to_owned()),
+        ["parse", rest @ ..] => parse(rest),
+        ["format", rest @ ..] => format(rest),
+        ["ts", "parse", rest @ ..] => ts_parse(rest),
+        ["ts", "format", rest @ ..] => ts_format(rest),
+        ["check", rest @ ..] => check(rest),
+        [command, ..] => Err(Error::Usage(format!("unknown command {:?}", command))),
+    }
+}
+
+/// Options as `(name, value)` pairs
+type Options<'a> = Vec<(&'a str, &'a str)>;
+
+/// Splits arguments into a single positional value and `--name value`
+/// options, only `allowed` option names are accepted
+fn split<'a>(args: &[&'a str], allowed: &[&str]) -> Result<(&'a str, Options<'a>), Error> {
+    let mut value = None;
+    let mut options = Vec::new();
+    let mut iter = args.iter();
+    while let Some(&arg) = iter.next() {
+        if let Some(name) = arg.strip_prefix("--") {
+            let (name, val) = match name.find('=') {
+                Some(idx) => (&name[..idx], &name[idx + 1..]),
+                None => match iter.next() {
+                    Some(&val) => (name, val),
+                    None => return Err(Error::Usage(format!("--{} needs a value", name))),
+                },
+            };
+            if !allowed.contains(&name) {
+                return Err(Error::Usage(format!("unknown option --{}", name)));
+            }
+            options.push((name, val));
+        } else if value.replace(arg).is_some() {
+            return Err(Error::Usage(format!("unexpected argument {:?}", arg)));
+        }
+    }
+    match value {
+        Some(value) => Ok((value, options)),
+        None => Err(Error::Usage("value is missing".to_owned())),
+    }
+}
+
+fn option<'a>(options: &[(&str, &'a str)], name: &str, default: &'a str) -> &'a str {
+    options
+        .iter()
+        .rev()
+        .find(|(n, _)| *n == name)
+        .map_or(default, |(_, v)| v)
+}
+
+/// Writes `nanos` as a decimal number of `scale` nanoseconds
+fn decimal(nanos: u128, scale: u128) -> String {
+    let mut result = (nanos / scale).to_string();
+    let mut rest = nanos % scale;
+    if rest > 0 {
+        result.push('.');
+        let mut digit = scale / 10;
+        while rest > 0 {
+            result.push(char::from(b'0' + (rest / digit) as u8));
+            rest %= digit;
+            digit /= 10;
+        }
+    }
+    result
+}
+
+fn scale(unit: &str) -> Result<(u128, &'static str), Error> {
+    match unit {
+        "secs" | "s" => Ok((1_000_000_000, "s")),
+        "millis" | "ms" => Ok((1_000_000, "ms")),
+        "micros" | "us" => Ok((1_000, "us")),
+        "nanos" | "ns" => Ok((1, "ns")),
+        _ => Err(Error::Usage(format!("unknown unit {:?}", unit))),
+    }
+}
+
+fn parse(args: &[&str]) -> Result<String, Error> {
+    let (value, options) = split(args, &["to"])?;
+    let duration = parse_duration(value).map_err(|e| Error::Value(e.to_string()))?;
+    match option(&options, "to", "human") {
+        "human" => Ok(format_duration(duration).to_string()),
+        unit => Ok(decimal(duration.as_nanos(), scale(unit)?.0)),
+    }
+}
+
+/// Parses a plain number of `unit`, like `5400` or `1.5`
+fn number(value: &str, unit: &str) -> Result<Duration, Error> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+        return Err(Error::Value(format!("{:?} is not a number", value)));
+    }
+    let suffix = scale(unit)?.1;
+    parse_duration(&format!("{}{}", value, suffix)).map_err(|e| Error::Value(e.to_string()))
+}
+
+fn format(args: &[&str]) -> Result<String, Error> {
+    let (value, options) = split(args, &["from", "style"])?;
+    let duration = number(value, option(&options, "from", "secs"))?;
+    Ok(match option(&options, "style", "human") {
+        "human" => format_duration(duration).to_string(),
+        "verbose" => FormatOptions::new()
+            .with_style(Style::Verbose)
+            .format(duration)
+            .to_string(),
+        "prometheus" => format_duration_prometheus(duration).to_string(),
+        "kubernetes" => format_duration_kubernetes(duration)
+            .map_err(|e| Error::Value(e.to_string()))?
+            .to_string(),
+        "postgres" => format_duration_postgres(duration).to_string(),
+        "iso8601" => format_duration_iso8601(duration).to_string(),
+        "ffmpeg" => format_duration_ffmpeg(duration).to_string(),
+        "machine" => FormatOptions::new()
+            .with_style(Style::Machine)
+            .format(duration)
+            .to_string(),
+        "go" => FormatOptions::new()
+            .with_style(Style::Go)
+            .format(duration)
+            .to_string(),
+        "abbreviated" => FormatOptions::new()
+            .with_style(Style::Abbreviated)
+            .format(duration)
+            .to_string(),
+        style => return Err(Error::Usage(format!("unknown style {:?}", style))),
+    })
+}
+
+fn ts_parse(args: &[&str]) -> Result<String, Error> {
+    let (value, options) = split(args, &["to"])?;
+    let time = parse_rfc3339_weak(value).map_err(|e| Error::Value(e.to_string()))?;
+    let since_epoch = time
+        .duration_since(UNIX_EPOCH)
+        .expect("parsed times are after the epoch");
+    match option(&options, "to", "secs") {
+        "rfc3339" => Ok(format_rfc3339(time).to_string()),
+        unit => Ok(decimal(since_epoch.as_nanos(), scale(unit)?.0)),
+    }
+}
+
+fn ts_format(args: &[&str]) -> Result<String, Error> {
+    let (value, options) = split(args, &["precision"])?;
+    let time: SystemTime = UNIX_EPOCH
+        .checked_add(number(value, "secs")?)
+        .ok_or_else(|| Error::Value("timestamp is out of range".to_owned()))?;
+    let formatted = match option(&options, "precision", "smart") {
+        "smart" => format_rfc3339(time),
+        "secs" => format_rfc3339_seconds(time),
+        "millis" => format_rfc3339_millis(time),
+        "micros" => format_rfc3339_micros(time),
+        "nanos" => format_rfc3339_nanos(time),
+        precision => return Err(Error::Usage(format!("unknown precision {:?}", precision))),
+    };
+    let mut result = String::new();
+    std::fmt::write(&mut result, format_args!("{}", formatted))
+        .map_err(|_| Error::Value("timestamp is after year 9999".to_owned()))?;
+    Ok(result)
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn check(args: &[&str]) -> Result<String, Error> {
+    let (value, _) = split(args, &[])?;
+    let err = match parse_duration(value) {
+        Ok(duration) => {
+            return Ok(format!(
+                r#"{{"valid":true,"seconds":{}}}"#,
+                decimal(duration.as_nanos(), 1_000_000_000)
+            ))
+        }
+        Err(err) => err,
+    };
+    let char_end = |pos: usize| pos + value[pos..].chars().next().map_or(0, |c| c.len_utf8());
+    let (offset, end) = match err.span() {
+        Some(span) if span.is_empty() && err.kind() != DurationErrorKind::MissingUnit => {
+            (span.start, char_end(span.start))
+        }
+        Some(span) => (span.start, span.end),
+        None => (0, value.len()),
+    };
+    let suggestion = match err {
+        DurationError::UnknownUnit { ref unit, .. } if unit.is_empty() => Some("s"),
+        DurationError::UnknownUnit { ref unit, .. } => grammar().suggest_unit(unit),
+        _ => None,
+    };
+    Err(Error::Invalid(format!(
+        r#"{{"valid":false,"diagnostics":[{{"offset":{},"end":{},"message":{},"suggestion":{}}}]}}"#,
+        offset,
+        end,
+        json_string(&err.to_string()),
+        suggestion.map_or("null".to_owned(), json_string),
+    )))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{run, Error};
+
+    fn cli(args: &str) -> Result<String, Error> {
+        let args: Vec<String> = shell_words(args);
+        run(&args)
+    }
+
+    /// Splits on spaces, except inside double quotes
+    fn shell_words(line: &str) -> Vec<String> {
+        line.split('"')
+            .enumerate()
+            .flat_map(|(idx, part)| {
+                if idx % 2 == 1 {
+                    vec![part.to_owned()]
+                } else {
+                    part.split_whitespace().map(|w| w.to_owned()).collect()
+                }
+            })
+            .collect()
+    }
+
+    fn value_error(message: &str) -> Result<String, Error> {
+        Err(Error::Value(message.to_owned()))
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(cli(r#"parse "1h 30m" --to secs"#).unwrap(), "5400");
+        assert_eq!(cli("parse 1.5s --to=secs").unwrap(), "1.5");
+        assert_eq!(cli("parse 1s --to millis").unwrap(), "1000");
+        assert_eq!(cli("parse 1500us --to ms").unwrap(), "1.5");
+        assert_eq!(cli("parse 90min").unwrap(), "1h 30m");
+        assert_eq!(
+            cli("parse 90"),
+            value_error("time unit needed, for example 90sec or 90ms")
+        );
+        assert!(matches!(cli("parse 1s --to weeks"), Err(Error::Usage(_))));
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(cli("format 5400").unwrap(), "1h 30m");
+        assert_eq!(
+            cli("format 5400 --style verbose").unwrap(),
+            "1 hour 30 minutes"
+        );
+        assert_eq!(
+            cli("format 90061.001 --style verbose").unwrap(),
+            "1 day 1 hour 1 minute 1 second 1 millisecond"
+        );
+        assert_eq!(cli("format 1500 --from ms").unwrap(), "1s 500ms");
+        assert_eq!(cli("format 5400 --style iso8601").unwrap(), "PT1H30M");
+        assert_eq!(cli("format 5400 --style ffmpeg").unwrap(), "01:30:00");
+        assert_eq!(cli("format 5400.5 --style machine").unwrap(), "1h30m500ms");
+        assert_eq!(cli("format 5400 --style kubernetes").unwrap(), "1h30m");
+        assert_eq!(cli("format 5400.000001 --style go").unwrap(), "1h30m1us");
+        assert_eq!(cli("format 5m"), value_error("\"5m\" is not a number"));
+        assert!(matches!(
+            cli("format 1 --style fancy"),
+            Err(Error::Usage(_))
+        ));
+        assert!(matches!(cli("format 1 2"), Err(Error::Usage(_))));
+        assert!(matches!(cli("format --style"), Err(Error::Usage(_))));
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(
+            cli(r#"ts parse "2018-02-14 00:28:07""#).unwrap(),
+            "1518568087"
+        );
+        assert_eq!(
+            cli("ts parse 2018-02-14T00:28:07.5Z --to millis").unwrap(),
+            "1518568087500"
+        );
+        assert_eq!(
+            cli(r#"ts parse "2018-02-14 00:28:07" --to rfc3339"#).unwrap(),
+            "2018-02-14T00:28:07Z"
+        );
+        assert_eq!(cli("ts format 1518568087").unwrap(), "2018-02-14T00:28:07Z");
+        assert_eq!(
+            cli("ts format 1518568087.25 --precision millis").unwrap(),
+            "2018-02-14T00:28:07.250Z"
+        );
+        assert_eq!(
+            cli("ts format 253402300800"),
+            value_error("timestamp is after year 9999")
+        );
+        assert_eq!(
+            cli("ts format 18446744073709551615"),
+            value_error("timestamp is out of range")
+        );
+        assert_eq!(
+            cli("ts parse 2018"),
+            value_error("timestamp format is invalid")
+        );
+    }
+
+    #[test]
+    fn usage() {
+        assert!(cli("").unwrap().starts_with("Usage:"));
+        assert!(cli("--help").unwrap().starts_with("Usage:"));
+        assert_eq!(
+            cli("convert 1s"),
+            Err(Error::Usage("unknown command \"convert\"".to_owned()))
+        );
+    }
+
+    #[test]
+    fn check() {
+        assert_eq!(
+            cli(r#"check "1h 30m""#).unwrap(),
+            r#"{"valid":true,"seconds":5400}"#
+        );
+        assert_eq!(
+            cli("check 1.5s").unwrap(),
+            r#"{"valid":true,"seconds":1.5}"#
+        );
+        assert_eq!(
+            cli("check 5mins3").unwrap_err(),
+            Error::Invalid(
+                r#"{"valid":false,"diagnostics":[{"offset":6,"end":6,"#.to_owned()
+                    + r#""message":"time unit needed, for example 3sec or 3ms","suggestion":"s"}]}"#
+            )
+        );
+        assert_eq!(
+            cli("check 5minz").unwrap_err(),
+            Error::Invalid(
+                r#"{"valid":false,"diagnostics":[{"offset":1,"end":5,"#.to_owned()
+                    + r#""message":"unknown time unit \"minz\", supported units: ns, us/µs, ms, "#
+                    + r#"sec, min, hours, days, weeks, months, years (and few variations)","#
+                    + r#""suggestion":"min"}]}"#
+            )
+        );
+        assert_eq!(
+            cli(r#"check "1h, 2m""#).unwrap_err(),
+            Error::Invalid(
+                r#"{"valid":false,"diagnostics":[{"offset":2,"end":3,"#.to_owned()
+                    + r#""message":"invalid character at 2","suggestion":null}]}"#
+            )
+        );
+        assert!(matches!(cli("check 1s --to secs"), Err(Error::Usage(_))));
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(super::json_string("a\"b\\\n"), r#""a\"b\\\n""#);
+    }
+}
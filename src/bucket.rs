@@ -0,0 +1,156 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+#[cfg(feature = "mu")]
+const MICROS: &str = "µs";
+#[cfg(not(feature = "mu"))]
+const MICROS: &str = "us";
+
+/// Units used for labels, largest first
+const UNITS: &[(u128, &str)] = &[
+    (86_400_000_000_000, "d"),
+    (3_600_000_000_000, "h"),
+    (60_000_000_000, "m"),
+    (1_000_000_000, "s"),
+    (1_000_000, "ms"),
+    (1_000, MICROS),
+    (1, "ns"),
+];
+
+/// Picks the largest unit in which all the values are whole numbers
+fn pick_unit<I: IntoIterator<Item = Duration>>(values: I) -> (u128, &'static str) {
+    let mut idx = 0;
+    let mut all_zero = true;
+    for value in values {
+        let nanos = value.as_nanos();
+        if nanos == 0 {
+            continue;
+        }
+        all_zero = false;
+        while nanos % UNITS[idx].0 != 0 {
+            idx += 1;
+        }
+    }
+    if all_zero {
+        // zero is written in seconds, like in `format_duration`
+        return UNITS[3];
+    }
+    UNITS[idx]
+}
+
+fn write_label(buf: &mut String, lower: Duration, upper: Option<Duration>, unit: (u128, &str)) {
+    let (nanos, suffix) = unit;
+    match upper {
+        Some(upper) => write!(
+            buf,
+            "{}{}\u{2013}{}{}",
+            lower.as_nanos() / nanos,
+            suffix,
+            upper.as_nanos() / nanos,
+            suffix
+        ),
+        None => write!(buf, "\u{2265}{}{}", lower.as_nanos() / nanos, suffix),
+    }
+    .expect("writing to a string never fails");
+}
+
+/// Formats a label for a histogram bucket, like `100ms–250ms` or `≥1s`
+///
+/// Both bounds are written as whole numbers of the same unit, the largest
+/// one which can represent both of them exactly. The upper bound of `None`
+/// means an unbounded bucket.
+///
+/// Use [`format_bucket_labels`] to get a consistent unit over a whole set
+/// of buckets.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::format_bucket_label;
+///
+/// let label = format_bucket_label(
+///     Duration::from_millis(100),
+///     Some(Duration::from_millis(250)),
+/// );
+/// assert_eq!(label, "100ms\u{2013}250ms");
+/// assert_eq!(format_bucket_label(Duration::from_secs(1), None), "\u{2265}1s");
+/// ```
+pub fn format_bucket_label(lower: Duration, upper: Option<Duration>) -> String {
+    let mut buf = String::new();
+    let unit = pick_unit(Some(lower).into_iter().chain(upper));
+    write_label(&mut buf, lower, upper, unit);
+    buf
+}
+
+/// Formats labels for histogram buckets delimited by `bounds`
+///
+/// Returns one label per bound: each bucket spans from its bound to the
+/// next one and the last bucket is unbounded. All labels use the same
+/// unit, the largest one which can represent every bound exactly.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::format_bucket_labels;
+///
+/// let bounds = [
+///     Duration::ZERO,
+///     Duration::from_millis(250),
+///     Duration::from_secs(1),
+/// ];
+/// assert_eq!(
+///     format_bucket_labels(&bounds),
+///     ["0ms\u{2013}250ms", "250ms\u{2013}1000ms", "\u{2265}1000ms"],
+/// );
+/// ```
+pub fn format_bucket_labels(bounds: &[Duration]) -> Vec<String> {
+    let unit = pick_unit(bounds.iter().copied());
+    bounds
+        .iter()
+        .enumerate()
+        .map(|(idx, &lower)| {
+            let mut buf = String::new();
+            write_label(&mut buf, lower, bounds.get(idx + 1).copied(), unit);
+            buf
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{format_bucket_label, format_bucket_labels};
+
+    #[test]
+    fn label() {
+        let ms = Duration::from_millis;
+        assert_eq!(format_bucket_label(ms(100), Some(ms(250))), "100ms–250ms");
+        assert_eq!(format_bucket_label(ms(500), Some(ms(1000))), "500ms–1000ms");
+        assert_eq!(format_bucket_label(ms(1000), Some(ms(2000))), "1s–2s");
+        assert_eq!(format_bucket_label(ms(0), Some(ms(60_000))), "0m–1m");
+        assert_eq!(format_bucket_label(ms(0), None), "≥0s");
+        assert_eq!(format_bucket_label(ms(7_200_000), None), "≥2h");
+        assert_eq!(
+            format_bucket_label(Duration::from_nanos(1500), None),
+            "≥1500ns"
+        );
+    }
+
+    #[test]
+    fn labels() {
+        let secs = Duration::from_secs;
+        assert_eq!(
+            format_bucket_labels(&[secs(60), secs(300), secs(3600)]),
+            ["1m–5m", "5m–60m", "≥60m"]
+        );
+        assert_eq!(
+            format_bucket_labels(&[secs(86400), secs(7 * 86400)]),
+            ["1d–7d", "≥7d"]
+        );
+        assert_eq!(format_bucket_labels(&[secs(0)]), ["≥0s"]);
+        assert!(format_bucket_labels(&[]).is_empty());
+    }
+}
@@ -0,0 +1,438 @@
+use std::collections::BTreeSet;
+use std::time::{Duration, SystemTime};
+
+use crate::civil::days_since_epoch;
+use crate::duration::{parse_duration, Error};
+
+/// Day of the week
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+    /// Monday
+    Monday,
+    /// Tuesday
+    Tuesday,
+    /// Wednesday
+    Wednesday,
+    /// Thursday
+    Thursday,
+    /// Friday
+    Friday,
+    /// Saturday
+    Saturday,
+    /// Sunday
+    Sunday,
+}
+
+/// Working schedule used to interpret business time units
+///
+/// By default the work week is Monday to Friday with 8 working hours a day
+/// and there are no holidays. Days are counted in UTC.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::{BusinessCalendar, Weekday};
+///
+/// let calendar = BusinessCalendar::new()
+///     .workdays(&[Weekday::Monday, Weekday::Tuesday, Weekday::Wednesday, Weekday::Thursday])
+///     .hours_per_day(Duration::from_secs(10 * 3600));
+/// assert!(!calendar.is_workday(Weekday::Friday));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BusinessCalendar {
+    workdays: u8,
+    hours_per_day: Duration,
+    holidays: BTreeSet<i64>,
+}
+
+/// Business time split into whole business days and the rest
+///
+/// This is a calendar-aware form of business time: the `days` are meant to
+/// be counted on a calendar, skipping non-working days, while the `time` is
+/// shorter than a working day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BusinessSpan {
+    /// Number of whole business days
+    pub days: u64,
+    /// Working time which is less than a business day
+    pub time: Duration,
+}
+
+impl Default for BusinessCalendar {
+    fn default() -> BusinessCalendar {
+        BusinessCalendar::new()
+    }
+}
+
+impl BusinessCalendar {
+    /// Calendar with Monday to Friday work week and 8-hour working days
+    pub fn new() -> BusinessCalendar {
+        BusinessCalendar {
+            workdays: 0b0001_1111,
+            hours_per_day: Duration::from_secs(8 * 3600),
+            holidays: BTreeSet::new(),
+        }
+    }
+
+    /// Set days of the week which are working days
+    pub fn workdays(mut self, days: &[Weekday]) -> BusinessCalendar {
+        self.workdays = days.iter().fold(0, |mask, &d| mask | 1 << d as u8);
+        self
+    }
+
+    /// Set the length of a working day
+    pub fn hours_per_day(mut self, value: Duration) -> BusinessCalendar {
+        self.hours_per_day = value;
+        self
+    }
+
+    /// Mark the (UTC) day containing `day` as a holiday
+    pub fn holiday(mut self, day: SystemTime) -> BusinessCalendar {
+        self.holidays.insert(days_since_epoch(day));
+        self
+    }
+
+    /// Returns `true` if the day of the week is a working day
+    pub fn is_workday(&self, day: Weekday) -> bool {
+        self.workdays & 1 << day as u8 != 0
+    }
+
+    /// Returns `true` if the (UTC) day containing `time` is a business day,
+    /// i.e. a working day of the week which is not a holiday
+    pub fn is_business_day(&self, time: SystemTime) -> bool {
+        self.is_business_day_number(days_since_epoch(time))
+    }
+
+    pub(crate) fn is_business_day_number(&self, day: i64) -> bool {
+        self.is_workday(weekday(day)) && !self.holidays.contains(&day)
+    }
+
+    /// Number of working days of the week
+    pub(crate) fn workdays_per_week(&self) -> u32 {
+        self.workdays.count_ones()
+    }
+
+    /// Number of holidays in `(after, until]` falling on a working day
+    pub(crate) fn holidays_between(&self, after: i64, until: i64) -> u64 {
+        self.holidays
+            .range(after + 1..=until)
+            .filter(|&&d| self.is_workday(weekday(d)))
+            .count() as u64
+    }
+
+    /// Returns the length of a working day
+    pub fn day_length(&self) -> Duration {
+        self.hours_per_day
+    }
+
+    /// Splits working time into business days and the remainder
+    pub fn span(&self, work: Duration) -> BusinessSpan {
+        let day = self.hours_per_day.as_nanos();
+        if day == 0 {
+            return BusinessSpan {
+                days: 0,
+                time: work,
+            };
+        }
+        let nanos = work.as_nanos();
+        let rem = nanos % day;
+        BusinessSpan {
+            days: (nanos / day) as u64,
+            time: Duration::new((rem / 1_000_000_000) as u64, (rem % 1_000_000_000) as u32),
+        }
+    }
+}
+
+pub(crate) fn weekday(day: i64) -> Weekday {
+    // 1970-01-01 is Thursday
+    match (day + 3).rem_euclid(7) {
+        0 => Weekday::Monday,
+        1 => Weekday::Tuesday,
+        2 => Weekday::Wednesday,
+        3 => Weekday::Thursday,
+        4 => Weekday::Friday,
+        5 => Weekday::Saturday,
+        _ => Weekday::Sunday,
+    }
+}
+
+enum BusinessUnit {
+    Day,
+    Hour,
+}
+
+fn business_unit(first: &str, second: Option<&str>) -> Option<BusinessUnit> {
+    match (first, second?) {
+        ("business", "day") | ("business", "days") => Some(BusinessUnit::Day),
+        ("work", "hour") | ("work", "hours") => Some(BusinessUnit::Hour),
+        ("working", "hour") | ("working", "hours") => Some(BusinessUnit::Hour),
+        _ => None,
+    }
+}
+
+/// Returns the number as a fraction `numerator / denominator`
+fn parse_number(s: &str, off: usize) -> Result<(u128, u128), Error> {
+    let mut numerator = 0u128;
+    let mut denominator = 1u128;
+    let mut fraction = false;
+    for (idx, c) in s.char_indices() {
+        match c {
+            '0'..='9' => {
+                numerator = numerator
+                    .checked_mul(10)
+                    .and_then(|n| n.checked_add(u128::from(c as u8 - b'0')))
+                    .ok_or(Error::NumberOverflow)?;
+                if fraction {
+                    denominator = denominator.checked_mul(10).ok_or(Error::NumberOverflow)?;
+                }
+            }
+            '.' if !fraction && idx > 0 => fraction = true,
+            _ => return Err(Error::InvalidCharacter(off + idx)),
+        }
+    }
+    if fraction && denominator == 1 {
+        return Err(Error::InvalidCharacter(off + s.len()));
+    }
+    Ok((numerator, denominator))
+}
+
+/// Parse working time which may contain business time units
+///
+/// In addition to everything accepted by
+/// [`parse_duration`](crate::parse_duration), understands:
+///
+/// * `business days`, `business day` -- a working day of the calendar
+/// * `work hours`, `work hour`, `working hours` -- an hour of work
+///
+/// Business units must be separated from the number by a space, and may
+/// have a fractional part: `1.5 business days`.
+///
+/// The result is the amount of working time, use
+/// [`BusinessCalendar::span`] or [`parse_business_span`] to get it as
+/// business days.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::{parse_business_duration, BusinessCalendar};
+///
+/// let calendar = BusinessCalendar::new();
+/// assert_eq!(
+///     parse_business_duration("3 business days", &calendar),
+///     Ok(Duration::from_secs(24 * 3600)),
+/// );
+/// assert_eq!(
+///     parse_business_duration("1 business day 4 work hours 30min", &calendar),
+///     Ok(Duration::from_secs(12 * 3600 + 1800)),
+/// );
+/// ```
+pub fn parse_business_duration(s: &str, calendar: &BusinessCalendar) -> Result<Duration, Error> {
+    let words: Vec<(usize, &str)> = s
+        .split_whitespace()
+        .map(|w| (w.as_ptr() as usize - s.as_ptr() as usize, w))
+        .collect();
+    if words.is_empty() {
+        return Err(Error::Empty);
+    }
+    let mut out = Duration::ZERO;
+    let mut idx = 0;
+    while idx < words.len() {
+        let (off, word) = words[idx];
+        if word.starts_with(|c: char| !c.is_ascii_digit()) {
+            return Err(Error::NumberExpected(off));
+        }
+        let unit = match words.get(idx + 1) {
+            Some(&(_, next)) if word.bytes().all(|b| b.is_ascii_digit() || b == b'.') => {
+                business_unit(next, words.get(idx + 2).map(|&(_, w)| w))
+            }
+            _ => None,
+        };
+        let value = match unit {
+            Some(unit) => {
+                let (num, den) = parse_number(word, off)?;
+                let per_unit = match unit {
+                    BusinessUnit::Day => calendar.hours_per_day.as_nanos(),
+                    BusinessUnit::Hour => 3_600_000_000_000,
+                };
+                let nanos = num.checked_mul(per_unit).ok_or(Error::NumberOverflow)?;
+                if nanos % den != 0 {
+                    // values below nanosecond can't be represented
+                    return Err(Error::NumberOverflow);
+                }
+                let nanos = nanos / den;
+                let secs =
+                    u64::try_from(nanos / 1_000_000_000).map_err(|_| Error::NumberOverflow)?;
+                idx += 3;
+                Duration::new(secs, (nanos % 1_000_000_000) as u32)
+            }
+            None => {
+                // a regular component, possibly with unit as a separate word
+                let mut end = off + word.len();
+                if word.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+                    if let Some(&(next_off, next)) = words.get(idx + 1) {
+                        end = next_off + next.len();
+                        idx += 1;
+                    }
+                }
+                idx += 1;
+                parse_duration(&s[off..end]).map_err(|e| e.offset_by(off))?
+            }
+        };
+        out = out.checked_add(value).ok_or(Error::NumberOverflow)?;
+    }
+    Ok(out)
+}
+
+/// Parse working time like [`parse_business_duration`] and split it into
+/// business days
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::{parse_business_span, BusinessCalendar, BusinessSpan};
+///
+/// let calendar = BusinessCalendar::new();
+/// assert_eq!(
+///     parse_business_span("20 work hours", &calendar),
+///     Ok(BusinessSpan { days: 2, time: Duration::from_secs(4 * 3600) }),
+/// );
+/// ```
+pub fn parse_business_span(s: &str, calendar: &BusinessCalendar) -> Result<BusinessSpan, Error> {
+    parse_business_duration(s, calendar).map(|work| calendar.span(work))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{parse_business_duration, BusinessCalendar, BusinessSpan, Weekday};
+    use crate::civil::days_since_epoch;
+    use crate::duration::Error;
+
+    fn hours(n: u64) -> Duration {
+        Duration::from_secs(n * 3600)
+    }
+
+    #[test]
+    fn units() {
+        let cal = BusinessCalendar::new();
+        let parse = |s| parse_business_duration(s, &cal);
+        assert_eq!(parse("1 business day"), Ok(hours(8)));
+        assert_eq!(parse("2 business days"), Ok(hours(16)));
+        assert_eq!(parse("1.5 business days"), Ok(hours(12)));
+        assert_eq!(parse("8 work hours"), Ok(hours(8)));
+        assert_eq!(parse("1 work hour"), Ok(hours(1)));
+        assert_eq!(parse("0.5 working hours"), Ok(Duration::from_secs(1800)));
+        assert_eq!(parse("2 business days 3 work hours"), Ok(hours(19)));
+    }
+
+    #[test]
+    fn regular_units() {
+        let cal = BusinessCalendar::new();
+        let parse = |s| parse_business_duration(s, &cal);
+        assert_eq!(parse("2h 30min"), Ok(Duration::from_secs(9000)));
+        assert_eq!(parse("2 h 30 min"), Ok(Duration::from_secs(9000)));
+        assert_eq!(
+            parse("1 business day 15 min"),
+            Ok(hours(8) + Duration::from_secs(900))
+        );
+        assert_eq!(parse("1h30m 1 work hour"), Ok(Duration::from_secs(9000)));
+    }
+
+    #[test]
+    fn custom_calendar() {
+        let cal = BusinessCalendar::new().hours_per_day(Duration::from_secs(7 * 3600 + 1800));
+        assert_eq!(
+            parse_business_duration("2 business days", &cal),
+            Ok(hours(15))
+        );
+        assert_eq!(
+            cal.span(hours(16)),
+            BusinessSpan {
+                days: 2,
+                time: hours(1)
+            }
+        );
+        let cal = cal.hours_per_day(Duration::ZERO);
+        assert_eq!(
+            parse_business_duration("2 business days", &cal),
+            Ok(Duration::ZERO)
+        );
+        assert_eq!(
+            cal.span(hours(1)),
+            BusinessSpan {
+                days: 0,
+                time: hours(1)
+            }
+        );
+    }
+
+    #[test]
+    fn workdays() {
+        let cal = BusinessCalendar::new();
+        assert!(cal.is_workday(Weekday::Monday));
+        assert!(cal.is_workday(Weekday::Friday));
+        assert!(!cal.is_workday(Weekday::Saturday));
+        assert!(!cal.is_workday(Weekday::Sunday));
+        let cal = cal.workdays(&[Weekday::Sunday]);
+        assert!(cal.is_workday(Weekday::Sunday));
+        assert!(!cal.is_workday(Weekday::Monday));
+    }
+
+    #[test]
+    fn business_days() {
+        // 2024-12-25 is Wednesday
+        let christmas = UNIX_EPOCH + Duration::from_secs(1_735_128_000);
+        let cal = BusinessCalendar::new().holiday(christmas);
+        assert!(!cal.is_business_day(christmas));
+        assert!(cal.is_business_day(christmas + Duration::from_secs(86400)));
+        assert!(!cal.is_business_day(christmas + Duration::from_secs(3 * 86400)));
+        // 1969-12-31 is Wednesday, 1969-12-28 is Sunday
+        assert_eq!(days_since_epoch(UNIX_EPOCH - Duration::from_secs(1)), -1);
+        assert_eq!(
+            days_since_epoch(UNIX_EPOCH - Duration::from_secs(86400)),
+            -1
+        );
+        assert!(cal.is_business_day(UNIX_EPOCH - Duration::from_secs(1)));
+        assert!(!cal.is_business_day(UNIX_EPOCH - Duration::from_secs(3 * 86400 + 1)));
+    }
+
+    #[test]
+    fn errors() {
+        let cal = BusinessCalendar::new();
+        let parse = |s| parse_business_duration(s, &cal);
+        assert_eq!(parse(""), Err(Error::Empty));
+        assert_eq!(parse("business days"), Err(Error::NumberExpected(0)));
+        assert_eq!(parse("1h business days"), Err(Error::NumberExpected(3)));
+        assert_eq!(
+            parse("1 business"),
+            Err(Error::UnknownUnit {
+                start: 2,
+                end: 10,
+                unit: "business".to_owned(),
+                value: 1
+            })
+        );
+        assert_eq!(
+            parse("3h 5"),
+            Err(Error::UnknownUnit {
+                start: 4,
+                end: 4,
+                unit: String::new(),
+                value: 5
+            })
+        );
+        assert_eq!(parse("1. business days"), Err(Error::InvalidCharacter(2)));
+        assert_eq!(
+            parse("0.0000000000001 work hours"),
+            Err(Error::NumberOverflow)
+        );
+        assert_eq!(
+            parse("99999999999999999999 business days"),
+            Err(Error::NumberOverflow)
+        );
+    }
+}
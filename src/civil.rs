@@ -0,0 +1,463 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::business::{weekday, Weekday};
+use crate::date::Error;
+
+/// A date in the proleptic Gregorian calendar, like `2024-06-01`
+///
+/// This is the date that [`format_rfc3339`](crate::format_rfc3339) writes,
+/// so it is always in UTC. Together with a [`CivilTime`] it converts back
+/// to a [`SystemTime`] with [`CivilDate::and_time`].
+///
+/// # Example
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use humantime::{parse_rfc3339, CivilDate, CivilTime, Weekday};
+///
+/// let time = parse_rfc3339("2024-06-01T13:45:07Z").unwrap();
+/// let date = CivilDate::try_from(time).unwrap();
+/// assert_eq!((date.year(), date.month(), date.day()), (2024, 6, 1));
+/// assert_eq!(date.weekday(), Weekday::Saturday);
+/// assert_eq!(date.to_string(), "2024-06-01");
+/// assert_eq!(date.and_time(CivilTime::from(time)), Some(time));
+/// assert!(CivilDate::new(2023, 2, 29).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CivilDate {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+/// A time of day in UTC, like `13:45:07`, with nanoseconds
+///
+/// There are no leap seconds, the last second of the day is `23:59:59`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CivilTime {
+    hour: u8,
+    minute: u8,
+    second: u8,
+    nanosecond: u32,
+}
+
+/// Converts the number of days since the epoch into the year, month and day
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    /* 2000-03-01 (mod 400 year, immediately after feb29 */
+    const LEAPOCH: i64 = 11017;
+    const DAYS_PER_400Y: i64 = 365 * 400 + 97;
+    const DAYS_PER_100Y: i64 = 365 * 100 + 24;
+    const DAYS_PER_4Y: i64 = 365 * 4 + 1;
+
+    let days = days - LEAPOCH;
+
+    let mut qc_cycles = days / DAYS_PER_400Y;
+    let mut remdays = days % DAYS_PER_400Y;
+
+    if remdays < 0 {
+        remdays += DAYS_PER_400Y;
+        qc_cycles -= 1;
+    }
+
+    let mut c_cycles = remdays / DAYS_PER_100Y;
+    if c_cycles == 4 {
+        c_cycles -= 1;
+    }
+    remdays -= c_cycles * DAYS_PER_100Y;
+
+    let mut q_cycles = remdays / DAYS_PER_4Y;
+    if q_cycles == 25 {
+        q_cycles -= 1;
+    }
+    remdays -= q_cycles * DAYS_PER_4Y;
+
+    let mut remyears = remdays / 365;
+    if remyears == 4 {
+        remyears -= 1;
+    }
+    remdays -= remyears * 365;
+
+    let mut year = 2000 + remyears + 4 * q_cycles + 100 * c_cycles + 400 * qc_cycles;
+
+    let months = [31, 30, 31, 30, 31, 31, 30, 31, 30, 31, 31, 29];
+    let mut mon = 0;
+    for mon_len in months.iter() {
+        mon += 1;
+        if remdays < *mon_len {
+            break;
+        }
+        remdays -= *mon_len;
+    }
+    let mday = remdays + 1;
+    let mon = if mon + 2 > 12 {
+        year += 1;
+        mon - 10
+    } else {
+        mon + 2
+    };
+    (year, mon as u32, mday as u32)
+}
+
+/// Converts a date into the number of days since the epoch
+///
+/// The month and the day must be valid for the year.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    // years starting in March, so that the leap day is the last one
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * i64::from((month + 9) % 12) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the number of days since the epoch, negative before it
+///
+/// This is the index of the day in UTC, for bucketing timestamps by day
+/// without a date library.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::{days_since_epoch, parse_rfc3339};
+///
+/// let time = parse_rfc3339("2024-06-01T13:45:07Z").unwrap();
+/// assert_eq!(days_since_epoch(time), 19875);
+/// assert_eq!(days_since_epoch(UNIX_EPOCH - Duration::from_secs(1)), -1);
+/// ```
+pub fn days_since_epoch(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => (d.as_secs() / 86400) as i64,
+        Err(e) => {
+            let d = e.duration();
+            let secs = d.as_secs() + u64::from(d.subsec_nanos() > 0);
+            -(((secs + 86399) / 86400) as i64)
+        }
+    }
+}
+
+/// Returns the ISO 8601 week-numbering year and the week, from 1 to 53
+///
+/// Weeks start on Monday, and the first week of the year is the one with
+/// its Thursday, so the week-numbering year differs from the calendar
+/// year around the new year. The week is in UTC.
+///
+/// # Example
+///
+/// ```
+/// use humantime::{iso_week, parse_rfc3339};
+///
+/// let time = parse_rfc3339("2024-06-01T13:45:07Z").unwrap();
+/// assert_eq!(iso_week(time), (2024, 22));
+/// // Sunday, in the last week of 2020
+/// let time = parse_rfc3339("2021-01-03T00:00:00Z").unwrap();
+/// assert_eq!(iso_week(time), (2020, 53));
+/// ```
+pub fn iso_week(time: SystemTime) -> (i64, u32) {
+    let day = days_since_epoch(time);
+    let thursday = day - weekday(day) as i64 + 3;
+    let (year, _, _) = civil_from_days(thursday);
+    let week = (thursday - days_from_civil(year, 1, 1)) / 7 + 1;
+    (year, week as u32)
+}
+
+/// Returns the number of months since the epoch, negative before it
+///
+/// January 1970 is zero, there are twelve months every year, so the year
+/// and the month are `1970 + index.div_euclid(12)` and
+/// `index.rem_euclid(12) + 1`. The month is in UTC.
+///
+/// # Example
+///
+/// ```
+/// use humantime::{month_index, parse_rfc3339};
+///
+/// let time = parse_rfc3339("2024-06-01T13:45:07Z").unwrap();
+/// let index = month_index(time);
+/// assert_eq!(index, 653);
+/// assert_eq!((1970 + index / 12, index % 12 + 1), (2024, 6));
+/// ```
+pub fn month_index(time: SystemTime) -> i64 {
+    let (year, month, _) = civil_from_days(days_since_epoch(time));
+    (year - 1970) * 12 + i64::from(month) - 1
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+impl CivilDate {
+    /// Creates a date, if the month and the day exist in the year
+    ///
+    /// Returns [`Error::OutOfRange`] otherwise, like for `2023-02-29`.
+    pub fn new(year: i32, month: u32, day: u32) -> Result<CivilDate, Error> {
+        if month == 0 || month > 12 || day == 0 || day > days_in_month(year, month) {
+            return Err(Error::OutOfRange);
+        }
+        Ok(CivilDate {
+            year,
+            month: month as u8,
+            day: day as u8,
+        })
+    }
+
+    /// The year, zero is 1 BC
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// The month, from 1 to 12
+    pub fn month(&self) -> u32 {
+        u32::from(self.month)
+    }
+
+    /// The day of the month, from 1 to 31
+    pub fn day(&self) -> u32 {
+        u32::from(self.day)
+    }
+
+    /// The day of the week
+    pub fn weekday(&self) -> Weekday {
+        weekday(self.days_since_epoch())
+    }
+
+    fn days_since_epoch(&self) -> i64 {
+        days_from_civil(i64::from(self.year), self.month(), self.day())
+    }
+
+    /// Returns the time at `time` on this date
+    ///
+    /// Returns `None` if the platform's [`SystemTime`] can't represent it.
+    pub fn and_time(&self, time: CivilTime) -> Option<SystemTime> {
+        let secs = self.days_since_epoch() * 86400 + i64::from(time.seconds_of_day());
+        if secs >= 0 {
+            UNIX_EPOCH.checked_add(Duration::new(secs as u64, time.nanosecond))
+        } else if time.nanosecond > 0 {
+            UNIX_EPOCH.checked_sub(Duration::new(
+                secs.unsigned_abs() - 1,
+                1_000_000_000 - time.nanosecond,
+            ))
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs()))
+        }
+    }
+}
+
+impl TryFrom<SystemTime> for CivilDate {
+    type Error = Error;
+
+    /// Returns the date of `time` in UTC
+    ///
+    /// Fails with [`Error::OutOfRange`] if the year doesn't fit an `i32`.
+    fn try_from(time: SystemTime) -> Result<CivilDate, Error> {
+        let (year, month, day) = civil_from_days(days_since_epoch(time));
+        let year = i32::try_from(year).map_err(|_| Error::OutOfRange)?;
+        Ok(CivilDate {
+            year,
+            month: month as u8,
+            day: day as u8,
+        })
+    }
+}
+
+impl fmt::Display for CivilDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if (0..=9999).contains(&self.year) {
+            write!(f, "{:04}", self.year)?;
+        } else {
+            // expanded representation of ISO 8601
+            write!(f, "{:+05}", self.year)?;
+        }
+        write!(f, "-{:02}-{:02}", self.month, self.day)
+    }
+}
+
+impl CivilTime {
+    /// Creates a time of day, if the components are in range
+    ///
+    /// Returns [`Error::OutOfRange`] otherwise.
+    pub fn new(hour: u32, minute: u32, second: u32, nanosecond: u32) -> Result<CivilTime, Error> {
+        if hour > 23 || minute > 59 || second > 59 || nanosecond > 999_999_999 {
+            return Err(Error::OutOfRange);
+        }
+        Ok(CivilTime {
+            hour: hour as u8,
+            minute: minute as u8,
+            second: second as u8,
+            nanosecond,
+        })
+    }
+
+    /// The hour, from 0 to 23
+    pub fn hour(&self) -> u32 {
+        u32::from(self.hour)
+    }
+
+    /// The minute, from 0 to 59
+    pub fn minute(&self) -> u32 {
+        u32::from(self.minute)
+    }
+
+    /// The second, from 0 to 59
+    pub fn second(&self) -> u32 {
+        u32::from(self.second)
+    }
+
+    /// The fraction of the second in nanoseconds
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    pub(crate) fn seconds_of_day(&self) -> u32 {
+        self.hour() * 3600 + self.minute() * 60 + self.second()
+    }
+}
+
+impl From<SystemTime> for CivilTime {
+    /// Returns the time of day of `time` in UTC
+    fn from(time: SystemTime) -> CivilTime {
+        let (secs, nanos) = match time.duration_since(UNIX_EPOCH) {
+            Ok(d) => ((d.as_secs() % 86400) as u32, d.subsec_nanos()),
+            Err(e) => {
+                let d = e.duration();
+                let (secs, nanos) = match d.subsec_nanos() {
+                    0 => (d.as_secs(), 0),
+                    n => (d.as_secs() + 1, 1_000_000_000 - n),
+                };
+                ((86400 - secs % 86400) as u32 % 86400, nanos)
+            }
+        };
+        CivilTime {
+            hour: (secs / 3600) as u8,
+            minute: (secs / 60 % 60) as u8,
+            second: (secs % 60) as u8,
+            nanosecond: nanos,
+        }
+    }
+}
+
+impl fmt::Display for CivilTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)?;
+        if self.nanosecond > 0 {
+            write!(f, ".{:09}", self.nanosecond)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryFrom;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{civil_from_days, days_from_civil, days_since_epoch, iso_week, month_index};
+    use super::{CivilDate, CivilTime};
+    use crate::date::{parse_rfc3339, Error};
+    use crate::test_support::random_timestamp;
+
+    #[test]
+    fn civil() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(-719_468), (0, 3, 1));
+        for day in -800_000..3_000_000 {
+            let (year, month, mday) = civil_from_days(day);
+            assert_eq!(days_from_civil(year, month, mday), day);
+        }
+    }
+
+    #[test]
+    fn weeks() {
+        let week = |s: &str| iso_week(parse_rfc3339(s).unwrap());
+        assert_eq!(week("1970-01-01T00:00:00Z"), (1970, 1));
+        assert_eq!(week("2018-12-31T00:00:00Z"), (2019, 1));
+        assert_eq!(week("2020-12-31T00:00:00Z"), (2020, 53));
+        assert_eq!(week("2021-01-04T00:00:00Z"), (2021, 1));
+        assert_eq!(week("2024-12-29T23:59:59Z"), (2024, 52));
+        assert_eq!(week("2026-01-01T00:00:00Z"), (2026, 1));
+        // Sunday, 1969-12-28
+        assert_eq!(
+            iso_week(UNIX_EPOCH - Duration::from_secs(4 * 86400)),
+            (1969, 52)
+        );
+    }
+
+    #[test]
+    fn months() {
+        let month = |s: &str| month_index(parse_rfc3339(s).unwrap());
+        assert_eq!(month("1970-01-31T23:59:59Z"), 0);
+        assert_eq!(month("1970-02-01T00:00:00Z"), 1);
+        assert_eq!(month("1999-12-31T00:00:00Z"), 359);
+        assert_eq!(month_index(UNIX_EPOCH - Duration::new(0, 1)), -1);
+        assert_eq!(
+            days_since_epoch(UNIX_EPOCH - Duration::from_secs(86401)),
+            -2
+        );
+    }
+
+    #[test]
+    fn dates() {
+        assert!(CivilDate::new(2024, 2, 29).is_ok());
+        assert_eq!(CivilDate::new(1900, 2, 29), Err(Error::OutOfRange));
+        assert_eq!(CivilDate::new(2024, 4, 31), Err(Error::OutOfRange));
+        assert_eq!(CivilDate::new(2024, 13, 1), Err(Error::OutOfRange));
+        assert_eq!(CivilDate::new(2024, 1, 0), Err(Error::OutOfRange));
+        assert_eq!(CivilTime::new(24, 0, 0, 0), Err(Error::OutOfRange));
+        assert_eq!(CivilTime::new(0, 0, 60, 0), Err(Error::OutOfRange));
+        assert_eq!(
+            CivilTime::new(0, 0, 0, 1_000_000_000),
+            Err(Error::OutOfRange)
+        );
+
+        let date = |y, m, d| CivilDate::new(y, m, d).unwrap();
+        assert_eq!(date(2024, 6, 1).to_string(), "2024-06-01");
+        assert_eq!(date(-44, 3, 15).to_string(), "-0044-03-15");
+        assert_eq!(date(12024, 1, 1).to_string(), "+12024-01-01");
+        let time = CivilTime::new(9, 5, 7, 0).unwrap();
+        assert_eq!(time.to_string(), "09:05:07");
+        let time = CivilTime::new(9, 5, 7, 250_000_000).unwrap();
+        assert_eq!(time.to_string(), "09:05:07.250000000");
+
+        let before = UNIX_EPOCH - Duration::new(0, 1);
+        assert_eq!(CivilDate::try_from(before), Ok(date(1969, 12, 31)));
+        assert_eq!(
+            CivilTime::from(before),
+            CivilTime::new(23, 59, 59, 999_999_999).unwrap()
+        );
+        assert_eq!(
+            CivilTime::from(UNIX_EPOCH - Duration::from_secs(86400)),
+            CivilTime::new(0, 0, 0, 0).unwrap()
+        );
+        let time = CivilTime::new(23, 59, 59, 999_999_999).unwrap();
+        assert_eq!(date(1969, 12, 31).and_time(time), Some(before));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = rand::rng();
+        for _ in 0..10000 {
+            let time = random_timestamp(&mut rng);
+            let date = CivilDate::try_from(time).unwrap();
+            assert_eq!(date.and_time(CivilTime::from(time)), Some(time));
+            assert!(format!("{}T{}Z", date, CivilTime::from(time))
+                .starts_with(&crate::date::format_rfc3339(time).to_string()[..19]));
+        }
+        for secs in [1, 86399, 86400, 86401, 1_000_000_000] {
+            for nanos in [0, 1, 999_999_999] {
+                let time = UNIX_EPOCH - Duration::new(secs, nanos);
+                let date = CivilDate::try_from(time).unwrap();
+                assert_eq!(date.and_time(CivilTime::from(time)), Some(time));
+            }
+        }
+    }
+}
@@ -0,0 +1,346 @@
+use std::fmt::{self, Write};
+use std::ops::Deref;
+use std::str;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::date::{format_rfc3339, format_rfc3339_seconds, Rfc3339Timestamp};
+
+/// Length of `2018-02-14T00:28:07`
+const PREFIX_LEN: usize = 19;
+
+/// A source of the current time
+///
+/// Implemented for [`SystemClock`] and for closures returning a
+/// `SystemTime`, the latter is useful to feed a fixed time in tests.
+pub trait Clock {
+    /// Returns the current time
+    fn now(&self) -> SystemTime;
+}
+
+/// The clock returning [`SystemTime::now`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+impl<F: Fn() -> SystemTime> Clock for F {
+    fn now(&self) -> SystemTime {
+        self()
+    }
+}
+
+/// Format the current time as an RFC3339 timestamp
+///
+/// This is a shortcut for `format_rfc3339(SystemTime::now())`, see
+/// [`Rfc3339Clock`] for formatting many timestamps in a row.
+pub fn now_rfc3339() -> Rfc3339Timestamp {
+    format_rfc3339(SystemTime::now())
+}
+
+/// A clock that formats the current time as an RFC3339 timestamp
+///
+/// It is meant for loggers which print a timestamp on every line: the
+/// `2018-02-14T00:28:07` part is formatted once per second and reused, only
+/// the fractional seconds are written for every call.
+///
+/// As with [`format_rfc3339`], the value is always UTC. Times before the
+/// epoch or after year 9999 cause a panic.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::Rfc3339Clock;
+///
+/// let now = || UNIX_EPOCH + Duration::new(1_518_568_087, 123_456_789);
+/// let mut clock = Rfc3339Clock::with_clock(now);
+/// assert_eq!(clock.now_millis(), "2018-02-14T00:28:07.123Z");
+/// assert_eq!(clock.now_micros(), "2018-02-14T00:28:07.123456Z");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rfc3339Clock<C = SystemClock> {
+    clock: C,
+    secs: Option<u64>,
+    buf: [u8; 30],
+}
+
+impl Rfc3339Clock {
+    /// Creates a clock reading the system time
+    pub fn new() -> Rfc3339Clock {
+        Rfc3339Clock::with_clock(SystemClock)
+    }
+}
+
+impl Default for Rfc3339Clock {
+    fn default() -> Rfc3339Clock {
+        Rfc3339Clock::new()
+    }
+}
+
+impl<C: Clock> Rfc3339Clock<C> {
+    /// Creates a clock reading the time from `clock`
+    pub fn with_clock(clock: C) -> Rfc3339Clock<C> {
+        Rfc3339Clock {
+            clock,
+            secs: None,
+            buf: *b"0000-00-00T00:00:00.000000000Z",
+        }
+    }
+
+    /// Returns the current time like `2018-02-14T00:28:07Z`
+    pub fn now_seconds(&mut self) -> &str {
+        self.render(0)
+    }
+
+    /// Returns the current time like `2018-02-14T00:28:07.000Z`
+    pub fn now_millis(&mut self) -> &str {
+        self.render(3)
+    }
+
+    /// Returns the current time like `2018-02-14T00:28:07.000000Z`
+    pub fn now_micros(&mut self) -> &str {
+        self.render(6)
+    }
+
+    /// Returns the current time like `2018-02-14T00:28:07.000000000Z`
+    pub fn now_nanos(&mut self) -> &str {
+        self.render(9)
+    }
+
+    fn render(&mut self, digits: usize) -> &str {
+        let now = self.clock.now();
+        let dur = now
+            .duration_since(UNIX_EPOCH)
+            .expect("all times should be after the epoch");
+        if self.secs != Some(dur.as_secs()) {
+            let mut prefix = BufWriter {
+                buf: &mut self.buf[..PREFIX_LEN],
+                pos: 0,
+            };
+            // the `Z` doesn't fit into the slice and is written after the
+            // fraction instead
+            let _ = write!(prefix, "{}", format_rfc3339_seconds(now));
+            assert_eq!(prefix.pos, PREFIX_LEN, "timestamp is out of range");
+            self.secs = Some(dur.as_secs());
+        }
+        let end = if digits == 0 {
+            PREFIX_LEN
+        } else {
+            self.buf[PREFIX_LEN] = b'.';
+            let mut nanos = dur.subsec_nanos() / 10u32.pow(9 - digits as u32);
+            for idx in (PREFIX_LEN + 1..=PREFIX_LEN + digits).rev() {
+                self.buf[idx] = b'0' + (nanos % 10) as u8;
+                nanos /= 10;
+            }
+            PREFIX_LEN + 1 + digits
+        };
+        self.buf[end] = b'Z';
+        // we know our chars are all ascii
+        str::from_utf8(&self.buf[..=end]).expect("Conversion to utf8 failed")
+    }
+}
+
+/// A formatter of the current time shared between threads
+///
+/// Like [`Rfc3339Clock`] it renders a timestamp like
+/// `2018-02-14T00:28:07Z` only when the second ticks over, but it can be
+/// used through a shared reference, e.g. from a `static` or an `Arc` held
+/// by every logging thread. The result is copied out, so no allocation is
+/// made either.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::CachedFormatter;
+///
+/// let formatter = CachedFormatter::with_clock(|| UNIX_EPOCH + Duration::from_secs(1_518_568_087));
+/// let stamp = formatter.format_now();
+/// assert_eq!(&*stamp, "2018-02-14T00:28:07Z");
+/// assert_eq!(format!("[{}] started", stamp), "[2018-02-14T00:28:07Z] started");
+/// ```
+#[derive(Debug)]
+pub struct CachedFormatter<C = SystemClock> {
+    clock: Mutex<Rfc3339Clock<C>>,
+}
+
+/// A timestamp returned by [`CachedFormatter::format_now`]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CachedTimestamp([u8; PREFIX_LEN + 1]);
+
+impl CachedFormatter {
+    /// Creates a formatter reading the system time
+    pub fn new() -> CachedFormatter {
+        CachedFormatter::with_clock(SystemClock)
+    }
+}
+
+impl Default for CachedFormatter {
+    fn default() -> CachedFormatter {
+        CachedFormatter::new()
+    }
+}
+
+impl<C: Clock> CachedFormatter<C> {
+    /// Creates a formatter reading the time from `clock`
+    pub fn with_clock(clock: C) -> CachedFormatter<C> {
+        CachedFormatter {
+            clock: Mutex::new(Rfc3339Clock::with_clock(clock)),
+        }
+    }
+
+    /// Returns the current time like `2018-02-14T00:28:07Z`
+    pub fn format_now(&self) -> CachedTimestamp {
+        let mut clock = match self.clock.lock() {
+            Ok(clock) => clock,
+            // the cache is always valid, panic could only happen in `Clock`
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let mut buf = [0; PREFIX_LEN + 1];
+        buf.copy_from_slice(clock.now_seconds().as_bytes());
+        CachedTimestamp(buf)
+    }
+}
+
+impl CachedTimestamp {
+    /// Returns the timestamp as a string slice
+    pub fn as_str(&self) -> &str {
+        // we know our chars are all ascii
+        str::from_utf8(&self.0).expect("Conversion to utf8 failed")
+    }
+}
+
+impl Deref for CachedTimestamp {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for CachedTimestamp {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Display for CachedTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for CachedTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Writes into a fixed buffer, failing once it is full
+struct BufWriter<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+
+impl Write for BufWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.pos + s.len();
+        if end > self.buf.len() {
+            let rest = self.buf.len() - self.pos;
+            self.buf[self.pos..].copy_from_slice(&s.as_bytes()[..rest]);
+            self.pos = self.buf.len();
+            return Err(fmt::Error);
+        }
+        self.buf[self.pos..end].copy_from_slice(s.as_bytes());
+        self.pos = end;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::{now_rfc3339, CachedFormatter, Rfc3339Clock};
+    use crate::date::{format_rfc3339_micros, format_rfc3339_nanos, parse_rfc3339};
+
+    #[test]
+    fn fractions() {
+        let time = UNIX_EPOCH + Duration::new(1_518_568_087, 1_002_003);
+        let mut clock = Rfc3339Clock::with_clock(move || time);
+        assert_eq!(clock.now_seconds(), "2018-02-14T00:28:07Z");
+        assert_eq!(clock.now_millis(), "2018-02-14T00:28:07.001Z");
+        assert_eq!(clock.now_micros(), "2018-02-14T00:28:07.001002Z");
+        assert_eq!(clock.now_nanos(), "2018-02-14T00:28:07.001002003Z");
+        assert_eq!(clock.now_seconds(), "2018-02-14T00:28:07Z");
+    }
+
+    #[test]
+    fn second_ticks() {
+        let time = Cell::new(UNIX_EPOCH + Duration::new(1_518_568_087, 999_999_999));
+        let mut clock = Rfc3339Clock::with_clock(|| time.get());
+        assert_eq!(clock.now_nanos(), "2018-02-14T00:28:07.999999999Z");
+        time.set(time.get() + Duration::new(0, 1));
+        assert_eq!(clock.now_nanos(), "2018-02-14T00:28:08.000000000Z");
+        // going back in time re-renders the prefix too
+        time.set(UNIX_EPOCH);
+        assert_eq!(clock.now_millis(), "1970-01-01T00:00:00.000Z");
+        for secs in (0..253_402_300_800).step_by(7_654_321) {
+            let t = UNIX_EPOCH + Duration::new(secs, 123_456_789);
+            time.set(t);
+            assert_eq!(clock.now_micros(), format_rfc3339_micros(t).to_string());
+            assert_eq!(clock.now_nanos(), format_rfc3339_nanos(t).to_string());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "timestamp is out of range")]
+    fn out_of_range() {
+        let mut clock =
+            Rfc3339Clock::with_clock(|| UNIX_EPOCH + Duration::from_secs(253_402_300_800));
+        clock.now_seconds();
+    }
+
+    #[test]
+    fn system_clock() {
+        let before = SystemTime::now() - Duration::from_secs(1);
+        let now = parse_rfc3339(&now_rfc3339().to_string()).unwrap();
+        assert!(now >= before);
+        let now = parse_rfc3339(Rfc3339Clock::new().now_micros()).unwrap();
+        assert!(now >= before);
+    }
+
+    #[test]
+    fn cached_formatter() {
+        let time = Cell::new(UNIX_EPOCH + Duration::new(1_518_568_087, 999_000_000));
+        let formatter = CachedFormatter::with_clock(|| time.get());
+        let first = formatter.format_now();
+        assert_eq!(first.as_str(), "2018-02-14T00:28:07Z");
+        time.set(time.get() + Duration::from_millis(1));
+        let second = formatter.format_now();
+        assert_eq!(&*second, "2018-02-14T00:28:08Z");
+        assert_ne!(first, second);
+        assert_eq!(format!("{:?}", second), r#""2018-02-14T00:28:08Z""#);
+    }
+
+    #[test]
+    fn shared_formatter() {
+        let formatter = Arc::new(CachedFormatter::new());
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let formatter = formatter.clone();
+                thread::spawn(move || parse_rfc3339(&formatter.format_now()).unwrap())
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.join().unwrap() <= SystemTime::now());
+        }
+    }
+}
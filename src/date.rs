@@ -3,13 +3,15 @@ use std::fmt;
 use std::str;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use crate::civil::civil_from_days;
+
 #[cfg(all(
     target_pointer_width = "32",
     not(target_os = "windows"),
     not(all(target_arch = "wasm32", not(target_os = "emscripten")))
 ))]
-mod max {
-    pub(super) const SECONDS: u64 = ::std::i32::MAX as u64;
+pub(crate) mod max {
+    pub(crate) const SECONDS: u64 = ::std::i32::MAX as u64;
     #[allow(unused)]
     pub(super) const TIMESTAMP: &'static str = "2038-01-19T03:14:07Z";
 }
@@ -19,14 +21,15 @@ mod max {
     target_os = "windows",
     all(target_arch = "wasm32", not(target_os = "emscripten")),
 ))]
-mod max {
-    pub(super) const SECONDS: u64 = 253_402_300_800 - 1; // last second of year 9999
+pub(crate) mod max {
+    pub(crate) const SECONDS: u64 = 253_402_300_800 - 1; // last second of year 9999
     #[allow(unused)]
     pub(super) const TIMESTAMP: &str = "9999-12-31T23:59:59Z";
 }
 
 /// Error parsing datetime (timestamp)
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[non_exhaustive]
 pub enum Error {
     /// Numeric component is out of range
     OutOfRange,
@@ -34,6 +37,33 @@ pub enum Error {
     InvalidDigit,
     /// Other formatting errors
     InvalidFormat,
+    /// Time zone abbreviation other than `UTC`, `GMT` or `Z`
+    ///
+    /// Only [`parse_rfc3339_weak`] accepts abbreviations.
+    UnknownTimezone,
+}
+
+impl Error {
+    /// Returns the stable code of the error, like `E0101`
+    ///
+    /// Like the codes of duration errors in
+    /// [`DurationErrorKind::stable_code`](crate::DurationErrorKind::stable_code), these
+    /// never change and are never reused, unlike the messages.
+    ///
+    /// | Code    | Error                      |
+    /// |---------|----------------------------|
+    /// | `E0101` | [`Error::InvalidFormat`]   |
+    /// | `E0102` | [`Error::InvalidDigit`]    |
+    /// | `E0103` | [`Error::OutOfRange`]      |
+    /// | `E0104` | [`Error::UnknownTimezone`] |
+    pub fn stable_code(&self) -> &'static str {
+        match self {
+            Error::InvalidFormat => "E0101",
+            Error::InvalidDigit => "E0102",
+            Error::OutOfRange => "E0103",
+            Error::UnknownTimezone => "E0104",
+        }
+    }
 }
 
 impl StdError for Error {}
@@ -44,11 +74,12 @@ impl fmt::Display for Error {
             Error::OutOfRange => write!(f, "numeric component is out of range"),
             Error::InvalidDigit => write!(f, "bad character where digit is expected"),
             Error::InvalidFormat => write!(f, "timestamp format is invalid"),
+            Error::UnknownTimezone => write!(f, "unknown time zone, only UTC is supported"),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Precision {
     Smart,
     Seconds,
@@ -58,8 +89,29 @@ enum Precision {
 }
 
 /// A wrapper type that allows you to Display a SystemTime
-#[derive(Debug, Clone)]
-pub struct Rfc3339Timestamp(SystemTime, Precision);
+///
+/// The alternate flag writes all nine fractional digits, whatever
+/// precision was chosen:
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::format_rfc3339;
+///
+/// let time = UNIX_EPOCH + Duration::new(1_518_568_087, 250_000_000);
+/// assert_eq!(format!("{}", format_rfc3339(time)), "2018-02-14T00:28:07.250000000Z");
+/// assert_eq!(format!("{:#}", format_rfc3339(UNIX_EPOCH)), "1970-01-01T00:00:00.000000000Z");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rfc3339Timestamp(SystemTime, Precision, Layout, u8, i32);
+
+/// Separators of the date and time in [`Rfc3339Timestamp`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Layout {
+    /// `2018-02-14T00:28:07Z`
+    Extended,
+    /// `20180214T002807Z`
+    Basic,
+}
 
 #[inline]
 /// Converts two digits given in ASCII to its proper decimal representation.
@@ -78,6 +130,8 @@ fn two_digits(b1: u8, b2: u8) -> Result<u64, Error> {
 ///
 /// Supported features:
 /// - Any precision of fractional digits `2018-02-14T00:28:07.133Z`.
+/// - A comma before the fractional digits `2018-02-14T00:28:07,133Z`, as
+///   ISO 8601 allows.
 /// - The UTC timezone can be indicated with `Z` or `+00:00`.
 ///
 /// Unsupported feature: localized timestamps. Only UTC is supported.
@@ -89,16 +143,20 @@ pub fn parse_rfc3339(s: &str) -> Result<SystemTime, Error> {
     if b[10] != b'T' || (b.last() != Some(&b'Z') && !s.ends_with("+00:00")) {
         return Err(Error::InvalidFormat);
     }
-    parse_rfc3339_weak(s)
+    parse_utc(s)
 }
 
 /// Parse RFC3339-like timestamp `2018-02-14 00:28:07`
 ///
 /// Supported features:
 ///
-/// 1. Any precision of fractional digits `2018-02-14 00:28:07.133`.
+/// 1. Any precision of fractional digits `2018-02-14 00:28:07.133`, also
+///    after a comma `2018-02-14 00:28:07,133`.
 /// 2. Supports timestamp with or without either of `T`, `Z` or `+00:00`.
-/// 3. Anything valid for [`parse_rfc3339`](parse_rfc3339) is valid for this function
+/// 3. A time zone abbreviation after a space, `UTC`, `GMT` or `Z`, like
+///    `2018-02-14 00:28:07 UTC`, instead of `Z` or `+00:00`. Other
+///    abbreviations are rejected with [`Error::UnknownTimezone`].
+/// 4. Anything valid for [`parse_rfc3339`](parse_rfc3339) is valid for this function
 ///
 /// Unsupported feature: localized timestamps. Only UTC is supported, even if
 /// `Z` is not specified.
@@ -106,6 +164,24 @@ pub fn parse_rfc3339(s: &str) -> Result<SystemTime, Error> {
 /// This function is intended to use for parsing human input. Whereas
 /// `parse_rfc3339` is for strings generated programmatically.
 pub fn parse_rfc3339_weak(s: &str) -> Result<SystemTime, Error> {
+    match s.rfind(' ') {
+        Some(idx) if idx >= 19 => match &s[idx + 1..] {
+            // only one abbreviation, and not after an offset
+            "UTC" | "GMT" | "Z" if !s[..idx].ends_with('Z') && !s[..idx].ends_with("+00:00") => {
+                parse_utc(&s[..idx])
+            }
+            "UTC" | "GMT" | "Z" => Err(Error::InvalidFormat),
+            zone if !zone.is_empty() && zone.bytes().all(|c| c.is_ascii_alphabetic()) => {
+                Err(Error::UnknownTimezone)
+            }
+            _ => Err(Error::InvalidFormat),
+        },
+        _ => parse_utc(s),
+    }
+}
+
+/// Parses the timestamp of `parse_rfc3339_weak` without an abbreviation
+fn parse_utc(s: &str) -> Result<SystemTime, Error> {
     if s.len() < "2018-02-14T00:28:07".len() {
         return Err(Error::InvalidFormat);
     }
@@ -166,7 +242,7 @@ pub fn parse_rfc3339_weak(s: &str) -> Result<SystemTime, Error> {
 
     let mut nanos = 0;
     let mut mult = 100_000_000;
-    if b.get(19) == Some(&b'.') {
+    if matches!(b.get(19), Some(b'.' | b',')) {
         for idx in 20..b.len() {
             if b[idx] == b'Z' {
                 if idx == b.len() - 1 {
@@ -175,7 +251,7 @@ pub fn parse_rfc3339_weak(s: &str) -> Result<SystemTime, Error> {
                 return Err(Error::InvalidDigit);
             } else if b[idx] == b'+' {
                 // start of "+00:00", which must be at the end
-                if idx == b.len() - 6 {
+                if &b[idx..] == b"+00:00" {
                     break;
                 }
                 return Err(Error::InvalidDigit);
@@ -184,7 +260,7 @@ pub fn parse_rfc3339_weak(s: &str) -> Result<SystemTime, Error> {
             nanos += mult * (b[idx] as char).to_digit(10).ok_or(Error::InvalidDigit)?;
             mult /= 10;
         }
-    } else if b.len() != 19 && (b.len() > 25 || (b[19] != b'Z' && (&b[19..] != b"+00:00"))) {
+    } else if b.len() != 19 && &b[19..] != b"Z" && &b[19..] != b"+00:00" {
         return Err(Error::InvalidFormat);
     }
 
@@ -208,7 +284,7 @@ fn is_leap_year(y: u64) -> bool {
 ///
 /// The value is always UTC and ignores system timezone.
 pub fn format_rfc3339(system_time: SystemTime) -> Rfc3339Timestamp {
-    Rfc3339Timestamp(system_time, Precision::Smart)
+    Rfc3339Timestamp(system_time, Precision::Smart, Layout::Extended, b'.', 0)
 }
 
 /// Format an RFC3339 timestamp `2018-02-14T00:28:07Z`
@@ -217,7 +293,7 @@ pub fn format_rfc3339(system_time: SystemTime) -> Rfc3339Timestamp {
 ///
 /// The value is always UTC and ignores system timezone.
 pub fn format_rfc3339_seconds(system_time: SystemTime) -> Rfc3339Timestamp {
-    Rfc3339Timestamp(system_time, Precision::Seconds)
+    Rfc3339Timestamp(system_time, Precision::Seconds, Layout::Extended, b'.', 0)
 }
 
 /// Format an RFC3339 timestamp `2018-02-14T00:28:07.000Z`
@@ -226,7 +302,7 @@ pub fn format_rfc3339_seconds(system_time: SystemTime) -> Rfc3339Timestamp {
 ///
 /// The value is always UTC and ignores system timezone.
 pub fn format_rfc3339_millis(system_time: SystemTime) -> Rfc3339Timestamp {
-    Rfc3339Timestamp(system_time, Precision::Millis)
+    Rfc3339Timestamp(system_time, Precision::Millis, Layout::Extended, b'.', 0)
 }
 
 /// Format an RFC3339 timestamp `2018-02-14T00:28:07.000000Z`
@@ -235,7 +311,7 @@ pub fn format_rfc3339_millis(system_time: SystemTime) -> Rfc3339Timestamp {
 ///
 /// The value is always UTC and ignores system timezone.
 pub fn format_rfc3339_micros(system_time: SystemTime) -> Rfc3339Timestamp {
-    Rfc3339Timestamp(system_time, Precision::Micros)
+    Rfc3339Timestamp(system_time, Precision::Micros, Layout::Extended, b'.', 0)
 }
 
 /// Format an RFC3339 timestamp `2018-02-14T00:28:07.000000000Z`
@@ -244,7 +320,72 @@ pub fn format_rfc3339_micros(system_time: SystemTime) -> Rfc3339Timestamp {
 ///
 /// The value is always UTC and ignores system timezone.
 pub fn format_rfc3339_nanos(system_time: SystemTime) -> Rfc3339Timestamp {
-    Rfc3339Timestamp(system_time, Precision::Nanos)
+    Rfc3339Timestamp(system_time, Precision::Nanos, Layout::Extended, b'.', 0)
+}
+
+/// Format an RFC3339 timestamp in the basic format `20180214T002807Z`
+///
+/// The basic format of ISO 8601 has no `-` and `:` separators, so the
+/// timestamp can be embedded in filenames and S3 keys. The precision is
+/// the same as in [`format_rfc3339`], `{:#}` writes all nine fractional
+/// digits. [`parse_rfc3339_basic`] parses it back.
+///
+/// The value is always UTC and ignores system timezone.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::format_rfc3339_basic;
+///
+/// let time = UNIX_EPOCH + Duration::new(1_717_249_507, 0);
+/// assert_eq!(format_rfc3339_basic(time).to_string(), "20240601T134507Z");
+/// let time = time + Duration::from_millis(250);
+/// assert_eq!(format_rfc3339_basic(time).to_string(), "20240601T134507.250000000Z");
+/// ```
+pub fn format_rfc3339_basic(system_time: SystemTime) -> Rfc3339Timestamp {
+    Rfc3339Timestamp(system_time, Precision::Smart, Layout::Basic, b'.', 0)
+}
+
+/// Parse an RFC3339 timestamp in the basic format `20180214T002807Z`
+///
+/// This is the output of [`format_rfc3339_basic`]. Like in
+/// [`parse_rfc3339`], any number of fractional digits is accepted, and the
+/// UTC timezone can be indicated with `Z` or `+0000`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::parse_rfc3339_basic;
+///
+/// let time = UNIX_EPOCH + Duration::new(1_717_249_507, 0);
+/// assert_eq!(parse_rfc3339_basic("20240601T134507Z"), Ok(time));
+/// assert!(parse_rfc3339_basic("2024-06-01T13:45:07Z").is_err());
+/// ```
+pub fn parse_rfc3339_basic(s: &str) -> Result<SystemTime, Error> {
+    let b = s.as_bytes();
+    if b.len() < "20180214T002807Z".len() || b[8] != b'T' {
+        return Err(Error::InvalidFormat);
+    }
+    if !b[..8].iter().chain(&b[9..15]).all(u8::is_ascii_digit) {
+        return Err(Error::InvalidDigit);
+    }
+    let rest = match s[15..].strip_suffix("+0000") {
+        Some(fraction) => format!("{}+00:00", fraction),
+        None => s[15..].to_owned(),
+    };
+    let extended = format!(
+        "{}-{}-{}T{}:{}:{}{}",
+        &s[..4],
+        &s[4..6],
+        &s[6..8],
+        &s[9..11],
+        &s[11..13],
+        &s[13..15],
+        rest
+    );
+    parse_rfc3339(&extended)
 }
 
 impl Rfc3339Timestamp {
@@ -252,6 +393,47 @@ impl Rfc3339Timestamp {
     pub fn get_ref(&self) -> &SystemTime {
         &self.0
     }
+
+    /// Writes a comma before the fractional digits instead of a dot
+    ///
+    /// ISO 8601 prefers the comma, and it's common in European tooling.
+    /// The parsers of this crate accept both.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use humantime::format_rfc3339_millis;
+    ///
+    /// let time = UNIX_EPOCH + Duration::new(1_518_568_087, 133_000_000);
+    /// let text = format_rfc3339_millis(time).with_decimal_comma().to_string();
+    /// assert_eq!(text, "2018-02-14T00:28:07,133Z");
+    /// assert_eq!(humantime::parse_rfc3339(&text), Ok(time));
+    /// ```
+    pub fn with_decimal_comma(self) -> Rfc3339Timestamp {
+        Rfc3339Timestamp(self.0, self.1, self.2, b',', self.4)
+    }
+
+    /// Writes the local time at `offset` seconds east of UTC
+    ///
+    /// The time is followed by the offset, like `2018-02-14T01:28:07+01:00`.
+    /// The offset is truncated to whole minutes, as RFC 3339 has no seconds
+    /// in offsets, and must be less than a day. A zero offset is written as
+    /// `Z`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use humantime::format_rfc3339;
+    ///
+    /// let time = UNIX_EPOCH + Duration::from_secs(1_518_568_087);
+    /// let text = format_rfc3339(time).with_offset(-5 * 3600).to_string();
+    /// assert_eq!(text, "2018-02-13T19:28:07-05:00");
+    /// ```
+    pub fn with_offset(self, offset: i32) -> Rfc3339Timestamp {
+        Rfc3339Timestamp(self.0, self.1, self.2, self.3, offset / 60 * 60)
+    }
 }
 
 impl fmt::Display for Rfc3339Timestamp {
@@ -262,71 +444,24 @@ impl fmt::Display for Rfc3339Timestamp {
             .0
             .duration_since(UNIX_EPOCH)
             .expect("all times should be after the epoch");
-        let secs_since_epoch = dur.as_secs();
         let nanos = dur.subsec_nanos();
-
-        if secs_since_epoch >= 253_402_300_800 {
-            // year 9999
+        if self.4.unsigned_abs() >= 86400 {
             return Err(fmt::Error);
         }
+        let local_secs = dur.as_secs() as i64 + i64::from(self.4);
 
-        /* 2000-03-01 (mod 400 year, immediately after feb29 */
-        const LEAPOCH: i64 = 11017;
-        const DAYS_PER_400Y: i64 = 365 * 400 + 97;
-        const DAYS_PER_100Y: i64 = 365 * 100 + 24;
-        const DAYS_PER_4Y: i64 = 365 * 4 + 1;
-
-        let days = (secs_since_epoch / 86400) as i64 - LEAPOCH;
-        let secs_of_day = secs_since_epoch % 86400;
-
-        let mut qc_cycles = days / DAYS_PER_400Y;
-        let mut remdays = days % DAYS_PER_400Y;
-
-        if remdays < 0 {
-            remdays += DAYS_PER_400Y;
-            qc_cycles -= 1;
-        }
-
-        let mut c_cycles = remdays / DAYS_PER_100Y;
-        if c_cycles == 4 {
-            c_cycles -= 1;
-        }
-        remdays -= c_cycles * DAYS_PER_100Y;
-
-        let mut q_cycles = remdays / DAYS_PER_4Y;
-        if q_cycles == 25 {
-            q_cycles -= 1;
-        }
-        remdays -= q_cycles * DAYS_PER_4Y;
-
-        let mut remyears = remdays / 365;
-        if remyears == 4 {
-            remyears -= 1;
+        if local_secs >= 253_402_300_800 {
+            // year 9999
+            return Err(fmt::Error);
         }
-        remdays -= remyears * 365;
 
-        let mut year = 2000 + remyears + 4 * q_cycles + 100 * c_cycles + 400 * qc_cycles;
-
-        let months = [31, 30, 31, 30, 31, 31, 30, 31, 30, 31, 31, 29];
-        let mut mon = 0;
-        for mon_len in months.iter() {
-            mon += 1;
-            if remdays < *mon_len {
-                break;
-            }
-            remdays -= *mon_len;
-        }
-        let mday = remdays + 1;
-        let mon = if mon + 2 > 12 {
-            year += 1;
-            mon - 10
-        } else {
-            mon + 2
-        };
+        let secs_of_day = local_secs.rem_euclid(86400);
+        let (year, mon, mday) = civil_from_days(local_secs.div_euclid(86400));
 
         const BUF_INIT: [u8; 30] = *b"0000-00-00T00:00:00.000000000Z";
 
         let mut buf: [u8; 30] = BUF_INIT;
+        buf[19] = self.3;
         buf[0] = b'0' + (year / 1000) as u8;
         buf[1] = b'0' + (year / 100 % 10) as u8;
         buf[2] = b'0' + (year / 10 % 10) as u8;
@@ -342,16 +477,18 @@ impl fmt::Display for Rfc3339Timestamp {
         buf[17] = b'0' + (secs_of_day / 10 % 6) as u8;
         buf[18] = b'0' + (secs_of_day % 10) as u8;
 
-        let offset = if self.1 == Seconds || nanos == 0 && self.1 == Smart {
+        // `{:#}` always writes all the digits
+        let precision = if f.alternate() { &Nanos } else { &self.1 };
+        let offset = if *precision == Seconds || nanos == 0 && *precision == Smart {
             buf[19] = b'Z';
             19
-        } else if self.1 == Millis {
+        } else if *precision == Millis {
             buf[20] = b'0' + (nanos / 100_000_000) as u8;
             buf[21] = b'0' + (nanos / 10_000_000 % 10) as u8;
             buf[22] = b'0' + (nanos / 1_000_000 % 10) as u8;
             buf[23] = b'Z';
             23
-        } else if self.1 == Micros {
+        } else if *precision == Micros {
             buf[20] = b'0' + (nanos / 100_000_000) as u8;
             buf[21] = b'0' + (nanos / 10_000_000 % 10) as u8;
             buf[22] = b'0' + (nanos / 1_000_000 % 10) as u8;
@@ -375,7 +512,35 @@ impl fmt::Display for Rfc3339Timestamp {
         };
 
         // we know our chars are all ascii
-        f.write_str(str::from_utf8(&buf[..=offset]).expect("Conversion to utf8 failed"))
+        let text = str::from_utf8(&buf[..=offset]).expect("Conversion to utf8 failed");
+        // the offset replaces `Z`
+        let text = if self.4 == 0 {
+            text
+        } else {
+            &text[..text.len() - 1]
+        };
+        match self.2 {
+            Layout::Extended => f.write_str(text)?,
+            Layout::Basic => {
+                for &(start, end) in &[(0, 4), (5, 7), (8, 13), (14, 16), (17, text.len())] {
+                    f.write_str(&text[start..end])?;
+                }
+            }
+        }
+        if self.4 != 0 {
+            let sign = if self.4 < 0 { '-' } else { '+' };
+            let minutes = self.4.unsigned_abs() / 60;
+            let separator = if self.2 == Layout::Basic { "" } else { ":" };
+            write!(
+                f,
+                "{}{:02}{}{:02}",
+                sign,
+                minutes / 60,
+                separator,
+                minutes % 60
+            )?;
+        }
+        Ok(())
     }
 }
 
@@ -388,10 +553,12 @@ mod test {
     use time::format_description::well_known::Rfc3339;
     use time::UtcDateTime;
 
-    use super::format_rfc3339_nanos;
-    use super::max;
     use super::{format_rfc3339, parse_rfc3339, parse_rfc3339_weak};
+    use super::{format_rfc3339_basic, parse_rfc3339_basic};
     use super::{format_rfc3339_micros, format_rfc3339_millis};
+    use super::{format_rfc3339_nanos, format_rfc3339_seconds};
+    use super::{max, Error};
+    use crate::test_support::{random_timestamp, random_timestamp_seconds};
 
     fn from_sec(sec: u64) -> (String, SystemTime) {
         let s = UtcDateTime::from_unix_timestamp(sec as i64)
@@ -576,8 +743,10 @@ mod test {
 
     #[test]
     fn random_wide_range() {
+        let mut rng = rand::rng();
         for _ in 0..100_000 {
-            let sec = rand::rng().random_range(0..max::SECONDS);
+            let time = random_timestamp_seconds(&mut rng);
+            let sec = time.duration_since(UNIX_EPOCH).unwrap().as_secs();
             let (s, time) = from_sec(sec);
             assert_eq!(parse_rfc3339(&s).unwrap(), time);
             assert_eq!(format_rfc3339(time).to_string(), s);
@@ -739,5 +908,195 @@ mod test {
         parse_rfc3339("1970-01-01 00:00:00.0000123+02:00").unwrap_err();
         parse_rfc3339("1970-01-01 00:00:00.0000123+00").unwrap_err();
         parse_rfc3339("1970-01-01 00:00:00.0000123+").unwrap_err();
+        parse_rfc3339_weak("1970-01-01 00:00:00.1+05:30").unwrap_err();
+        parse_rfc3339_weak("1970-01-01 00:00:00Zjunk").unwrap_err();
+        parse_rfc3339_weak("1970-01-01 00:00:00+00:00Z").unwrap_err();
+    }
+
+    #[test]
+    fn alternate() {
+        let time = UNIX_EPOCH + Duration::new(1_518_568_087, 5_000);
+        assert_eq!(
+            format!("{:#}", format_rfc3339_seconds(time)),
+            "2018-02-14T00:28:07.000005000Z"
+        );
+        assert_eq!(
+            format!("{:#}", crate::Timestamp::from(time)),
+            "2018-02-14T00:28:07.000005000Z"
+        );
+        assert_eq!(
+            format!("{}", format_rfc3339_millis(time)),
+            "2018-02-14T00:28:07.000Z"
+        );
+    }
+
+    #[test]
+    fn codes() {
+        let code = |s| parse_rfc3339_weak(s).unwrap_err().stable_code();
+        assert_eq!(code("2018-02-14"), "E0101");
+        assert_eq!(code("2018-0x-14 00:28:07"), "E0102");
+        assert_eq!(code("2018-13-14 00:28:07"), "E0103");
+        assert_eq!(code("2018-02-14 00:28:07 CET"), "E0104");
+    }
+
+    #[test]
+    fn timezone_abbreviations() {
+        let time = UNIX_EPOCH + Duration::new(1_518_568_087, 133_000_000);
+        assert_eq!(parse_rfc3339_weak("2018-02-14 00:28:07.133 UTC"), Ok(time));
+        assert_eq!(parse_rfc3339_weak("2018-02-14T00:28:07.133 GMT"), Ok(time));
+        assert_eq!(parse_rfc3339_weak("2018-02-14 00:28:07.133 Z"), Ok(time));
+        assert_eq!(
+            parse_rfc3339_weak("2018-02-14 00:28:07 CET"),
+            Err(Error::UnknownTimezone)
+        );
+        assert_eq!(
+            parse_rfc3339_weak("2018-02-14 00:28:07 utc"),
+            Err(Error::UnknownTimezone)
+        );
+        assert_eq!(
+            parse_rfc3339_weak("2018-02-14 00:28:07 +01"),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            parse_rfc3339_weak("2018-02-14 00:28:07 "),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            parse_rfc3339("2018-02-14T00:28:07 UTC"),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            parse_rfc3339("2018-02-14T00:28:07 Z"),
+            Err(Error::InvalidFormat)
+        );
+        for input in &[
+            "2018-02-14 00:28:07 UTC UTC",
+            "2018-02-14 00:28:07 GMT Z",
+            "2018-02-14 00:28:07Z GMT",
+            "2018-02-14 00:28:07+00:00 UTC",
+            "2018-02-14 00:28:07.133Z UTC",
+        ] {
+            assert_eq!(
+                parse_rfc3339_weak(input),
+                Err(Error::InvalidFormat),
+                "{}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn offsets() {
+        use std::fmt::Write;
+
+        let time = UNIX_EPOCH + Duration::new(1_518_568_087, 5_000_000);
+        assert_eq!(
+            format_rfc3339(time).with_offset(3600).to_string(),
+            "2018-02-14T01:28:07.005000000+01:00"
+        );
+        assert_eq!(
+            format_rfc3339_seconds(time).with_offset(-9000).to_string(),
+            "2018-02-13T21:58:07-02:30"
+        );
+        assert_eq!(
+            format_rfc3339_basic(time).with_offset(20700).to_string(),
+            "20180214T061307.005000000+0545"
+        );
+        // seconds of the offset are dropped
+        assert_eq!(
+            format_rfc3339_seconds(time).with_offset(3659).to_string(),
+            "2018-02-14T01:28:07+01:00"
+        );
+        assert_eq!(
+            format_rfc3339(time).with_offset(0).to_string(),
+            format_rfc3339(time).to_string()
+        );
+        assert_eq!(
+            format_rfc3339(UNIX_EPOCH).with_offset(-3600).to_string(),
+            "1969-12-31T23:00:00-01:00"
+        );
+        let last = UNIX_EPOCH + Duration::from_secs(253_402_300_799);
+        let mut out = String::new();
+        assert!(write!(out, "{}", format_rfc3339(last).with_offset(3600)).is_err());
+        assert!(write!(out, "{}", format_rfc3339(time).with_offset(86400)).is_err());
+    }
+
+    #[test]
+    fn decimal_comma() {
+        let time = UNIX_EPOCH + Duration::new(1_518_568_087, 133_000_000);
+        assert_eq!(parse_rfc3339("2018-02-14T00:28:07,133Z"), Ok(time));
+        assert_eq!(parse_rfc3339("2018-02-14T00:28:07,133+00:00"), Ok(time));
+        assert_eq!(parse_rfc3339_weak("2018-02-14 00:28:07,133"), Ok(time));
+        assert_eq!(parse_rfc3339_basic("20180214T002807,133Z"), Ok(time));
+        assert!(parse_rfc3339("2018-02-14T00:28:07,13,3Z").is_err());
+        assert_eq!(
+            format_rfc3339(time).with_decimal_comma().to_string(),
+            "2018-02-14T00:28:07,133000000Z"
+        );
+        assert_eq!(
+            format_rfc3339_basic(time).with_decimal_comma().to_string(),
+            "20180214T002807,133000000Z"
+        );
+        assert_eq!(
+            format_rfc3339_seconds(time)
+                .with_decimal_comma()
+                .to_string(),
+            "2018-02-14T00:28:07Z"
+        );
+        assert_eq!(
+            format!("{:#}", format_rfc3339(UNIX_EPOCH).with_decimal_comma()),
+            "1970-01-01T00:00:00,000000000Z"
+        );
+    }
+
+    #[test]
+    fn basic() {
+        let time = UNIX_EPOCH + Duration::new(1_518_568_087, 5_000);
+        assert_eq!(
+            format_rfc3339_basic(time).to_string(),
+            "20180214T002807.000005000Z"
+        );
+        assert_eq!(
+            format!("{:#}", format_rfc3339_basic(UNIX_EPOCH)),
+            "19700101T000000.000000000Z"
+        );
+        assert_eq!(parse_rfc3339_basic("20180214T002807.000005Z"), Ok(time));
+        assert_eq!(parse_rfc3339_basic("20180214T002807.000005+0000"), Ok(time));
+        assert_eq!(parse_rfc3339_basic("19700101T000000+0000"), Ok(UNIX_EPOCH));
+        let mut rng = rand::rng();
+        for _ in 0..10000 {
+            let time = random_timestamp(&mut rng);
+            let text = format_rfc3339_basic(time).to_string();
+            assert!(!text.contains(['-', ':']), "{}", text);
+            assert_eq!(parse_rfc3339_basic(&text), Ok(time));
+        }
+    }
+
+    #[test]
+    fn basic_errors() {
+        use super::Error;
+
+        assert_eq!(parse_rfc3339_basic(""), Err(Error::InvalidFormat));
+        assert_eq!(
+            parse_rfc3339_basic("20180214 002807Z"),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            parse_rfc3339_basic("2018021xT002807Z"),
+            Err(Error::InvalidDigit)
+        );
+        assert_eq!(
+            parse_rfc3339_basic("20180214T0028µ7Z"),
+            Err(Error::InvalidDigit)
+        );
+        assert_eq!(
+            parse_rfc3339_basic("20180214T002807"),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            parse_rfc3339_basic("20181314T002807Z"),
+            Err(Error::OutOfRange)
+        );
+        assert!(parse_rfc3339_basic("20180214T002807+0100").is_err());
     }
 }
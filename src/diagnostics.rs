@@ -0,0 +1,168 @@
+use std::fmt::Display;
+use std::ops::Range;
+
+use miette::{Diagnostic, LabeledSpan};
+
+use crate::date::Error as TimestampError;
+use crate::duration::{Error, ErrorKind};
+use crate::grammar::grammar;
+
+impl Error {
+    /// Byte range of the input the error points at, if any
+    fn input_span(&self, input: &str) -> Option<Range<usize>> {
+        match *self {
+            Error::InvalidCharacter(pos)
+            | Error::NumberExpected(pos)
+            | Error::WhitespaceExpected(pos) => {
+                let len = input.get(pos..)?.chars().next().map_or(0, char::len_utf8);
+                Some(pos..pos + len)
+            }
+            _ => self.span(),
+        }
+    }
+
+    fn help_text(&self) -> Option<String> {
+        match self {
+            Error::UnknownUnit { value, .. } if self.kind() == ErrorKind::MissingUnit => {
+                Some(format!("add a unit, like {}s", value))
+            }
+            Error::UnknownUnit { unit, .. } => grammar()
+                .suggest_unit(unit)
+                .map(|alias| format!("did you mean {:?}?", alias)),
+            Error::WhitespaceExpected(_) => Some("separate the components, like 1h 30m".into()),
+            Error::MissingNumber { unit, .. } => Some(format!("add a number, like 1{}", unit)),
+            _ => None,
+        }
+    }
+
+    /// Renders the error with the `input` it was returned for
+    ///
+    /// The input is printed with carets under the offending span, and a
+    /// suggestion for misspelled units. Enabled by the `diagnostics`
+    /// feature.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let err = humantime::parse_duration("5 hrz").unwrap_err();
+    /// assert_eq!(err.render("5 hrz"), "\
+    /// error: unknown time unit \"hrz\", supported units: ns, us/µs, ms, sec, \
+    /// min, hours, days, weeks, months, years (and few variations)
+    ///   |
+    ///   | 5 hrz
+    ///   |   ^^^
+    ///   = help: did you mean \"hr\"?
+    /// ");
+    /// ```
+    pub fn render(&self, input: &str) -> String {
+        let mut out = format!("error: {}\n  |\n  | {}\n", self, input);
+        if let Some(span) = self.input_span(input) {
+            let indent = input.get(..span.start).unwrap_or(input).chars().count();
+            let width = input.get(span).map_or(0, |s| s.chars().count()).max(1);
+            out.push_str(&format!(
+                "  | {}{}\n",
+                " ".repeat(indent),
+                "^".repeat(width)
+            ));
+        }
+        if let Some(help) = self.help_text() {
+            out.push_str(&format!("  = help: {}\n", help));
+        }
+        out
+    }
+}
+
+impl Diagnostic for Error {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let code = match self.kind() {
+            ErrorKind::InvalidCharacter => "humantime::invalid_character",
+            ErrorKind::NumberExpected => "humantime::number_expected",
+            ErrorKind::WhitespaceExpected => "humantime::whitespace_expected",
+            ErrorKind::MissingNumber => "humantime::missing_number",
+            ErrorKind::MissingUnit => "humantime::missing_unit",
+            ErrorKind::UnknownUnit => "humantime::unknown_unit",
+            ErrorKind::NumberOverflow => "humantime::number_overflow",
+            ErrorKind::Empty => "humantime::empty",
+        };
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        self.help_text()
+            .map(|help| Box::new(help) as Box<dyn Display>)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let span = self.span()?;
+        let label = match self.kind() {
+            ErrorKind::InvalidCharacter => "invalid character",
+            ErrorKind::NumberExpected => "expected a number",
+            ErrorKind::WhitespaceExpected => "expected whitespace before this",
+            ErrorKind::MissingNumber => "number expected before this unit",
+            ErrorKind::MissingUnit => "unit expected here",
+            _ => "unknown unit",
+        };
+        let label = LabeledSpan::at(span, label);
+        Some(Box::new(std::iter::once(label)))
+    }
+}
+
+impl Diagnostic for TimestampError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let code = match self {
+            TimestampError::OutOfRange => "humantime::out_of_range",
+            TimestampError::InvalidDigit => "humantime::invalid_digit",
+            TimestampError::InvalidFormat => "humantime::invalid_format",
+            TimestampError::UnknownTimezone => "humantime::unknown_timezone",
+        };
+        Some(Box::new(code))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new("expected a timestamp like 2018-02-14T00:28:07Z"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use miette::Diagnostic;
+
+    use crate::date::parse_rfc3339;
+    use crate::duration::parse_duration;
+
+    fn render(input: &str) -> String {
+        parse_duration(input).unwrap_err().render(input)
+    }
+
+    #[test]
+    fn carets() {
+        assert!(render("1h!").ends_with("  | 1h!\n  |   ^\n"));
+        assert!(render("µ5s").ends_with("  | µ5s\n  | ^\n"));
+        assert_eq!(
+            render("12"),
+            "error: time unit needed, for example 12sec or 12ms\n  |\n  | 12\n  \
+            |   ^\n  = help: add a unit, like 12s\n"
+        );
+        assert!(render("3 fortnights").ends_with("  |   ^^^^^^^^^^\n"));
+        assert_eq!(render(""), "error: value was empty\n  |\n  | \n");
+    }
+
+    #[test]
+    fn diagnostic() {
+        let err = parse_duration("5 minz").unwrap_err();
+        assert_eq!(err.code().unwrap().to_string(), "humantime::unknown_unit");
+        assert_eq!(err.help().unwrap().to_string(), "did you mean \"min\"?");
+        let labels: Vec<_> = err.labels().unwrap().collect();
+        assert_eq!(labels.len(), 1);
+        assert_eq!((labels[0].offset(), labels[0].len()), (2, 4));
+
+        let err = parse_duration("1h 2").unwrap_err();
+        assert_eq!(err.code().unwrap().to_string(), "humantime::missing_unit");
+        let err = parse_duration("100000000000000000000s").unwrap_err();
+        assert!(err.labels().is_none());
+
+        let err = parse_rfc3339("2018-02-14").unwrap_err();
+        assert_eq!(err.code().unwrap().to_string(), "humantime::invalid_format");
+        assert_eq!(err.stable_code(), "E0101");
+    }
+}
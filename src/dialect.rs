@@ -0,0 +1,705 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::Duration;
+
+use crate::duration::{format_duration_with, FormattedDuration, Style};
+
+/// Error parsing a duration written in another tool's syntax
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// Character which can't appear in the duration
+    ///
+    /// The field is a byte offset of the character in the string.
+    InvalidCharacter(usize),
+    /// Non-numeric value where number is expected
+    ///
+    /// The field is a byte offset of the character in the string.
+    NumberExpected(usize),
+    /// Unit is missing or is not supported by the syntax
+    UnknownUnit {
+        /// Start of the invalid unit inside the original string
+        start: usize,
+        /// End of the invalid unit inside the original string
+        end: usize,
+        /// The unit verbatim
+        unit: String,
+    },
+    /// Unit is repeated or is not in order from largest to smallest
+    UnitOrder {
+        /// Start of the unit inside the original string
+        start: usize,
+        /// End of the unit inside the original string
+        end: usize,
+    },
+    /// The value exceeds the range allowed by the syntax
+    NumberOverflow,
+    /// The value was an empty string
+    Empty,
+}
+
+impl Error {
+    /// Moves the offsets of the error by `off`
+    ///
+    /// For errors from parsing a part of the input starting at `off`.
+    pub(crate) fn offset_by(self, off: usize) -> Error {
+        match self {
+            Error::InvalidCharacter(pos) => Error::InvalidCharacter(pos + off),
+            Error::NumberExpected(pos) => Error::NumberExpected(pos + off),
+            Error::UnknownUnit { start, end, unit } => Error::UnknownUnit {
+                start: start + off,
+                end: end + off,
+                unit,
+            },
+            Error::UnitOrder { start, end } => Error::UnitOrder {
+                start: start + off,
+                end: end + off,
+            },
+            Error::NumberOverflow => Error::NumberOverflow,
+            Error::Empty => Error::Empty,
+        }
+    }
+}
+
+impl StdError for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidCharacter(offset) => write!(f, "invalid character at {}", offset),
+            Error::NumberExpected(offset) => write!(f, "expected number at {}", offset),
+            Error::UnknownUnit { unit, start, .. } if unit.is_empty() => {
+                write!(f, "time unit needed at {}", start)
+            }
+            Error::UnknownUnit { unit, .. } => {
+                write!(f, "time unit {:?} is not supported", unit)
+            }
+            Error::UnitOrder { start, .. } => write!(
+                f,
+                "time unit at {} is repeated or not in order from largest to smallest",
+                start
+            ),
+            Error::NumberOverflow => write!(f, "duration is out of range"),
+            Error::Empty => write!(f, "value was empty"),
+        }
+    }
+}
+
+const US: u128 = 1000;
+const MS: u128 = 1000 * US;
+const SEC: u128 = 1000 * MS;
+const MIN: u128 = 60 * SEC;
+const HOUR: u128 = 60 * MIN;
+const DAY: u128 = 24 * HOUR;
+const WEEK: u128 = 7 * DAY;
+const YEAR: u128 = 365 * DAY;
+
+/// Units of the Prometheus syntax, largest first
+const PROMETHEUS: &[(&str, u128)] = &[
+    ("y", YEAR),
+    ("w", WEEK),
+    ("d", DAY),
+    ("h", HOUR),
+    ("m", MIN),
+    ("s", SEC),
+    ("ms", MS),
+];
+
+/// Units of the Kubernetes (Gateway API) syntax, largest first
+const KUBERNETES: &[(&str, u128)] = &[("h", HOUR), ("m", MIN), ("s", SEC), ("ms", MS)];
+
+/// Units of [`Style::Go`], accepted by Go's `time.ParseDuration`
+const GO: &[(&str, u128)] = &[
+    ("h", HOUR),
+    ("m", MIN),
+    ("s", SEC),
+    ("ms", MS),
+    ("us", US),
+    ("ns", 1),
+];
+
+/// Units of [`Style::Machine`], which only has `[0-9a-z]` characters
+const METRIC_SAFE: &[(&str, u128)] = &[
+    ("d", DAY),
+    ("h", HOUR),
+    ("m", MIN),
+    ("s", SEC),
+    ("ms", MS),
+    ("us", US),
+    ("ns", 1),
+];
+
+/// Maximum number of digits in a component of the Kubernetes syntax
+const KUBERNETES_DIGITS: usize = 5;
+
+/// Parses concatenated `<integer><unit>` components, with units from the
+/// table in order, each appearing at most once. Returns nanoseconds.
+///
+/// With `fractions`, the integer may be followed by a fractional part
+/// like in `1.5h`, which is truncated to nanoseconds.
+fn parse_components(
+    s: &str,
+    units: &[(&str, u128)],
+    max_digits: usize,
+    fractions: bool,
+    max: u128,
+) -> Result<u128, Error> {
+    if s.is_empty() {
+        return Err(Error::Empty);
+    }
+    let bytes = s.as_bytes();
+    let mut pos = 0;
+    let mut next_unit = 0;
+    let mut total = 0u128;
+    while pos < bytes.len() {
+        let start = pos;
+        let mut value = 0u128;
+        while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+            value = value
+                .checked_mul(10)
+                .and_then(|v| v.checked_add(u128::from(bytes[pos] - b'0')))
+                .ok_or(Error::NumberOverflow)?;
+            pos += 1;
+        }
+        if pos - start > max_digits {
+            return Err(Error::NumberOverflow);
+        }
+        if pos == start {
+            return Err(if bytes[pos].is_ascii_alphabetic() {
+                Error::NumberExpected(pos)
+            } else {
+                Error::InvalidCharacter(pos)
+            });
+        }
+        // numerator and denominator of the fractional part
+        let (mut numer, mut denom) = (0u128, 1u128);
+        if fractions && pos < bytes.len() && bytes[pos] == b'.' {
+            pos += 1;
+            let frac_start = pos;
+            while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                // digits below a nanosecond of a year don't matter
+                if denom < 1_000_000_000_000_000_000 {
+                    numer = numer * 10 + u128::from(bytes[pos] - b'0');
+                    denom *= 10;
+                }
+                pos += 1;
+            }
+            if pos == frac_start {
+                return Err(Error::InvalidCharacter(pos - 1));
+            }
+        }
+        let unit_start = pos;
+        while pos < bytes.len() && bytes[pos].is_ascii_alphabetic() {
+            pos += 1;
+        }
+        if pos < bytes.len() && !bytes[pos].is_ascii_digit() {
+            return Err(Error::InvalidCharacter(pos));
+        }
+        let unit = &s[unit_start..pos];
+        let idx = units
+            .iter()
+            .position(|&(name, _)| name == unit)
+            .ok_or_else(|| Error::UnknownUnit {
+                start: unit_start,
+                end: pos,
+                unit: unit.to_owned(),
+            })?;
+        if idx < next_unit {
+            return Err(Error::UnitOrder {
+                start: unit_start,
+                end: pos,
+            });
+        }
+        next_unit = idx + 1;
+        total = value
+            .checked_mul(units[idx].1)
+            .and_then(|v| v.checked_add(numer * units[idx].1 / denom))
+            .and_then(|v| v.checked_add(total))
+            .filter(|&v| v <= max)
+            .ok_or(Error::NumberOverflow)?;
+    }
+    Ok(total)
+}
+
+fn to_duration(nanos: u128) -> Duration {
+    Duration::new((nanos / SEC) as u64, (nanos % SEC) as u32)
+}
+
+/// Parse duration in the syntax of Prometheus, like `1h30m`
+///
+/// This is the syntax used in Prometheus configuration and PromQL: a
+/// concatenation of values with units `y` (365 days), `w`, `d`, `h`, `m`,
+/// `s`, `ms`, written from the largest to the smallest unit, each at most
+/// once and without whitespace. Values may have a fractional part, like
+/// `1.5h`, truncated to nanoseconds. A single `0` is also accepted. Like
+/// in Prometheus, the maximum value is about 292 years.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::parse_duration_prometheus;
+///
+/// assert_eq!(parse_duration_prometheus("1h30m"), Ok(Duration::from_secs(5400)));
+/// assert_eq!(parse_duration_prometheus("1w2d"), Ok(Duration::from_secs(9 * 86400)));
+/// assert!(parse_duration_prometheus("30m1h").is_err());
+/// assert_eq!(parse_duration_prometheus("1.5h"), Ok(Duration::from_secs(5400)));
+/// ```
+pub fn parse_duration_prometheus(s: &str) -> Result<Duration, Error> {
+    if s == "0" {
+        return Ok(Duration::ZERO);
+    }
+    parse_components(s, PROMETHEUS, usize::MAX, true, i64::MAX as u128).map(to_duration)
+}
+
+/// Formats duration in the syntax of Prometheus, like `1h30m`
+///
+/// The output is the same as Prometheus produces: years and weeks are only
+/// used if they represent the value exactly, and precision below
+/// milliseconds is truncated.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::format_duration_prometheus;
+///
+/// let val = Duration::from_secs(5400);
+/// assert_eq!(format_duration_prometheus(val).to_string(), "1h30m");
+/// let val = Duration::from_secs(90 * 86400);
+/// assert_eq!(format_duration_prometheus(val).to_string(), "90d");
+/// ```
+pub fn format_duration_prometheus(val: Duration) -> FormattedDuration {
+    format_duration_with(val, Style::Prometheus)
+}
+
+/// Parse duration in the syntax of Kubernetes Gateway API (GEP-2257)
+///
+/// This is a strict subset of Go durations, used to validate fields of
+/// custom resources: one to four components of up to 5 digits with units
+/// `h`, `m`, `s`, `ms`, from the largest to the smallest unit, each at most
+/// once. A unit is always required, so zero must be written as `0s`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::parse_duration_kubernetes;
+///
+/// assert_eq!(parse_duration_kubernetes("1h30m"), Ok(Duration::from_secs(5400)));
+/// assert_eq!(parse_duration_kubernetes("500ms"), Ok(Duration::from_millis(500)));
+/// assert!(parse_duration_kubernetes("0").is_err());
+/// assert!(parse_duration_kubernetes("1d").is_err());
+/// ```
+pub fn parse_duration_kubernetes(s: &str) -> Result<Duration, Error> {
+    parse_components(s, KUBERNETES, KUBERNETES_DIGITS, false, u128::MAX).map(to_duration)
+}
+
+/// Formats duration in the syntax of Kubernetes Gateway API (GEP-2257)
+///
+/// Zero is written as `0s`, otherwise non-zero components of `h`, `m`, `s`
+/// and `ms` are written. Returns [`Error::NumberOverflow`] if the duration
+/// has precision below milliseconds or is longer than 99999 hours, as such
+/// values are not representable in this syntax.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::format_duration_kubernetes;
+///
+/// let val = Duration::from_millis(5_400_250);
+/// assert_eq!(format_duration_kubernetes(val).unwrap().to_string(), "1h30m250ms");
+/// assert!(format_duration_kubernetes(Duration::from_micros(1)).is_err());
+/// ```
+pub fn format_duration_kubernetes(val: Duration) -> Result<FormattedDuration, Error> {
+    if val.subsec_nanos() % 1_000_000 != 0 || val.as_secs() / 3600 >= 100_000 {
+        return Err(Error::NumberOverflow);
+    }
+    Ok(format_duration_with(val, Style::Kubernetes))
+}
+
+/// Formats duration for use in metric names and label values, like `1h30m250ms`
+///
+/// The output only has ASCII digits and lowercase letters, so it is a valid
+/// Prometheus label value, a valid part of a Prometheus metric name (though
+/// not its start) and a valid Graphite path segment. This is the
+/// [`Style::Machine`] format, it is exact down to nanoseconds.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::{format_metric_safe, parse_metric_safe};
+///
+/// let val = Duration::new(5400, 250_000_500);
+/// assert_eq!(format_metric_safe(val).to_string(), "1h30m250ms500ns");
+/// assert_eq!(parse_metric_safe("1h30m250ms500ns"), Ok(val));
+/// let name = format!("http_requests_window_{}_total", format_metric_safe(Duration::from_secs(300)));
+/// assert_eq!(name, "http_requests_window_5m_total");
+/// ```
+pub fn format_metric_safe(val: Duration) -> FormattedDuration {
+    format_duration_with(val, Style::Machine)
+}
+
+/// Parses a duration written by [`format_metric_safe`]
+///
+/// Accepts concatenated integer components with units `d`, `h`, `m`, `s`,
+/// `ms`, `us` and `ns`, from the largest to the smallest unit, each at most
+/// once. Any other character, including whitespace, is rejected.
+pub fn parse_metric_safe(s: &str) -> Result<Duration, Error> {
+    let max = u128::from(u64::MAX) * SEC + (SEC - 1);
+    parse_components(s, METRIC_SAFE, usize::MAX, false, max).map(to_duration)
+}
+
+/// Parse duration in the syntax of Java clients, like Kafka's `request.timeout.ms`
+///
+/// Only a plain integer number of milliseconds is accepted, up to the
+/// maximum of Java's `long`, and `-1`, which these configs use for an
+/// infinite value and is returned as `None`. Signs, whitespace, units and
+/// fractions are rejected, so a value accepted here is accepted by the
+/// Java client too.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::parse_duration_kafka;
+///
+/// assert_eq!(parse_duration_kafka("30000"), Ok(Some(Duration::from_secs(30))));
+/// assert_eq!(parse_duration_kafka("-1"), Ok(None));
+/// assert!(parse_duration_kafka("30s").is_err());
+/// ```
+pub fn parse_duration_kafka(s: &str) -> Result<Option<Duration>, Error> {
+    if s.is_empty() {
+        return Err(Error::Empty);
+    }
+    if s == "-1" {
+        return Ok(None);
+    }
+    let bytes = s.as_bytes();
+    let end = bytes
+        .iter()
+        .position(|b| !b.is_ascii_digit())
+        .unwrap_or(bytes.len());
+    if end == 0 {
+        return Err(if bytes[0].is_ascii_alphabetic() {
+            Error::NumberExpected(0)
+        } else {
+            Error::InvalidCharacter(0)
+        });
+    }
+    if end < bytes.len() {
+        let unit_end = bytes[end..]
+            .iter()
+            .position(|b| !b.is_ascii_alphabetic())
+            .map_or(bytes.len(), |i| end + i);
+        return Err(if unit_end == end {
+            Error::InvalidCharacter(end)
+        } else {
+            Error::UnknownUnit {
+                start: end,
+                end: unit_end,
+                unit: s[end..unit_end].to_owned(),
+            }
+        });
+    }
+    let ms = s
+        .parse::<u64>()
+        .ok()
+        .filter(|&v| v <= i64::MAX as u64)
+        .ok_or(Error::NumberOverflow)?;
+    Ok(Some(Duration::from_millis(ms)))
+}
+
+/// Writes `h`, `m`, `s`, `ms` components, omitting zero ones
+pub(crate) fn fmt_kubernetes(val: Duration, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut ms = val.as_millis();
+    if ms == 0 {
+        return f.write_str("0s");
+    }
+    for &(unit, nanos) in KUBERNETES {
+        let mult = nanos / MS;
+        let value = ms / mult;
+        if value > 0 {
+            write!(f, "{}{}", value, unit)?;
+            ms -= value * mult;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn fmt_go(val: Duration, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut nanos = val.as_nanos();
+    if nanos == 0 {
+        return f.write_str("0s");
+    }
+    for &(unit, mult) in GO {
+        let value = nanos / mult;
+        if value > 0 {
+            write!(f, "{}{}", value, unit)?;
+            nanos -= value * mult;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn fmt_prometheus(val: Duration, f: &mut fmt::Formatter) -> fmt::Result {
+    let mut ms = val.as_millis();
+    if ms == 0 {
+        return f.write_str("0s");
+    }
+    for &(unit, nanos) in PROMETHEUS {
+        let mult = nanos / MS;
+        // years and weeks are only used when exact, 90d reads
+        // better than 12w6d
+        if (unit == "y" || unit == "w") && ms % mult != 0 {
+            continue;
+        }
+        let value = ms / mult;
+        if value > 0 {
+            write!(f, "{}{}", value, unit)?;
+            ms -= value * mult;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{format_duration_kubernetes, parse_duration_kubernetes, Error};
+    use super::{format_duration_prometheus, parse_duration_kafka, parse_duration_prometheus};
+    use super::{format_metric_safe, parse_metric_safe};
+
+    #[test]
+    fn parse_prometheus() {
+        let parse = parse_duration_prometheus;
+        assert_eq!(parse("0"), Ok(Duration::ZERO));
+        assert_eq!(parse("0s"), Ok(Duration::ZERO));
+        assert_eq!(parse("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse("1y"), Ok(Duration::from_secs(365 * 86400)));
+        assert_eq!(
+            parse("1y2w3d4h5m6s7ms"),
+            Ok(Duration::new(
+                365 * 86400 + 17 * 86400 + 4 * 3600 + 5 * 60 + 6,
+                7_000_000
+            ))
+        );
+        assert_eq!(parse("010s"), Ok(Duration::from_secs(10)));
+        assert_eq!(parse("1s500ms"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse("1.5h"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse("1h0.5m"), Ok(Duration::from_secs(3630)));
+        assert_eq!(parse("0.001s"), Ok(Duration::from_millis(1)));
+        assert_eq!(parse("1.5ms"), Ok(Duration::from_micros(1500)));
+        assert_eq!(
+            parse("0.333333333333333333333333s"),
+            Ok(Duration::new(0, 333_333_333))
+        );
+    }
+
+    #[test]
+    fn parse_prometheus_errors() {
+        let parse = parse_duration_prometheus;
+        assert_eq!(parse(""), Err(Error::Empty));
+        assert_eq!(parse("h"), Err(Error::NumberExpected(0)));
+        assert_eq!(parse("1.h"), Err(Error::InvalidCharacter(1)));
+        assert_eq!(parse(".5h"), Err(Error::InvalidCharacter(0)));
+        assert_eq!(parse("1.5.5h"), Err(Error::InvalidCharacter(3)));
+        assert_eq!(parse("1h 30m"), Err(Error::InvalidCharacter(2)));
+        assert_eq!(parse("-1h"), Err(Error::InvalidCharacter(0)));
+        assert_eq!(
+            parse("5"),
+            Err(Error::UnknownUnit {
+                start: 1,
+                end: 1,
+                unit: String::new()
+            })
+        );
+        assert_eq!(
+            parse("5min"),
+            Err(Error::UnknownUnit {
+                start: 1,
+                end: 4,
+                unit: "min".to_owned()
+            })
+        );
+        assert_eq!(parse("30m1h"), Err(Error::UnitOrder { start: 4, end: 5 }));
+        assert_eq!(parse("1h1h"), Err(Error::UnitOrder { start: 3, end: 4 }));
+        assert_eq!(parse("292y"), Ok(Duration::from_secs(292 * 365 * 86400)));
+        assert_eq!(parse("293y"), Err(Error::NumberOverflow));
+        assert_eq!(
+            parse("99999999999999999999999999999999999999999s"),
+            Err(Error::NumberOverflow)
+        );
+    }
+
+    #[test]
+    fn format_prometheus() {
+        let fmt = |d| format_duration_prometheus(d).to_string();
+        assert_eq!(fmt(Duration::ZERO), "0s");
+        assert_eq!(fmt(Duration::from_micros(999)), "0s");
+        assert_eq!(fmt(Duration::from_millis(1500)), "1s500ms");
+        assert_eq!(fmt(Duration::from_secs(7 * 86400)), "1w");
+        assert_eq!(fmt(Duration::from_secs(8 * 86400)), "8d");
+        assert_eq!(fmt(Duration::from_secs(365 * 86400 + 3600)), "365d1h");
+        assert_eq!(fmt(Duration::from_secs(2 * 365 * 86400)), "2y");
+        assert_eq!(fmt(Duration::new(3661, 1_000_001)), "1h1m1s1ms");
+    }
+
+    #[test]
+    fn round_trip_prometheus() {
+        for input in &["1h30m", "2w", "5y", "3d12h", "10s250ms", "0s"] {
+            let d = parse_duration_prometheus(input).unwrap();
+            assert_eq!(format_duration_prometheus(d).to_string(), *input);
+        }
+    }
+
+    #[test]
+    fn parse_kubernetes() {
+        let parse = parse_duration_kubernetes;
+        assert_eq!(parse("0s"), Ok(Duration::ZERO));
+        assert_eq!(parse("0h0m0s0ms"), Ok(Duration::ZERO));
+        assert_eq!(parse("1h"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse("90m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse("1s500ms"), Ok(Duration::from_millis(1500)));
+        assert_eq!(
+            parse("99999h99999m99999s99999ms"),
+            Ok(Duration::from_millis(
+                99999 * 3_600_000 + 99999 * 60_000 + 99999 * 1000 + 99999
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_kubernetes_errors() {
+        let parse = parse_duration_kubernetes;
+        assert_eq!(parse(""), Err(Error::Empty));
+        assert_eq!(
+            parse("0"),
+            Err(Error::UnknownUnit {
+                start: 1,
+                end: 1,
+                unit: String::new()
+            })
+        );
+        assert_eq!(
+            parse("1d"),
+            Err(Error::UnknownUnit {
+                start: 1,
+                end: 2,
+                unit: "d".to_owned()
+            })
+        );
+        assert_eq!(
+            parse("1us"),
+            Err(Error::UnknownUnit {
+                start: 1,
+                end: 3,
+                unit: "us".to_owned()
+            })
+        );
+        assert_eq!(parse("100000h"), Err(Error::NumberOverflow));
+        assert_eq!(parse("1s1m"), Err(Error::UnitOrder { start: 3, end: 4 }));
+        assert_eq!(parse("1.5s"), Err(Error::InvalidCharacter(1)));
+        assert_eq!(parse("1h 1m"), Err(Error::InvalidCharacter(2)));
+        assert_eq!(parse("-1s"), Err(Error::InvalidCharacter(0)));
+    }
+
+    #[test]
+    fn format_kubernetes() {
+        let fmt = |d| format_duration_kubernetes(d).map(|f| f.to_string());
+        assert_eq!(fmt(Duration::ZERO), Ok("0s".to_owned()));
+        assert_eq!(fmt(Duration::from_secs(86400)), Ok("24h".to_owned()));
+        assert_eq!(
+            fmt(Duration::new(3601, 1_000_000)),
+            Ok("1h1s1ms".to_owned())
+        );
+        assert_eq!(
+            fmt(Duration::from_secs(99999 * 3600 + 3599)),
+            Ok("99999h59m59s".to_owned())
+        );
+        assert_eq!(
+            fmt(Duration::from_secs(100_000 * 3600)),
+            Err(Error::NumberOverflow)
+        );
+        assert_eq!(fmt(Duration::new(1, 1)), Err(Error::NumberOverflow));
+        for input in &["1h30m", "5s", "250ms", "2h0m1s"] {
+            let d = parse_duration_kubernetes(input).unwrap();
+            let formatted = fmt(d).unwrap();
+            assert_eq!(parse_duration_kubernetes(&formatted), Ok(d));
+        }
+    }
+
+    #[test]
+    fn parse_kafka() {
+        let parse = parse_duration_kafka;
+        assert_eq!(parse("0"), Ok(Some(Duration::ZERO)));
+        assert_eq!(parse("-1"), Ok(None));
+        assert_eq!(parse("1500"), Ok(Some(Duration::from_millis(1500))));
+        assert_eq!(
+            parse("9223372036854775807"),
+            Ok(Some(Duration::from_millis(i64::MAX as u64)))
+        );
+        assert_eq!(parse("9223372036854775808"), Err(Error::NumberOverflow));
+        assert_eq!(parse("99999999999999999999"), Err(Error::NumberOverflow));
+    }
+
+    #[test]
+    fn parse_kafka_errors() {
+        let parse = parse_duration_kafka;
+        assert_eq!(parse(""), Err(Error::Empty));
+        assert_eq!(parse("-2"), Err(Error::InvalidCharacter(0)));
+        assert_eq!(parse("+5"), Err(Error::InvalidCharacter(0)));
+        assert_eq!(parse(" 5"), Err(Error::InvalidCharacter(0)));
+        assert_eq!(parse("5 "), Err(Error::InvalidCharacter(1)));
+        assert_eq!(parse("1.5"), Err(Error::InvalidCharacter(1)));
+        assert_eq!(parse("ms"), Err(Error::NumberExpected(0)));
+        assert_eq!(
+            parse("30s"),
+            Err(Error::UnknownUnit {
+                start: 2,
+                end: 3,
+                unit: "s".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn metric_safe() {
+        for &val in &[
+            Duration::ZERO,
+            Duration::from_secs(5400),
+            Duration::new(86_400 * 400 + 1, 1_001_001),
+            Duration::MAX,
+        ] {
+            let text = format_metric_safe(val).to_string();
+            assert!(text
+                .bytes()
+                .all(|c| c.is_ascii_digit() || c.is_ascii_lowercase()));
+            assert_eq!(parse_metric_safe(&text), Ok(val));
+        }
+        assert_eq!(parse_metric_safe("0s"), Ok(Duration::ZERO));
+        assert_eq!(parse_metric_safe(""), Err(Error::Empty));
+        assert_eq!(parse_metric_safe("1h 30m"), Err(Error::InvalidCharacter(2)));
+        assert_eq!(parse_metric_safe("1.5s"), Err(Error::InvalidCharacter(1)));
+        assert_eq!(
+            parse_metric_safe("1s1m"),
+            Err(Error::UnitOrder { start: 3, end: 4 })
+        );
+        assert_eq!(
+            parse_metric_safe("1w"),
+            Err(Error::UnknownUnit {
+                start: 1,
+                end: 2,
+                unit: "w".into()
+            })
+        );
+        assert_eq!(
+            parse_metric_safe("18446744073709551616s"),
+            Err(Error::NumberOverflow)
+        );
+    }
+}
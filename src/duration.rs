@@ -1,10 +1,30 @@
 use std::error::Error as StdError;
+use std::ffi::OsStr;
 use std::fmt;
+use std::ops::Range;
 use std::str::{Chars, FromStr};
 use std::time::Duration;
 
+use crate::dialect;
+use crate::ffmpeg;
+use crate::grammar::{self, grammar};
+use crate::observer::{self, ParsePath};
+use crate::options::{current_style, default_options, ParseOptions};
+use crate::postgres;
+
+const SECS_PER_MINUTE: u64 = 60;
+const SECS_PER_HOUR: u64 = 3600;
+const SECS_PER_DAY: u64 = 86_400;
+const SECS_PER_WEEK: u64 = 7 * SECS_PER_DAY;
+const SECS_PER_MONTH: u64 = 2_630_016; // 30.44d
+const SECS_PER_YEAR: u64 = 31_557_600; // 365.25d
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
 /// Error parsing human-friendly duration
+///
+/// Use [`Error::kind`] to branch on the category of the error.
 #[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
 pub enum Error {
     /// Invalid character during parsing
     ///
@@ -14,13 +34,31 @@ pub enum Error {
     InvalidCharacter(usize),
     /// Non-numeric value where number is expected
     ///
-    /// This usually means that either time unit is broken into words,
-    /// e.g. `m sec` instead of `msec`, or just number is omitted,
-    /// for example `2 hours min` instead of `2 hours 1 min`
+    /// This usually means that a word which is not a unit is written
+    /// between components, e.g. `2 hours and 1 min`. A unit without a
+    /// number, like `2 hours min`, is [`Error::MissingNumber`].
     ///
     /// The field is an byte offset of the errorneous character
     /// in the string.
     NumberExpected(usize),
+    /// Number directly follows a unit, like `30m` in `1h30m`, while
+    /// [`ParseOptions::with_concatenation`](crate::ParseOptions::with_concatenation)
+    /// denies it
+    ///
+    /// The field is an byte offset of the number in the string.
+    WhitespaceExpected(usize),
+    /// A unit without a number, like `day` or `5m h`
+    ///
+    /// [`ParseOptions::with_lenient`](crate::ParseOptions::with_lenient)
+    /// accepts these as one unit instead.
+    MissingNumber {
+        /// Start of the unit inside the original string
+        start: usize,
+        /// End of the unit inside the original string
+        end: usize,
+        /// The unit verbatim
+        unit: String,
+    },
     /// Unit in the number is not one of allowed units
     ///
     /// See documentation of `parse_duration` for the list of supported
@@ -53,6 +91,148 @@ pub enum Error {
     Empty,
 }
 
+/// Category of a duration parsing [`Error`]
+///
+/// # Example
+///
+/// ```
+/// use humantime::{parse_duration, DurationErrorKind};
+///
+/// let err = parse_duration("5 fortnights").unwrap_err();
+/// assert_eq!(err.kind(), DurationErrorKind::UnknownUnit);
+/// assert_eq!(err.span(), Some(2..12));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// See [`Error::InvalidCharacter`]
+    InvalidCharacter,
+    /// See [`Error::NumberExpected`]
+    NumberExpected,
+    /// See [`Error::WhitespaceExpected`]
+    WhitespaceExpected,
+    /// See [`Error::MissingNumber`]
+    MissingNumber,
+    /// A number without a unit, like `12`
+    MissingUnit,
+    /// See [`Error::UnknownUnit`]
+    UnknownUnit,
+    /// See [`Error::NumberOverflow`]
+    NumberOverflow,
+    /// See [`Error::Empty`]
+    Empty,
+}
+
+impl Error {
+    /// Returns the category of the error
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::InvalidCharacter(_) => ErrorKind::InvalidCharacter,
+            Error::NumberExpected(_) => ErrorKind::NumberExpected,
+            Error::WhitespaceExpected(_) => ErrorKind::WhitespaceExpected,
+            Error::MissingNumber { .. } => ErrorKind::MissingNumber,
+            Error::UnknownUnit { unit, .. } if unit.is_empty() => ErrorKind::MissingUnit,
+            Error::UnknownUnit { .. } => ErrorKind::UnknownUnit,
+            Error::NumberOverflow => ErrorKind::NumberOverflow,
+            Error::Empty => ErrorKind::Empty,
+        }
+    }
+
+    /// Byte range of the input the error points at
+    ///
+    /// Errors about a single character return an empty range at its
+    /// offset, errors about the whole input return `None`.
+    pub fn span(&self) -> Option<Range<usize>> {
+        match *self {
+            Error::InvalidCharacter(pos)
+            | Error::NumberExpected(pos)
+            | Error::WhitespaceExpected(pos) => Some(pos..pos),
+            Error::MissingNumber { start, end, .. } | Error::UnknownUnit { start, end, .. } => {
+                Some(start..end)
+            }
+            Error::NumberOverflow | Error::Empty => None,
+        }
+    }
+
+    /// Moves the offsets of the error by `off`
+    ///
+    /// For errors from parsing a part of the input starting at `off`.
+    pub(crate) fn offset_by(self, off: usize) -> Error {
+        match self {
+            Error::InvalidCharacter(pos) => Error::InvalidCharacter(pos + off),
+            Error::NumberExpected(pos) => Error::NumberExpected(pos + off),
+            Error::WhitespaceExpected(pos) => Error::WhitespaceExpected(pos + off),
+            Error::MissingNumber { start, end, unit } => Error::MissingNumber {
+                start: start + off,
+                end: end + off,
+                unit,
+            },
+            Error::UnknownUnit {
+                start,
+                end,
+                unit,
+                value,
+            } => Error::UnknownUnit {
+                start: start + off,
+                end: end + off,
+                unit,
+                value,
+            },
+            Error::NumberOverflow => Error::NumberOverflow,
+            Error::Empty => Error::Empty,
+        }
+    }
+
+    /// Returns the stable code of the error, like `E0001`
+    ///
+    /// See [`ErrorKind::stable_code`].
+    pub fn stable_code(&self) -> &'static str {
+        self.kind().stable_code()
+    }
+}
+
+impl ErrorKind {
+    /// Returns the stable code of the error kind, like `E0001`
+    ///
+    /// Codes never change and are never reused, so tests and user
+    /// interfaces can match on them. The messages of `Display` are for
+    /// people and may be reworded in any release.
+    ///
+    /// | Code    | Kind                             |
+    /// |---------|----------------------------------|
+    /// | `E0001` | [`ErrorKind::UnknownUnit`]        |
+    /// | `E0002` | [`ErrorKind::MissingUnit`]        |
+    /// | `E0003` | [`ErrorKind::MissingNumber`]      |
+    /// | `E0004` | [`ErrorKind::InvalidCharacter`]   |
+    /// | `E0005` | [`ErrorKind::NumberExpected`]     |
+    /// | `E0006` | [`ErrorKind::WhitespaceExpected`] |
+    /// | `E0007` | [`ErrorKind::NumberOverflow`]     |
+    /// | `E0008` | [`ErrorKind::Empty`]              |
+    ///
+    /// Timestamp errors have codes from `E0101`, see
+    /// [`TimestampError::stable_code`](crate::TimestampError::stable_code).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use humantime::parse_duration;
+    ///
+    /// assert_eq!(parse_duration("5 fortnights").unwrap_err().stable_code(), "E0001");
+    /// ```
+    pub fn stable_code(&self) -> &'static str {
+        match self {
+            ErrorKind::UnknownUnit => "E0001",
+            ErrorKind::MissingUnit => "E0002",
+            ErrorKind::MissingNumber => "E0003",
+            ErrorKind::InvalidCharacter => "E0004",
+            ErrorKind::NumberExpected => "E0005",
+            ErrorKind::WhitespaceExpected => "E0006",
+            ErrorKind::NumberOverflow => "E0007",
+            ErrorKind::Empty => "E0008",
+        }
+    }
+}
+
 impl StdError for Error {}
 
 impl fmt::Display for Error {
@@ -60,6 +240,12 @@ impl fmt::Display for Error {
         match self {
             Error::InvalidCharacter(offset) => write!(f, "invalid character at {}", offset),
             Error::NumberExpected(offset) => write!(f, "expected number at {}", offset),
+            Error::WhitespaceExpected(offset) => {
+                write!(f, "expected whitespace before number at {}", offset)
+            }
+            Error::MissingNumber { unit, .. } => {
+                write!(f, "number needed, for example 2{}", unit)
+            }
             Error::UnknownUnit { unit, value, .. } if unit.is_empty() => {
                 write!(f, "time unit needed, for example {0}sec or {0}ms", value)
             }
@@ -79,8 +265,75 @@ impl fmt::Display for Error {
 }
 
 /// A wrapper type that allows you to Display a Duration
-#[derive(Debug, Clone)]
-pub struct FormattedDuration(Duration);
+///
+/// The alternate flag picks the [`Style::Verbose`] form of the default
+/// style:
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::format_duration;
+///
+/// let val = Duration::from_secs(9420);
+/// assert_eq!(format!("{}", format_duration(val)), "2h 37m");
+/// assert_eq!(format!("{:#}", format_duration(val)), "2 hours 37 minutes");
+/// ```
+///
+/// Formatted durations are ordered by the duration first, so they can be
+/// sorted or used as map keys as they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FormattedDuration(Duration, Style);
+
+/// Output format of a [`FormattedDuration`]
+///
+/// Most styles have a dedicated function, like
+/// [`format_duration_prometheus`](crate::format_duration_prometheus), the
+/// enum is used to pick one in
+/// [`FormatOptions`](crate::FormatOptions).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Style {
+    /// Default style of [`format_duration`], like `1h 30m 250ms`
+    Human,
+    /// Words for the units, like `1 hour 30 minutes 250 milliseconds`
+    Verbose,
+    /// Prometheus, like `1h30m`, sub-millisecond precision is dropped
+    Prometheus,
+    /// Kubernetes Gateway API, like `1h30m`, sub-millisecond precision is
+    /// dropped
+    Kubernetes,
+    /// PostgreSQL interval output, like `01:30:00`
+    Postgres,
+    /// ISO 8601, like `PT1H30M`
+    Iso8601,
+    /// FFmpeg, like `01:30:00.000`
+    Ffmpeg,
+    /// Compact exact form for filenames and URLs, like `1h30m250ms`
+    ///
+    /// The output has no spaces, and only the units `d`, `h`, `m`, `s`,
+    /// `ms`, `us` and `ns`, regardless of the `mu` feature. It parses back
+    /// to the same value with [`parse_duration`] and
+    /// [`parse_metric_safe`](crate::parse_metric_safe), and with every
+    /// [`ParseOptions`](crate::ParseOptions) which allow concatenated
+    /// components: with months and years denied, `M` as minutes or the
+    /// lenient mode.
+    ///
+    /// It is *not* accepted when
+    /// [`ParseOptions::with_concatenation`](crate::ParseOptions::with_concatenation)
+    /// denies concatenated components, as that mode requires whitespace
+    /// between them. Use [`Style::Human`] for such parsers.
+    Machine,
+    /// Abbreviations for all the units, like `2y 3mo 1d 1h 30m`
+    ///
+    /// Unlike [`Style::Human`], the calendar units are abbreviated too, with
+    /// `mo` for months, which can't be mistaken for minutes.
+    Abbreviated,
+    /// Go's `time.ParseDuration`, like `1h30m250ms`
+    ///
+    /// Like [`Style::Kubernetes`] hours are the largest unit, but the
+    /// output is exact, with `us` and `ns` below milliseconds. Go durations
+    /// are limited to about 292 years, so Go can't read longer values.
+    Go,
+}
 
 trait OverflowOp: Sized {
     fn mul(self, other: Self) -> Result<Self, Error>;
@@ -109,17 +362,38 @@ struct Fraction {
     denominator: u64,
 }
 
+const HALF: Fraction = Fraction {
+    numerator: 1,
+    denominator: 2,
+};
+
+/// Running total of the parsed components
+trait Total: Default {
+    fn add(&mut self, n: u64, frac: Option<Fraction>, unit: Unit) -> Result<(), Error>;
+}
+
 struct Parser<'a> {
     iter: Chars<'a>,
     src: &'a str,
+    options: ParseOptions,
+    /// Unit of the last component, for `and a half`
+    last: Option<Unit>,
+    /// Fraction of the next component, for `half an hour`
+    pending: Option<Fraction>,
+    /// Syntax used so far, for the `ParseObserver`
+    path: ParsePath,
 }
 
-impl Parser<'_> {
-    fn parse(mut self) -> Result<Duration, Error> {
-        let mut n = self.parse_first_char()?.ok_or(Error::Empty)?; // integer part
-        let mut out = Duration::ZERO;
+fn is_article(word: &str) -> bool {
+    word.eq_ignore_ascii_case("a") || word.eq_ignore_ascii_case("an")
+}
+
+impl<'a> Parser<'a> {
+    fn parse<T: Total>(&mut self) -> Result<T, Error> {
+        let mut out = T::default();
+        let mut n = self.parse_first_char(&mut out)?.ok_or(Error::Empty)?; // integer part
         'outer: loop {
-            let mut frac = None; // fractional part
+            let mut frac = self.pending.take(); // fractional part
             let mut off = self.off();
             while let Some(c) = self.iter.next() {
                 match c {
@@ -135,6 +409,7 @@ impl Parser<'_> {
                     }
                     '.' => {
                         // decimal separator, the fractional part begins now
+                        self.path.fractions = true;
                         frac = Some(self.parse_fractional_part(&mut off)?);
                         break;
                     }
@@ -148,7 +423,11 @@ impl Parser<'_> {
             let mut off = self.off();
             while let Some(c) = self.iter.next() {
                 match c {
+                    '0'..='9' if !self.options.concatenation() => {
+                        return Err(Error::WhitespaceExpected(off));
+                    }
                     '0'..='9' => {
+                        self.path.concatenated = true;
                         self.parse_unit(n, frac, start, off, &mut out)?;
                         n = c as u64 - '0' as u64;
                         continue 'outer;
@@ -163,27 +442,132 @@ impl Parser<'_> {
             }
 
             self.parse_unit(n, frac, start, off, &mut out)?;
-            n = match self.parse_first_char()? {
+            n = match self.parse_first_char(&mut out)? {
                 Some(n) => n,
                 None => return Ok(out),
             };
         }
     }
 
-    fn parse_first_char(&mut self) -> Result<Option<u64>, Error> {
+    fn parse_first_char(&mut self, out: &mut impl Total) -> Result<Option<u64>, Error> {
         let off = self.off();
-        for c in self.iter.by_ref() {
-            match c {
-                '0'..='9' => {
-                    return Ok(Some(c as u64 - '0' as u64));
-                }
-                c if c.is_whitespace() => continue,
-                _ => {
-                    return Err(Error::NumberExpected(off));
-                }
+        self.skip_whitespace();
+        match self.iter.clone().next() {
+            Some(c @ '0'..='9') => {
+                self.iter.next();
+                Ok(Some(c as u64 - '0' as u64))
+            }
+            Some(_) => self.parse_word(off, out),
+            None => Ok(None),
+        }
+    }
+
+    /// Handles a component starting with a word instead of a number
+    ///
+    /// Bare units are left unparsed for the unit loop in the lenient mode.
+    fn parse_word(&mut self, off: usize, out: &mut impl Total) -> Result<Option<u64>, Error> {
+        let start = self.off();
+        let word = self.peek_word();
+        if word.is_empty() {
+            return Err(Error::NumberExpected(off));
+        }
+        if grammar::unit_named(word).is_some() {
+            if self.options.lenient() {
+                self.path.lenient = true;
+                return Ok(Some(1));
             }
+            return Err(Error::MissingNumber {
+                start,
+                end: start + word.len(),
+                unit: word.to_owned(),
+            });
         }
-        Ok(None)
+        if !self.options.lenient() {
+            return Err(Error::NumberExpected(off));
+        }
+        self.path.lenient = true;
+        #[cfg(feature = "word-numbers")]
+        {
+            if let Some((n, len)) = crate::words::parse_number(self.iter.as_str()) {
+                // `forty-five minutes`
+                self.skip(len);
+                self.expect_unit(off)?;
+                return Ok(Some(n));
+            }
+        }
+        self.skip(word.len());
+        if is_article(word) {
+            // `an hour`
+            self.expect_unit(off)?;
+            return Ok(Some(1));
+        }
+        if word.eq_ignore_ascii_case("half") {
+            // `half an hour` or `half hour`
+            self.skip_whitespace();
+            if is_article(self.peek_word()) {
+                self.skip(self.peek_word().len());
+            }
+            self.expect_unit(off)?;
+            self.pending = Some(HALF);
+            return Ok(Some(0));
+        }
+        if word.eq_ignore_ascii_case("and") && self.last.is_some() {
+            if let (Some(unit), true) = (self.last, self.skip_half()) {
+                // `a day and a half`
+                self.last = None;
+                out.add(0, Some(HALF), unit)?;
+                return self.parse_first_char(out);
+            }
+            // `an hour and 30 minutes`
+            return match self.parse_first_char(out)? {
+                Some(n) => Ok(Some(n)),
+                None => Err(Error::NumberExpected(start)),
+            };
+        }
+        Err(Error::NumberExpected(off))
+    }
+
+    /// Skips `a half` or `an half`, if the input continues with it
+    fn skip_half(&mut self) -> bool {
+        let save = self.iter.clone();
+        self.skip_whitespace();
+        if is_article(self.peek_word()) {
+            self.skip(self.peek_word().len());
+            self.skip_whitespace();
+            let word = self.peek_word();
+            if word.eq_ignore_ascii_case("half") {
+                self.skip(word.len());
+                return true;
+            }
+        }
+        self.iter = save;
+        false
+    }
+
+    /// Checks that a unit follows, so that `a` isn't taken for a number
+    fn expect_unit(&mut self, off: usize) -> Result<(), Error> {
+        self.skip_whitespace();
+        match self.peek_word() {
+            "" => Err(Error::NumberExpected(off)),
+            _ => Ok(()),
+        }
+    }
+
+    fn peek_word(&self) -> &'a str {
+        let rest = self.iter.as_str();
+        let len = rest
+            .find(|c| !matches!(c, 'a'..='z' | 'A'..='Z' | 'µ'))
+            .unwrap_or(rest.len());
+        &rest[..len]
+    }
+
+    fn skip(&mut self, len: usize) {
+        self.iter = self.iter.as_str()[len..].chars();
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.iter.as_str();
+        self.skip(rest.len() - rest.trim_start().len());
     }
 
     fn parse_fractional_part(&mut self, off: &mut usize) -> Result<Fraction, Error> {
@@ -237,11 +621,22 @@ impl Parser<'_> {
         frac: Option<Fraction>,
         start: usize,
         end: usize,
-        out: &mut Duration,
+        out: &mut impl Total,
     ) -> Result<(), Error> {
-        let unit = match Unit::from_str(&self.src[start..end]) {
-            Ok(u) => u,
-            Err(()) => {
+        let unit = match grammar::unit_named(&self.src[start..end]) {
+            Some(Unit::Month) if self.options.m_as_minutes() && &self.src[start..end] == "M" => {
+                Unit::Minute
+            }
+            Some(u) if u.is_calendar() && !self.options.calendar_units() => {
+                return Err(Error::UnknownUnit {
+                    start,
+                    end,
+                    unit: self.src[start..end].to_owned(),
+                    value: n,
+                });
+            }
+            Some(u) => u,
+            None => {
                 return Err(Error::UnknownUnit {
                     start,
                     end,
@@ -251,20 +646,28 @@ impl Parser<'_> {
             }
         };
 
+        self.last = Some(unit);
+        self.path.add_unit(unit);
+        out.add(n, frac, unit)
+    }
+}
+
+impl Total for Duration {
+    fn add(&mut self, n: u64, frac: Option<Fraction>, unit: Unit) -> Result<(), Error> {
         // add the integer part
         let (sec, nsec) = match unit {
             Unit::Nanosecond => (0u64, n),
             Unit::Microsecond => (0u64, n.mul(1000)?),
             Unit::Millisecond => (0u64, n.mul(1_000_000)?),
             Unit::Second => (n, 0),
-            Unit::Minute => (n.mul(60)?, 0),
-            Unit::Hour => (n.mul(3600)?, 0),
-            Unit::Day => (n.mul(86400)?, 0),
-            Unit::Week => (n.mul(86400 * 7)?, 0),
-            Unit::Month => (n.mul(2_630_016)?, 0), // 30.44d
-            Unit::Year => (n.mul(31_557_600)?, 0), // 365.25d
+            Unit::Minute => (n.mul(SECS_PER_MINUTE)?, 0),
+            Unit::Hour => (n.mul(SECS_PER_HOUR)?, 0),
+            Unit::Day => (n.mul(SECS_PER_DAY)?, 0),
+            Unit::Week => (n.mul(SECS_PER_WEEK)?, 0),
+            Unit::Month => (n.mul(SECS_PER_MONTH)?, 0),
+            Unit::Year => (n.mul(SECS_PER_YEAR)?, 0),
         };
-        add_current(sec, nsec, out)?;
+        add_current(sec, nsec, self)?;
 
         // add the fractional part
         if let Some(Fraction {
@@ -276,21 +679,44 @@ impl Parser<'_> {
                 Unit::Nanosecond => return Err(Error::NumberOverflow),
                 Unit::Microsecond => (0, n.mul(1000)?.div(d)?),
                 Unit::Millisecond => (0, n.mul(1_000_000)?.div(d)?),
-                Unit::Second => (0, n.mul(1_000_000_000)?.div(d)?),
-                Unit::Minute => (0, n.mul(60_000_000_000)?.div(d)?),
-                Unit::Hour => (n.mul(3600)?.div(d)?, 0),
-                Unit::Day => (n.mul(86400)?.div(d)?, 0),
-                Unit::Week => (n.mul(86400 * 7)?.div(d)?, 0),
-                Unit::Month => (n.mul(2_630_016)?.div(d)?, 0), // 30.44d
-                Unit::Year => (n.mul(31_557_600)?.div(d)?, 0), // 365.25d
+                Unit::Second => (0, n.mul(NANOS_PER_SEC)?.div(d)?),
+                Unit::Minute => (0, n.mul(SECS_PER_MINUTE * NANOS_PER_SEC)?.div(d)?),
+                Unit::Hour => (n.mul(SECS_PER_HOUR)?.div(d)?, 0),
+                Unit::Day => (n.mul(SECS_PER_DAY)?.div(d)?, 0),
+                Unit::Week => (n.mul(SECS_PER_WEEK)?.div(d)?, 0),
+                Unit::Month => (n.mul(SECS_PER_MONTH)?.div(d)?, 0),
+                Unit::Year => (n.mul(SECS_PER_YEAR)?.div(d)?, 0),
             };
-            add_current(sec, nsec, out)?;
+            add_current(sec, nsec, self)?;
         }
 
         Ok(())
     }
 }
 
+impl Total for u128 {
+    fn add(&mut self, n: u64, frac: Option<Fraction>, unit: Unit) -> Result<(), Error> {
+        let unit_nanos = u128::from(unit.nanos_per_unit());
+        let mut nanos = u128::from(n) * unit_nanos;
+        if let Some(Fraction {
+            numerator: n,
+            denominator: d,
+        }) = frac
+        {
+            // same precision as for `Duration`: whole nanoseconds, or
+            // whole seconds for hours and larger units
+            let step = if unit >= Unit::Hour { 1_000_000_000 } else { 1 };
+            let steps = u128::from(n) * (unit_nanos / step);
+            if unit == Unit::Nanosecond || steps % u128::from(d) != 0 {
+                return Err(Error::NumberOverflow);
+            }
+            nanos += steps / u128::from(d) * step;
+        }
+        *self = self.checked_add(nanos).ok_or(Error::NumberOverflow)?;
+        Ok(())
+    }
+}
+
 fn add_current(mut sec: u64, nsec: u64, out: &mut Duration) -> Result<(), Error> {
     let mut nsec = (out.subsec_nanos() as u64).add(nsec)?;
     if nsec >= 1_000_000_000 {
@@ -302,53 +728,165 @@ fn add_current(mut sec: u64, nsec: u64, out: &mut Duration) -> Result<(), Error>
     Ok(())
 }
 
-enum Unit {
+/// Time unit recognized by the duration parser
+///
+/// Units are ordered by their length. [`FromStr`] accepts all the aliases
+/// of the parser, like `min` or `minutes`, see [`grammar`](crate::grammar)
+/// for the full list.
+///
+/// # Example
+///
+/// ```
+/// use humantime::Unit;
+///
+/// let unit: Unit = "minutes".parse().unwrap();
+/// assert_eq!(unit, Unit::Minute);
+/// assert_eq!(unit.as_str(), "m");
+/// assert_eq!(unit.nanos_per_unit(), 60_000_000_000);
+/// assert!(Unit::Hour > unit);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Unit {
+    /// Nanosecond, `ns`
     Nanosecond,
+    /// Microsecond, `us` or `µs`
     Microsecond,
+    /// Millisecond, `ms`
     Millisecond,
+    /// Second, `s`
     Second,
+    /// Minute, `m` or `min`
     Minute,
+    /// Hour, `h`
     Hour,
+    /// Day, `d` -- always 86400 seconds
     Day,
+    /// Week, `w` -- 7 days
     Week,
+    /// Month, `M` -- defined as 30.44 days
     Month,
+    /// Year, `y` -- defined as 365.25 days
     Year,
 }
 
+impl Unit {
+    /// All the units, from the smallest to the largest
+    pub const ALL: [Unit; 10] = [
+        Unit::Nanosecond,
+        Unit::Microsecond,
+        Unit::Millisecond,
+        Unit::Second,
+        Unit::Minute,
+        Unit::Hour,
+        Unit::Day,
+        Unit::Week,
+        Unit::Month,
+        Unit::Year,
+    ];
+
+    /// Length of the unit in nanoseconds
+    ///
+    /// Months and years have their average length.
+    pub const fn nanos_per_unit(self) -> u64 {
+        match self {
+            Unit::Nanosecond => 1,
+            Unit::Microsecond => 1000,
+            Unit::Millisecond => 1_000_000,
+            Unit::Second => NANOS_PER_SEC,
+            Unit::Minute => SECS_PER_MINUTE * NANOS_PER_SEC,
+            Unit::Hour => SECS_PER_HOUR * NANOS_PER_SEC,
+            Unit::Day => SECS_PER_DAY * NANOS_PER_SEC,
+            Unit::Week => SECS_PER_WEEK * NANOS_PER_SEC,
+            Unit::Month => SECS_PER_MONTH * NANOS_PER_SEC,
+            Unit::Year => SECS_PER_YEAR * NANOS_PER_SEC,
+        }
+    }
+
+    /// The shortest name of the unit, like `ms` or `h`
+    ///
+    /// Months are `M`, to tell them from minutes.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Unit::Nanosecond => "ns",
+            Unit::Microsecond => "us",
+            Unit::Millisecond => "ms",
+            Unit::Second => "s",
+            Unit::Minute => "m",
+            Unit::Hour => "h",
+            Unit::Day => "d",
+            Unit::Week => "w",
+            Unit::Month => "M",
+            Unit::Year => "y",
+        }
+    }
+
+    /// Spellings of the unit accepted by [`parse_duration`], longest first
+    ///
+    /// This is [`Grammar::aliases`](crate::Grammar::aliases) of the
+    /// default parser.
+    pub fn aliases(self) -> &'static [&'static str] {
+        grammar().aliases(self)
+    }
+
+    /// Returns `true` for months and years, which have no exact length
+    ///
+    /// These are the units denied by
+    /// [`ParseOptions::with_calendar_units`](crate::ParseOptions::with_calendar_units).
+    /// Days and weeks are always 24 hours and 7 days.
+    pub const fn is_calendar(self) -> bool {
+        matches!(self, Unit::Month | Unit::Year)
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 impl FromStr for Unit {
-    type Err = ();
+    type Err = ParseUnitError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "nanos" | "nsec" | "ns" => Ok(Self::Nanosecond),
-            "usec" | "us" | "µs" => Ok(Self::Microsecond),
-            "millis" | "msec" | "ms" => Ok(Self::Millisecond),
-            "seconds" | "second" | "secs" | "sec" | "s" => Ok(Self::Second),
-            "minutes" | "minute" | "min" | "mins" | "m" => Ok(Self::Minute),
-            "hours" | "hour" | "hr" | "hrs" | "h" => Ok(Self::Hour),
-            "days" | "day" | "d" => Ok(Self::Day),
-            "weeks" | "week" | "wk" | "wks" | "w" => Ok(Self::Week),
-            "months" | "month" | "M" => Ok(Self::Month),
-            "years" | "year" | "yr" | "yrs" | "y" => Ok(Self::Year),
-            _ => Err(()),
-        }
+        grammar::unit_named(s).ok_or_else(|| ParseUnitError { name: s.to_owned() })
+    }
+}
+
+/// Error parsing a [`Unit`] from its name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseUnitError {
+    name: String,
+}
+
+impl ParseUnitError {
+    /// The name which is not a unit
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for ParseUnitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown time unit {:?}", self.name)
     }
 }
 
+impl StdError for ParseUnitError {}
+
 /// Parse duration object `1hour 12min 5s`
 ///
 /// The duration object is a concatenation of time spans. Where each time
 /// span is an integer number and a suffix. Supported suffixes:
 ///
-/// * `nsec`, `ns` -- nanoseconds
-/// * `usec`, `us`, `µs` -- microseconds
-/// * `msec`, `ms` -- milliseconds
+/// * `nanoseconds`, `nanosecond`, `nsec`, `ns`
+/// * `microseconds`, `microsecond`, `usec`, `us`, `µs`
+/// * `milliseconds`, `millisecond`, `msec`, `ms`
 /// * `seconds`, `second`, `sec`, `s`
 /// * `minutes`, `minute`, `min`, `m`
 /// * `hours`, `hour`, `hr`, `hrs`, `h`
 /// * `days`, `day`, `d`
 /// * `weeks`, `week`, `wk`, `wks`, `w`
-/// * `months`, `month`, `M` -- defined as 30.44 days
+/// * `months`, `month`, `mos`, `mo`, `M` -- defined as 30.44 days
 /// * `years`, `year`, `yr`, `yrs`, `y` -- defined as 365.25 days
 ///
 /// # Examples
@@ -361,15 +899,98 @@ impl FromStr for Unit {
 /// assert_eq!(parse_duration("32ms"), Ok(Duration::new(0, 32_000_000)));
 /// assert_eq!(parse_duration("4.2s"), Ok(Duration::new(4, 200_000_000)));
 /// ```
+///
+/// Components may be written without whitespace between them, like
+/// `1h30m`. Months and years, and such components, are rejected if the
+/// default options set with [`set_default_options`](crate::set_default_options)
+/// deny them.
 pub fn parse_duration(s: &str) -> Result<Duration, Error> {
-    if s == "0" {
-        return Ok(Duration::ZERO);
-    }
-    Parser {
+    parse_with(s, &default_options().0)
+}
+
+// The only entry point to the parser: `parse_duration`, `parse_duration_nanos`
+// and `ParseOptions::parse` differ in the options and the total type only.
+fn parse_with<T: Total>(s: &str, options: &ParseOptions) -> Result<T, Error> {
+    let mut parser = Parser {
         iter: s.chars(),
         src: s,
+        options: *options,
+        last: None,
+        pending: None,
+        path: ParsePath::default(),
+    };
+    let result = if s == "0" {
+        parser.path.bare_zero = true;
+        Ok(T::default())
+    } else {
+        parser.parse()
+    };
+    observer::notify(s, result.as_ref().map(|_| ()), &parser.path);
+    result
+}
+
+pub(crate) fn parse_duration_with(s: &str, options: &ParseOptions) -> Result<Duration, Error> {
+    parse_with(s, options)
+}
+
+/// Parses the trimmed `part`, a slice of `input`, with the error offsets
+/// in `input`
+pub(crate) fn parse_duration_in(input: &str, part: &str) -> Result<Duration, Error> {
+    let part = part.trim();
+    let off = part.as_ptr() as usize - input.as_ptr() as usize;
+    parse_duration(part).map_err(|e| e.offset_by(off))
+}
+
+/// Parses a duration into the total number of nanoseconds
+///
+/// Accepts the same syntax as [`parse_duration`], but the total may be
+/// larger than `std::time::Duration` can hold: up to about 10^22 years.
+/// Numbers in each component are still limited to `u64`.
+///
+/// # Example
+///
+/// ```
+/// use humantime::parse_duration_nanos;
+///
+/// assert_eq!(parse_duration_nanos("1.5us"), Ok(1500));
+/// let age_of_universe = parse_duration_nanos("13787000000 years").unwrap();
+/// assert_eq!(age_of_universe, 13_787_000_000 * 31_557_600_000_000_000);
+/// assert!(parse_duration_nanos("18446744073709551615 years").is_ok());
+/// ```
+pub fn parse_duration_nanos(s: &str) -> Result<u128, Error> {
+    parse_with(s, &default_options().0)
+}
+
+/// Parses a duration from a command-line argument or a path component
+///
+/// Like [`parse_duration`], for arguments handed out as `OsString` by
+/// argument parsers, and for `Path` with [`Path::as_os_str`][as_os_str].
+/// A value which isn't valid UTF-8 is rejected with
+/// [`Error::InvalidCharacter`] at the first character that can't be
+/// decoded.
+///
+/// [as_os_str]: std::path::Path::as_os_str
+///
+/// # Example
+///
+/// ```
+/// use std::ffi::OsString;
+/// use std::time::Duration;
+/// use humantime::parse_duration_os;
+///
+/// let arg = OsString::from("1m 30s");
+/// assert_eq!(parse_duration_os(&arg), Ok(Duration::from_secs(90)));
+/// ```
+pub fn parse_duration_os(s: &OsStr) -> Result<Duration, Error> {
+    match s.to_str() {
+        Some(s) => parse_duration(s),
+        // the replacement character is the first one that isn't valid
+        None => Err(Error::InvalidCharacter(
+            s.to_string_lossy()
+                .find(char::REPLACEMENT_CHARACTER)
+                .unwrap_or(0),
+        )),
     }
-    .parse()
 }
 
 /// Formats duration into a human-readable string
@@ -389,30 +1010,160 @@ pub fn parse_duration(s: &str) -> Result<Duration, Error> {
 /// let val2 = Duration::new(0, 32_000_000);
 /// assert_eq!(format_duration(val2).to_string(), "32ms");
 /// ```
+///
+/// The style can be changed for the whole application with
+/// [`set_default_options`](crate::set_default_options), or for a part of
+/// the code with [`with_format_style`](crate::with_format_style).
+///
+/// Formatting and parsing use integer arithmetic only, so every value up to
+/// `Duration::MAX` parses back exactly, in both the short and the verbose
+/// form.
 pub fn format_duration(val: Duration) -> FormattedDuration {
-    FormattedDuration(val)
+    FormattedDuration(val, current_style())
 }
 
-fn item_plural(f: &mut fmt::Formatter, started: &mut bool, name: &str, value: u64) -> fmt::Result {
-    if value > 0 {
-        if *started {
-            f.write_str(" ")?;
+pub(crate) fn format_duration_with(val: Duration, style: Style) -> FormattedDuration {
+    FormattedDuration(val, style)
+}
+
+/// Writes ` 12name`, without going through the formatting machinery which
+/// is a large part of the time spent in `format_duration`
+#[inline]
+fn write_item(f: &mut fmt::Formatter, started: &mut bool, value: u64, name: &str) -> fmt::Result {
+    let mut buf = [0u8; 21];
+    let mut pos = buf.len();
+    let mut rest = value;
+    loop {
+        pos -= 1;
+        buf[pos] = b'0' + (rest % 10) as u8;
+        rest /= 10;
+        if rest == 0 {
+            break;
         }
-        write!(f, "{}{}", value, name)?;
-        if value > 1 {
-            f.write_str("s")?;
+    }
+    if *started {
+        pos -= 1;
+        buf[pos] = b' ';
+    }
+    *started = true;
+    let digits = std::str::from_utf8(&buf[pos..]).expect("only ASCII digits and a space");
+    f.write_str(digits)?;
+    f.write_str(name)
+}
+
+#[cfg(feature = "mu")]
+const MICROS: &str = "µs";
+#[cfg(not(feature = "mu"))]
+const MICROS: &str = "us";
+
+/// Splits the duration into the units of the default style, largest first
+fn decompose(val: Duration) -> [(Unit, u64); 9] {
+    let secs = val.as_secs();
+    let nanos = val.subsec_nanos();
+
+    let years = secs / SECS_PER_YEAR;
+    let ydays = secs % SECS_PER_YEAR;
+    let months = ydays / SECS_PER_MONTH;
+    let mdays = ydays % SECS_PER_MONTH;
+    let days = mdays / SECS_PER_DAY;
+    let day_secs = mdays % SECS_PER_DAY;
+    let hours = day_secs / SECS_PER_HOUR;
+    let minutes = day_secs % SECS_PER_HOUR / SECS_PER_MINUTE;
+    let seconds = day_secs % SECS_PER_MINUTE;
+
+    let millis = nanos / 1_000_000;
+    let micros = nanos / 1000 % 1000;
+    let nanosec = nanos % 1000;
+
+    [
+        (Unit::Year, years),
+        (Unit::Month, months),
+        (Unit::Day, days),
+        (Unit::Hour, hours),
+        (Unit::Minute, minutes),
+        (Unit::Second, seconds),
+        (Unit::Millisecond, u64::from(millis)),
+        (Unit::Microsecond, u64::from(micros)),
+        (Unit::Nanosecond, u64::from(nanosec)),
+    ]
+}
+
+/// Name and verbose name of the items of `decompose`; only the calendar
+/// units have plurals in the short form
+const NAMES: [(&str, &str); 9] = [
+    ("year", "year"),
+    ("month", "month"),
+    ("day", "day"),
+    ("h", "hour"),
+    ("m", "minute"),
+    ("s", "second"),
+    ("ms", "millisecond"),
+    (MICROS, "microsecond"),
+    ("ns", "nanosecond"),
+];
+
+fn fmt_human(val: Duration, f: &mut fmt::Formatter, verbose: bool) -> fmt::Result {
+    if val.is_zero() {
+        return f.write_str(if verbose { "0 seconds" } else { "0s" });
+    }
+
+    let started = &mut false;
+    for (idx, &(_, value)) in decompose(val).iter().enumerate() {
+        if value == 0 {
+            continue;
+        }
+        let (name, long) = NAMES[idx];
+        if verbose {
+            write_item(f, started, value, " ")?;
+            f.write_str(long)?;
+            if value > 1 {
+                f.write_str("s")?;
+            }
+        } else {
+            write_item(f, started, value, name)?;
+            if idx < 3 && value > 1 {
+                f.write_str("s")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Names of the items of `decompose` in [`Style::Abbreviated`]
+const ABBREVIATIONS: [&str; 9] = ["y", "mo", "d", "h", "m", "s", "ms", MICROS, "ns"];
+
+fn fmt_abbreviated(val: Duration, f: &mut fmt::Formatter) -> fmt::Result {
+    if val.is_zero() {
+        return f.write_str("0s");
+    }
+    let started = &mut false;
+    for (idx, &(_, value)) in decompose(val).iter().enumerate() {
+        if value > 0 {
+            write_item(f, started, value, ABBREVIATIONS[idx])?;
         }
-        *started = true;
     }
     Ok(())
 }
-fn item(f: &mut fmt::Formatter, started: &mut bool, name: &str, value: u32) -> fmt::Result {
-    if value > 0 {
-        if *started {
-            f.write_str(" ")?;
+
+fn fmt_machine(val: Duration, f: &mut fmt::Formatter) -> fmt::Result {
+    if val.is_zero() {
+        return f.write_str("0s");
+    }
+    let secs = val.as_secs();
+    let nanos = val.subsec_nanos();
+    let items = [
+        (secs / 86400, "d"),
+        (secs / 3600 % 24, "h"),
+        (secs / 60 % 60, "m"),
+        (secs % 60, "s"),
+        (u64::from(nanos / 1_000_000), "ms"),
+        (u64::from(nanos / 1000 % 1000), "us"),
+        (u64::from(nanos % 1000), "ns"),
+    ];
+    for &(value, unit) in &items {
+        if value > 0 {
+            write!(f, "{}{}", value, unit)?;
         }
-        write!(f, "{}{}", value, name)?;
-        *started = true;
     }
     Ok(())
 }
@@ -422,46 +1173,47 @@ impl FormattedDuration {
     pub fn get_ref(&self) -> &Duration {
         &self.0
     }
+
+    /// Returns the non-zero components of the duration, largest unit first
+    ///
+    /// These are the numbers the default style writes, whichever style is
+    /// used for `Display`, so callers can render them in their own markup.
+    /// A zero duration has no components.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use humantime::{format_duration, Unit};
+    ///
+    /// let formatted = format_duration(Duration::from_millis(5_400_250));
+    /// let components: Vec<(Unit, u64)> = formatted.components().collect();
+    /// assert_eq!(
+    ///     components,
+    ///     [(Unit::Hour, 1), (Unit::Minute, 30), (Unit::Millisecond, 250)]
+    /// );
+    /// ```
+    pub fn components(&self) -> impl Iterator<Item = (Unit, u64)> {
+        decompose(self.0)
+            .into_iter()
+            .filter(|&(_, value)| value != 0)
+    }
 }
 
 impl fmt::Display for FormattedDuration {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let secs = self.0.as_secs();
-        let nanos = self.0.subsec_nanos();
-
-        if secs == 0 && nanos == 0 {
-            f.write_str("0s")?;
-            return Ok(());
-        }
-
-        let years = secs / 31_557_600; // 365.25d
-        let ydays = secs % 31_557_600;
-        let months = ydays / 2_630_016; // 30.44d
-        let mdays = ydays % 2_630_016;
-        let days = mdays / 86400;
-        let day_secs = mdays % 86400;
-        let hours = day_secs / 3600;
-        let minutes = day_secs % 3600 / 60;
-        let seconds = day_secs % 60;
-
-        let millis = nanos / 1_000_000;
-        let micros = nanos / 1000 % 1000;
-        let nanosec = nanos % 1000;
-
-        let started = &mut false;
-        item_plural(f, started, "year", years)?;
-        item_plural(f, started, "month", months)?;
-        item_plural(f, started, "day", days)?;
-        item(f, started, "h", hours as u32)?;
-        item(f, started, "m", minutes as u32)?;
-        item(f, started, "s", seconds as u32)?;
-        item(f, started, "ms", millis)?;
-        #[cfg(feature = "mu")]
-        item(f, started, "µs", micros)?;
-        #[cfg(not(feature = "mu"))]
-        item(f, started, "us", micros)?;
-        item(f, started, "ns", nanosec)?;
-        Ok(())
+        match self.1 {
+            Style::Human => fmt_human(self.0, f, f.alternate()),
+            Style::Verbose => fmt_human(self.0, f, true),
+            Style::Prometheus => dialect::fmt_prometheus(self.0, f),
+            Style::Kubernetes => dialect::fmt_kubernetes(self.0, f),
+            Style::Postgres => postgres::fmt_postgres(self.0, f),
+            Style::Iso8601 => postgres::fmt_iso8601(self.0, f),
+            Style::Ffmpeg => ffmpeg::fmt_ffmpeg(self.0, f),
+            Style::Machine => fmt_machine(self.0, f),
+            Style::Abbreviated => fmt_abbreviated(self.0, f),
+            Style::Go => dialect::fmt_go(self.0, f),
+        }
     }
 }
 
@@ -469,10 +1221,10 @@ impl fmt::Display for FormattedDuration {
 mod test {
     use std::time::Duration;
 
-    use rand::Rng;
-
-    use super::Error;
-    use super::{format_duration, parse_duration};
+    use super::{format_duration, parse_duration, parse_duration_nanos, parse_duration_os};
+    use super::{Error, ErrorKind, Style, Unit};
+    use crate::options::{FormatOptions, ParseOptions};
+    use crate::test_support::{random_duration, random_seconds};
 
     #[test]
     #[allow(clippy::cognitive_complexity)]
@@ -723,19 +1475,18 @@ mod test {
 
     #[test]
     fn random_second() {
+        let mut rng = rand::rng();
         for _ in 0..10000 {
-            let sec = rand::rng().random_range(0..253_370_764_800);
-            let d = Duration::new(sec, 0);
+            let d = random_seconds(&mut rng);
             assert_eq!(d, parse_duration(&format_duration(d).to_string()).unwrap());
         }
     }
 
     #[test]
     fn random_any() {
+        let mut rng = rand::rng();
         for _ in 0..10000 {
-            let sec = rand::rng().random_range(0..253_370_764_800);
-            let nanos = rand::rng().random_range(0..1_000_000_000);
-            let d = Duration::new(sec, nanos);
+            let d = random_duration(&mut rng);
             assert_eq!(d, parse_duration(&format_duration(d).to_string()).unwrap());
         }
     }
@@ -813,6 +1564,33 @@ mod test {
         );
     }
 
+    #[test]
+    fn missing_number() {
+        assert_eq!(
+            parse_duration("s"),
+            Err(Error::MissingNumber {
+                start: 0,
+                end: 1,
+                unit: "s".to_owned(),
+            })
+        );
+        assert_eq!(
+            parse_duration(" hours").unwrap_err().to_string(),
+            "number needed, for example 2hours"
+        );
+        assert!(matches!(
+            parse_duration("5m µs"),
+            Err(Error::MissingNumber {
+                start: 3,
+                end: 6,
+                ..
+            })
+        ));
+        // not a unit, so this could be anything
+        assert_eq!(parse_duration("soon"), Err(Error::NumberExpected(0)));
+        assert_eq!(parse_duration("1h ~"), Err(Error::NumberExpected(3)));
+    }
+
     #[cfg(feature = "mu")]
     #[test]
     fn test_format_micros() {
@@ -831,6 +1609,76 @@ mod test {
         );
     }
 
+    #[test]
+    fn machine() {
+        let machine = |d| {
+            FormatOptions::new()
+                .with_style(Style::Machine)
+                .format(d)
+                .to_string()
+        };
+        assert_eq!(machine(Duration::ZERO), "0s");
+        assert_eq!(machine(Duration::new(5415, 250_000_000)), "1h30m15s250ms");
+        assert_eq!(
+            machine(Duration::new(2 * 31_557_600, 1001)),
+            "730d12h1us1ns"
+        );
+        assert_eq!(machine(Duration::from_micros(123)), "123us");
+        // every mode allowing concatenation reads it back
+        let modes = [
+            ParseOptions::new(),
+            ParseOptions::new().with_calendar_units(false),
+            ParseOptions::new().with_lenient(true),
+            ParseOptions::new()
+                .with_calendar_units(false)
+                .with_m_as_minutes(true)
+                .with_lenient(true),
+        ];
+        let mut rng = rand::rng();
+        for _ in 0..10000 {
+            let d = random_duration(&mut rng);
+            let text = machine(d);
+            assert!(!text.contains(' '), "{}", text);
+            for options in &modes {
+                assert_eq!(options.parse(&text), Ok(d), "{:?}", options);
+            }
+            assert_eq!(crate::dialect::parse_metric_safe(&text), Ok(d));
+        }
+        for options in &modes {
+            assert_eq!(options.parse(&machine(Duration::MAX)), Ok(Duration::MAX));
+        }
+        // but not the mode denying it, unless there is a single component
+        let spaced = ParseOptions::new().with_concatenation(false);
+        assert!(spaced.parse(&machine(Duration::from_secs(90))).is_err());
+        assert_eq!(
+            spaced.parse(&machine(Duration::from_secs(60))),
+            Ok(Duration::from_secs(60))
+        );
+    }
+
+    #[test]
+    fn abbreviated() {
+        let abbreviated = |d| {
+            FormatOptions::new()
+                .with_style(Style::Abbreviated)
+                .format(d)
+                .to_string()
+        };
+        assert_eq!(abbreviated(Duration::ZERO), "0s");
+        assert_eq!(
+            abbreviated(Duration::new(2 * 31_557_600 + 3 * 2_630_016 + 90_000, 0)),
+            "2y 3mo 1d 1h"
+        );
+        assert_eq!(abbreviated(Duration::new(61, 5_000_000)), "1m 1s 5ms");
+        assert_eq!(parse_duration("3mo"), parse_duration("3 months"));
+        assert_eq!(parse_duration("1mo 2min"), parse_duration("1M 2m"));
+        let mut rng = rand::rng();
+        for _ in 0..10000 {
+            let d = random_duration(&mut rng);
+            assert_eq!(parse_duration(&abbreviated(d)), Ok(d));
+        }
+    }
+
     #[test]
     fn test_error_cases() {
         assert_eq!(
@@ -852,4 +1700,233 @@ mod test {
         assert_eq!(parse_duration("222nsec221nanosmsec7s5msec572s").unwrap_err().to_string(),
                    "unknown time unit \"nanosmsec\", supported units: ns, us/µs, ms, sec, min, hours, days, weeks, months, years (and few variations)");
     }
+
+    #[test]
+    fn kind_and_span() {
+        let kind = |s| parse_duration(s).unwrap_err().kind();
+        let span = |s| parse_duration(s).unwrap_err().span();
+        assert_eq!(kind("1h!"), ErrorKind::InvalidCharacter);
+        assert_eq!(span("1h!"), Some(2..2));
+        assert_eq!(kind("1h min"), ErrorKind::MissingNumber);
+        assert_eq!(span("1h min"), Some(3..6));
+        assert_eq!(kind("1h -1m"), ErrorKind::NumberExpected);
+        assert_eq!(kind("12"), ErrorKind::MissingUnit);
+        assert_eq!(span("12"), Some(2..2));
+        assert_eq!(kind("1 fortnight"), ErrorKind::UnknownUnit);
+        assert_eq!(span("1 fortnight"), Some(2..11));
+        assert_eq!(kind("100000000000000000000s"), ErrorKind::NumberOverflow);
+        assert_eq!(kind(" "), ErrorKind::Empty);
+        assert_eq!(span(" "), None);
+    }
+
+    #[test]
+    fn offset_by() {
+        for input in &["5m h", "1h 2x", "1h 2", "1!h", "1h h5", ""] {
+            let err = parse_duration(input).unwrap_err();
+            let moved = err.clone().offset_by(3);
+            assert_eq!(moved.kind(), err.kind());
+            assert_eq!(moved.span(), err.span().map(|r| r.start + 3..r.end + 3));
+        }
+        let err = ParseOptions::new()
+            .with_concatenation(false)
+            .parse("1h30m")
+            .unwrap_err();
+        assert_eq!(err.offset_by(3), Error::WhitespaceExpected(5));
+    }
+
+    #[test]
+    fn codes() {
+        let code = |s| parse_duration(s).unwrap_err().stable_code();
+        assert_eq!(code("1 fortnight"), "E0001");
+        assert_eq!(code("12"), "E0002");
+        assert_eq!(code("1h min"), "E0003");
+        assert_eq!(code("1h!"), "E0004");
+        assert_eq!(code("1h -1m"), "E0005");
+        assert_eq!(code("100000000000000000000s"), "E0007");
+        assert_eq!(code(" "), "E0008");
+        assert_eq!(ErrorKind::WhitespaceExpected.stable_code(), "E0006");
+    }
+
+    #[test]
+    fn nanos() {
+        assert_eq!(parse_duration_nanos("0"), Ok(0));
+        assert_eq!(parse_duration_nanos("1h 2.5s 3ns"), Ok(3_602_500_000_003));
+        assert_eq!(
+            parse_duration_nanos("100000000000000ms"),
+            Ok(10u128.pow(20))
+        );
+        assert_eq!(
+            parse_duration_nanos(&format!("{}s 999999999ns 1ns", u64::MAX)),
+            Ok(u128::from(u64::MAX) * 1_000_000_000 + 1_000_000_000)
+        );
+        assert_eq!(parse_duration_nanos("0.5ns"), Err(Error::NumberOverflow));
+        assert_eq!(parse_duration_nanos("0.016h"), Err(Error::NumberOverflow));
+        assert_eq!(parse_duration_nanos("0.25h"), Ok(900_000_000_000));
+        assert_eq!(
+            parse_duration_nanos("0.0000000002s"),
+            Err(Error::NumberOverflow)
+        );
+        let max = format!("{}y", u64::MAX);
+        let too_many = vec![max.as_str(); 1000].join(" ");
+        assert_eq!(parse_duration_nanos(&too_many), Err(Error::NumberOverflow));
+        for s in ["1.5us", "3 days 1.25h", "7.123us 4ns", "12.1mins"] {
+            let d = parse_duration(s).unwrap();
+            assert_eq!(parse_duration_nanos(s), Ok(d.as_nanos()), "{}", s);
+        }
+    }
+
+    #[test]
+    fn one_grammar() {
+        let inputs = [
+            "1h30m",
+            "1h 30m",
+            " 1h",
+            "1h\t\n30m",
+            "1 h",
+            "1.5 days",
+            "2 hours min",
+            "3 fortnights",
+            "1h 2",
+            "",
+            "0",
+        ];
+        for s in inputs {
+            let default = parse_duration(s);
+            assert_eq!(ParseOptions::new().parse(s), default, "{:?}", s);
+            assert_eq!(
+                parse_duration_nanos(s),
+                default.clone().map(|d| d.as_nanos()),
+                "{:?}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn os_str() {
+        use std::ffi::OsStr;
+        use std::path::Path;
+
+        assert_eq!(
+            parse_duration_os(OsStr::new("2h 5m")),
+            Ok(Duration::new(7500, 0))
+        );
+        let path = Path::new("5min");
+        assert_eq!(
+            parse_duration_os(path.as_os_str()),
+            Ok(Duration::new(300, 0))
+        );
+        assert_eq!(
+            parse_duration_os(OsStr::new("5 parsecs")),
+            parse_duration("5 parsecs")
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::ffi::OsStrExt;
+
+            let arg = OsStr::from_bytes(b"15m \xff");
+            assert_eq!(parse_duration_os(arg), Err(Error::InvalidCharacter(4)));
+        }
+    }
+
+    #[test]
+    fn alternate() {
+        let val = Duration::new(86_401, 1_000_000);
+        assert_eq!(
+            format!("{:#}", format_duration(val)),
+            "1 day 1 second 1 millisecond"
+        );
+        let wrapped = crate::Duration::from(val);
+        assert_eq!(format!("{:#}", wrapped), "1 day 1 second 1 millisecond");
+        // only the human style has a long form
+        let prometheus = crate::format_duration_prometheus(val);
+        assert_eq!(format!("{:#}", prometheus), "1d1s1ms");
+    }
+
+    #[test]
+    fn components() {
+        let parts = |secs, nanos| {
+            format_duration(Duration::new(secs, nanos))
+                .components()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(parts(0, 0), []);
+        assert_eq!(
+            parts(31_557_600 + 2_630_016 + 86400 + 3600 + 61, 1_001_001),
+            [
+                (Unit::Year, 1),
+                (Unit::Month, 1),
+                (Unit::Day, 1),
+                (Unit::Hour, 1),
+                (Unit::Minute, 1),
+                (Unit::Second, 1),
+                (Unit::Millisecond, 1),
+                (Unit::Microsecond, 1),
+                (Unit::Nanosecond, 1),
+            ]
+        );
+        let prometheus = crate::format_duration_prometheus(Duration::from_secs(90));
+        assert_eq!(
+            prometheus.components().collect::<Vec<_>>(),
+            [(Unit::Minute, 1), (Unit::Second, 30)]
+        );
+    }
+
+    #[test]
+    fn units() {
+        let units = Unit::ALL;
+        for pair in units.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert!(pair[0].nanos_per_unit() < pair[1].nanos_per_unit());
+        }
+        for &unit in &units {
+            assert_eq!(unit.as_str().parse(), Ok(unit));
+            assert_eq!(unit.to_string(), unit.as_str());
+            assert!(unit.aliases().contains(&unit.as_str()));
+            for alias in unit.aliases() {
+                assert_eq!(alias.parse(), Ok(unit));
+            }
+        }
+        let calendar: Vec<Unit> = units.iter().copied().filter(|u| u.is_calendar()).collect();
+        assert_eq!(calendar, [Unit::Month, Unit::Year]);
+    }
+
+    #[test]
+    fn ordered() {
+        use std::collections::HashSet;
+
+        let mut values: Vec<_> = [90, 5, 3600]
+            .iter()
+            .map(|&s| format_duration(Duration::from_secs(s)))
+            .collect();
+        values.sort();
+        let sorted: Vec<_> = values.iter().map(|v| v.to_string()).collect();
+        assert_eq!(sorted, ["5s", "1m 30s", "1h"]);
+        let set: HashSet<_> = values.iter().chain(&values).copied().collect();
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn max_round_trip() {
+        let values = [
+            Duration::MAX,
+            Duration::new(u64::MAX, 0),
+            Duration::new(u64::MAX - 1, 999_999_999),
+            Duration::new(u64::MAX - 31_557_600, 5),
+            Duration::new(584_542_046_090 * 31_557_600, 0),
+        ];
+        for &d in &values {
+            let short = format_duration(d).to_string();
+            assert_eq!(parse_duration(&short), Ok(d), "{}", short);
+            assert_eq!(parse_duration_nanos(&short), Ok(d.as_nanos()), "{}", short);
+            let verbose = format!("{:#}", format_duration(d));
+            assert_eq!(parse_duration(&verbose), Ok(d), "{}", verbose);
+        }
+        assert!(format_duration(Duration::MAX)
+            .to_string()
+            .starts_with("584542046090years 7months 15days 17h 5m 3s 999ms 999"));
+        let over = "584542046090years 7months 15days 17h 5m 3s 999ms 999us 1000ns";
+        assert_eq!(parse_duration(over), Err(Error::NumberOverflow));
+        assert_eq!(parse_duration_nanos(over), Ok(Duration::MAX.as_nanos() + 1));
+    }
 }
@@ -0,0 +1,193 @@
+//! Reading durations and timestamps from environment variables
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! std::env::set_var("MY_SERVICE_TIMEOUT", " 1m 30s ");
+//! assert_eq!(
+//!     humantime::env::duration("MY_SERVICE_TIMEOUT"),
+//!     Ok(Duration::from_secs(90)),
+//! );
+//! let err = humantime::env::duration("MY_SERVICE_NO_SUCH_VAR").unwrap_err();
+//! assert_eq!(err.to_string(), "environment variable MY_SERVICE_NO_SUCH_VAR is not set");
+//! ```
+
+use std::env::{self, VarError};
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use crate::date::{self, parse_rfc3339_weak};
+use crate::duration::{self, parse_duration};
+
+/// Error reading a value from an environment variable
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// The variable is not set
+    NotPresent {
+        /// Name of the variable
+        name: String,
+    },
+    /// The value of the variable is not valid unicode
+    NotUnicode {
+        /// Name of the variable
+        name: String,
+    },
+    /// The value is not a valid duration
+    Duration {
+        /// Name of the variable
+        name: String,
+        /// The parse error
+        error: duration::Error,
+    },
+    /// The value is not a valid timestamp
+    Timestamp {
+        /// Name of the variable
+        name: String,
+        /// The parse error
+        error: date::Error,
+    },
+}
+
+impl Error {
+    /// Returns the name of the variable
+    pub fn name(&self) -> &str {
+        match self {
+            Error::NotPresent { name }
+            | Error::NotUnicode { name }
+            | Error::Duration { name, .. }
+            | Error::Timestamp { name, .. } => name,
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Duration { error, .. } => Some(error),
+            Error::Timestamp { error, .. } => Some(error),
+            Error::NotPresent { .. } | Error::NotUnicode { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotPresent { name } => write!(f, "environment variable {} is not set", name),
+            Error::NotUnicode { name } => {
+                write!(f, "environment variable {} is not valid unicode", name)
+            }
+            Error::Duration { name, error } => {
+                write!(
+                    f,
+                    "environment variable {}: invalid duration: {}",
+                    name, error
+                )
+            }
+            Error::Timestamp { name, error } => {
+                write!(
+                    f,
+                    "environment variable {}: invalid timestamp: {}",
+                    name, error
+                )
+            }
+        }
+    }
+}
+
+fn read(name: &str) -> Result<String, Error> {
+    env::var(name).map_err(|e| match e {
+        VarError::NotPresent => Error::NotPresent {
+            name: name.to_owned(),
+        },
+        VarError::NotUnicode(_) => Error::NotUnicode {
+            name: name.to_owned(),
+        },
+    })
+}
+
+/// Reads a duration like `30s` from the environment variable
+///
+/// Leading and trailing whitespace is ignored.
+pub fn duration(name: &str) -> Result<Duration, Error> {
+    let value = read(name)?;
+    parse_duration(value.trim()).map_err(|error| Error::Duration {
+        name: name.to_owned(),
+        error,
+    })
+}
+
+/// Reads a timestamp like `2018-02-14 00:28:07` from the environment
+/// variable
+///
+/// The value is parsed with [`parse_rfc3339_weak`], leading and trailing
+/// whitespace is ignored.
+pub fn timestamp(name: &str) -> Result<SystemTime, Error> {
+    let value = read(name)?;
+    parse_rfc3339_weak(value.trim()).map_err(|error| Error::Timestamp {
+        name: name.to_owned(),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{duration, timestamp, Error};
+    use crate::date::Error as TimestampError;
+    use crate::duration::Error as DurationError;
+
+    #[test]
+    fn read_duration() {
+        env::set_var("HUMANTIME_TEST_DURATION", "\t5min\n");
+        assert_eq!(
+            duration("HUMANTIME_TEST_DURATION"),
+            Ok(Duration::from_secs(300))
+        );
+        env::set_var("HUMANTIME_TEST_BAD_DURATION", "5");
+        let err = duration("HUMANTIME_TEST_BAD_DURATION").unwrap_err();
+        assert_eq!(err.name(), "HUMANTIME_TEST_BAD_DURATION");
+        assert_eq!(
+            err.to_string(),
+            "environment variable HUMANTIME_TEST_BAD_DURATION: \
+            invalid duration: time unit needed, for example 5sec or 5ms"
+        );
+        assert_eq!(
+            duration("HUMANTIME_TEST_MISSING"),
+            Err(Error::NotPresent {
+                name: "HUMANTIME_TEST_MISSING".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn read_timestamp() {
+        env::set_var("HUMANTIME_TEST_TIMESTAMP", " 2018-02-14 00:28:07 ");
+        assert_eq!(
+            timestamp("HUMANTIME_TEST_TIMESTAMP"),
+            Ok(UNIX_EPOCH + Duration::from_secs(1_518_568_087))
+        );
+        env::set_var("HUMANTIME_TEST_BAD_TIMESTAMP", "2018-02-14");
+        assert_eq!(
+            timestamp("HUMANTIME_TEST_BAD_TIMESTAMP"),
+            Err(Error::Timestamp {
+                name: "HUMANTIME_TEST_BAD_TIMESTAMP".to_owned(),
+                error: TimestampError::InvalidFormat,
+            })
+        );
+        env::set_var("HUMANTIME_TEST_EMPTY", "");
+        assert_eq!(
+            duration("HUMANTIME_TEST_EMPTY"),
+            Err(Error::Duration {
+                name: "HUMANTIME_TEST_EMPTY".to_owned(),
+                error: DurationError::Empty,
+            })
+        );
+    }
+}
@@ -0,0 +1,319 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::date::{self, format_rfc3339, parse_rfc3339_weak};
+use crate::duration::{self, format_duration, parse_duration};
+
+/// Error parsing an expiry
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// The value looks like a duration, but is not valid
+    Duration(duration::Error),
+    /// The value looks like a timestamp, but is not valid
+    Timestamp(date::Error),
+    /// The Redis option is not one of `EX`, `PX`, `EXAT`, `PXAT`
+    RedisOption(String),
+    /// The Redis expire time is not a positive integer
+    RedisValue,
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Duration(e) => Some(e),
+            Error::Timestamp(e) => Some(e),
+            Error::RedisOption(_) | Error::RedisValue => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Duration(e) => write!(f, "invalid expiry duration: {}", e),
+            Error::Timestamp(e) => write!(f, "invalid expiry timestamp: {}", e),
+            Error::RedisOption(o) => write!(
+                f,
+                "unknown redis expiry option {:?}, expected EX, PX, EXAT or PXAT",
+                o
+            ),
+            Error::RedisValue => write!(f, "invalid expire time in redis expiry"),
+        }
+    }
+}
+
+/// Expiration time given either relative to "now" or as a timestamp
+///
+/// Parses either a duration (`30d`, see `parse_duration`) or a timestamp
+/// (`2025-01-01T00:00:00Z`, see `parse_rfc3339_weak`). Values starting
+/// with a date, e.g. `2025-01-01`, are always treated as timestamps.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::Expiry;
+///
+/// let now = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// let ttl: Expiry = "30d".parse().unwrap();
+/// assert_eq!(ttl.deadline(now), Some(now + Duration::from_secs(30 * 86400)));
+///
+/// let at: Expiry = "2025-01-01T00:00:00Z".parse().unwrap();
+/// assert_eq!(at.deadline(now), Some(UNIX_EPOCH + Duration::from_secs(1_735_689_600)));
+/// assert!(!at.is_expired(now));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Expiry {
+    /// Expires after the duration passes
+    After(Duration),
+    /// Expires at the specified time
+    At(SystemTime),
+}
+
+fn looks_like_date(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() >= 5 && b[..4].iter().all(u8::is_ascii_digit) && b[4] == b'-'
+}
+
+impl Expiry {
+    /// Returns the absolute deadline relative to `now`
+    ///
+    /// Returns `None` if the deadline can't be represented by `SystemTime`.
+    pub fn deadline(&self, now: SystemTime) -> Option<SystemTime> {
+        match *self {
+            Expiry::After(d) => now.checked_add(d),
+            Expiry::At(t) => Some(t),
+        }
+    }
+
+    /// Returns the time left until the deadline, zero if it has passed
+    pub fn remaining(&self, now: SystemTime) -> Duration {
+        match *self {
+            Expiry::After(d) => d,
+            Expiry::At(t) => t.duration_since(now).unwrap_or(Duration::ZERO),
+        }
+    }
+
+    /// Returns `true` if the deadline is at or before `now`
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.remaining(now) == Duration::ZERO
+    }
+
+    /// Converts an expiry option of a Redis command, like `EX 60`
+    ///
+    /// `EX` and `PX` are relative seconds and milliseconds, `EXAT` and
+    /// `PXAT` are absolute Unix time in seconds and milliseconds. The option
+    /// is case-insensitive, and like in Redis, the value must be a positive
+    /// integer.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::{Duration, UNIX_EPOCH};
+    /// use humantime::Expiry;
+    ///
+    /// let ttl = Expiry::from_redis("EX", "60").unwrap();
+    /// assert_eq!(ttl, Expiry::After(Duration::from_secs(60)));
+    /// let at = Expiry::from_redis("pxat", "1700000000500").unwrap();
+    /// assert_eq!(at, Expiry::At(UNIX_EPOCH + Duration::from_millis(1_700_000_000_500)));
+    /// ```
+    pub fn from_redis(option: &str, value: &str) -> Result<Expiry, Error> {
+        let value = value
+            .parse::<i64>()
+            .ok()
+            .filter(|&v| v > 0)
+            .ok_or(Error::RedisValue)? as u64;
+        let option = option.to_ascii_uppercase();
+        let expiry = match &option[..] {
+            "EX" => Expiry::After(Duration::from_secs(value)),
+            "PX" => Expiry::After(Duration::from_millis(value)),
+            "EXAT" => UNIX_EPOCH
+                .checked_add(Duration::from_secs(value))
+                .map(Expiry::At)
+                .ok_or(Error::RedisValue)?,
+            "PXAT" => UNIX_EPOCH
+                .checked_add(Duration::from_millis(value))
+                .map(Expiry::At)
+                .ok_or(Error::RedisValue)?,
+            _ => return Err(Error::RedisOption(option)),
+        };
+        Ok(expiry)
+    }
+
+    /// Returns the Redis option and value for this expiry
+    ///
+    /// The option is `PX` or `PXAT`, milliseconds keep the precision of
+    /// most expiries, and the rest is truncated. Returns `None` if Redis
+    /// would reject the value: a duration shorter than a millisecond, a
+    /// time before 1970-01-01 00:00:00.001, or a value beyond `i64`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use humantime::Expiry;
+    ///
+    /// let ttl: Expiry = "1h 30m".parse().unwrap();
+    /// assert_eq!(ttl.to_redis(), Some(("PX", 5_400_000)));
+    /// ```
+    pub fn to_redis(&self) -> Option<(&'static str, i64)> {
+        let (option, ms) = match *self {
+            Expiry::After(d) => ("PX", d.as_millis()),
+            Expiry::At(t) => ("PXAT", t.duration_since(UNIX_EPOCH).ok()?.as_millis()),
+        };
+        if ms == 0 || ms > i64::MAX as u128 {
+            return None;
+        }
+        Some((option, ms as i64))
+    }
+}
+
+impl From<Duration> for Expiry {
+    fn from(d: Duration) -> Expiry {
+        Expiry::After(d)
+    }
+}
+
+impl From<SystemTime> for Expiry {
+    fn from(t: SystemTime) -> Expiry {
+        Expiry::At(t)
+    }
+}
+
+impl FromStr for Expiry {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Expiry, Error> {
+        let s = s.trim();
+        if looks_like_date(s) {
+            parse_rfc3339_weak(s)
+                .map(Expiry::At)
+                .map_err(Error::Timestamp)
+        } else {
+            parse_duration(s)
+                .map(Expiry::After)
+                .map_err(Error::Duration)
+        }
+    }
+}
+
+impl fmt::Display for Expiry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Expiry::After(d) => format_duration(d).fmt(f),
+            Expiry::At(t) => format_rfc3339(t).fmt(f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{Error, Expiry};
+    use crate::date::Error as TimestampError;
+    use crate::duration::Error as DurationError;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "1h 30m".parse(),
+            Ok(Expiry::After(Duration::from_secs(5400)))
+        );
+        assert_eq!(
+            " 2018-02-13 23:08:32 ".parse(),
+            Ok(Expiry::At(UNIX_EPOCH + Duration::from_secs(1_518_563_312)))
+        );
+        assert_eq!(
+            "2018-02-13".parse::<Expiry>(),
+            Err(Error::Timestamp(TimestampError::InvalidFormat))
+        );
+        assert_eq!(
+            "2018 days".parse(),
+            Ok(Expiry::After(Duration::from_secs(2018 * 86400)))
+        );
+        assert_eq!(
+            "".parse::<Expiry>(),
+            Err(Error::Duration(DurationError::Empty))
+        );
+    }
+
+    #[test]
+    fn deadline() {
+        let now = UNIX_EPOCH + Duration::from_secs(1000);
+        let ttl = Expiry::After(Duration::from_secs(10));
+        assert_eq!(ttl.deadline(now), Some(now + Duration::from_secs(10)));
+        assert_eq!(ttl.remaining(now), Duration::from_secs(10));
+        assert!(!ttl.is_expired(now));
+        assert_eq!(Expiry::After(Duration::MAX).deadline(now), None);
+
+        let at = Expiry::At(UNIX_EPOCH + Duration::from_secs(500));
+        assert_eq!(at.remaining(now), Duration::ZERO);
+        assert!(at.is_expired(now));
+        assert!(Expiry::At(now).is_expired(now));
+        assert!(Expiry::After(Duration::ZERO).is_expired(now));
+    }
+
+    #[test]
+    fn redis() {
+        let secs = |s| UNIX_EPOCH + Duration::from_secs(s);
+        assert_eq!(
+            Expiry::from_redis("ex", "10"),
+            Ok(Expiry::After(Duration::from_secs(10)))
+        );
+        assert_eq!(
+            Expiry::from_redis("Px", "1500"),
+            Ok(Expiry::After(Duration::from_millis(1500)))
+        );
+        assert_eq!(
+            Expiry::from_redis("EXAT", "1000"),
+            Ok(Expiry::At(secs(1000)))
+        );
+        assert_eq!(Expiry::from_redis("PXAT", "1000"), Ok(Expiry::At(secs(1))));
+        assert_eq!(Expiry::from_redis("EX", "0"), Err(Error::RedisValue));
+        assert_eq!(Expiry::from_redis("EX", "-5"), Err(Error::RedisValue));
+        assert_eq!(Expiry::from_redis("EX", "1.5"), Err(Error::RedisValue));
+        assert_eq!(Expiry::from_redis("EX", " 5"), Err(Error::RedisValue));
+        assert_eq!(
+            Expiry::from_redis("KEEPTTL", "5"),
+            Err(Error::RedisOption("KEEPTTL".to_owned()))
+        );
+        assert_eq!(
+            Expiry::from_redis("px", "9223372036854775808"),
+            Err(Error::RedisValue)
+        );
+        // out of range where `SystemTime` is narrower, like on Windows
+        let max = Duration::from_millis(i64::MAX as u64);
+        assert_eq!(
+            Expiry::from_redis("PXAT", "9223372036854775807").is_ok(),
+            UNIX_EPOCH.checked_add(max).is_some()
+        );
+    }
+
+    #[test]
+    fn to_redis() {
+        assert_eq!(
+            Expiry::After(Duration::from_secs(2)).to_redis(),
+            Some(("PX", 2000))
+        );
+        assert_eq!(
+            Expiry::At(UNIX_EPOCH + Duration::from_secs(2)).to_redis(),
+            Some(("PXAT", 2000))
+        );
+        assert_eq!(Expiry::After(Duration::from_micros(999)).to_redis(), None);
+        assert_eq!(Expiry::At(UNIX_EPOCH).to_redis(), None);
+        assert_eq!(Expiry::After(Duration::MAX).to_redis(), None);
+        for &(option, value) in &[("PX", 1), ("PXAT", 1_700_000_000_123)] {
+            let expiry = Expiry::from_redis(option, &value.to_string()).unwrap();
+            assert_eq!(expiry.to_redis(), Some((option, value)));
+        }
+    }
+
+    #[test]
+    fn display() {
+        for input in &["30days", "2018-02-13T23:08:32Z"] {
+            assert_eq!(input.parse::<Expiry>().unwrap().to_string(), *input);
+        }
+    }
+}
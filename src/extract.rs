@@ -0,0 +1,413 @@
+use std::ops::Range;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::date::parse_rfc3339_weak;
+use crate::duration::parse_duration;
+use crate::grammar;
+
+const WEEKDAYS: &[&str] = &["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const MONTHS: &[&str] = &[
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+fn is_unit_char(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == 'µ'
+}
+
+/// Length of the leading part of `s` matching `f`
+fn prefix_len(s: &str, f: impl Fn(char) -> bool) -> usize {
+    s.find(|c| !f(c)).unwrap_or(s.len())
+}
+
+/// Length of spaces and tabs at the start of `s`
+fn blank_len(s: &str) -> usize {
+    prefix_len(s, |c| c == ' ' || c == '\t')
+}
+
+/// End of a component like `1.5 hours` at the start of `s`
+fn component(s: &str) -> Option<usize> {
+    let mut end = prefix_len(s, |c| c.is_ascii_digit());
+    if end == 0 {
+        return None;
+    }
+    if s[end..].starts_with('.') {
+        let frac = prefix_len(&s[end + 1..], |c| c.is_ascii_digit());
+        if frac == 0 {
+            return None;
+        }
+        end += 1 + frac;
+    }
+    end += blank_len(&s[end..]);
+    let unit = prefix_len(&s[end..], is_unit_char);
+    grammar::unit_named(&s[end..end + unit])?;
+    end += unit;
+    match s[end..].chars().next() {
+        Some(c) if (c.is_alphanumeric() && !c.is_ascii_digit()) || c == '_' => None,
+        _ => Some(end),
+    }
+}
+
+/// End of a duration like `1h 30min` at the start of `s`
+fn expression(s: &str) -> Option<usize> {
+    let mut end = component(s)?;
+    loop {
+        let next = end + blank_len(&s[end..]);
+        match component(&s[next..]) {
+            Some(len) => end = next + len,
+            None => break,
+        }
+    }
+    match s[end..].chars().next() {
+        Some(c) if c.is_ascii_digit() => None,
+        _ => Some(end),
+    }
+}
+
+/// Reads timestamps, keeping the position in the input
+struct Cursor<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl Cursor<'_> {
+    fn rest(&self) -> &str {
+        &self.s[self.pos..]
+    }
+
+    fn literal(&mut self, prefix: &str) -> Option<()> {
+        if self.rest().starts_with(prefix) {
+            self.pos += prefix.len();
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// A number of `min..=max` digits
+    fn number(&mut self, min: usize, max: usize) -> Option<u64> {
+        let len = prefix_len(self.rest(), |c| c.is_ascii_digit());
+        if len < min || len > max {
+            return None;
+        }
+        let value = self.rest()[..len].parse().ok()?;
+        self.pos += len;
+        Some(value)
+    }
+
+    /// Index of the word of `words` the input starts with
+    fn word(&mut self, words: &[&str]) -> Option<usize> {
+        let idx = words.iter().position(|w| self.rest().starts_with(w))?;
+        self.pos += words[idx].len();
+        Some(idx)
+    }
+
+    /// `Z` or an offset like `+05:30`, `+0530` with `colon` false, in
+    /// seconds east of UTC
+    fn offset(&mut self, colon: bool) -> Option<i64> {
+        let sign = match self.rest().chars().next()? {
+            '+' => 1,
+            '-' => -1,
+            _ => return None,
+        };
+        self.pos += 1;
+        let (hours, minutes) = if colon {
+            let hours = self.number(2, 2)?;
+            self.literal(":")?;
+            (hours, self.number(2, 2)?)
+        } else {
+            let value = self.number(4, 4)?;
+            (value / 100, value % 100)
+        };
+        if hours > 23 || minutes > 59 {
+            return None;
+        }
+        Some(sign * (hours * 3600 + minutes * 60) as i64)
+    }
+
+    /// Checks that the timestamp isn't followed by more of a word
+    fn end(&self) -> Option<usize> {
+        match self.rest().chars().next() {
+            Some(c) if c.is_alphanumeric() || c == '_' => None,
+            _ => Some(self.pos),
+        }
+    }
+}
+
+fn apply_offset(time: SystemTime, offset: i64) -> Option<SystemTime> {
+    let shift = Duration::from_secs(offset.unsigned_abs());
+    if offset > 0 {
+        time.checked_sub(shift)
+    } else {
+        time.checked_add(shift)
+    }
+}
+
+/// `2018-02-14T00:28:07.5+01:00`, or without the offset
+fn rfc3339(c: &mut Cursor<'_>) -> Option<SystemTime> {
+    let start = c.pos;
+    c.number(4, 4)?;
+    c.literal("-")?;
+    c.number(2, 2)?;
+    c.literal("-")?;
+    c.number(2, 2)?;
+    c.literal("T").or_else(|| c.literal(" "))?;
+    c.number(2, 2)?;
+    c.literal(":")?;
+    c.number(2, 2)?;
+    c.literal(":")?;
+    c.number(2, 2)?;
+    if c.literal(".").is_some() {
+        c.number(1, usize::MAX)?;
+    }
+    let time = parse_rfc3339_weak(&c.s[start..c.pos]).ok()?;
+    let offset = if c.literal("Z").is_some() {
+        0
+    } else {
+        let before = c.pos;
+        c.offset(true).unwrap_or_else(|| {
+            c.pos = before;
+            0
+        })
+    };
+    apply_offset(time, offset)
+}
+
+/// `Wed, 14 Feb 2018 00:28:07 +0000`, the weekday and seconds are optional
+fn rfc2822(c: &mut Cursor<'_>) -> Option<SystemTime> {
+    if c.word(WEEKDAYS).is_some() {
+        c.literal(", ")?;
+    }
+    let day = c.number(1, 2)?;
+    c.literal(" ")?;
+    let month = c.word(MONTHS)? + 1;
+    c.literal(" ")?;
+    let year = c.number(4, 4)?;
+    c.literal(" ")?;
+    let hour = c.number(2, 2)?;
+    c.literal(":")?;
+    let minute = c.number(2, 2)?;
+    let second = match c.literal(":") {
+        Some(()) => c.number(2, 2)?,
+        None => 0,
+    };
+    c.literal(" ")?;
+    let offset = match c.word(&["GMT", "UTC", "UT", "Z"]) {
+        Some(_) => 0,
+        None => c.offset(false)?,
+    };
+    let time = format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, hour, minute, second
+    );
+    apply_offset(parse_rfc3339_weak(&time).ok()?, offset)
+}
+
+/// Seconds since the epoch with ten digits and an optional fraction,
+/// like `1518567287.25`, or milliseconds with thirteen digits
+fn epoch(c: &mut Cursor<'_>) -> Option<SystemTime> {
+    if let Some(millis) = c.number(13, 13) {
+        return UNIX_EPOCH.checked_add(Duration::from_millis(millis));
+    }
+    let secs = c.number(10, 10)?;
+    let mut nanos = 0;
+    if c.literal(".").is_some() {
+        let start = c.pos;
+        c.number(1, 9)?;
+        let digits = &c.s[start..c.pos];
+        nanos = digits.parse::<u32>().ok()? * 10u32.pow(9 - digits.len() as u32);
+    }
+    UNIX_EPOCH.checked_add(Duration::new(secs, nanos))
+}
+
+/// Finds the durations in free text, like log lines or chat messages
+///
+/// Returns the byte range and the value of each duration found. A
+/// duration is a sequence of components accepted by [`parse_duration`],
+/// like `1h 30min` or `250ms`, standing as a separate word: `v2s` or
+/// `3seconds_total` don't contain durations. Numbers without a unit and
+/// values too large to represent are skipped.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::extract_durations;
+///
+/// let text = "request took 1.5s (retried after 2 min 30s), timeout is 10s";
+/// let found = extract_durations(text);
+/// assert_eq!(found.len(), 3);
+/// assert_eq!(&text[found[1].0.clone()], "2 min 30s");
+/// assert_eq!(found[1].1, Duration::from_secs(150));
+/// ```
+pub fn extract_durations(text: &str) -> Vec<(Range<usize>, Duration)> {
+    let mut found = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut pos = 0;
+    while let Some(c) = text[pos..].chars().next() {
+        let boundary = !matches!(prev, Some(p) if p == '.' || p == '_' || p.is_alphanumeric());
+        if c.is_ascii_digit() && boundary {
+            if let Some(len) = expression(&text[pos..]) {
+                if let Ok(duration) = parse_duration(&text[pos..pos + len]) {
+                    found.push((pos..pos + len, duration));
+                }
+                prev = text[..pos + len].chars().next_back();
+                pos += len;
+                continue;
+            }
+        }
+        prev = Some(c);
+        pos += c.len_utf8();
+    }
+    found
+}
+
+/// Finds the timestamps in free text, like log lines
+///
+/// Returns the byte range and the value of each timestamp found. These
+/// forms are recognized, standing as separate words:
+///
+/// * RFC 3339, like `2018-02-14T00:28:07Z` or `2018-02-14 01:28:07.5+01:00`,
+///   timestamps without an offset are taken as UTC
+/// * RFC 2822, like `Wed, 14 Feb 2018 00:28:07 +0000`
+/// * seconds since the Unix epoch with ten digits, like `1518567287` or
+///   `1518567287.250`, and milliseconds with thirteen digits
+///
+/// Other numbers, including epoch seconds with another number of digits,
+/// are skipped as they can't be told apart from counters or identifiers.
+///
+/// # Example
+///
+/// ```
+/// use humantime::{extract_timestamps, parse_rfc3339};
+///
+/// let line = "[2018-02-14 00:28:07Z] job 17 started at 1518567287.5";
+/// let found = extract_timestamps(line);
+/// assert_eq!(found.len(), 2);
+/// assert_eq!(found[0].0, 1..21);
+/// assert_eq!(found[0].1, parse_rfc3339("2018-02-14T00:28:07Z").unwrap());
+/// assert_eq!(found[1].1, parse_rfc3339("2018-02-14T00:14:47.5Z").unwrap());
+/// ```
+pub fn extract_timestamps(text: &str) -> Vec<(Range<usize>, SystemTime)> {
+    let parsers: [fn(&mut Cursor<'_>) -> Option<SystemTime>; 3] = [rfc3339, rfc2822, epoch];
+    let mut found = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut pos = 0;
+    'outer: while let Some(c) = text[pos..].chars().next() {
+        let boundary = !matches!(prev, Some(p) if p == '.' || p == '_' || p.is_alphanumeric());
+        if c.is_ascii_alphanumeric() && boundary {
+            for parse in &parsers {
+                let mut cursor = Cursor { s: text, pos };
+                if let Some(time) = parse(&mut cursor) {
+                    if let Some(end) = cursor.end() {
+                        found.push((pos..end, time));
+                        prev = text[..end].chars().next_back();
+                        pos = end;
+                        continue 'outer;
+                    }
+                }
+            }
+        }
+        prev = Some(c);
+        pos += c.len_utf8();
+    }
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{extract_durations, extract_timestamps};
+    use crate::date::parse_rfc3339;
+
+    fn spans(text: &str) -> Vec<&str> {
+        extract_durations(text)
+            .into_iter()
+            .map(|(span, _)| &text[span])
+            .collect()
+    }
+
+    #[test]
+    fn found() {
+        assert_eq!(spans("1h30m"), ["1h30m"]);
+        assert_eq!(spans("took 250ms, then 3 seconds."), ["250ms", "3 seconds"]);
+        assert_eq!(spans("(5s)\t[2h 1m]"), ["5s", "2h 1m"]);
+        assert_eq!(spans("wait 10 µs\nwait 1.5 hours"), ["10 µs", "1.5 hours"]);
+        assert_eq!(
+            extract_durations("eta: 1d 2h"),
+            [(5..10, Duration::from_secs(93600))]
+        );
+    }
+
+    #[test]
+    fn skipped() {
+        assert!(spans("v2s 3seconds_total x5m 1.2.3s").is_empty());
+        assert!(spans("2018-02-14 00:28:07 at 12 apples").is_empty());
+        assert!(spans("1.5 and 2h3").is_empty());
+        assert!(spans("99999999999999999999999 years").is_empty());
+        assert_eq!(spans("5 sec\n10 min"), ["5 sec", "10 min"]);
+        assert_eq!(spans("1.5s."), ["1.5s"]);
+    }
+
+    fn timestamps(text: &str) -> Vec<(&str, String)> {
+        extract_timestamps(text)
+            .into_iter()
+            .map(|(span, time)| (&text[span], crate::format_rfc3339(time).to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn rfc3339_found() {
+        assert_eq!(
+            timestamps("at 2018-02-14T00:28:07Z, 2018-02-14 02:28:07.25+02:00."),
+            [
+                ("2018-02-14T00:28:07Z", "2018-02-14T00:28:07Z".to_owned()),
+                (
+                    "2018-02-14 02:28:07.25+02:00",
+                    "2018-02-14T00:28:07.250000000Z".to_owned()
+                ),
+            ]
+        );
+        assert_eq!(
+            timestamps("ts=2018-02-14T00:28:07 level=info"),
+            [("2018-02-14T00:28:07", "2018-02-14T00:28:07Z".to_owned())]
+        );
+        assert_eq!(
+            extract_timestamps("2018-02-14T05:58:07+05:30"),
+            [(0..25, parse_rfc3339("2018-02-14T00:28:07Z").unwrap())]
+        );
+    }
+
+    #[test]
+    fn rfc2822_found() {
+        assert_eq!(
+            timestamps("Date: Wed, 14 Feb 2018 01:28:07 +0100\r\n"),
+            [(
+                "Wed, 14 Feb 2018 01:28:07 +0100",
+                "2018-02-14T00:28:07Z".to_owned()
+            )]
+        );
+        assert_eq!(
+            timestamps("sent 4 Mar 2019 10:00 GMT"),
+            [("4 Mar 2019 10:00 GMT", "2019-03-04T10:00:00Z".to_owned())]
+        );
+    }
+
+    #[test]
+    fn epoch_found() {
+        assert_eq!(
+            timestamps("t=1518567287 ms=1518567287250"),
+            [
+                ("1518567287", "2018-02-14T00:14:47Z".to_owned()),
+                ("1518567287250", "2018-02-14T00:14:47.250000000Z".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn timestamps_skipped() {
+        let text = "id 151856728 v1518567287 15185672870 2018-13-01T00:00:00Z \
+            2018-02-14T00:28 14 Foo 2018 00:00 GMT 2018-02-14T00:28:07Zulu";
+        assert!(timestamps(text).is_empty());
+    }
+}
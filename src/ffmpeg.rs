@@ -0,0 +1,296 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::dialect::Error;
+use crate::duration::{self, format_duration_with, parse_duration, FormattedDuration, Style};
+use crate::postgres::write_fraction;
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Parse duration in the syntax of ffmpeg's `-ss` and `-t` options
+///
+/// Two forms are accepted:
+///
+/// * `[HH:]MM:SS[.m...]` -- sexagesimal, minutes and seconds must be below
+///   60, hours are optional
+/// * `S+[.m...][s|ms|us]` -- a number of seconds (or milliseconds or
+///   microseconds, if the suffix is given) with optional fraction
+///
+/// Digits of the fraction below nanoseconds are ignored. Negative values
+/// are not supported.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::parse_duration_ffmpeg;
+///
+/// assert_eq!(parse_duration_ffmpeg("00:01:23.456"), Ok(Duration::from_millis(83_456)));
+/// assert_eq!(parse_duration_ffmpeg("1:23.456"), Ok(Duration::from_millis(83_456)));
+/// assert_eq!(parse_duration_ffmpeg("83.456"), Ok(Duration::from_millis(83_456)));
+/// assert_eq!(parse_duration_ffmpeg("200ms"), Ok(Duration::from_millis(200)));
+/// ```
+pub fn parse_duration_ffmpeg(s: &str) -> Result<Duration, Error> {
+    if s.is_empty() {
+        return Err(Error::Empty);
+    }
+    let bytes = s.as_bytes();
+    let mut pos = 0;
+    let mut fields = [(0u128, 0usize); 3];
+    let mut count = 0;
+    loop {
+        let start = pos;
+        let mut value = 0u128;
+        while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+            value = value
+                .checked_mul(10)
+                .and_then(|v| v.checked_add(u128::from(bytes[pos] - b'0')))
+                .ok_or(Error::NumberOverflow)?;
+            pos += 1;
+        }
+        if pos == start {
+            return Err(match bytes.get(pos) {
+                Some(b) if !b.is_ascii_alphabetic() && *b != b'.' && *b != b':' => {
+                    Error::InvalidCharacter(pos)
+                }
+                _ => Error::NumberExpected(pos),
+            });
+        }
+        fields[count] = (value, pos - start);
+        count += 1;
+        if bytes.get(pos) != Some(&b':') {
+            break;
+        }
+        if count == fields.len() {
+            return Err(Error::InvalidCharacter(pos));
+        }
+        pos += 1;
+    }
+    let mut fraction = 0u128;
+    if bytes.get(pos) == Some(&b'.') {
+        pos += 1;
+        let mut scale = NANOS_PER_SEC;
+        while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+            scale /= 10;
+            fraction += u128::from(bytes[pos] - b'0') * scale;
+            pos += 1;
+        }
+    }
+    let mut divisor = 1;
+    if count == 1 && pos < bytes.len() {
+        let unit_start = pos;
+        while pos < bytes.len() && bytes[pos].is_ascii_alphabetic() {
+            pos += 1;
+        }
+        if pos < bytes.len() {
+            return Err(Error::InvalidCharacter(pos));
+        }
+        divisor = match &s[unit_start..] {
+            "s" => 1,
+            "ms" => 1000,
+            "us" => 1_000_000,
+            unit => {
+                return Err(Error::UnknownUnit {
+                    start: unit_start,
+                    end: pos,
+                    unit: unit.to_owned(),
+                })
+            }
+        };
+    } else if pos < bytes.len() {
+        return Err(Error::InvalidCharacter(pos));
+    }
+    let mut secs = 0u128;
+    for (idx, &(value, digits)) in fields[..count].iter().enumerate() {
+        // minutes and seconds are limited to two digits and 0..59
+        let is_hours = count == 3 && idx == 0;
+        if count > 1 && !is_hours && (digits > 2 || value >= 60) {
+            return Err(Error::NumberOverflow);
+        }
+        secs = secs
+            .checked_mul(60)
+            .and_then(|s| s.checked_add(value))
+            .ok_or(Error::NumberOverflow)?;
+    }
+    let nanos = secs
+        .checked_mul(NANOS_PER_SEC)
+        .and_then(|n| n.checked_add(fraction))
+        .ok_or(Error::NumberOverflow)?
+        / divisor;
+    let secs = u64::try_from(nanos / NANOS_PER_SEC).map_err(|_| Error::NumberOverflow)?;
+    Ok(Duration::new(secs, (nanos % NANOS_PER_SEC) as u32))
+}
+
+/// Parse duration either in ffmpeg syntax or in the usual free form
+///
+/// Values consisting only of digits, colons and periods, like `1:23.5`
+/// or `83.5`, are parsed with [`parse_duration_ffmpeg`], anything else
+/// with [`parse_duration`](crate::parse_duration), so both `00:01:30` and
+/// `1min 30s` are accepted.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::parse_duration_media;
+///
+/// assert_eq!(parse_duration_media("00:01:30"), Ok(Duration::from_secs(90)));
+/// assert_eq!(parse_duration_media("90"), Ok(Duration::from_secs(90)));
+/// assert_eq!(parse_duration_media("1min 30s"), Ok(Duration::from_secs(90)));
+/// ```
+pub fn parse_duration_media(s: &str) -> Result<Duration, duration::Error> {
+    if s.is_empty()
+        || !s
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b == b':' || b == b'.')
+    {
+        return parse_duration(s);
+    }
+    parse_duration_ffmpeg(s).map_err(|e| match e {
+        Error::InvalidCharacter(pos)
+        | Error::UnknownUnit { start: pos, .. }
+        | Error::UnitOrder { start: pos, .. } => duration::Error::InvalidCharacter(pos),
+        Error::NumberExpected(pos) => duration::Error::NumberExpected(pos),
+        Error::NumberOverflow => duration::Error::NumberOverflow,
+        Error::Empty => duration::Error::Empty,
+    })
+}
+
+/// Formats duration in the sexagesimal ffmpeg syntax, like `00:01:23.456`
+///
+/// Hours are always written and may exceed two digits, precision below
+/// microseconds is truncated.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::format_duration_ffmpeg;
+///
+/// let val = Duration::from_millis(83_456);
+/// assert_eq!(format_duration_ffmpeg(val).to_string(), "00:01:23.456");
+/// ```
+pub fn format_duration_ffmpeg(val: Duration) -> FormattedDuration {
+    format_duration_with(val, Style::Ffmpeg)
+}
+
+pub(crate) fn fmt_ffmpeg(val: Duration, f: &mut fmt::Formatter) -> fmt::Result {
+    let secs = val.as_secs();
+    write!(
+        f,
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )?;
+    write_fraction(f, val.subsec_micros())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{format_duration_ffmpeg, parse_duration_ffmpeg, parse_duration_media};
+    use crate::dialect::Error;
+    use crate::duration::Error as DurationError;
+
+    #[test]
+    fn parse_sexagesimal() {
+        let parse = parse_duration_ffmpeg;
+        assert_eq!(parse("00:00:00"), Ok(Duration::ZERO));
+        assert_eq!(parse("01:02:03"), Ok(Duration::from_secs(3723)));
+        assert_eq!(parse("1:2:3"), Ok(Duration::from_secs(3723)));
+        assert_eq!(parse("123:00:00"), Ok(Duration::from_secs(123 * 3600)));
+        assert_eq!(parse("02:03"), Ok(Duration::from_secs(123)));
+        assert_eq!(parse("02:03.5"), Ok(Duration::from_millis(123_500)));
+        assert_eq!(
+            parse("00:00:00.1234567891"),
+            Ok(Duration::new(0, 123_456_789))
+        );
+    }
+
+    #[test]
+    fn parse_seconds() {
+        let parse = parse_duration_ffmpeg;
+        assert_eq!(parse("0"), Ok(Duration::ZERO));
+        assert_eq!(parse("83"), Ok(Duration::from_secs(83)));
+        assert_eq!(parse("83."), Ok(Duration::from_secs(83)));
+        assert_eq!(parse("3600"), Ok(Duration::from_secs(3600)));
+        assert_eq!(parse("83.456s"), Ok(Duration::from_millis(83_456)));
+        assert_eq!(parse("1.5ms"), Ok(Duration::from_micros(1500)));
+        assert_eq!(parse("250us"), Ok(Duration::from_micros(250)));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let parse = parse_duration_ffmpeg;
+        assert_eq!(parse(""), Err(Error::Empty));
+        assert_eq!(parse(".5"), Err(Error::NumberExpected(0)));
+        assert_eq!(parse("s"), Err(Error::NumberExpected(0)));
+        assert_eq!(parse("-5"), Err(Error::InvalidCharacter(0)));
+        assert_eq!(parse("1::2"), Err(Error::NumberExpected(2)));
+        assert_eq!(parse("1:2:3:4"), Err(Error::InvalidCharacter(5)));
+        assert_eq!(parse("1.5:00"), Err(Error::InvalidCharacter(3)));
+        assert_eq!(parse("00:60"), Err(Error::NumberOverflow));
+        assert_eq!(parse("90:00"), Err(Error::NumberOverflow));
+        assert_eq!(parse("00:00:60"), Err(Error::NumberOverflow));
+        assert_eq!(parse("00:000:00"), Err(Error::NumberOverflow));
+        assert_eq!(parse("00:01s"), Err(Error::InvalidCharacter(5)));
+        assert_eq!(parse("5 s"), Err(Error::InvalidCharacter(1)));
+        assert_eq!(
+            parse("5min"),
+            Err(Error::UnknownUnit {
+                start: 1,
+                end: 4,
+                unit: "min".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn media() {
+        assert_eq!(parse_duration_media("1:30"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration_media("1.5"), Ok(Duration::from_millis(1500)));
+        assert_eq!(
+            parse_duration_media("1h 30m"),
+            Ok(Duration::from_secs(5400))
+        );
+        assert_eq!(
+            parse_duration_media("500ms"),
+            Ok(Duration::from_millis(500))
+        );
+        assert_eq!(parse_duration_media(""), Err(DurationError::Empty));
+        assert_eq!(
+            parse_duration_media("1:2:3:4"),
+            Err(DurationError::InvalidCharacter(5))
+        );
+        assert_eq!(
+            parse_duration_media("1:60"),
+            Err(DurationError::NumberOverflow)
+        );
+    }
+
+    #[test]
+    fn format() {
+        let fmt = |d| format_duration_ffmpeg(d).to_string();
+        assert_eq!(fmt(Duration::ZERO), "00:00:00");
+        assert_eq!(fmt(Duration::from_millis(83_456)), "00:01:23.456");
+        assert_eq!(fmt(Duration::from_secs(100 * 3600)), "100:00:00");
+        assert_eq!(fmt(Duration::new(1, 5_000)), "00:00:01.000005");
+        for input in &["01:02:03", "00:00:00.5", "12:34:56.789012"] {
+            let d = parse_duration_ffmpeg(input).unwrap();
+            assert_eq!(fmt(d), *input);
+        }
+    }
+
+    #[test]
+    fn max_round_trip() {
+        for &d in &[
+            Duration::new(u64::MAX, 999_999_000),
+            Duration::new(u64::MAX, 0),
+        ] {
+            let text = format_duration_ffmpeg(d).to_string();
+            assert_eq!(parse_duration_ffmpeg(&text), Ok(d), "{}", text);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::Duration;
+
+use crate::duration::{self, format_duration, parse_duration_nanos, FormattedDuration};
+
+/// Error converting floating point seconds to a duration
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// The value is NaN or infinite
+    NotFinite,
+    /// The value is less than zero, or is negative zero
+    Negative,
+    /// The value is larger than a duration can hold
+    Overflow,
+}
+
+impl StdError for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFinite => write!(f, "number of seconds is not finite"),
+            Error::Negative => write!(f, "number of seconds is negative"),
+            Error::Overflow => write!(f, "number of seconds is too large"),
+        }
+    }
+}
+
+/// Parses a duration into floating point seconds
+///
+/// The duration is parsed exactly, as [`parse_duration_nanos`] does, and
+/// then rounded to the nearest `f64`. Durations up to about 104 days keep
+/// nanosecond precision, longer ones lose some of the last digits. Values
+/// near `Duration::MAX` round up to 2<sup>64</sup> seconds, which
+/// [`duration_from_secs_f64`] rejects, use [`parse_duration_nanos`] where
+/// they have to be exact.
+///
+/// # Example
+///
+/// ```
+/// use humantime::parse_duration_secs_f64;
+///
+/// assert_eq!(parse_duration_secs_f64("1min 1.5s"), Ok(61.5));
+/// assert_eq!(parse_duration_secs_f64("250ms"), Ok(0.25));
+/// ```
+pub fn parse_duration_secs_f64(s: &str) -> Result<f64, duration::Error> {
+    let nanos = parse_duration_nanos(s)?;
+    let secs = nanos / 1_000_000_000;
+    let subsec = (nanos % 1_000_000_000) as f64 / 1e9;
+    Ok(secs as f64 + subsec)
+}
+
+/// Converts floating point seconds to a duration
+///
+/// The value is rounded to the nearest nanosecond, halfway values away
+/// from zero. Whole seconds and the fraction are rounded separately, so a
+/// fraction which rounds up to a full second, like in `1.9999999999`, is
+/// carried into the seconds. Unlike `Duration::from_secs_f64`, the rule
+/// doesn't depend on the Rust version and the function doesn't panic:
+/// NaN, infinities and negative values are rejected. Negative zero is
+/// rejected too, as it usually comes from a computation which went wrong,
+/// like rounding a small negative value.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::{duration_from_secs_f64, FloatSecsError};
+///
+/// assert_eq!(duration_from_secs_f64(1.5), Ok(Duration::from_millis(1500)));
+/// assert_eq!(duration_from_secs_f64(1.9999999999), Ok(Duration::from_secs(2)));
+/// assert_eq!(duration_from_secs_f64(f64::NAN), Err(FloatSecsError::NotFinite));
+/// ```
+pub fn duration_from_secs_f64(secs: f64) -> Result<Duration, Error> {
+    if !secs.is_finite() {
+        return Err(Error::NotFinite);
+    }
+    if secs.is_sign_negative() {
+        return Err(Error::Negative);
+    }
+    // first value which doesn't fit into `u64`
+    if secs >= 18_446_744_073_709_551_616.0 {
+        return Err(Error::Overflow);
+    }
+    let whole = secs.trunc();
+    // the subtraction is exact, only the multiplication rounds
+    let nanos = ((secs - whole) * 1e9).round() as u32;
+    let whole = whole as u64;
+    if nanos >= 1_000_000_000 {
+        let secs = whole.checked_add(1).ok_or(Error::Overflow)?;
+        return Ok(Duration::from_secs(secs));
+    }
+    Ok(Duration::new(whole, nanos))
+}
+
+/// Formats floating point seconds into a human-readable string
+///
+/// The value is converted as [`duration_from_secs_f64`] does, so NaN,
+/// infinity and negative values, including negative zero, are rejected.
+///
+/// # Example
+///
+/// ```
+/// use humantime::{format_secs_f64, FloatSecsError};
+///
+/// assert_eq!(format_secs_f64(5400.25).unwrap().to_string(), "1h 30m 250ms");
+/// assert_eq!(format_secs_f64(1e-10).unwrap().to_string(), "0s");
+/// assert_eq!(format_secs_f64(-1.0).unwrap_err(), FloatSecsError::Negative);
+/// ```
+pub fn format_secs_f64(secs: f64) -> Result<FormattedDuration, Error> {
+    duration_from_secs_f64(secs).map(format_duration)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{duration_from_secs_f64, format_secs_f64, parse_duration_secs_f64, Error};
+    use crate::duration;
+
+    #[test]
+    fn parse() {
+        assert_eq!(parse_duration_secs_f64("0"), Ok(0.0));
+        assert_eq!(parse_duration_secs_f64("1ns"), Ok(1e-9));
+        assert_eq!(parse_duration_secs_f64("1.5h"), Ok(5400.0));
+        assert_eq!(parse_duration_secs_f64("1 ms"), Ok(0.001));
+        assert_eq!(
+            parse_duration_secs_f64("1 fortnight").unwrap_err().kind(),
+            duration::ErrorKind::UnknownUnit
+        );
+        let max = parse_duration_secs_f64("18446744073709551615s 999999999ns").unwrap();
+        assert_eq!(max, 18_446_744_073_709_551_616.0);
+        assert_eq!(duration_from_secs_f64(max), Err(Error::Overflow));
+        let big = parse_duration_secs_f64("18446744073709551615 years").unwrap();
+        assert!((big / 5.821e26 - 1.0).abs() < 1e-3, "{}", big);
+    }
+
+    #[test]
+    fn rounding() {
+        let d = duration_from_secs_f64;
+        assert_eq!(d(0.0), Ok(Duration::ZERO));
+        assert_eq!(d(-0.0), Err(Error::Negative));
+        assert_eq!(d(1e-9), Ok(Duration::new(0, 1)));
+        assert_eq!(d(0.4e-9), Ok(Duration::ZERO));
+        assert_eq!(d(0.6e-9), Ok(Duration::new(0, 1)));
+        assert_eq!(d(0.9999999994), Ok(Duration::new(0, 999_999_999)));
+        assert_eq!(d(1.9999999995), Ok(Duration::from_secs(2)));
+        assert_eq!(d(1.9999999999), Ok(Duration::from_secs(2)));
+        assert_eq!(d(86400.000000001), Ok(Duration::new(86400, 1)));
+        // the largest value below 2^64 has no fraction
+        assert_eq!(
+            d(1.844674407370955e19),
+            Ok(Duration::from_secs(18_446_744_073_709_549_568))
+        );
+        assert_eq!(d(1.8446744073709552e19), Err(Error::Overflow));
+        assert_eq!(d(-1e-300), Err(Error::Negative));
+        assert_eq!(d(f64::NEG_INFINITY), Err(Error::NotFinite));
+        assert_eq!(d(-f64::NAN), Err(Error::NotFinite));
+        for secs in 0..2000 {
+            let value = secs as f64 + 0.9999999996;
+            assert_eq!(d(value), Ok(Duration::from_secs(secs + 1)), "{}", value);
+        }
+    }
+
+    #[test]
+    fn format() {
+        let f = |secs| format_secs_f64(secs).map(|d| d.to_string());
+        assert_eq!(f(0.0), Ok("0s".to_owned()));
+        assert_eq!(f(-0.0), Err(Error::Negative));
+        assert_eq!(f(1.5e-9), Ok("2ns".to_owned()));
+        assert_eq!(f(0.9999999999), Ok("1s".to_owned()));
+        assert_eq!(f(86400.5), Ok("1day 500ms".to_owned()));
+        assert_eq!(f(f64::NAN), Err(Error::NotFinite));
+        assert_eq!(f(f64::INFINITY), Err(Error::NotFinite));
+        assert_eq!(f(-1e-9), Err(Error::Negative));
+        assert_eq!(f(1.8446744073709552e19), Err(Error::Overflow));
+        assert!(f(1.844674407370955e19).is_ok());
+    }
+}
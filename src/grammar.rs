@@ -0,0 +1,830 @@
+use crate::duration::Unit;
+use crate::options::{default_options, ParseOptions};
+
+/// Spellings of a unit accepted by the parser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnitSyntax {
+    unit: Unit,
+    aliases: &'static [&'static str],
+}
+
+/// Description of the syntax accepted by a duration parser
+///
+/// Returned by [`grammar`] and [`ParseOptions::grammar`], it is meant for
+/// documentation generators and `--help` texts which need to list what a
+/// field accepts.
+///
+/// # Example
+///
+/// ```
+/// use humantime::Unit;
+///
+/// let grammar = humantime::grammar();
+/// assert_eq!(grammar.aliases(Unit::Hour), ["hours", "hour", "hrs", "hr", "h"]);
+/// assert_eq!(grammar.unit("min"), Some(Unit::Minute));
+/// assert!(grammar.allows_fractions());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grammar {
+    units: Vec<UnitSyntax>,
+    fractions: bool,
+    bare_zero: bool,
+    whitespace: bool,
+    concatenation: bool,
+    words: bool,
+    case_sensitive: bool,
+}
+
+const UNITS: &[UnitSyntax] = &[
+    UnitSyntax {
+        unit: Unit::Nanosecond,
+        aliases: &["nanoseconds", "nanosecond", "nanos", "nsec", "ns"],
+    },
+    UnitSyntax {
+        unit: Unit::Microsecond,
+        aliases: &["microseconds", "microsecond", "usec", "us", "µs"],
+    },
+    UnitSyntax {
+        unit: Unit::Millisecond,
+        aliases: &["milliseconds", "millisecond", "millis", "msec", "ms"],
+    },
+    UnitSyntax {
+        unit: Unit::Second,
+        aliases: &["seconds", "second", "secs", "sec", "s"],
+    },
+    UnitSyntax {
+        unit: Unit::Minute,
+        aliases: &["minutes", "minute", "mins", "min", "m"],
+    },
+    UnitSyntax {
+        unit: Unit::Hour,
+        aliases: &["hours", "hour", "hrs", "hr", "h"],
+    },
+    UnitSyntax {
+        unit: Unit::Day,
+        aliases: &["days", "day", "d"],
+    },
+    UnitSyntax {
+        unit: Unit::Week,
+        aliases: &["weeks", "week", "wks", "wk", "w"],
+    },
+    UnitSyntax {
+        unit: Unit::Month,
+        aliases: &["months", "month", "mos", "mo", "M"],
+    },
+    UnitSyntax {
+        unit: Unit::Year,
+        aliases: &["years", "year", "yrs", "yr", "y"],
+    },
+];
+
+/// Minutes when `M` means minutes
+const MINUTES_M: UnitSyntax = UnitSyntax {
+    unit: Unit::Minute,
+    aliases: &["minutes", "minute", "mins", "min", "m", "M"],
+};
+
+/// Months when `M` means minutes
+const MONTHS_NO_M: UnitSyntax = UnitSyntax {
+    unit: Unit::Month,
+    aliases: &["months", "month", "mos", "mo"],
+};
+
+/// Returns the grammar of [`parse_duration`](crate::parse_duration)
+///
+/// This follows the options set with
+/// [`set_default_options`](crate::set_default_options).
+pub fn grammar() -> Grammar {
+    default_options().0.grammar()
+}
+
+/// Returns the unit spelled as `name` in any mode of the parser
+///
+/// `M` is months here, like in the default grammar.
+pub(crate) fn unit_named(name: &str) -> Option<Unit> {
+    UNITS
+        .iter()
+        .find(|u| u.aliases.contains(&name))
+        .map(|u| u.unit)
+}
+
+/// Returns the grammar of the parser with `options`
+pub(crate) fn of(options: &ParseOptions) -> Grammar {
+    let units = UNITS
+        .iter()
+        .filter(|u| options.calendar_units() || !u.unit.is_calendar())
+        .map(|&u| match u.unit {
+            Unit::Minute if options.m_as_minutes() => MINUTES_M,
+            Unit::Month if options.m_as_minutes() => MONTHS_NO_M,
+            _ => u,
+        })
+        .collect();
+    Grammar {
+        units,
+        fractions: true,
+        bare_zero: true,
+        whitespace: true,
+        concatenation: options.concatenation(),
+        words: options.lenient(),
+        case_sensitive: true,
+    }
+}
+
+impl UnitSyntax {
+    /// The unit
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    /// All accepted spellings, the longest first
+    pub fn aliases(&self) -> &'static [&'static str] {
+        self.aliases
+    }
+}
+
+impl Grammar {
+    /// Accepted units from the smallest to the largest
+    pub fn units(&self) -> &[UnitSyntax] {
+        &self.units
+    }
+
+    /// Accepted spellings of `unit`, empty if the unit is not accepted
+    pub fn aliases(&self, unit: Unit) -> &'static [&'static str] {
+        self.units
+            .iter()
+            .find(|u| u.unit == unit)
+            .map_or(&[], |u| u.aliases)
+    }
+
+    /// Returns the unit spelled as `alias`, if it's accepted
+    pub fn unit(&self, alias: &str) -> Option<Unit> {
+        self.units
+            .iter()
+            .find(|u| u.aliases.contains(&alias))
+            .map(|u| u.unit)
+    }
+
+    /// Returns the accepted spelling closest to a misspelled `alias`
+    ///
+    /// Spellings differing in case or in up to two characters are
+    /// considered, `None` is returned if nothing is close enough.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let grammar = humantime::grammar();
+    /// assert_eq!(grammar.suggest_unit("mins"), Some("mins"));
+    /// assert_eq!(grammar.suggest_unit("secnds"), Some("seconds"));
+    /// assert_eq!(grammar.suggest_unit("fortnight"), None);
+    /// ```
+    pub fn suggest_unit(&self, alias: &str) -> Option<&'static str> {
+        let alias = alias.to_lowercase();
+        self.units
+            .iter()
+            .flat_map(|u| u.aliases.iter().copied())
+            .map(|a| (edit_distance(&alias, a), a))
+            .filter(|&(dist, a)| dist <= 2 && dist < a.chars().count())
+            .min_by_key(|&(dist, a)| (dist, a.len()))
+            .map(|(_, a)| a)
+    }
+
+    /// Numbers may have a fraction, like `1.5h`
+    ///
+    /// The fraction must be a whole number of nanoseconds, or of seconds
+    /// for hours and larger units.
+    pub fn allows_fractions(&self) -> bool {
+        self.fractions
+    }
+
+    /// A bare `0` without a unit is accepted
+    pub fn allows_bare_zero(&self) -> bool {
+        self.bare_zero
+    }
+
+    /// Whitespace is allowed around and between the components and
+    /// between a number and its unit, like ` 1 h 30 min `
+    pub fn allows_whitespace(&self) -> bool {
+        self.whitespace
+    }
+
+    /// Components may be written without whitespace between them, like
+    /// `1h30m`
+    pub fn allows_concatenation(&self) -> bool {
+        self.concatenation
+    }
+
+    /// Natural-language words, like `an hour and a half`, are accepted
+    ///
+    /// These are the words of
+    /// [`ParseOptions::with_lenient`](crate::ParseOptions::with_lenient),
+    /// which [`to_ebnf`](Self::to_ebnf) and [`to_regex`](Self::to_regex)
+    /// leave out.
+    pub fn allows_words(&self) -> bool {
+        self.words
+    }
+
+    /// Units are case sensitive, `M` is month and `m` is minute
+    pub fn is_case_sensitive(&self) -> bool {
+        self.case_sensitive
+    }
+
+    /// Returns the grammar in ISO 14977 EBNF
+    ///
+    /// Like [`to_regex`](Self::to_regex), the grammar describes exactly the
+    /// inputs the parser reads, including its quirks: whitespace inside
+    /// numbers is skipped, and a bare `0` is accepted only as the whole
+    /// input.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let ebnf = humantime::grammar().to_ebnf();
+    /// assert!(ebnf.contains(r#"hour = "hours" | "hour" | "hrs" | "hr" | "h" ;"#));
+    /// ```
+    pub fn to_ebnf(&self) -> String {
+        let ws = if self.whitespace { "ws, " } else { "" };
+        let between = if self.concatenation {
+            ws
+        } else {
+            "space, ws, "
+        };
+        let trailing = if self.whitespace || !self.concatenation {
+            ", ws"
+        } else {
+            ""
+        };
+        let mut body = format!("{}component, {{ {}component }}{}", ws, between, trailing);
+        if self.bare_zero {
+            body = format!("\"0\" | {}", body);
+        }
+        let mut out = format!("duration = {} ;\n", body);
+        let fraction = if self.fractions { ", [ fraction ]" } else { "" };
+        out.push_str(&format!("component = number{}, unit ;\n", fraction));
+        let digit = if self.whitespace {
+            "{ digit | space }"
+        } else {
+            "{ digit }"
+        };
+        out.push_str(&format!("number = digit, {} ;\n", digit));
+        if self.fractions {
+            if self.whitespace {
+                out.push_str(&format!("fraction = \".\", {0}, digit, {0} ;\n", digit));
+            } else {
+                out.push_str("fraction = \".\", digit, { digit } ;\n");
+            }
+        }
+        let names: Vec<&str> = self.units.iter().map(|u| unit_name(u.unit)).collect();
+        out.push_str(&format!("unit = {} ;\n", names.join(" | ")));
+        for syntax in &self.units {
+            let aliases: Vec<String> = syntax
+                .aliases
+                .iter()
+                .map(|a| format!("\"{}\"", a))
+                .collect();
+            out.push_str(&format!(
+                "{} = {} ;\n",
+                unit_name(syntax.unit),
+                aliases.join(" | ")
+            ));
+        }
+        let digits: Vec<String> = (0..10).map(|d| format!("\"{}\"", d)).collect();
+        out.push_str(&format!("digit = {} ;\n", digits.join(" | ")));
+        if self.whitespace || !self.concatenation {
+            out.push_str("ws = { space } ;\n");
+            out.push_str("space = ? white space character ? ;\n");
+        }
+        out
+    }
+
+    /// Returns a regular expression matching the whole accepted input
+    ///
+    /// The expression uses only the common subset of syntax (`\s`,
+    /// groups, alternation and character classes) so it can be used as a
+    /// JSON Schema `pattern` or with most regex engines. Longer unit
+    /// spellings come first in the alternation, as some engines pick the
+    /// first matching alternative.
+    ///
+    /// The expression matches exactly the inputs the parser reads, apart
+    /// from numbers too large for it. This includes its quirks, like
+    /// whitespace inside numbers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let re = humantime::grammar().to_regex();
+    /// assert!(re.starts_with(r"^(0|\s*([0-9]([0-9]|\s)*(\.([0-9]|\s)*[0-9]([0-9]|\s)*)?("));
+    /// ```
+    pub fn to_regex(&self) -> String {
+        let mut aliases: Vec<&str> = self
+            .units
+            .iter()
+            .flat_map(|u| u.aliases.iter().copied())
+            .collect();
+        aliases.sort_by_key(|a| std::cmp::Reverse(a.chars().count()));
+        let (ws, digit) = if self.whitespace {
+            (r"\s*", r"([0-9]|\s)")
+        } else {
+            ("", "[0-9]")
+        };
+        let fraction = if self.fractions {
+            format!(r"(\.{}*[0-9]{}*)?", digit, digit)
+        } else {
+            String::new()
+        };
+        let after = if self.concatenation { ws } else { r"(\s+|$)" };
+        let component = format!(
+            "([0-9]{}*{}({}){})+",
+            digit,
+            fraction,
+            aliases.join("|"),
+            after
+        );
+        if self.bare_zero {
+            // the parser takes `0` without a unit only if it's the whole input
+            format!("^(0|{}{})$", ws, component)
+        } else {
+            format!("^{}{}$", ws, component)
+        }
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == cb {
+                prev
+            } else {
+                1 + prev.min(cur).min(row[j])
+            };
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+fn unit_name(unit: Unit) -> &'static str {
+    match unit {
+        Unit::Nanosecond => "nanosecond",
+        Unit::Microsecond => "microsecond",
+        Unit::Millisecond => "millisecond",
+        Unit::Second => "second",
+        Unit::Minute => "minute",
+        Unit::Hour => "hour",
+        Unit::Day => "day",
+        Unit::Week => "week",
+        Unit::Month => "month",
+        Unit::Year => "year",
+    }
+}
+
+/// Tells if `input` matches a pattern like those of [`Grammar::to_regex`]
+///
+/// A small backtracking matcher for the syntax of the patterns in this
+/// crate, so that tests can check them against the parsers without a regex
+/// engine.
+#[cfg(test)]
+pub(crate) fn regex_is_match(pattern: &str, input: &str) -> bool {
+    use std::iter::Peekable;
+    use std::str::Chars;
+
+    enum Node {
+        Start,
+        End,
+        Char(char),
+        Class(Vec<(char, char)>),
+        Space,
+        Alt(Vec<Vec<Node>>),
+        Repeat(Box<Node>, usize, Option<usize>),
+    }
+
+    fn parse(p: &mut Peekable<Chars>) -> Vec<Vec<Node>> {
+        let mut alts = vec![Vec::new()];
+        while let Some(c) = p.next_if(|&c| c != ')') {
+            let node = match c {
+                '|' => {
+                    alts.push(Vec::new());
+                    continue;
+                }
+                '?' | '+' | '*' | '{' => {
+                    let (min, max) = match c {
+                        '?' => (0, Some(1)),
+                        '+' => (1, None),
+                        '*' => (0, None),
+                        _ => {
+                            let count: String = p.by_ref().take_while(|&c| c != '}').collect();
+                            let mut bounds = count.split(',').map(|n| n.parse().unwrap());
+                            let min = bounds.next().unwrap();
+                            (min, Some(bounds.next().unwrap_or(min)))
+                        }
+                    };
+                    let seq = alts.last_mut().unwrap();
+                    let atom = seq.pop().expect("quantifier follows an atom");
+                    seq.push(Node::Repeat(Box::new(atom), min, max));
+                    continue;
+                }
+                '^' => Node::Start,
+                '$' => Node::End,
+                '(' => {
+                    let group = parse(p);
+                    assert_eq!(p.next(), Some(')'));
+                    Node::Alt(group)
+                }
+                '[' => {
+                    let class: Vec<char> = p.by_ref().take_while(|&c| c != ']').collect();
+                    let mut ranges = Vec::new();
+                    let mut i = 0;
+                    while i < class.len() {
+                        if class.get(i + 1) == Some(&'-') && i + 2 < class.len() {
+                            ranges.push((class[i], class[i + 2]));
+                            i += 3;
+                        } else {
+                            ranges.push((class[i], class[i]));
+                            i += 1;
+                        }
+                    }
+                    Node::Class(ranges)
+                }
+                '\\' => match p.next() {
+                    Some('s') => Node::Space,
+                    Some(c) => Node::Char(c),
+                    None => panic!("trailing backslash"),
+                },
+                c => Node::Char(c),
+            };
+            alts.last_mut().unwrap().push(node);
+        }
+        alts
+    }
+
+    fn go(seq: &[Node], s: &[char], pos: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        let (node, rest) = match seq.split_first() {
+            Some(split) => split,
+            None => return k(pos),
+        };
+        match node {
+            Node::Start => pos == 0 && go(rest, s, pos, k),
+            Node::End => pos == s.len() && go(rest, s, pos, k),
+            Node::Char(c) => s.get(pos) == Some(c) && go(rest, s, pos + 1, k),
+            Node::Class(ranges) => {
+                let hit = s.get(pos).map_or(false, |&c| {
+                    ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&c))
+                });
+                hit && go(rest, s, pos + 1, k)
+            }
+            Node::Space => {
+                s.get(pos).map_or(false, |c| c.is_whitespace()) && go(rest, s, pos + 1, k)
+            }
+            Node::Alt(alts) => alts
+                .iter()
+                .any(|alt| go(alt, s, pos, &mut |p| go(rest, s, p, k))),
+            Node::Repeat(atom, min, max) => repeat(atom, *min, *max, rest, s, pos, k),
+        }
+    }
+
+    fn repeat(
+        atom: &Node,
+        min: usize,
+        max: Option<usize>,
+        rest: &[Node],
+        s: &[char],
+        pos: usize,
+        k: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        let more = max != Some(0)
+            && go(std::slice::from_ref(atom), s, pos, &mut |p| {
+                p > pos
+                    && repeat(
+                        atom,
+                        min.saturating_sub(1),
+                        max.map(|m| m - 1),
+                        rest,
+                        s,
+                        p,
+                        k,
+                    )
+            });
+        more || min == 0 && go(rest, s, pos, k)
+    }
+
+    let alts = parse(&mut pattern.chars().peekable());
+    let input: Vec<char> = input.chars().collect();
+    alts.iter().any(|alt| go(alt, &input, 0, &mut |_| true))
+}
+
+/// Tells if `input` is a sentence of a grammar like [`Grammar::to_ebnf`]
+///
+/// The first rule is the start symbol. Like [`regex_is_match`], this only
+/// supports the syntax the grammars of this crate use.
+#[cfg(test)]
+pub(crate) fn ebnf_is_match(ebnf: &str, input: &str) -> bool {
+    use std::collections::HashMap;
+
+    enum Expr {
+        Alt(Vec<Vec<Expr>>),
+        Repeat(Box<Expr>),
+        Optional(Box<Expr>),
+        Literal(Vec<char>),
+        Rule(String),
+        Space,
+    }
+
+    fn tokens(ebnf: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        let mut chars = ebnf.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                c if c.is_whitespace() => {}
+                '"' | '?' => {
+                    let body: String = chars.by_ref().take_while(|&e| e != c).collect();
+                    out.push(format!("{}{}", c, body));
+                }
+                c if c.is_alphanumeric() => {
+                    let mut name = c.to_string();
+                    while let Some(c) = chars.next_if(|c| c.is_alphanumeric()) {
+                        name.push(c);
+                    }
+                    out.push(name);
+                }
+                c => out.push(c.to_string()),
+            }
+        }
+        out
+    }
+
+    fn parse(tokens: &[String], pos: &mut usize) -> Expr {
+        let mut alts = vec![Vec::new()];
+        while let Some(token) = tokens.get(*pos) {
+            *pos += 1;
+            let item = match token.as_str() {
+                "," => continue,
+                "|" => {
+                    alts.push(Vec::new());
+                    continue;
+                }
+                ";" | ")" | "}" | "]" => break,
+                "(" => parse(tokens, pos),
+                "{" => Expr::Repeat(Box::new(parse(tokens, pos))),
+                "[" => Expr::Optional(Box::new(parse(tokens, pos))),
+                t if t.starts_with('"') => Expr::Literal(t[1..].chars().collect()),
+                t if t.starts_with('?') => Expr::Space,
+                t => Expr::Rule(t.to_owned()),
+            };
+            alts.last_mut().unwrap().push(item);
+        }
+        Expr::Alt(alts)
+    }
+
+    type Rules = HashMap<String, Expr>;
+
+    fn seq(
+        items: &[Expr],
+        r: &Rules,
+        s: &[char],
+        pos: usize,
+        k: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        match items.split_first() {
+            Some((item, rest)) => go(item, r, s, pos, &mut |p| seq(rest, r, s, p, k)),
+            None => k(pos),
+        }
+    }
+
+    fn go(e: &Expr, r: &Rules, s: &[char], pos: usize, k: &mut dyn FnMut(usize) -> bool) -> bool {
+        match e {
+            Expr::Alt(alts) => alts.iter().any(|alt| seq(alt, r, s, pos, k)),
+            Expr::Repeat(inner) => {
+                go(inner, r, s, pos, &mut |p| p > pos && go(e, r, s, p, k)) || k(pos)
+            }
+            Expr::Optional(inner) => go(inner, r, s, pos, k) || k(pos),
+            Expr::Literal(lit) => s[pos..].starts_with(lit) && k(pos + lit.len()),
+            Expr::Rule(name) => go(&r[name], r, s, pos, k),
+            Expr::Space => s.get(pos).map_or(false, |c| c.is_whitespace()) && k(pos + 1),
+        }
+    }
+
+    let tokens = tokens(ebnf);
+    let mut rules = HashMap::new();
+    let mut start = None;
+    let mut pos = 0;
+    while pos + 1 < tokens.len() {
+        assert_eq!(tokens[pos + 1], "=");
+        let name = tokens[pos].clone();
+        pos += 2;
+        rules.insert(name.clone(), parse(&tokens, &mut pos));
+        start.get_or_insert(name);
+    }
+    let input: Vec<char> = input.chars().collect();
+    let start = Expr::Rule(start.expect("a rule"));
+    go(&start, &rules, &input, 0, &mut |p| p == input.len())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use super::{ebnf_is_match, grammar, regex_is_match};
+    use crate::duration::{parse_duration, Unit};
+    use crate::options::ParseOptions;
+
+    #[test]
+    fn aliases_parse() {
+        let grammar = grammar();
+        for syntax in grammar.units() {
+            for alias in syntax.aliases() {
+                assert_eq!(Unit::from_str(alias), Ok(syntax.unit()), "{}", alias);
+                assert_eq!(grammar.unit(alias), Some(syntax.unit()));
+                assert!(parse_duration(&format!("1 {}", alias)).is_ok());
+            }
+        }
+        assert_eq!(grammar.units().len(), 10);
+        assert_eq!(grammar.unit("H"), None);
+        let err = Unit::from_str("H").unwrap_err();
+        assert_eq!(err.name(), "H");
+        assert_eq!(err.to_string(), "unknown time unit \"H\"");
+    }
+
+    #[test]
+    fn flags() {
+        let grammar = grammar();
+        assert_eq!(grammar.allows_fractions(), parse_duration("1.5h").is_ok());
+        assert_eq!(grammar.allows_bare_zero(), parse_duration("0").is_ok());
+        assert_eq!(grammar.allows_whitespace(), parse_duration(" 1 h ").is_ok());
+        assert!(grammar.is_case_sensitive());
+        assert_eq!(
+            grammar.aliases(Unit::Month),
+            ["months", "month", "mos", "mo", "M"]
+        );
+    }
+
+    #[test]
+    fn suggestions() {
+        let grammar = grammar();
+        assert_eq!(grammar.suggest_unit("hrz"), Some("hr"));
+        assert_eq!(grammar.suggest_unit("Hours"), Some("hours"));
+        assert_eq!(grammar.suggest_unit("MS"), Some("ms"));
+        assert_eq!(grammar.suggest_unit("minz"), Some("min"));
+        assert_eq!(grammar.suggest_unit("x"), None);
+        assert_eq!(grammar.suggest_unit(""), None);
+    }
+
+    #[test]
+    fn ebnf() {
+        let ebnf = grammar().to_ebnf();
+        let lines: Vec<&str> = ebnf.lines().collect();
+        assert_eq!(
+            lines[..5],
+            [
+                r#"duration = "0" | ws, component, { ws, component }, ws ;"#,
+                "component = number, [ fraction ], unit ;",
+                "number = digit, { digit | space } ;",
+                r#"fraction = ".", { digit | space }, digit, { digit | space } ;"#,
+                "unit = nanosecond | microsecond | millisecond | second | minute \
+                | hour | day | week | month | year ;",
+            ]
+        );
+        assert_eq!(
+            lines[6],
+            r#"microsecond = "microseconds" | "microsecond" | "usec" | "us" | "µs" ;"#
+        );
+        assert_eq!(
+            lines[lines.len() - 2..],
+            ["ws = { space } ;", "space = ? white space character ? ;"]
+        );
+    }
+
+    #[test]
+    fn ebnf_regex_and_parser_agree() {
+        let inputs = &[
+            "0",
+            " 0",
+            "0 ",
+            "00",
+            "0s",
+            "",
+            " ",
+            "1h 30min",
+            "1h30m",
+            "1h  30m",
+            "1h\t30m",
+            " 2 days ",
+            "1 h",
+            "1 2s",
+            "1 2 h",
+            "1.5h",
+            "1.5 h",
+            "1 .5h",
+            "1. 5h",
+            "1 . 5h",
+            "1.h",
+            ".5h",
+            "1.5.5h",
+            "1.5h2m",
+            "µs",
+            "5µs",
+            "5 fortnights",
+            "2 months",
+            "5M",
+            "3y",
+            "h",
+            "1h h",
+        ];
+        let options = [
+            ParseOptions::new(),
+            ParseOptions::new().with_concatenation(false),
+            ParseOptions::new().with_calendar_units(false),
+            ParseOptions::new().with_m_as_minutes(true),
+        ];
+        for options in &options {
+            let grammar = options.grammar();
+            let (ebnf, re) = (grammar.to_ebnf(), grammar.to_regex());
+            for input in inputs {
+                let parsed = options.parse(input).is_ok();
+                assert_eq!(
+                    ebnf_is_match(&ebnf, input),
+                    parsed,
+                    "{:?} {:?}",
+                    options,
+                    input
+                );
+                assert_eq!(
+                    regex_is_match(&re, input),
+                    parsed,
+                    "{:?} {:?}",
+                    options,
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn regex() {
+        let re = grammar().to_regex();
+        assert!(re.ends_with(r"|y)\s*)+)$"), "{}", re);
+        for input in &[
+            "0",
+            " 0",
+            "00",
+            "1h 30min",
+            "1h30m",
+            " 2 days ",
+            "1.5h",
+            "1.h",
+            "1 2s",
+            "µs",
+            "5µs",
+            "5 fortnights",
+            "",
+        ] {
+            let matched = regex_is_match(&re, input);
+            assert_eq!(matched, parse_duration(input).is_ok(), "{:?}", input);
+        }
+        assert!(
+            re.contains("(microseconds|milliseconds|nanoseconds|microsecond|millisecond|"),
+            "{}",
+            re
+        );
+    }
+
+    #[test]
+    fn options() {
+        let strict = ParseOptions::new()
+            .with_calendar_units(false)
+            .with_concatenation(false);
+        let minutes = ParseOptions::new().with_m_as_minutes(true);
+        let both = strict.with_m_as_minutes(true);
+        assert_eq!(strict.grammar().units().len(), 8);
+        assert_eq!(minutes.grammar().unit("M"), Some(Unit::Minute));
+        assert_eq!(minutes.grammar().unit("mo"), Some(Unit::Month));
+        assert_eq!(both.grammar().unit("M"), Some(Unit::Minute));
+        assert!(ParseOptions::new()
+            .with_lenient(true)
+            .grammar()
+            .allows_words());
+        assert_eq!(grammar(), ParseOptions::new().grammar());
+        for options in &[strict, minutes, both] {
+            let re = options.grammar().to_regex();
+            for input in &[
+                "1h 30m", "1h30m", "1h 30m ", "1 h 30 m", "2 months", "5M", "3y", "0", "1.5h2m",
+            ] {
+                let matched = regex_is_match(&re, input);
+                assert_eq!(
+                    matched,
+                    options.parse(input).is_ok(),
+                    "{:?} {:?}",
+                    options,
+                    input
+                );
+            }
+        }
+        assert!(strict
+            .grammar()
+            .to_ebnf()
+            .contains("component, { space, ws, component }"));
+    }
+}
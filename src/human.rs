@@ -0,0 +1,203 @@
+use std::fmt;
+use std::time::{Duration, SystemTime};
+
+use crate::date::{self, format_rfc3339, parse_rfc3339_weak};
+use crate::duration::{self, parse_duration};
+use crate::options::FormatOptions;
+use crate::wrapper;
+
+/// Types which can be parsed from their human-friendly form
+///
+/// This is a single entry point for generic code, like config loaders,
+/// which reads values of different types. Durations use
+/// [`parse_duration`] and timestamps [`parse_rfc3339_weak`], the wrapper
+/// types parse as their `FromStr` implementations do.
+pub trait FromHuman: Sized {
+    /// The parse error
+    type Err;
+
+    /// Parses the value
+    fn from_human(s: &str) -> Result<Self, Self::Err>;
+}
+
+/// Parses any type supported by the crate
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, SystemTime};
+/// use humantime::Timeout;
+///
+/// let timeout: Duration = humantime::parse("90s").unwrap();
+/// assert_eq!(timeout, Duration::from_secs(90));
+/// let start: SystemTime = humantime::parse("2018-02-16 00:31:37").unwrap();
+/// assert_eq!(humantime::parse::<Timeout>("off"), Ok(Timeout::NONE));
+/// ```
+pub fn parse<T: FromHuman>(s: &str) -> Result<T, T::Err> {
+    T::from_human(s)
+}
+
+impl FromHuman for Duration {
+    type Err = duration::Error;
+    fn from_human(s: &str) -> Result<Duration, Self::Err> {
+        parse_duration(s)
+    }
+}
+
+impl FromHuman for SystemTime {
+    type Err = date::Error;
+    fn from_human(s: &str) -> Result<SystemTime, Self::Err> {
+        parse_rfc3339_weak(s)
+    }
+}
+
+impl FromHuman for wrapper::Duration {
+    type Err = duration::Error;
+    fn from_human(s: &str) -> Result<wrapper::Duration, Self::Err> {
+        s.parse()
+    }
+}
+
+impl FromHuman for wrapper::Timestamp {
+    type Err = date::Error;
+    fn from_human(s: &str) -> Result<wrapper::Timestamp, Self::Err> {
+        s.parse()
+    }
+}
+
+impl FromHuman for wrapper::Timeout {
+    type Err = duration::Error;
+    fn from_human(s: &str) -> Result<wrapper::Timeout, Self::Err> {
+        s.parse()
+    }
+}
+
+/// Types which can be written in a human-friendly form
+///
+/// The output-side counterpart of [`FromHuman`], for templating and
+/// logging layers formatting values of different types. Durations are
+/// written with the style of the options, timestamps are always RFC 3339
+/// as [`format_rfc3339`] writes them.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::{FormatOptions, Style, ToHuman};
+///
+/// let options = FormatOptions::new().with_style(Style::Verbose);
+/// assert_eq!(Duration::from_secs(90).to_human(&options), "1 minute 30 seconds");
+/// assert_eq!(UNIX_EPOCH.to_human(&options), "1970-01-01T00:00:00Z");
+/// ```
+pub trait ToHuman {
+    /// Writes the value with `options`
+    fn fmt_human(&self, options: &FormatOptions, f: &mut fmt::Formatter) -> fmt::Result;
+
+    /// Returns the value written with `options`
+    fn to_human(&self, options: &FormatOptions) -> String {
+        Human(self, options).to_string()
+    }
+}
+
+struct Human<'a, T: ?Sized>(&'a T, &'a FormatOptions);
+
+impl<T: ToHuman + ?Sized> fmt::Display for Human<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_human(self.1, f)
+    }
+}
+
+impl ToHuman for Duration {
+    fn fmt_human(&self, options: &FormatOptions, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&options.format(*self), f)
+    }
+}
+
+impl ToHuman for SystemTime {
+    fn fmt_human(&self, _options: &FormatOptions, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&format_rfc3339(*self), f)
+    }
+}
+
+impl ToHuman for wrapper::Duration {
+    fn fmt_human(&self, options: &FormatOptions, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt_human(options, f)
+    }
+}
+
+impl ToHuman for wrapper::Timestamp {
+    fn fmt_human(&self, options: &FormatOptions, f: &mut fmt::Formatter) -> fmt::Result {
+        (**self).fmt_human(options, f)
+    }
+}
+
+impl ToHuman for wrapper::Timeout {
+    /// Writes `none` for a disabled timeout, like `Display`
+    fn fmt_human(&self, options: &FormatOptions, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.get() {
+            Some(d) => d.fmt_human(options, f),
+            None => f.write_str("none"),
+        }
+    }
+}
+
+impl ToHuman for wrapper::HumanInstant {
+    /// Writes the time elapsed since the instant
+    fn fmt_human(&self, options: &FormatOptions, f: &mut fmt::Formatter) -> fmt::Result {
+        self.elapsed().fmt_human(options, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::{parse, FromHuman, ToHuman};
+    use crate::duration::Style;
+    use crate::options::FormatOptions;
+    use crate::wrapper::{self, HumanInstant, Timeout, Timestamp};
+
+    fn parse_all<T: FromHuman>(values: &[&str]) -> Result<Vec<T>, T::Err> {
+        values.iter().map(|s| parse(s)).collect()
+    }
+
+    #[test]
+    fn generic() {
+        assert_eq!(
+            parse_all::<Duration>(&["1s", "2m"]),
+            Ok(vec![Duration::from_secs(1), Duration::from_secs(120)])
+        );
+        assert_eq!(
+            parse::<SystemTime>("1970-01-01T00:00:01Z"),
+            Ok(UNIX_EPOCH + Duration::from_secs(1))
+        );
+        assert_eq!(
+            parse::<wrapper::Duration>("1h"),
+            Ok(Duration::from_secs(3600).into())
+        );
+        assert_eq!(
+            parse::<Timestamp>("1970-01-01 00:00:00"),
+            Ok(UNIX_EPOCH.into())
+        );
+        assert_eq!(parse::<Timeout>("none"), Ok(Timeout::NONE));
+        assert!(parse_all::<Duration>(&["1s", "soon"]).is_err());
+        assert!(parse::<SystemTime>("1s").is_err());
+    }
+
+    #[test]
+    fn formatted() {
+        let verbose = FormatOptions::new().with_style(Style::Verbose);
+        let iso = FormatOptions::new().with_style(Style::Iso8601);
+        let d = Duration::from_secs(5400);
+        assert_eq!(d.to_human(&verbose), "1 hour 30 minutes");
+        assert_eq!(wrapper::Duration::from(d).to_human(&iso), "PT1H30M");
+        assert_eq!(Timeout::from(d).to_human(&iso), "PT1H30M");
+        assert_eq!(Timeout::NONE.to_human(&iso), "none");
+        let time = Timestamp::from(UNIX_EPOCH + d);
+        assert_eq!(time.to_human(&verbose), "1970-01-01T01:30:00Z");
+        assert!(HumanInstant::now().to_human(&verbose).contains("second"));
+        let values: [&dyn ToHuman; 2] = [&d, &SystemTime::UNIX_EPOCH];
+        let text: Vec<String> = values.iter().map(|v| v.to_human(&iso)).collect();
+        assert_eq!(text, ["PT1H30M", "1970-01-01T00:00:00Z"]);
+    }
+}
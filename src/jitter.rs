@@ -0,0 +1,213 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::duration::{self, format_duration, parse_duration_in};
+use crate::tolerance::{self, Tolerance};
+
+/// Error parsing a jittered duration
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// The base duration is invalid
+    Base(duration::Error),
+    /// The jitter is neither a valid duration nor a percentage
+    Jitter(tolerance::Error),
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Base(e) => Some(e),
+            Error::Jitter(e) => Some(e),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Base(e) => write!(f, "invalid base duration: {}", e),
+            Error::Jitter(e) => write!(f, "invalid jitter: {}", e),
+        }
+    }
+}
+
+/// A duration with random jitter, like `30s ± 5s` or `1m ± 10%`
+///
+/// The jitter can be written with `±`, `+-` or `+/-`. When omitted, the
+/// jitter is zero. Percentage is taken of the base duration.
+///
+/// With the `rand` feature enabled, `sample` picks a random duration from
+/// the range.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::Jittered;
+///
+/// let retry: Jittered = "1m ± 10%".parse().unwrap();
+/// assert_eq!(retry.base(), Duration::from_secs(60));
+/// assert_eq!(retry.min(), Duration::from_secs(54));
+/// assert_eq!(retry.max(), Duration::from_secs(66));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Jittered {
+    base: Duration,
+    jitter: Tolerance,
+}
+
+const SEPARATORS: &[&str] = &["±", "+/-", "+-"];
+
+impl Jittered {
+    /// Create a jittered duration from its base and absolute jitter
+    pub const fn new(base: Duration, jitter: Duration) -> Jittered {
+        Jittered {
+            base,
+            jitter: Tolerance::absolute(jitter),
+        }
+    }
+
+    /// The duration in the middle of the range
+    pub fn base(&self) -> Duration {
+        self.base
+    }
+
+    /// The maximum deviation from the base, in either direction
+    pub fn jitter(&self) -> Duration {
+        self.jitter.amount(self.base)
+    }
+
+    /// The shortest duration in the range (saturates at zero)
+    pub fn min(&self) -> Duration {
+        self.base.saturating_sub(self.jitter())
+    }
+
+    /// The longest duration in the range (saturates at `Duration::MAX`)
+    pub fn max(&self) -> Duration {
+        self.base.saturating_add(self.jitter())
+    }
+
+    /// Pick a uniformly distributed random duration from the range
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use humantime::Jittered;
+    ///
+    /// let delay: Jittered = "30s ± 5s".parse().unwrap();
+    /// let value = delay.sample(&mut rand::rng());
+    /// assert!(value >= delay.min() && value <= delay.max());
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn sample(&self, rng: &mut impl rand::Rng) -> Duration {
+        let nanos = rng.random_range(self.min().as_nanos()..=self.max().as_nanos());
+        Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        )
+    }
+}
+
+impl FromStr for Jittered {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Jittered, Error> {
+        let split = SEPARATORS
+            .iter()
+            .filter_map(|sep| s.find(sep).map(|idx| (idx, sep.len())))
+            .min();
+        let (base, jitter) = match split {
+            Some((idx, len)) => {
+                let jitter = s[idx + len..].parse().map_err(Error::Jitter)?;
+                (&s[..idx], jitter)
+            }
+            None => (s, Tolerance::absolute(Duration::ZERO)),
+        };
+        Ok(Jittered {
+            base: parse_duration_in(s, base).map_err(Error::Base)?,
+            jitter,
+        })
+    }
+}
+
+impl fmt::Display for Jittered {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ± {}", format_duration(self.base), self.jitter)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{Error, Jittered};
+    use crate::duration::Error as DurationError;
+    use crate::tolerance::Error as ToleranceError;
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn parse() {
+        for input in &["30s ± 5s", "30s+-5s", "30s +/- 5s", "  30s ±5s "] {
+            let value: Jittered = input.parse().unwrap();
+            assert_eq!(value, Jittered::new(secs(30), secs(5)), "{:?}", input);
+        }
+        let value: Jittered = "1m ± 10%".parse().unwrap();
+        assert_eq!((value.min(), value.max()), (secs(54), secs(66)));
+        let value: Jittered = "2h".parse().unwrap();
+        assert_eq!((value.min(), value.max()), (secs(7200), secs(7200)));
+    }
+
+    #[test]
+    fn saturate() {
+        let value: Jittered = "1s ± 200%".parse().unwrap();
+        assert_eq!((value.min(), value.max()), (secs(0), secs(3)));
+        let value = Jittered::new(Duration::MAX, secs(1));
+        assert_eq!(value.max(), Duration::MAX);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            "± 5s".parse::<Jittered>(),
+            Err(Error::Base(DurationError::Empty))
+        );
+        // offsets are in the whole input
+        match " 5x ± 1s".parse::<Jittered>() {
+            Err(Error::Base(e)) => assert_eq!(e.span(), Some(2..3)),
+            other => panic!("{:?}", other),
+        }
+        assert_eq!(
+            "5s ± 5".parse::<Jittered>().unwrap_err().to_string(),
+            "invalid jitter: invalid tolerance: \
+            time unit needed, for example 5sec or 5ms"
+        );
+        assert_eq!(
+            "5s ± x%".parse::<Jittered>(),
+            Err(Error::Jitter(ToleranceError::InvalidPercentage))
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Jittered::new(secs(90), secs(5)).to_string(), "1m 30s ± 5s");
+        let value: Jittered = "1m+-10%".parse().unwrap();
+        assert_eq!(value.to_string(), "1m ± 10%");
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn sample() {
+        let value: Jittered = "10ms ± 50%".parse().unwrap();
+        let mut rng = rand::rng();
+        for _ in 0..1000 {
+            let d = value.sample(&mut rng);
+            assert!(d >= Duration::from_millis(5) && d <= Duration::from_millis(15));
+        }
+        assert_eq!(Jittered::new(secs(1), secs(0)).sample(&mut rng), secs(1));
+    }
+}
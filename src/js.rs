@@ -0,0 +1,129 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::wrapper::Timestamp;
+
+/// The largest value of a JavaScript `Date`, 100 000 000 days
+const MAX_JS_MILLIS: f64 = 8.64e15;
+
+/// Converts JavaScript epoch milliseconds, like `Date.now()`, to a time
+///
+/// Fractions of a millisecond are kept up to nanoseconds. Returns `None`
+/// for `NaN`, infinities and values outside of the range of a JavaScript
+/// `Date`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::{format_rfc3339_millis, from_js_millis};
+///
+/// let time = from_js_millis(1_518_568_087_250.0).unwrap();
+/// assert_eq!(time, UNIX_EPOCH + Duration::from_millis(1_518_568_087_250));
+/// assert_eq!(format_rfc3339_millis(time).to_string(), "2018-02-14T00:28:07.250Z");
+/// assert_eq!(from_js_millis(f64::NAN), None);
+/// ```
+pub fn from_js_millis(millis: f64) -> Option<SystemTime> {
+    if !millis.is_finite() || millis.abs() > MAX_JS_MILLIS {
+        return None;
+    }
+    // multiplying the whole value by 1e6 loses precision of whole millis
+    let whole = millis.abs().trunc();
+    let fraction = ((millis.abs() - whole) * 1e6).round() as u128;
+    let nanos = whole as u128 * 1_000_000 + fraction;
+    let dur = Duration::new(
+        (nanos / 1_000_000_000) as u64,
+        (nanos % 1_000_000_000) as u32,
+    );
+    if millis >= 0.0 {
+        UNIX_EPOCH.checked_add(dur)
+    } else {
+        UNIX_EPOCH.checked_sub(dur)
+    }
+}
+
+/// Converts a time to JavaScript epoch milliseconds, for `new Date(millis)`
+///
+/// The value is whole milliseconds, rounded towards the past like
+/// `Date` does.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::to_js_millis;
+///
+/// let time = UNIX_EPOCH + Duration::new(1_518_568_087, 250_999_999);
+/// assert_eq!(to_js_millis(time), 1_518_568_087_250.0);
+/// ```
+pub fn to_js_millis(time: SystemTime) -> f64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(dur) => dur.as_millis() as f64,
+        Err(err) => -(((err.duration().as_nanos() + 999_999) / 1_000_000) as f64),
+    }
+}
+
+impl Timestamp {
+    /// Creates a timestamp from JavaScript epoch milliseconds
+    ///
+    /// See [`from_js_millis`](crate::from_js_millis).
+    pub fn from_js_millis(millis: f64) -> Option<Timestamp> {
+        from_js_millis(millis).map(Timestamp::from)
+    }
+
+    /// Returns the timestamp as JavaScript epoch milliseconds
+    ///
+    /// See [`to_js_millis`](crate::to_js_millis).
+    pub fn to_js_millis(&self) -> f64 {
+        to_js_millis(**self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{from_js_millis, to_js_millis};
+    use crate::wrapper::Timestamp;
+
+    #[test]
+    fn from_millis() {
+        assert_eq!(from_js_millis(0.0), Some(UNIX_EPOCH));
+        assert_eq!(from_js_millis(-0.0), Some(UNIX_EPOCH));
+        assert_eq!(
+            from_js_millis(1.5),
+            Some(UNIX_EPOCH + Duration::from_micros(1500))
+        );
+        assert_eq!(
+            from_js_millis(-1000.0),
+            UNIX_EPOCH.checked_sub(Duration::from_secs(1))
+        );
+        assert!(from_js_millis(8.64e15).is_some());
+        assert_eq!(from_js_millis(8.64e15 + 1.0), None);
+        assert_eq!(from_js_millis(f64::INFINITY), None);
+        assert_eq!(from_js_millis(f64::NEG_INFINITY), None);
+        assert_eq!(from_js_millis(f64::NAN), None);
+    }
+
+    #[test]
+    fn to_millis() {
+        assert_eq!(to_js_millis(UNIX_EPOCH), 0.0);
+        assert_eq!(
+            to_js_millis(UNIX_EPOCH + Duration::from_nanos(999_999)),
+            0.0
+        );
+        let before = UNIX_EPOCH.checked_sub(Duration::from_nanos(1)).unwrap();
+        assert_eq!(to_js_millis(before), -1.0);
+        let before = UNIX_EPOCH.checked_sub(Duration::from_millis(1)).unwrap();
+        assert_eq!(to_js_millis(before), -1.0);
+        for &millis in &[0.0, 1.0, 1_518_568_087_250.0, -86_400_000.0, 8.64e15] {
+            assert_eq!(to_js_millis(from_js_millis(millis).unwrap()), millis);
+        }
+    }
+
+    #[test]
+    fn timestamp() {
+        let ts = Timestamp::from_js_millis(1_518_568_087_000.0).unwrap();
+        assert_eq!(ts.to_string(), "2018-02-14T00:28:07Z");
+        assert_eq!(ts.to_js_millis(), 1_518_568_087_000.0);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::civil::CivilDate;
+use crate::date::Error;
+
+/// Order of the day and the month in a numeric date like `01/06/2024`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DateOrder {
+    /// `DD/MM/YYYY`, as in most of Europe
+    DayFirst,
+    /// `MM/DD/YYYY`, as in the United States
+    MonthFirst,
+}
+
+/// Parser of numeric dates as found in spreadsheets and CSV exports
+///
+/// Accepts `01/06/2024`, `1-6-24` or `01.06.2024`, in the [`DateOrder`]
+/// given, with the same separator twice, which is one of `/`, `-` and `.`.
+/// The day and the month are one or two digits, the year two or four.
+/// Dates starting with a four-digit year, like `2024-06-01`, are always
+/// read year first.
+///
+/// Two-digit years fall into the hundred years starting at the century
+/// pivot, 1969 by default as in POSIX `strptime`, so `68` is 2068 and `69`
+/// is 1969.
+///
+/// # Example
+///
+/// ```
+/// use humantime::{CivilDate, DateOrder, DateParser};
+///
+/// let us = DateParser::new(DateOrder::MonthFirst);
+/// assert_eq!(us.parse("06/01/2024"), CivilDate::new(2024, 6, 1));
+/// assert_eq!(us.parse("6-1-24"), CivilDate::new(2024, 6, 1));
+/// let eu = DateParser::new(DateOrder::DayFirst).with_century_pivot(1950);
+/// assert_eq!(eu.parse("01.06.49"), CivilDate::new(2049, 6, 1));
+/// assert_eq!(eu.parse("01.06.50"), CivilDate::new(1950, 6, 1));
+/// assert!(eu.parse("31/06/2024").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateParser {
+    order: DateOrder,
+    pivot: i32,
+}
+
+fn number(s: &str, widths: &[usize]) -> Result<u32, Error> {
+    if !widths.contains(&s.len()) {
+        return Err(Error::InvalidFormat);
+    }
+    s.bytes().try_fold(0, |n, c| match c {
+        b'0'..=b'9' => Ok(n * 10 + u32::from(c - b'0')),
+        _ => Err(Error::InvalidDigit),
+    })
+}
+
+impl DateParser {
+    /// Creates a parser with the day and the month in `order`
+    pub fn new(order: DateOrder) -> DateParser {
+        DateParser { order, pivot: 1969 }
+    }
+
+    /// Sets the first year that a two-digit year can mean
+    ///
+    /// `with_century_pivot(1950)` reads `50` to `99` as 1950 to 1999 and
+    /// `00` to `49` as 2000 to 2049.
+    pub fn with_century_pivot(self, pivot: i32) -> DateParser {
+        DateParser { pivot, ..self }
+    }
+
+    /// Parses the date, ignoring surrounding whitespace
+    pub fn parse(&self, s: &str) -> Result<CivilDate, Error> {
+        let s = s.trim();
+        let sep = match s.find(|c: char| !c.is_ascii_digit()) {
+            Some(idx) => s[idx..].chars().next().unwrap(),
+            None => return Err(Error::InvalidFormat),
+        };
+        if !matches!(sep, '/' | '-' | '.') {
+            return Err(Error::InvalidDigit);
+        }
+        let mut parts = s.split(sep);
+        let (first, second, third) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(a), Some(b), Some(c)) if parts.next().is_none() => (a, b, c),
+            _ => return Err(Error::InvalidFormat),
+        };
+        if first.len() == 4 {
+            let year = number(first, &[4])?;
+            let month = number(second, &[1, 2])?;
+            let day = number(third, &[1, 2])?;
+            return CivilDate::new(year as i32, month, day);
+        }
+        let (day, month) = match self.order {
+            DateOrder::DayFirst => (first, second),
+            DateOrder::MonthFirst => (second, first),
+        };
+        let day = number(day, &[1, 2])?;
+        let month = number(month, &[1, 2])?;
+        let year = match third.len() {
+            2 => {
+                let yy = number(third, &[2])? as i32;
+                // the year in the window with these last two digits
+                self.pivot + (yy - self.pivot).rem_euclid(100)
+            }
+            _ => number(third, &[4])? as i32,
+        };
+        CivilDate::new(year, month, day)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DateOrder, DateParser};
+    use crate::civil::CivilDate;
+    use crate::date::Error;
+
+    fn date(year: i32, month: u32, day: u32) -> Result<CivilDate, Error> {
+        CivilDate::new(year, month, day)
+    }
+
+    #[test]
+    fn orders() {
+        let us = DateParser::new(DateOrder::MonthFirst);
+        let eu = DateParser::new(DateOrder::DayFirst);
+        assert_eq!(us.parse("01/06/2024"), date(2024, 1, 6));
+        assert_eq!(eu.parse("01/06/2024"), date(2024, 6, 1));
+        assert_eq!(us.parse(" 12.31.1999 "), date(1999, 12, 31));
+        assert_eq!(eu.parse("31-12-1999"), date(1999, 12, 31));
+        assert_eq!(us.parse("2024-06-01"), date(2024, 6, 1));
+        assert_eq!(eu.parse("2024/6/1"), date(2024, 6, 1));
+        assert_eq!(us.parse("13/01/2024"), Err(Error::OutOfRange));
+        assert_eq!(eu.parse("29/02/2023"), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    fn two_digit_years() {
+        let us = DateParser::new(DateOrder::MonthFirst);
+        assert_eq!(us.parse("06-01-24"), date(2024, 6, 1));
+        assert_eq!(us.parse("06-01-68"), date(2068, 6, 1));
+        assert_eq!(us.parse("06-01-69"), date(1969, 6, 1));
+        assert_eq!(us.parse("06-01-00"), date(2000, 6, 1));
+        let us = us.with_century_pivot(2000);
+        assert_eq!(us.parse("06-01-99"), date(2099, 6, 1));
+        let us = us.with_century_pivot(1901);
+        assert_eq!(us.parse("06-01-00"), date(2000, 6, 1));
+        assert_eq!(us.parse("06-01-01"), date(1901, 6, 1));
+    }
+
+    #[test]
+    fn errors() {
+        let eu = DateParser::new(DateOrder::DayFirst);
+        assert_eq!(eu.parse(""), Err(Error::InvalidFormat));
+        assert_eq!(eu.parse("01062024"), Err(Error::InvalidFormat));
+        assert_eq!(eu.parse("01 06 2024"), Err(Error::InvalidDigit));
+        assert_eq!(eu.parse("01/06-2024"), Err(Error::InvalidFormat));
+        assert_eq!(eu.parse("01/06"), Err(Error::InvalidFormat));
+        assert_eq!(eu.parse("01/06/2024/1"), Err(Error::InvalidFormat));
+        assert_eq!(eu.parse("001/06/2024"), Err(Error::InvalidFormat));
+        assert_eq!(eu.parse("01/06/202"), Err(Error::InvalidFormat));
+        assert_eq!(eu.parse("01//2024"), Err(Error::InvalidFormat));
+        assert_eq!(eu.parse("0x/06/2024"), Err(Error::InvalidDigit));
+    }
+}
@@ -6,11 +6,47 @@
 //! * Formats durations in similar form `2years 2min 12us`
 //! * Parses and formats timestamp in `rfc3339` format: `2018-01-01T12:53:00Z`
 //! * Parses timestamps in a weaker format: `2018-01-01 12:53:00`
+//! * Applies durations to timestamps: `add_human(now, "2h")`
 //!
 //! Timestamp parsing/formatting is super-fast because format is basically
 //! fixed.
 //!
 //! See [humantime-serde] for serde integration (previous crate [serde-humantime] looks unmaintained).
+//! With the `serde` feature enabled, the `serde` module of this crate
+//! provides helpers accepting both strings and bare numbers.
+//!
+//! With the `wasm` feature enabled, `from_js_millis` and `to_js_millis`
+//! convert times from and to JavaScript epoch milliseconds. On
+//! `wasm32-unknown-unknown` the system clock is not available, so use the
+//! functions taking an explicit `now` (like [`parse_date_phrase`] or
+//! [`Expiry::deadline`]) and [`Rfc3339Clock::with_clock`] instead of
+//! [`now_rfc3339`] and [`HumanInstant::now`].
+//!
+//! With the `arbitrary` feature enabled, the [`Duration`], [`Timestamp`] and
+//! [`Timeout`] wrappers implement `arbitrary::Arbitrary`, generating only
+//! values which can be formatted and parsed back.
+//!
+//! With the `diagnostics` feature enabled, `DurationError::render` prints
+//! the offending part of the input and the parse errors implement
+//! `miette::Diagnostic`.
+//!
+//! With the `word-numbers` feature enabled, the lenient mode of
+//! [`ParseOptions::with_lenient`] also accepts spelled-out English numbers
+//! below a million, like `forty-five minutes`.
+//!
+//! The `toml-datetime` feature adds `parse_toml_datetime`, reading TOML
+//! offset and local datetimes, and lets the [`Timestamp`] wrapper
+//! deserialize TOML datetimes with the `serde` feature.
+//!
+//! The `local-offset` feature adds `format_rfc3339_local` and
+//! `Zone::local`, which read the time zone of the system from the `TZ`
+//! environment variable or `/etc/localtime`.
+//!
+//! The `proptest` feature adds the `proptest` module with strategies
+//! generating valid durations, timestamps and their string forms.
+//!
+//! The crate has no unsafe code. It is built with `#![forbid(unsafe_code)]`,
+//! so any unsafe block added later fails to compile.
 //!
 //! [serde-humantime]: https://docs.rs/serde-humantime/0.1.1/serde_humantime/
 //! [humantime-serde]: https://docs.rs/humantime-serde
@@ -18,16 +54,139 @@
 #![forbid(unsafe_code)]
 #![warn(missing_debug_implementations, missing_docs, unreachable_pub)]
 
+mod age;
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impl;
+mod backoff;
+mod bucket;
+mod business;
+mod civil;
+mod clock;
 mod date;
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
+mod dialect;
 mod duration;
+pub mod env;
+mod expiry;
+mod extract;
+mod ffmpeg;
+mod float;
+mod grammar;
+mod human;
+mod jitter;
+#[cfg(feature = "wasm")]
+mod js;
+mod lenient_date;
+mod log_value;
+mod lossy;
+mod normalize;
+mod observer;
+mod options;
+mod postgres;
+mod predicate;
+mod progress;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+mod protobuf;
+mod quantize;
+mod relative;
+mod retention;
+#[cfg(feature = "schemars")]
+mod schemars_impl;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod signed;
+mod sort;
+mod sortable;
+pub mod styles;
+mod summary;
+pub mod templates;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+mod ticks;
+mod tolerance;
+#[cfg(feature = "toml-datetime")]
+mod toml_datetime;
+mod window;
+#[cfg(feature = "word-numbers")]
+mod words;
 mod wrapper;
+mod zone;
 
+pub use self::age::{parse_age_filter, AgeFilter, Error as AgeFilterError};
+pub use self::backoff::{Backoff, Delays, Error as BackoffError};
+pub use self::bucket::{format_bucket_label, format_bucket_labels};
+pub use self::business::{
+    parse_business_duration, parse_business_span, BusinessCalendar, BusinessSpan, Weekday,
+};
+pub use self::civil::{days_since_epoch, iso_week, month_index, CivilDate, CivilTime};
+pub use self::clock::{
+    now_rfc3339, CachedFormatter, CachedTimestamp, Clock, Rfc3339Clock, SystemClock,
+};
 pub use self::date::Rfc3339Timestamp;
 pub use self::date::{
-    format_rfc3339, format_rfc3339_micros, format_rfc3339_millis, format_rfc3339_nanos,
-    format_rfc3339_seconds,
+    format_rfc3339, format_rfc3339_basic, format_rfc3339_micros, format_rfc3339_millis,
+    format_rfc3339_nanos, format_rfc3339_seconds,
+};
+pub use self::date::{
+    parse_rfc3339, parse_rfc3339_basic, parse_rfc3339_weak, Error as TimestampError,
+};
+pub use self::dialect::parse_duration_kafka;
+pub use self::dialect::{format_duration_kubernetes, parse_duration_kubernetes};
+pub use self::dialect::{
+    format_duration_prometheus, parse_duration_prometheus, Error as DialectError,
+};
+pub use self::dialect::{format_metric_safe, parse_metric_safe};
+pub use self::duration::ErrorKind as DurationErrorKind;
+pub use self::duration::{format_duration, FormattedDuration, Style};
+pub use self::duration::{parse_duration, parse_duration_nanos, parse_duration_os};
+pub use self::duration::{Error as DurationError, ParseUnitError, Unit};
+pub use self::expiry::{Error as ExpiryError, Expiry};
+pub use self::extract::{extract_durations, extract_timestamps};
+pub use self::ffmpeg::{format_duration_ffmpeg, parse_duration_ffmpeg, parse_duration_media};
+pub use self::float::{
+    duration_from_secs_f64, format_secs_f64, parse_duration_secs_f64, Error as FloatSecsError,
+};
+pub use self::grammar::{grammar, Grammar, UnitSyntax};
+pub use self::human::{parse, FromHuman, ToHuman};
+pub use self::jitter::{Error as JitterError, Jittered};
+#[cfg(feature = "wasm")]
+pub use self::js::{from_js_millis, to_js_millis};
+pub use self::lenient_date::{DateOrder, DateParser};
+pub use self::log_value::LogValue;
+pub use self::lossy::{parse_duration_lossy, Diagnostic};
+pub use self::normalize::{convert, normalize};
+pub use self::observer::{set_parse_observer, with_parse_observer, ParseObserver, ParsePath};
+pub use self::options::{default_options, set_default_options, with_format_style};
+pub use self::options::{FormatOptions, ParseOptions};
+pub use self::postgres::{
+    format_duration_iso8601, format_duration_postgres, parse_duration_postgres, PgInterval,
+};
+pub use self::predicate::{
+    parse_duration_predicate, parse_latency_spec, Comparison, DurationPredicate,
+    Error as PredicateError, LatencySpec,
+};
+pub use self::progress::{estimate_eta, format_eta, FormattedEta};
+pub use self::protobuf::{Error as ProtobufError, ProtoDuration, ProtoTimestamp};
+pub use self::quantize::{align_up, ceil_to, floor_to, round_to, truncate_to};
+pub use self::relative::{
+    add_human, advance_business, parse_date_phrase, sub_human, Error as RelativeError,
 };
-pub use self::date::{parse_rfc3339, parse_rfc3339_weak, Error as TimestampError};
-pub use self::duration::{format_duration, FormattedDuration};
-pub use self::duration::{parse_duration, Error as DurationError};
-pub use self::wrapper::{Duration, Timestamp};
+pub use self::retention::{Error as RetentionError, Period, RetentionPolicy};
+pub use self::signed::{format_change, format_signed, FormattedChange, FormattedSigned};
+pub use self::sort::{compare_durations, sort_key};
+pub use self::sortable::{format_sortable, parse_sortable, SortableTimestamp};
+pub use self::styles::format_with_style;
+pub use self::summary::{summarize, DurationSummary};
+pub use self::ticks::{nice_tick_labels, nice_ticks};
+pub use self::tolerance::{approx_eq, within, Error as ToleranceError, Tolerance};
+#[cfg(feature = "toml-datetime")]
+pub use self::toml_datetime::{parse_toml_datetime, LocalCompletion};
+pub use self::window::{parse_time_window, Error as TimeWindowError, TimeWindow};
+pub use self::wrapper::{Duration, HumanInstant, Timeout, Timestamp};
+#[cfg(feature = "local-offset")]
+pub use self::zone::format_rfc3339_local;
+pub use self::zone::{next_wall_clock, Ambiguity, Error as ZoneError, Zone};
@@ -0,0 +1,68 @@
+use std::fmt;
+
+use crate::date::{format_rfc3339, Rfc3339Timestamp};
+use crate::duration::FormattedDuration;
+use crate::wrapper::Timestamp;
+
+/// Adapter recording a value as its human-friendly text in structured logs
+///
+/// Both `Display` and `Debug` write the formatted value, so the field reads
+/// the same whether the logging macro uses `%value` or `?value`, as the
+/// `tracing` and `slog` macros and the `log` key-value macros allow.
+/// Nothing is allocated, the value is written straight into the event.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::format_duration;
+///
+/// let elapsed = format_duration(Duration::from_millis(1500));
+/// // tracing::info!(elapsed = ?elapsed.as_value(), "request done");
+/// assert_eq!(format!("{:?}", elapsed.as_value()), "1s 500ms");
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LogValue<T>(T);
+
+impl<T: fmt::Display> fmt::Display for LogValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Debug for LogValue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FormattedDuration {
+    /// Returns an adapter for recording the duration as a log field
+    pub fn as_value(&self) -> LogValue<FormattedDuration> {
+        LogValue(*self)
+    }
+}
+
+impl Timestamp {
+    /// Returns an adapter for recording the timestamp as a log field
+    pub fn as_value(&self) -> LogValue<Rfc3339Timestamp> {
+        LogValue(format_rfc3339(**self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::duration::{format_duration_with, Style};
+    use crate::wrapper::Timestamp;
+
+    #[test]
+    fn debug_is_display() {
+        let d = format_duration_with(Duration::from_secs(90), Style::Verbose);
+        assert_eq!(format!("{:?}", d.as_value()), "1 minute 30 seconds");
+        assert_eq!(d.as_value().to_string(), "1 minute 30 seconds");
+        let t = Timestamp::from(UNIX_EPOCH + Duration::from_secs(1_518_568_087));
+        assert_eq!(format!("{:?}", t.as_value()), "2018-02-14T00:28:07Z");
+    }
+}
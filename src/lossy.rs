@@ -0,0 +1,211 @@
+use std::fmt;
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::duration::{parse_duration, Error};
+
+/// Part of the input ignored by [`parse_duration_lossy`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    span: Range<usize>,
+    error: Error,
+}
+
+impl Diagnostic {
+    /// Byte range of the ignored part of the input
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// The error which made the parser ignore this part
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    /// The ignored part of `input`
+    pub fn ignored<'a>(&self, input: &'a str) -> &'a str {
+        &input[self.span.clone()]
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ignored {}..{}: {}",
+            self.span.start, self.span.end, self.error
+        )
+    }
+}
+
+fn is_number(c: char) -> bool {
+    c.is_ascii_digit() || c == '.'
+}
+
+fn is_word(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == 'µ'
+}
+
+/// Start of the number ending before `end`, including whitespace between
+/// its digits, the parser accepts `1 2h` as `12h`
+fn number_start(s: &str, end: usize) -> usize {
+    let mut start = end;
+    for (i, c) in s[..end].char_indices().rev() {
+        if is_number(c) {
+            start = i;
+        } else if !c.is_whitespace() {
+            break;
+        }
+    }
+    start
+}
+
+/// Range to ignore so that parsing can continue after `err`
+fn skipped(s: &str, err: &Error) -> Option<Range<usize>> {
+    let end_of = |pos: usize, f: fn(char) -> bool| {
+        s[pos..]
+            .char_indices()
+            .find(|&(_, c)| !f(c))
+            .map_or(s.len(), |(i, _)| pos + i)
+    };
+    // a word or a run of other characters, or everything if no number
+    // follows
+    let junk_end = |pos: usize| {
+        if !s[pos..].contains(|c: char| c.is_ascii_digit()) {
+            s.len()
+        } else if s[pos..].starts_with(is_word) {
+            end_of(pos, is_word)
+        } else {
+            end_of(pos, |c| !is_word(c) && !is_number(c) && !c.is_whitespace())
+        }
+    };
+    match *err {
+        Error::InvalidCharacter(pos) => Some(number_start(s, pos)..junk_end(pos)),
+        Error::NumberExpected(pos) => {
+            let pos = pos + s[pos..].len() - s[pos..].trim_start().len();
+            Some(pos..junk_end(pos))
+        }
+        // the concatenated component, like `30m` in `1h30m`
+        Error::WhitespaceExpected(pos) => Some(pos..end_of(end_of(pos, is_number), is_word)),
+        Error::MissingNumber { start, end, .. } => Some(start..end),
+        Error::UnknownUnit { start, end, .. } => Some(number_start(s, start)..end),
+        Error::NumberOverflow | Error::Empty => None,
+    }
+}
+
+/// Parses a duration, skipping the parts which can't be parsed
+///
+/// Components with an unknown unit, numbers without a unit, stray words
+/// and characters are ignored, and reported in the returned diagnostics.
+/// The duration is `None` if nothing could be parsed or a number is too
+/// large.
+///
+/// This is meant for migrating hand-written configs, where the report
+/// can be reviewed, use [`parse_duration`] everywhere else.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::parse_duration_lossy;
+///
+/// let input = "1h 3 fortnights 30min (approx.)";
+/// let (duration, diagnostics) = parse_duration_lossy(input);
+/// assert_eq!(duration, Some(Duration::from_secs(5400)));
+/// let ignored: Vec<&str> = diagnostics.iter().map(|d| d.ignored(input)).collect();
+/// assert_eq!(ignored, ["3 fortnights", "(approx.)"]);
+/// ```
+pub fn parse_duration_lossy(s: &str) -> (Option<Duration>, Vec<Diagnostic>) {
+    let mut buf = s.to_owned();
+    let mut diagnostics = Vec::new();
+    loop {
+        let error = match parse_duration(&buf) {
+            Ok(duration) => return (Some(duration), diagnostics),
+            Err(error) => error,
+        };
+        let span = match skipped(&buf, &error) {
+            Some(span) if !buf[span.clone()].trim().is_empty() => span,
+            _ => {
+                if diagnostics.is_empty() || error != Error::Empty {
+                    diagnostics.push(Diagnostic {
+                        span: 0..s.len(),
+                        error,
+                    });
+                }
+                return (None, diagnostics);
+            }
+        };
+        let text = &buf[span.clone()];
+        let start = span.start + text.len() - text.trim_start().len();
+        let end = span.end - text.len() + text.trim_end().len();
+        // spaces keep the offsets of the rest of the input
+        buf.replace_range(span.clone(), &" ".repeat(span.len()));
+        diagnostics.push(Diagnostic {
+            span: start..end,
+            error,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::parse_duration_lossy;
+    use crate::duration::{Error, ErrorKind};
+
+    fn lossy(s: &str) -> (Option<u64>, Vec<&str>) {
+        let (duration, diagnostics) = parse_duration_lossy(s);
+        let ignored = diagnostics.iter().map(|d| d.ignored(s)).collect();
+        (duration.map(|d| d.as_secs()), ignored)
+    }
+
+    #[test]
+    fn valid() {
+        assert_eq!(
+            parse_duration_lossy("1h 5s"),
+            (Some(Duration::from_secs(3605)), vec![])
+        );
+    }
+
+    #[test]
+    fn skipped() {
+        assert_eq!(lossy("1h 2x 3s"), (Some(3603), vec!["2x"]));
+        assert_eq!(lossy("1h30m!"), (Some(5400), vec!["!"]));
+        assert_eq!(lossy("1h, 2m"), (Some(3720), vec![","]));
+        assert_eq!(lossy("1!2h"), (Some(7200), vec!["1!"]));
+        assert_eq!(lossy("5s and 2min"), (Some(125), vec!["and"]));
+        assert_eq!(lossy("10 min 5"), (Some(600), vec!["5"]));
+        assert_eq!(lossy("2h # two hours"), (Some(7200), vec!["# two hours"]));
+        assert_eq!(lossy("µ5s"), (Some(5), vec!["µ"]));
+    }
+
+    #[test]
+    fn concatenated() {
+        // returned by the parser only when the defaults deny concatenation
+        let error = Error::WhitespaceExpected(2);
+        assert_eq!(super::skipped("1h30m 5s", &error), Some(2..5));
+        assert_eq!(super::skipped("1h2.5m", &error), Some(2..6));
+    }
+
+    #[test]
+    fn fatal() {
+        assert_eq!(lossy(""), (None, vec![""]));
+        assert_eq!(lossy("bogus"), (None, vec!["bogus"]));
+        let (duration, diagnostics) = parse_duration_lossy("1h 100000000000000000000s");
+        assert_eq!(duration, None);
+        assert_eq!(diagnostics[0].error(), &Error::NumberOverflow);
+        assert_eq!(diagnostics[0].span(), 0..25);
+    }
+
+    #[test]
+    fn display() {
+        let (_, diagnostics) = parse_duration_lossy("1h 2x");
+        assert_eq!(diagnostics[0].error().kind(), ErrorKind::UnknownUnit);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "ignored 3..5: unknown time unit \"x\", supported units: ns, us/µs, ms, \
+            sec, min, hours, days, weeks, months, years (and few variations)"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::convert::TryFrom;
+use std::time::Duration;
+
+use crate::duration::{format_duration_with, parse_duration, parse_duration_nanos};
+use crate::duration::{Error, Style, Unit};
+
+/// Rewrites a duration in the canonical mixed form of [`format_duration`](crate::format_duration)
+///
+/// The output is always in the default style, regardless of
+/// [`set_default_options`](crate::set_default_options), so scripts
+/// migrating configs produce the same result everywhere.
+///
+/// # Example
+///
+/// ```
+/// use humantime::normalize;
+///
+/// assert_eq!(normalize("90m").unwrap(), "1h 30m");
+/// assert_eq!(normalize("1 hour 1800 seconds").unwrap(), "1h 30m");
+/// assert!(normalize("90").is_err());
+/// ```
+pub fn normalize(s: &str) -> Result<String, Error> {
+    let duration = parse_duration(s)?;
+    Ok(format_duration_with(duration, Style::Human).to_string())
+}
+
+/// Rewrites a duration as a single number of `unit`
+///
+/// The output always parses back to the same duration. Values which have
+/// no exact decimal in `unit`, like a second in hours, are written in the
+/// canonical mixed form of [`normalize`] instead. Months and years use
+/// their average lengths, as in the parser.
+///
+/// # Example
+///
+/// ```
+/// use humantime::{convert, Unit};
+///
+/// assert_eq!(convert("1h 30m", Unit::Second).unwrap(), "5400s");
+/// assert_eq!(convert("90s", Unit::Minute).unwrap(), "1.5m");
+/// assert_eq!(convert("1 week", Unit::Day).unwrap(), "7d");
+/// assert_eq!(convert("1s", Unit::Hour).unwrap(), "1s");
+/// ```
+///
+/// Returns [`Error::NumberOverflow`] if such a value is also too large for
+/// [`format_duration`](crate::format_duration).
+pub fn convert(s: &str, unit: Unit) -> Result<String, Error> {
+    let nanos = parse_duration_nanos(s)?;
+    let len = u128::from(unit.nanos_per_unit());
+    let mut out = (nanos / len).to_string();
+    let mut rest = nanos % len;
+    if rest != 0 {
+        out.push('.');
+        // exact fractions of the units end within this many digits
+        for _ in 0..20 {
+            rest *= 10;
+            out.push(char::from(b'0' + (rest / len) as u8));
+            rest %= len;
+            if rest == 0 {
+                break;
+            }
+        }
+    }
+    out.push_str(unit.as_str());
+    // the parser rounds some fractions, so the check is needed
+    if rest == 0 && parse_duration_nanos(&out) == Ok(nanos) {
+        return Ok(out);
+    }
+    let secs = u64::try_from(nanos / 1_000_000_000).map_err(|_| Error::NumberOverflow)?;
+    let duration = Duration::new(secs, (nanos % 1_000_000_000) as u32);
+    Ok(format_duration_with(duration, Style::Human).to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{convert, normalize};
+    use crate::duration::{parse_duration_nanos, Error, Unit};
+
+    #[test]
+    fn normalized() {
+        assert_eq!(normalize("0s").unwrap(), "0s");
+        assert_eq!(normalize("3600s").unwrap(), "1h");
+        assert_eq!(normalize("1500ms").unwrap(), "1s 500ms");
+        assert_eq!(normalize("25h").unwrap(), "1day 1h");
+        assert_eq!(normalize(""), Err(Error::Empty));
+    }
+
+    #[test]
+    fn converted() {
+        assert_eq!(convert("0s", Unit::Hour).unwrap(), "0h");
+        assert_eq!(convert("1s", Unit::Nanosecond).unwrap(), "1000000000ns");
+        assert_eq!(convert("1.5ms", Unit::Microsecond).unwrap(), "1500us");
+        assert_eq!(convert("1s", Unit::Minute).unwrap(), "1s");
+        assert_eq!(convert("1s", Unit::Hour).unwrap(), "1s");
+        assert_eq!(convert("1ns", Unit::Year).unwrap(), "1ns");
+        assert_eq!(convert("59.5m", Unit::Hour).unwrap(), "59m 30s");
+        assert_eq!(convert("36h", Unit::Day).unwrap(), "1.5d");
+        assert_eq!(convert("1ns", Unit::Second).unwrap(), "0.000000001s");
+        assert_eq!(convert("2y", Unit::Year).unwrap(), "2y");
+        assert_eq!(
+            convert("18446744073709551615y", Unit::Year).unwrap(),
+            "18446744073709551615y"
+        );
+        assert_eq!(
+            convert("18446744073709551615y 1s", Unit::Year),
+            Err(Error::NumberOverflow)
+        );
+        assert!(convert("5 parsecs", Unit::Second).is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let inputs = [
+            "2h 6m",
+            "1s",
+            "1ns",
+            "999ms",
+            "1.5h",
+            "90s",
+            "1day 1ns",
+            "3w",
+            "1M",
+            "1y 1s",
+            "7.123us 4ns",
+        ];
+        for input in inputs {
+            let nanos = parse_duration_nanos(input).unwrap();
+            for unit in Unit::ALL {
+                let converted = convert(input, unit).unwrap();
+                assert_eq!(
+                    parse_duration_nanos(&converted),
+                    Ok(nanos),
+                    "{} in {}: {}",
+                    input,
+                    unit,
+                    converted
+                );
+            }
+        }
+    }
+}
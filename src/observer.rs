@@ -0,0 +1,320 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+
+use crate::duration::{Error, Unit};
+
+/// The parts of the duration syntax an input used
+///
+/// On errors, only the components before the error are recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ParsePath {
+    pub(crate) units: u16,
+    pub(crate) concatenated: bool,
+    pub(crate) fractions: bool,
+    pub(crate) lenient: bool,
+    pub(crate) bare_zero: bool,
+}
+
+impl ParsePath {
+    pub(crate) fn add_unit(&mut self, unit: Unit) {
+        self.units |= 1 << unit as u16;
+    }
+
+    /// Returns `true` if a component had the unit
+    ///
+    /// Minutes written `M` with
+    /// [`ParseOptions::with_m_as_minutes`](crate::ParseOptions::with_m_as_minutes)
+    /// are minutes.
+    pub fn uses_unit(&self, unit: Unit) -> bool {
+        self.units & 1 << unit as u16 != 0
+    }
+
+    /// The units of the components, from the smallest to the largest
+    pub fn units(&self) -> impl Iterator<Item = Unit> + '_ {
+        Unit::ALL
+            .iter()
+            .copied()
+            .filter(move |&u| self.uses_unit(u))
+    }
+
+    /// Returns `true` if components were written without whitespace, like `1h30m`
+    pub fn is_concatenated(&self) -> bool {
+        self.concatenated
+    }
+
+    /// Returns `true` if a number had a fractional part, like `1.5h`
+    pub fn has_fractions(&self) -> bool {
+        self.fractions
+    }
+
+    /// Returns `true` if the input needed the lenient mode
+    ///
+    /// These are bare units, articles, `half`, `and` and spelled-out
+    /// numbers, accepted by
+    /// [`ParseOptions::with_lenient`](crate::ParseOptions::with_lenient).
+    pub fn is_lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Returns `true` for the input `0`, which needs no unit
+    pub fn is_bare_zero(&self) -> bool {
+        self.bare_zero
+    }
+}
+
+/// Receives the parsed durations
+///
+/// Install one with [`with_parse_observer`] for a thread, or with
+/// [`set_parse_observer`] for the whole process, to measure which syntaxes
+/// and units users write, for example before denying calendar units or
+/// concatenation with [`set_default_options`](crate::set_default_options).
+/// Closures taking the same arguments as [`observe`](Self::observe) are
+/// observers.
+pub trait ParseObserver {
+    /// Called after parsing `input`, with the outcome and the syntax used
+    fn observe(&self, input: &str, outcome: Result<(), &Error>, path: &ParsePath);
+}
+
+impl<F: Fn(&str, Result<(), &Error>, &ParsePath)> ParseObserver for F {
+    fn observe(&self, input: &str, outcome: Result<(), &Error>, path: &ParsePath) {
+        self(input, outcome, path)
+    }
+}
+
+type GlobalObserver = &'static (dyn ParseObserver + Send + Sync);
+
+thread_local! {
+    /// Observer set by `with_parse_observer` on this thread
+    static OBSERVER: RefCell<Option<Rc<dyn ParseObserver>>> = const { RefCell::new(None) };
+    /// Whether this thread is running an observer
+    static NOTIFYING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Observer set by `set_parse_observer`, never replaced once set
+static GLOBAL: Mutex<Option<GlobalObserver>> = Mutex::new(None);
+/// Tells if `GLOBAL` is set, so that parsing doesn't lock it before
+static GLOBAL_SET: AtomicBool = AtomicBool::new(false);
+
+/// Sets the observer receiving the durations parsed on every thread
+///
+/// The observer can be set only once, later calls return it back. It is
+/// called in addition to the observer of [`with_parse_observer`], for the
+/// same parsers, and durations parsed by the observer itself aren't
+/// reported either.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use humantime::{parse_duration, set_parse_observer, DurationError, ParsePath};
+///
+/// static FAILED: AtomicUsize = AtomicUsize::new(0);
+/// let observer = |_: &str, outcome: Result<(), &DurationError>, _: &ParsePath| {
+///     if outcome.is_err() {
+///         FAILED.fetch_add(1, Ordering::Relaxed);
+///     }
+/// };
+/// assert!(set_parse_observer(Box::new(observer)).is_ok());
+/// assert!(set_parse_observer(Box::new(observer)).is_err());
+///
+/// std::thread::spawn(|| parse_duration("2x").unwrap_err()).join().unwrap();
+/// parse_duration("1h").unwrap();
+/// assert_eq!(FAILED.load(Ordering::Relaxed), 1);
+/// ```
+pub fn set_parse_observer(
+    observer: Box<dyn ParseObserver + Send + Sync>,
+) -> Result<(), Box<dyn ParseObserver + Send + Sync>> {
+    let mut global = GLOBAL.lock().unwrap_or_else(PoisonError::into_inner);
+    if global.is_some() {
+        return Err(observer);
+    }
+    *global = Some(Box::leak(observer));
+    GLOBAL_SET.store(true, Ordering::Release);
+    Ok(())
+}
+
+fn global_observer() -> Option<GlobalObserver> {
+    if !GLOBAL_SET.load(Ordering::Acquire) {
+        return None;
+    }
+    *GLOBAL.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Runs `f` with `observer` receiving the durations parsed on this thread
+///
+/// This covers [`parse_duration`](crate::parse_duration),
+/// [`parse_duration_nanos`](crate::parse_duration_nanos),
+/// [`ParseOptions::parse`](crate::ParseOptions::parse) and everything
+/// built on them, like the wrapper types and the `serde` helpers. The
+/// previous observer is restored when `f` returns or panics. Durations
+/// parsed by the observer itself aren't reported.
+///
+/// # Example
+///
+/// ```
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+/// use humantime::{parse_duration, with_parse_observer, DurationError, ParsePath, Unit};
+///
+/// let concatenated = Rc::new(Cell::new(0));
+/// let count = concatenated.clone();
+/// with_parse_observer(
+///     Rc::new(move |_: &str, _: Result<(), &DurationError>, path: &ParsePath| {
+///         if path.is_concatenated() {
+///             count.set(count.get() + 1);
+///         }
+///         assert!(!path.uses_unit(Unit::Month));
+///     }),
+///     || {
+///         parse_duration("1h30m").unwrap();
+///         parse_duration("1h 30m").unwrap();
+///         parse_duration("2x").unwrap_err();
+///     },
+/// );
+/// assert_eq!(concatenated.get(), 1);
+/// ```
+pub fn with_parse_observer<R>(observer: Rc<dyn ParseObserver>, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore(OBSERVER.with(|o| o.replace(Some(observer))));
+    f()
+}
+
+struct Restore(Option<Rc<dyn ParseObserver>>);
+
+struct Notified;
+
+impl Drop for Notified {
+    fn drop(&mut self) {
+        NOTIFYING.with(|n| n.set(false));
+    }
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        OBSERVER.with(|o| *o.borrow_mut() = self.0.take());
+    }
+}
+
+/// Reports a parsed duration to the observers of the process and of this
+/// thread, if any
+pub(crate) fn notify(input: &str, outcome: Result<(), &Error>, path: &ParsePath) {
+    // parsing in an observer isn't reported, so that it doesn't recurse
+    if NOTIFYING.with(|n| n.replace(true)) {
+        return;
+    }
+    let _done = Notified;
+    if let Some(observer) = global_observer() {
+        observer.observe(input, outcome, path);
+    }
+    let observer = OBSERVER.with(|o| o.borrow().clone());
+    if let Some(observer) = observer {
+        observer.observe(input, outcome, path);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::{set_parse_observer, with_parse_observer, ParsePath};
+    use crate::duration::{parse_duration, Error, Unit};
+    use crate::options::ParseOptions;
+
+    fn observe(inputs: &[&str], options: ParseOptions) -> Vec<(String, bool, ParsePath)> {
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let sink = seen.clone();
+        with_parse_observer(
+            Rc::new(
+                move |input: &str, outcome: Result<(), &Error>, path: &ParsePath| {
+                    sink.borrow_mut()
+                        .push((input.to_owned(), outcome.is_ok(), *path));
+                },
+            ),
+            || {
+                for input in inputs {
+                    let _ = options.parse(input);
+                }
+            },
+        );
+        Rc::try_unwrap(seen).unwrap().into_inner()
+    }
+
+    #[test]
+    fn paths() {
+        let seen = observe(
+            &["1h30m", "0", "1.5 days", "2 fortnights"],
+            ParseOptions::new(),
+        );
+        assert_eq!(seen.len(), 4);
+        let (ref input, ok, path) = seen[0];
+        assert_eq!((input.as_str(), ok), ("1h30m", true));
+        assert!(path.is_concatenated() && !path.has_fractions() && !path.is_lenient());
+        assert_eq!(path.units().collect::<Vec<_>>(), [Unit::Minute, Unit::Hour]);
+        assert!(seen[1].2.is_bare_zero());
+        assert!(seen[2].2.has_fractions() && seen[2].2.uses_unit(Unit::Day));
+        assert!(!seen[2].2.is_concatenated());
+        assert!(!seen[3].1);
+        assert_eq!(seen[3].2.units().count(), 0);
+    }
+
+    #[test]
+    fn lenient() {
+        let seen = observe(
+            &["an hour and a half", "2h", "hour"],
+            ParseOptions::new().with_lenient(true),
+        );
+        assert!(seen[0].1 && seen[0].2.is_lenient() && seen[0].2.uses_unit(Unit::Hour));
+        assert!(!seen[1].2.is_lenient());
+        assert!(seen[2].2.is_lenient());
+    }
+
+    #[test]
+    fn scoped() {
+        let seen = observe(&[], ParseOptions::new());
+        assert!(seen.is_empty());
+        // reentrant parsing isn't reported, and doesn't recurse
+        let count = Rc::new(RefCell::new(0));
+        let c = count.clone();
+        with_parse_observer(
+            Rc::new(move |_: &str, _: Result<(), &Error>, _: &ParsePath| {
+                *c.borrow_mut() += 1;
+                parse_duration("1s").unwrap();
+            }),
+            || parse_duration("5m").unwrap(),
+        );
+        assert_eq!(*count.borrow(), 1);
+        parse_duration("5m").unwrap();
+        assert_eq!(*count.borrow(), 1);
+    }
+
+    #[test]
+    fn global() {
+        // the only test setting it, other tests' inputs are reported too
+        static SEEN: AtomicUsize = AtomicUsize::new(0);
+        let observer = |input: &str, _: Result<(), &Error>, _: &ParsePath| {
+            if input == "17 global minutes" {
+                SEEN.fetch_add(1, Ordering::Relaxed);
+                parse_duration("17 global minutes").unwrap_err();
+            }
+        };
+        assert!(set_parse_observer(Box::new(observer)).is_ok());
+        assert!(set_parse_observer(Box::new(observer)).is_err());
+
+        let count = Rc::new(RefCell::new(0));
+        let c = count.clone();
+        with_parse_observer(
+            Rc::new(move |_: &str, _: Result<(), &Error>, _: &ParsePath| {
+                *c.borrow_mut() += 1;
+            }),
+            || parse_duration("17 global minutes").unwrap_err(),
+        );
+        assert_eq!(*count.borrow(), 1);
+        std::thread::spawn(|| parse_duration("17 global minutes").unwrap_err())
+            .join()
+            .unwrap();
+        assert_eq!(SEEN.load(Ordering::Relaxed), 2);
+    }
+}
@@ -0,0 +1,684 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use crate::duration::{self, format_duration_with, parse_duration_with, FormattedDuration, Style};
+use crate::grammar::{self, Grammar};
+
+/// Options of the duration parser
+///
+/// # Example
+///
+/// ```
+/// use humantime::ParseOptions;
+///
+/// let options = ParseOptions::new().with_calendar_units(false);
+/// assert!(options.parse("36h").is_ok());
+/// assert!(options.parse("1 month").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    calendar_units: bool,
+    concatenation: bool,
+    lenient: bool,
+    m_as_minutes: bool,
+}
+
+/// Options of the duration formatter
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::{FormatOptions, Style};
+///
+/// let options = FormatOptions::new().with_style(Style::Verbose);
+/// let text = options.format(Duration::from_secs(5400)).to_string();
+/// assert_eq!(text, "1 hour 30 minutes");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FormatOptions {
+    style: Style,
+}
+
+impl ParseOptions {
+    /// Options of [`parse_duration`](crate::parse_duration) when no defaults
+    /// are set
+    pub const fn new() -> ParseOptions {
+        ParseOptions {
+            calendar_units: true,
+            concatenation: true,
+            lenient: false,
+            m_as_minutes: false,
+        }
+    }
+
+    /// Allows or denies months and years
+    ///
+    /// Their length is an average, so applications may want to accept only
+    /// exact units.
+    pub const fn with_calendar_units(self, allow: bool) -> ParseOptions {
+        ParseOptions {
+            calendar_units: allow,
+            ..self
+        }
+    }
+
+    /// Allows or denies components without whitespace between them
+    ///
+    /// Compact input like `1h30m` is accepted by default, as written by Go
+    /// and Prometheus. Denying it requires whitespace after every unit,
+    /// like `1h 30m`, which suits prose where `1h30m` is more likely a
+    /// typo. Whitespace between a number and its unit stays optional.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use humantime::{DurationError, ParseOptions};
+    ///
+    /// let strict = ParseOptions::new().with_concatenation(false);
+    /// assert!(strict.parse("1h 30m").is_ok());
+    /// assert!(strict.parse("1 hour 30 min").is_ok());
+    /// assert_eq!(strict.parse("1h30m"), Err(DurationError::WhitespaceExpected(2)));
+    /// ```
+    pub const fn with_concatenation(self, allow: bool) -> ParseOptions {
+        ParseOptions {
+            concatenation: allow,
+            ..self
+        }
+    }
+
+    /// Enables or disables the lenient mode for natural-language input
+    ///
+    /// Chat bots and prompts get input like `an hour` or `a day and a
+    /// half`, so the lenient mode accepts:
+    ///
+    /// * units without a number, like `day`, meaning one unit
+    /// * the articles `a` and `an` before a unit, also meaning one unit
+    /// * `half` before a unit, like `half an hour`, meaning half of it
+    /// * `and a half` after a component, adding half of its unit
+    /// * `and` between components, like `1 hour and 30 minutes`
+    ///
+    /// With the `word-numbers` feature, numbers may also be spelled out in
+    /// English, like `two hours` or `one hundred and five seconds`, up to
+    /// `nine hundred ninety-nine thousand nine hundred ninety-nine`.
+    ///
+    /// The words are case-insensitive, unlike units. By default bare
+    /// units are reported as
+    /// [`DurationError::MissingNumber`](crate::DurationError::MissingNumber)
+    /// and the words as [`DurationError::NumberExpected`](crate::DurationError::NumberExpected).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use humantime::ParseOptions;
+    ///
+    /// let lenient = ParseOptions::new().with_lenient(true);
+    /// assert_eq!(lenient.parse("day"), Ok(Duration::from_secs(86400)));
+    /// assert_eq!(lenient.parse("hour 30 min"), Ok(Duration::from_secs(5400)));
+    /// assert_eq!(lenient.parse("an hour and a half"), Ok(Duration::from_secs(5400)));
+    /// assert_eq!(lenient.parse("half a minute"), Ok(Duration::from_secs(30)));
+    /// assert!(ParseOptions::new().parse("day").is_err());
+    /// ```
+    pub const fn with_lenient(self, allow: bool) -> ParseOptions {
+        ParseOptions {
+            lenient: allow,
+            ..self
+        }
+    }
+
+    /// Makes `M` mean minutes instead of months
+    ///
+    /// For users used to Java's `Duration.toString()`, like `PT5M`, or other
+    /// tools where an uppercase `M` is minutes. Only the exact spelling `M`
+    /// changes, `mo` and `month` are still months. Minutes are not a
+    /// calendar unit, so `M` is accepted even when
+    /// [`with_calendar_units`](ParseOptions::with_calendar_units) denies
+    /// months.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use humantime::ParseOptions;
+    ///
+    /// let options = ParseOptions::new().with_m_as_minutes(true);
+    /// assert_eq!(options.parse("5M"), Ok(Duration::from_secs(300)));
+    /// assert_eq!(options.parse("1mo"), ParseOptions::new().parse("1M"));
+    /// ```
+    pub const fn with_m_as_minutes(self, enable: bool) -> ParseOptions {
+        ParseOptions {
+            m_as_minutes: enable,
+            ..self
+        }
+    }
+
+    /// Months and years are accepted
+    pub fn calendar_units(&self) -> bool {
+        self.calendar_units
+    }
+
+    /// Components without whitespace between them, like `1h30m`, are accepted
+    pub fn concatenation(&self) -> bool {
+        self.concatenation
+    }
+
+    /// Natural-language input, like `a day and a half`, is accepted
+    pub fn lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// `M` means minutes instead of months
+    pub fn m_as_minutes(&self) -> bool {
+        self.m_as_minutes
+    }
+
+    /// Parses a duration with these options
+    ///
+    /// Denied units are reported as [`DurationError::UnknownUnit`](crate::DurationError::UnknownUnit).
+    pub fn parse(&self, s: &str) -> Result<Duration, duration::Error> {
+        parse_duration_with(s, self)
+    }
+
+    /// Returns the syntax accepted by [`parse`](ParseOptions::parse)
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use humantime::{ParseOptions, Unit};
+    ///
+    /// let strict = ParseOptions::new()
+    ///     .with_calendar_units(false)
+    ///     .with_concatenation(false);
+    /// let grammar = strict.grammar();
+    /// assert!(grammar.aliases(Unit::Month).is_empty());
+    /// assert!(!grammar.allows_concatenation());
+    /// ```
+    pub fn grammar(&self) -> Grammar {
+        grammar::of(self)
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions::new()
+    }
+}
+
+impl FormatOptions {
+    /// Options of [`format_duration`](crate::format_duration) when no
+    /// defaults are set
+    pub const fn new() -> FormatOptions {
+        FormatOptions {
+            style: Style::Human,
+        }
+    }
+
+    /// Sets the output style
+    pub const fn with_style(self, style: Style) -> FormatOptions {
+        FormatOptions { style }
+    }
+
+    /// The output style
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Returns a built-in profile by name
+    ///
+    /// The profiles are `compact` (`1h 30m`, the default), `verbose` or
+    /// `words` (`1 hour 30 minutes`, words for all the units),
+    /// `abbreviated` (`2y 3mo 1h`, abbreviations for all the units), `go`
+    /// (`1h30m`, exact and accepted by Go's `time.ParseDuration`, see
+    /// [`Style::Go`]), `iso8601` (`PT1H30M`) and `machine`
+    /// (`1h30m`, exact, not read by parsers denying concatenation, see
+    /// [`Style::Machine`]). With the `serde` feature, the options can be
+    /// deserialized from a profile name, so the style can be a setting in
+    /// application config.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use humantime::FormatOptions;
+    ///
+    /// let options = FormatOptions::profile("go").unwrap();
+    /// assert_eq!(options.format(Duration::from_secs(5400)).to_string(), "1h30m");
+    /// assert!(FormatOptions::profile("fancy").is_none());
+    /// ```
+    pub fn profile(name: &str) -> Option<FormatOptions> {
+        PROFILES
+            .iter()
+            .find(|&&(n, _)| n == name)
+            .map(|&(_, style)| FormatOptions::new().with_style(style))
+    }
+
+    /// Formats a duration with these options
+    pub fn format(&self, val: Duration) -> FormattedDuration {
+        format_duration_with(val, self.style)
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> FormatOptions {
+        FormatOptions::new()
+    }
+}
+
+/// Styles with their names in serialized options
+pub(crate) const STYLES: [(Style, &str); 10] = [
+    (Style::Human, "human"),
+    (Style::Verbose, "verbose"),
+    (Style::Prometheus, "prometheus"),
+    (Style::Kubernetes, "kubernetes"),
+    (Style::Postgres, "postgres"),
+    (Style::Iso8601, "iso8601"),
+    (Style::Ffmpeg, "ffmpeg"),
+    (Style::Machine, "machine"),
+    (Style::Abbreviated, "abbreviated"),
+    (Style::Go, "go"),
+];
+
+/// Names accepted by [`FormatOptions::profile`]
+pub(crate) const PROFILES: [(&str, Style); 7] = [
+    ("compact", Style::Human),
+    ("verbose", Style::Verbose),
+    ("words", Style::Verbose),
+    ("abbreviated", Style::Abbreviated),
+    ("go", Style::Go),
+    ("iso8601", Style::Iso8601),
+    ("machine", Style::Machine),
+];
+
+#[cfg(feature = "serde")]
+pub(crate) fn style_name(style: Style) -> &'static str {
+    STYLES
+        .iter()
+        .find(|&&(s, _)| s == style)
+        .map_or("human", |&(_, name)| name)
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn style_by_name(name: &str) -> Option<Style> {
+    STYLES.iter().find(|&&(_, n)| n == name).map(|&(s, _)| s)
+}
+
+const SET: usize = 1;
+const NO_CALENDAR_UNITS: usize = 2;
+const NO_CONCATENATION: usize = 4;
+const LENIENT: usize = 8;
+const M_AS_MINUTES: usize = 16;
+const STYLE_SHIFT: u32 = 8;
+
+/// The default options packed into a single word, zero if they weren't set
+static DEFAULTS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Style set by `with_format_style` on this thread
+    static SCOPED_STYLE: Cell<Option<Style>> = const { Cell::new(None) };
+}
+
+fn encode(parse: ParseOptions, format: FormatOptions) -> usize {
+    let style = STYLES
+        .iter()
+        .position(|&(s, _)| s == format.style)
+        .unwrap_or(0);
+    let mut bits = SET | style << STYLE_SHIFT;
+    if !parse.calendar_units {
+        bits |= NO_CALENDAR_UNITS;
+    }
+    if !parse.concatenation {
+        bits |= NO_CONCATENATION;
+    }
+    if parse.lenient {
+        bits |= LENIENT;
+    }
+    if parse.m_as_minutes {
+        bits |= M_AS_MINUTES;
+    }
+    bits
+}
+
+/// Sets the options used by [`parse_duration`](crate::parse_duration)
+/// and [`format_duration`](crate::format_duration)
+///
+/// This lets applications enforce a policy, like denying months and years
+/// or verbose output, without passing options to every call. It also
+/// affects the wrapper types, which use these functions, except for
+/// serialization which is always in [`Style::Human`]. The options can be
+/// set only once, usually at the start of `main`, further calls return the
+/// given options back.
+///
+/// Only styles which the parser reads back are accepted as defaults, so
+/// that `Display` and `FromStr` of the wrapper types agree: these are
+/// [`Style::Human`], [`Style::Verbose`], [`Style::Abbreviated`], and
+/// [`Style::Machine`] and [`Style::Go`] if concatenation is allowed. The
+/// options are also returned back for other styles.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::{FormatOptions, ParseOptions, Style};
+///
+/// let parse = ParseOptions::new().with_calendar_units(false);
+/// let format = FormatOptions::new().with_style(Style::Verbose);
+/// humantime::set_default_options(parse, format).unwrap();
+/// assert!(humantime::set_default_options(parse, format).is_err());
+///
+/// let iso = FormatOptions::new().with_style(Style::Iso8601);
+/// assert!(humantime::set_default_options(parse, iso).is_err());
+///
+/// assert!(humantime::parse_duration("1 year").is_err());
+/// let text = humantime::format_duration(Duration::from_secs(60)).to_string();
+/// assert_eq!(text, "1 minute");
+/// ```
+pub fn set_default_options(
+    parse: ParseOptions,
+    format: FormatOptions,
+) -> Result<(), (ParseOptions, FormatOptions)> {
+    if !reads_back(&parse, format.style) {
+        return Err((parse, format));
+    }
+    // the value is self-contained, so there is nothing to synchronize with
+    DEFAULTS
+        .compare_exchange(
+            0,
+            encode(parse, format),
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        )
+        .map(|_| ())
+        .map_err(|_| (parse, format))
+}
+
+/// Tells if the parser with `options` reads back the output of `style`
+fn reads_back(options: &ParseOptions, style: Style) -> bool {
+    match style {
+        Style::Human | Style::Verbose | Style::Abbreviated => true,
+        Style::Machine | Style::Go => options.concatenation,
+        _ => false,
+    }
+}
+
+/// Returns the options set by [`set_default_options`], or the defaults
+pub fn default_options() -> (ParseOptions, FormatOptions) {
+    decode(DEFAULTS.load(Ordering::Relaxed))
+}
+
+/// Uses `style` for [`format_duration`](crate::format_duration) during
+/// `f`, on the current thread
+///
+/// This also changes the `Display` output of the [`Duration`](crate::Duration)
+/// and [`Timeout`](crate::Timeout) wrappers, like for a `--verbose` mode,
+/// and takes precedence over [`set_default_options`]. Calls can be nested,
+/// the previous style is restored when `f` returns or panics.
+///
+/// Serialization of the wrappers is not affected, they are always
+/// serialized in [`Style::Human`] so that the values deserialize back.
+/// Unlike the defaults, any style is accepted here, even if `FromStr` of
+/// the wrappers doesn't read it, like [`Style::Iso8601`].
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::{with_format_style, Style};
+///
+/// let timeout = humantime::Duration::from(Duration::from_secs(90));
+/// let text = with_format_style(Style::Verbose, || format!("timeout is {}", timeout));
+/// assert_eq!(text, "timeout is 1 minute 30 seconds");
+/// assert_eq!(timeout.to_string(), "1m 30s");
+/// ```
+pub fn with_format_style<R>(style: Style, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Style>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            SCOPED_STYLE.with(|s| s.set(self.0));
+        }
+    }
+
+    let _restore = Restore(SCOPED_STYLE.with(|s| s.replace(Some(style))));
+    f()
+}
+
+/// Style of `format_duration` on this thread
+pub(crate) fn current_style() -> Style {
+    SCOPED_STYLE
+        .with(Cell::get)
+        .unwrap_or_else(|| default_options().1.style())
+}
+
+fn decode(bits: usize) -> (ParseOptions, FormatOptions) {
+    let parse = ParseOptions::new()
+        .with_calendar_units(bits & NO_CALENDAR_UNITS == 0)
+        .with_concatenation(bits & NO_CONCATENATION == 0)
+        .with_lenient(bits & LENIENT != 0)
+        .with_m_as_minutes(bits & M_AS_MINUTES != 0);
+    let (style, _) = STYLES[(bits >> STYLE_SHIFT).min(STYLES.len() - 1)];
+    (parse, FormatOptions::new().with_style(style))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{decode, default_options, encode, reads_back, set_default_options};
+    use super::{with_format_style, FormatOptions, ParseOptions};
+    use super::{PROFILES, STYLES};
+    use crate::duration::{format_duration, Error, Style};
+    use crate::wrapper::Timeout;
+
+    #[test]
+    fn parse() {
+        let options = ParseOptions::new().with_calendar_units(false);
+        assert_eq!(options.parse("2 weeks"), Ok(Duration::from_secs(1_209_600)));
+        assert!(matches!(
+            options.parse("1h 2M"),
+            Err(Error::UnknownUnit {
+                start: 4,
+                end: 5,
+                ..
+            })
+        ));
+        assert!(ParseOptions::default().parse("2 years").is_ok());
+    }
+
+    #[test]
+    fn m_as_minutes() {
+        let options = ParseOptions::new().with_m_as_minutes(true);
+        assert_eq!(options.parse("90M"), Ok(Duration::from_secs(5400)));
+        assert_eq!(options.parse("1h 30M"), options.parse("1h 30m"));
+        assert_eq!(options.parse("2mos"), ParseOptions::new().parse("2 months"));
+        let exact = options.with_calendar_units(false);
+        assert_eq!(exact.parse("5M"), Ok(Duration::from_secs(300)));
+        assert!(exact.parse("5mo").is_err());
+        let lenient = options.with_lenient(true);
+        assert_eq!(lenient.parse("M"), Ok(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn lenient() {
+        let lenient = ParseOptions::new().with_lenient(true);
+        assert_eq!(lenient.parse("s"), Ok(Duration::from_secs(1)));
+        assert_eq!(lenient.parse(" week "), Ok(Duration::from_secs(604_800)));
+        assert_eq!(lenient.parse("1h min"), Ok(Duration::from_secs(3660)));
+        assert_eq!(lenient.parse("hour30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(lenient.parse("soon"), Err(Error::NumberExpected(0)));
+        assert!(matches!(
+            lenient.with_calendar_units(false).parse("year"),
+            Err(Error::UnknownUnit {
+                start: 0,
+                end: 4,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn natural_language() {
+        let lenient = ParseOptions::new().with_lenient(true);
+        let secs = |s| lenient.parse(s).map(|d| d.as_secs());
+        assert_eq!(secs("an hour"), Ok(3600));
+        assert_eq!(secs("A day and a half"), Ok(129_600));
+        assert_eq!(secs("2 hours and a half"), Ok(9000));
+        assert_eq!(secs("a week and 2 days"), Ok(777_600));
+        assert_eq!(secs("half an hour"), Ok(1800));
+        assert_eq!(secs("Half hour"), Ok(1800));
+        assert_eq!(secs("1h and half a minute"), Ok(3630));
+        assert_eq!(secs("an hour and a minute"), Ok(3660));
+        // only once per component
+        assert_eq!(
+            secs("a day and a half and a half"),
+            Err(Error::NumberExpected(16))
+        );
+        assert_eq!(secs("and a half"), Err(Error::NumberExpected(0)));
+        assert_eq!(secs("1h and"), Err(Error::NumberExpected(3)));
+        assert_eq!(secs("a 5m"), Err(Error::NumberExpected(0)));
+        assert_eq!(secs("half"), Err(Error::NumberExpected(0)));
+        assert_eq!(
+            ParseOptions::new().parse("an hour"),
+            Err(Error::NumberExpected(0))
+        );
+        assert_eq!(lenient.parse("half a ns"), Err(Error::NumberOverflow));
+    }
+
+    #[cfg(feature = "word-numbers")]
+    #[test]
+    fn word_numbers() {
+        let lenient = ParseOptions::new().with_lenient(true);
+        let secs = |s| lenient.parse(s).map(|d| d.as_secs());
+        assert_eq!(secs("two hours"), Ok(7200));
+        assert_eq!(secs("forty five minutes"), Ok(2700));
+        assert_eq!(secs("Twenty-One days"), Ok(1_814_400));
+        assert_eq!(secs("one hour and thirty minutes"), Ok(5400));
+        assert_eq!(secs("two hundred and five seconds"), Ok(205));
+        assert_eq!(secs("two days and a half"), Ok(216_000));
+        assert_eq!(secs("zero seconds"), Ok(0));
+        assert_eq!(secs("five 5m"), Err(Error::NumberExpected(0)));
+        assert!(matches!(
+            secs("one two hours"),
+            Err(Error::UnknownUnit {
+                start: 4,
+                end: 7,
+                ..
+            })
+        ));
+        assert_eq!(
+            ParseOptions::new().parse("two hours"),
+            Err(Error::NumberExpected(0))
+        );
+    }
+
+    #[test]
+    fn concatenation() {
+        let strict = ParseOptions::new().with_concatenation(false);
+        let d = Duration::from_secs(5400);
+        assert_eq!(ParseOptions::new().parse("1h30m"), Ok(d));
+        assert_eq!(strict.parse("1h 30m"), Ok(d));
+        assert_eq!(strict.parse("1 h\t30 m"), Ok(d));
+        assert_eq!(strict.parse("1.5h"), Ok(d));
+        assert_eq!(strict.parse("1h30m"), Err(Error::WhitespaceExpected(2)));
+        assert_eq!(strict.parse("1h 30m15s"), Err(Error::WhitespaceExpected(6)));
+        assert_eq!(
+            strict.parse("1h30m").unwrap_err().to_string(),
+            "expected whitespace before number at 2"
+        );
+    }
+
+    #[test]
+    fn format() {
+        let verbose = |secs, nanos| {
+            FormatOptions::new()
+                .with_style(Style::Verbose)
+                .format(Duration::new(secs, nanos))
+                .to_string()
+        };
+        assert_eq!(verbose(0, 0), "0 seconds");
+        assert_eq!(verbose(1, 1), "1 second 1 nanosecond");
+        assert_eq!(
+            verbose(2 * 31_557_600 + 86400 + 61, 2_003_000),
+            "2 years 1 day 1 minute 1 second 2 milliseconds 3 microseconds"
+        );
+        let short = FormatOptions::default().format(Duration::from_secs(90));
+        assert_eq!(short.to_string(), "1m 30s");
+    }
+
+    #[test]
+    fn packing() {
+        for &(style, _) in &STYLES {
+            for &calendar_units in &[true, false] {
+                for &concatenation in &[true, false] {
+                    let parse = ParseOptions::new()
+                        .with_calendar_units(calendar_units)
+                        .with_concatenation(concatenation)
+                        .with_lenient(!concatenation)
+                        .with_m_as_minutes(calendar_units);
+                    let format = FormatOptions::new().with_style(style);
+                    assert_eq!(decode(encode(parse, format)), (parse, format));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn profiles() {
+        let d = Duration::from_millis(5_400_250);
+        let format = |name| FormatOptions::profile(name).unwrap().format(d).to_string();
+        assert_eq!(format("compact"), "1h 30m 250ms");
+        assert_eq!(format("verbose"), "1 hour 30 minutes 250 milliseconds");
+        assert_eq!(format("go"), "1h30m250ms");
+        let go = FormatOptions::profile("go").unwrap();
+        let d = Duration::new(90_000, 1_500);
+        assert_eq!(go.format(d).to_string(), "25h1us500ns");
+        assert_eq!(format("iso8601"), "PT1H30M0.25S");
+        assert_eq!(format("machine"), "1h30m250ms");
+        assert_eq!(format("words"), format("verbose"));
+        assert_eq!(format("abbreviated"), "1h 30m 250ms");
+        assert_eq!(FormatOptions::profile("Go"), None);
+        assert_eq!(FormatOptions::profile("human"), None);
+        for &(name, style) in &PROFILES {
+            assert_eq!(FormatOptions::profile(name).unwrap().style(), style);
+        }
+    }
+
+    #[test]
+    fn defaults_read_back() {
+        // rejected options are not set, so this doesn't affect other tests
+        let parse = ParseOptions::new();
+        for &style in &[
+            Style::Iso8601,
+            Style::Postgres,
+            Style::Ffmpeg,
+            Style::Prometheus,
+        ] {
+            let format = FormatOptions::new().with_style(style);
+            assert_eq!(set_default_options(parse, format), Err((parse, format)));
+        }
+        let strict = parse.with_concatenation(false);
+        let machine = FormatOptions::new().with_style(Style::Machine);
+        assert!(set_default_options(strict, machine).is_err());
+        assert!(reads_back(&parse, Style::Machine));
+        assert!(reads_back(&strict, Style::Verbose));
+        assert_eq!(default_options(), (parse, FormatOptions::new()));
+    }
+
+    #[test]
+    fn scoped_style() {
+        let d = Duration::from_millis(5_400_250);
+        let t = Timeout::new(Some(d));
+        let (outer, inner) = with_format_style(Style::Prometheus, || {
+            let inner = with_format_style(Style::Verbose, || t.to_string());
+            (format_duration(d).to_string(), inner)
+        });
+        assert_eq!(outer, "1h30m250ms");
+        assert_eq!(inner, "1 hour 30 minutes 250 milliseconds");
+        assert_eq!(format_duration(d).to_string(), "1h 30m 250ms");
+
+        let result = std::panic::catch_unwind(|| with_format_style(Style::Iso8601, || panic!()));
+        assert!(result.is_err());
+        assert_eq!(format_duration(d).to_string(), "1h 30m 250ms");
+    }
+}
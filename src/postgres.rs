@@ -0,0 +1,620 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::Duration;
+
+use crate::dialect::Error;
+use crate::duration::{format_duration_with, FormattedDuration, Style};
+
+const US: u128 = 1000;
+const MS: u128 = 1000 * US;
+const SEC: u128 = 1000 * MS;
+const MIN: u128 = 60 * SEC;
+const HOUR: u128 = 60 * MIN;
+const DAY: u128 = 24 * HOUR;
+/// PostgreSQL converts months to 30 days when it needs a fixed length
+const MONTH: u128 = 30 * DAY;
+/// Year of 365.25 days, like in PostgreSQL
+const YEAR: u128 = 31_557_600 * SEC;
+
+/// Units of the postgres interval style, each can be used once
+const UNITS: &[(&[&str], u128)] = &[
+    (&["microsecond", "microseconds", "us", "usec", "usecs"], US),
+    (&["millisecond", "milliseconds", "ms", "msec", "msecs"], MS),
+    (&["second", "seconds", "s", "sec", "secs"], SEC),
+    (&["minute", "minutes", "m", "min", "mins"], MIN),
+    (&["hour", "hours", "h", "hr", "hrs"], HOUR),
+    (&["day", "days", "d"], DAY),
+    (&["week", "weeks", "w"], 7 * DAY),
+    (&["mon", "mons", "month", "months"], MONTH),
+    (&["year", "years", "y", "yr", "yrs"], YEAR),
+];
+
+/// Parses a decimal number as a fraction `numerator / denominator`
+fn parse_number(s: &str, off: usize) -> Result<(u128, u128), Error> {
+    let mut numerator = 0u128;
+    let mut denominator = 1u128;
+    let mut fraction = false;
+    let mut digits = 0;
+    for (idx, b) in s.bytes().enumerate() {
+        match b {
+            b'0'..=b'9' => {
+                numerator = numerator
+                    .checked_mul(10)
+                    .and_then(|n| n.checked_add(u128::from(b - b'0')))
+                    .ok_or(Error::NumberOverflow)?;
+                if fraction {
+                    denominator = denominator.checked_mul(10).ok_or(Error::NumberOverflow)?;
+                }
+                digits += 1;
+            }
+            b'.' if !fraction => fraction = true,
+            _ => return Err(Error::InvalidCharacter(off + idx)),
+        }
+    }
+    if digits == 0 {
+        return Err(Error::NumberExpected(off));
+    }
+    Ok((numerator, denominator))
+}
+
+/// Multiplies a fraction by a unit, truncating below nanoseconds
+fn scale(number: (u128, u128), unit: u128) -> Result<u128, Error> {
+    let (numerator, denominator) = number;
+    numerator
+        .checked_mul(unit)
+        .map(|n| n / denominator)
+        .ok_or(Error::NumberOverflow)
+}
+
+fn to_duration(nanos: u128) -> Result<Duration, Error> {
+    let secs = u64::try_from(nanos / SEC).map_err(|_| Error::NumberOverflow)?;
+    Ok(Duration::new(secs, (nanos % SEC) as u32))
+}
+
+/// Parses time field `HH:MM[:SS[.fraction]]`
+fn parse_time(s: &str, off: usize) -> Result<u128, Error> {
+    let mut total = 0u128;
+    let mut pos = 0;
+    let units = [HOUR, MIN, SEC];
+    for (idx, part) in s.split(':').enumerate() {
+        if idx >= units.len() {
+            return Err(Error::InvalidCharacter(off + pos - 1));
+        }
+        let is_seconds = idx == 2;
+        if !is_seconds && part.contains('.') {
+            let dot = part.find('.').unwrap_or(0);
+            return Err(Error::InvalidCharacter(off + pos + dot));
+        }
+        let number = parse_number(part, off + pos)?;
+        let value = scale(number, units[idx])?;
+        if idx > 0 && value >= 60 * units[idx] {
+            return Err(Error::NumberOverflow);
+        }
+        total = total.checked_add(value).ok_or(Error::NumberOverflow)?;
+        pos += part.len() + 1;
+    }
+    Ok(total)
+}
+
+/// Parses the `postgres` interval style, like `1 day 02:03:04`
+fn parse_postgres_style(s: &str) -> Result<Duration, Error> {
+    let mut words = s
+        .split_whitespace()
+        .map(|w| (w.as_ptr() as usize - s.as_ptr() as usize, w))
+        .peekable();
+    if words.peek().map_or(false, |&(_, w)| w == "@") {
+        words.next();
+    }
+    let mut seen = [false; 9];
+    let mut seen_time = false;
+    let mut total = 0u128;
+    while let Some((off, word)) = words.next() {
+        let value = if word.contains(':') {
+            if seen_time {
+                return Err(Error::UnitOrder {
+                    start: off,
+                    end: off + word.len(),
+                });
+            }
+            seen_time = true;
+            parse_time(word, off)?
+        } else {
+            let num_len = word
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(word.len());
+            if num_len == 0 {
+                return Err(if word.starts_with(|c: char| c.is_alphabetic()) {
+                    Error::NumberExpected(off)
+                } else {
+                    Error::InvalidCharacter(off)
+                });
+            }
+            let number = parse_number(&word[..num_len], off)?;
+            let (unit_off, unit) = if num_len < word.len() {
+                (off + num_len, &word[num_len..])
+            } else {
+                words.next().unwrap_or((off + word.len(), ""))
+            };
+            let idx = UNITS
+                .iter()
+                .position(|(names, _)| names.contains(&unit))
+                .ok_or_else(|| Error::UnknownUnit {
+                    start: unit_off,
+                    end: unit_off + unit.len(),
+                    unit: unit.to_owned(),
+                })?;
+            if seen[idx] {
+                return Err(Error::UnitOrder {
+                    start: unit_off,
+                    end: unit_off + unit.len(),
+                });
+            }
+            seen[idx] = true;
+            scale(number, UNITS[idx].1)?
+        };
+        total = total.checked_add(value).ok_or(Error::NumberOverflow)?;
+    }
+    to_duration(total)
+}
+
+/// Parses the `iso_8601` interval style, like `P1DT2H3M4S`
+fn parse_iso_style(s: &str) -> Result<Duration, Error> {
+    const DATE: &[(u8, u128)] = &[(b'Y', YEAR), (b'M', MONTH), (b'W', 7 * DAY), (b'D', DAY)];
+    const TIME: &[(u8, u128)] = &[(b'H', HOUR), (b'M', MIN), (b'S', SEC)];
+    let bytes = s.as_bytes();
+    let mut pos = 1; // skip `P`
+    let mut units = DATE;
+    let mut in_time = false;
+    let mut next_unit = 0;
+    let mut total = 0u128;
+    let mut components = 0;
+    while pos < bytes.len() {
+        if bytes[pos] == b'T' && !in_time {
+            if pos + 1 == bytes.len() {
+                return Err(Error::NumberExpected(pos + 1));
+            }
+            units = TIME;
+            in_time = true;
+            next_unit = 0;
+            pos += 1;
+            continue;
+        }
+        let start = pos;
+        while pos < bytes.len() && (bytes[pos].is_ascii_digit() || bytes[pos] == b'.') {
+            pos += 1;
+        }
+        if pos == start {
+            return Err(if bytes[pos].is_ascii_alphabetic() {
+                Error::NumberExpected(pos)
+            } else {
+                Error::InvalidCharacter(pos)
+            });
+        }
+        let number = parse_number(&s[start..pos], start)?;
+        let unit = match s[pos..].chars().next() {
+            Some(c) => c,
+            None => {
+                return Err(Error::UnknownUnit {
+                    start: pos,
+                    end: pos,
+                    unit: String::new(),
+                })
+            }
+        };
+        let idx = units
+            .iter()
+            .position(|&(name, _)| char::from(name) == unit)
+            .ok_or_else(|| Error::UnknownUnit {
+                start: pos,
+                end: pos + unit.len_utf8(),
+                unit: unit.to_string(),
+            })?;
+        if idx < next_unit {
+            return Err(Error::UnitOrder {
+                start: pos,
+                end: pos + 1,
+            });
+        }
+        next_unit = idx + 1;
+        let value = scale(number, units[idx].1)?;
+        total = total.checked_add(value).ok_or(Error::NumberOverflow)?;
+        components += 1;
+        pos += 1;
+    }
+    if components == 0 {
+        return Err(Error::NumberExpected(pos));
+    }
+    to_duration(total)
+}
+
+/// Parse duration from a PostgreSQL interval
+///
+/// Accepts both the default `postgres` output style, like
+/// `1 year 2 mons 3 days 04:05:06.789`, and the `iso_8601` style, like
+/// `P1Y2M3DT4H5M6.789S`. The `postgres_verbose` style (`@ 1 day 2 hours`)
+/// is accepted as well.
+///
+/// Months are converted as 30 days and years as 365.25 days, the same
+/// way PostgreSQL does when it needs a fixed length (e.g. in
+/// `EXTRACT(EPOCH FROM ...)`). Negative intervals and `ago` are not
+/// supported.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::parse_duration_postgres;
+///
+/// assert_eq!(parse_duration_postgres("1 day 02:03:04"), Ok(Duration::from_secs(93784)));
+/// assert_eq!(parse_duration_postgres("3 mons 2 days"), Ok(Duration::from_secs(92 * 86400)));
+/// assert_eq!(parse_duration_postgres("P1DT2H3M4S"), Ok(Duration::from_secs(93784)));
+/// ```
+pub fn parse_duration_postgres(s: &str) -> Result<Duration, Error> {
+    let trimmed = s.trim();
+    if trimmed.is_empty() {
+        return Err(Error::Empty);
+    }
+    if trimmed.starts_with('P') {
+        let off = trimmed.as_ptr() as usize - s.as_ptr() as usize;
+        return parse_iso_style(trimmed).map_err(|e| e.offset_by(off));
+    }
+    parse_postgres_style(s)
+}
+
+/// Formats duration as a PostgreSQL interval in the `postgres` style
+///
+/// Produces days and a time field, like `1 day 02:03:04.5`. Months and
+/// years are never used, as their length is not fixed. Precision below
+/// microseconds is truncated, like in PostgreSQL.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::format_duration_postgres;
+///
+/// let val = Duration::from_secs(93784);
+/// assert_eq!(format_duration_postgres(val).to_string(), "1 day 02:03:04");
+/// let val = Duration::from_secs(3 * 86400);
+/// assert_eq!(format_duration_postgres(val).to_string(), "3 days");
+/// ```
+pub fn format_duration_postgres(val: Duration) -> FormattedDuration {
+    format_duration_with(val, Style::Postgres)
+}
+
+/// Formats duration in the ISO 8601 format, like `P1DT2H3M4S`
+///
+/// This is also the `iso_8601` interval style of PostgreSQL. Only days,
+/// hours, minutes and (fractional) seconds are used. Precision below
+/// microseconds is truncated.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::format_duration_iso8601;
+///
+/// let val = Duration::from_millis(93_784_500);
+/// assert_eq!(format_duration_iso8601(val).to_string(), "P1DT2H3M4.5S");
+/// assert_eq!(format_duration_iso8601(Duration::ZERO).to_string(), "PT0S");
+/// ```
+pub fn format_duration_iso8601(val: Duration) -> FormattedDuration {
+    format_duration_with(val, Style::Iso8601)
+}
+
+/// The fields of a PostgreSQL `INTERVAL` value
+///
+/// These are the fields of the binary wire format, and of the interval
+/// types of database drivers like `sqlx::postgres::types::PgInterval`,
+/// so converting to the driver type is a struct literal.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use std::time::Duration;
+/// use humantime::{parse_duration, PgInterval};
+///
+/// let interval = PgInterval::try_from(parse_duration("1h 30m").unwrap()).unwrap();
+/// assert_eq!(interval.microseconds, 5_400_000_000);
+/// let stored = PgInterval { months: 1, days: 2, microseconds: 0 };
+/// assert_eq!(stored.duration(), Some(Duration::from_secs(32 * 86400)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PgInterval {
+    /// Number of months
+    pub months: i32,
+    /// Number of days
+    pub days: i32,
+    /// Number of microseconds
+    pub microseconds: i64,
+}
+
+impl PgInterval {
+    /// Returns the length of the interval
+    ///
+    /// Months are 30 days and days are 24 hours, as when PostgreSQL
+    /// extracts the epoch of an interval. Returns `None` if the interval
+    /// is negative, or any of its fields is.
+    pub fn duration(&self) -> Option<Duration> {
+        let months = u128::try_from(self.months).ok()?;
+        let days = u128::try_from(self.days).ok()?;
+        let micros = u128::try_from(self.microseconds).ok()?;
+        to_duration(months * MONTH + days * DAY + micros * US).ok()
+    }
+}
+
+impl TryFrom<Duration> for PgInterval {
+    type Error = Error;
+
+    /// Converts the duration to microseconds, truncating below them like
+    /// PostgreSQL does
+    ///
+    /// Fails with [`Error::NumberOverflow`] if the duration exceeds the
+    /// range of an interval.
+    fn try_from(val: Duration) -> Result<PgInterval, Error> {
+        let microseconds = i64::try_from(val.as_micros()).map_err(|_| Error::NumberOverflow)?;
+        Ok(PgInterval {
+            months: 0,
+            days: 0,
+            microseconds,
+        })
+    }
+}
+
+/// Writes `.fraction` of a second with trailing zeros removed
+pub(crate) fn write_fraction(f: &mut fmt::Formatter, micros: u32) -> fmt::Result {
+    if micros > 0 {
+        let mut digits = 6;
+        let mut value = micros;
+        while value % 10 == 0 {
+            value /= 10;
+            digits -= 1;
+        }
+        write!(f, ".{:0width$}", value, width = digits)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn fmt_postgres(val: Duration, f: &mut fmt::Formatter) -> fmt::Result {
+    let secs = val.as_secs();
+    let micros = val.subsec_micros();
+    let days = secs / 86400;
+    let rest = secs % 86400;
+    if days > 0 {
+        write!(f, "{} {}", days, if days == 1 { "day" } else { "days" })?;
+        if rest == 0 && micros == 0 {
+            return Ok(());
+        }
+        f.write_str(" ")?;
+    }
+    write!(
+        f,
+        "{:02}:{:02}:{:02}",
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    )?;
+    write_fraction(f, micros)
+}
+
+pub(crate) fn fmt_iso8601(val: Duration, f: &mut fmt::Formatter) -> fmt::Result {
+    let secs = val.as_secs();
+    let micros = val.subsec_micros();
+    if secs == 0 && micros == 0 {
+        return f.write_str("PT0S");
+    }
+    let days = secs / 86400;
+    let hours = secs % 86400 / 3600;
+    let minutes = secs % 3600 / 60;
+    let seconds = secs % 60;
+    f.write_str("P")?;
+    if days > 0 {
+        write!(f, "{}D", days)?;
+    }
+    if hours == 0 && minutes == 0 && seconds == 0 && micros == 0 {
+        return Ok(());
+    }
+    f.write_str("T")?;
+    if hours > 0 {
+        write!(f, "{}H", hours)?;
+    }
+    if minutes > 0 {
+        write!(f, "{}M", minutes)?;
+    }
+    if seconds > 0 || micros > 0 {
+        write!(f, "{}", seconds)?;
+        write_fraction(f, micros)?;
+        f.write_str("S")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use std::convert::TryFrom;
+
+    use super::PgInterval;
+    use super::{format_duration_iso8601, format_duration_postgres, parse_duration_postgres};
+    use crate::dialect::Error;
+
+    const DAY: u64 = 86400;
+
+    #[test]
+    fn parse_postgres_style() {
+        let parse = parse_duration_postgres;
+        assert_eq!(parse("00:00:00"), Ok(Duration::ZERO));
+        assert_eq!(parse("1 day"), Ok(Duration::from_secs(DAY)));
+        assert_eq!(
+            parse("2 days 01:00"),
+            Ok(Duration::from_secs(2 * DAY + 3600))
+        );
+        assert_eq!(
+            parse("1 year 2 mons 3 days 04:05:06.789"),
+            Ok(Duration::new(
+                31_557_600 + 63 * DAY + 4 * 3600 + 5 * 60 + 6,
+                789_000_000
+            ))
+        );
+        assert_eq!(parse("25:00:00"), Ok(Duration::from_secs(25 * 3600)));
+        assert_eq!(parse("00:00:00.000001"), Ok(Duration::from_micros(1)));
+        assert_eq!(parse("1.5 days"), Ok(Duration::from_secs(DAY + DAY / 2)));
+        assert_eq!(
+            parse("3 hours 1day"),
+            Ok(Duration::from_secs(DAY + 3 * 3600))
+        );
+        assert_eq!(
+            parse("@ 1 day 2 hours"),
+            Ok(Duration::from_secs(DAY + 7200))
+        );
+        assert_eq!(parse(" 1 mon "), Ok(Duration::from_secs(30 * DAY)));
+    }
+
+    #[test]
+    fn parse_iso_style() {
+        let parse = parse_duration_postgres;
+        assert_eq!(parse("PT0S"), Ok(Duration::ZERO));
+        assert_eq!(parse("P1Y"), Ok(Duration::from_secs(31_557_600)));
+        assert_eq!(
+            parse("P1M2W3D"),
+            Ok(Duration::from_secs(30 * DAY + 17 * DAY))
+        );
+        assert_eq!(parse("PT1M"), Ok(Duration::from_secs(60)));
+        assert_eq!(parse("PT1.5S"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse("P0.5D"), Ok(Duration::from_secs(DAY / 2)));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let parse = parse_duration_postgres;
+        assert_eq!(parse(""), Err(Error::Empty));
+        assert_eq!(parse("  "), Err(Error::Empty));
+        assert_eq!(parse("day"), Err(Error::NumberExpected(0)));
+        assert_eq!(parse("-1 day"), Err(Error::InvalidCharacter(0)));
+        assert_eq!(parse("1 day ago"), Err(Error::NumberExpected(6)));
+        assert_eq!(
+            parse("1"),
+            Err(Error::UnknownUnit {
+                start: 1,
+                end: 1,
+                unit: String::new()
+            })
+        );
+        assert_eq!(
+            parse("1 fortnight"),
+            Err(Error::UnknownUnit {
+                start: 2,
+                end: 11,
+                unit: "fortnight".to_owned()
+            })
+        );
+        assert_eq!(
+            parse("1 day 2 days"),
+            Err(Error::UnitOrder { start: 8, end: 12 })
+        );
+        assert_eq!(
+            parse("01:00 02:00"),
+            Err(Error::UnitOrder { start: 6, end: 11 })
+        );
+        assert_eq!(parse("00:60:00"), Err(Error::NumberOverflow));
+        assert_eq!(parse("00:00:60"), Err(Error::NumberOverflow));
+        assert_eq!(parse("1:2:3:4"), Err(Error::InvalidCharacter(5)));
+        assert_eq!(parse("1.5:00"), Err(Error::InvalidCharacter(1)));
+        assert_eq!(parse("01:"), Err(Error::NumberExpected(3)));
+        assert_eq!(parse("P"), Err(Error::NumberExpected(1)));
+        assert_eq!(parse("P1DT"), Err(Error::NumberExpected(4)));
+        assert_eq!(
+            parse("P1H"),
+            Err(Error::UnknownUnit {
+                start: 2,
+                end: 3,
+                unit: "H".to_owned()
+            })
+        );
+        assert_eq!(parse("PT1S1M"), Err(Error::UnitOrder { start: 5, end: 6 }));
+        assert_eq!(parse(" PD"), Err(Error::NumberExpected(2)));
+    }
+
+    #[test]
+    fn format_postgres() {
+        let fmt = |d| format_duration_postgres(d).to_string();
+        assert_eq!(fmt(Duration::ZERO), "00:00:00");
+        assert_eq!(fmt(Duration::from_secs(DAY)), "1 day");
+        assert_eq!(fmt(Duration::from_secs(2 * DAY + 1)), "2 days 00:00:01");
+        assert_eq!(fmt(Duration::from_millis(1500)), "00:00:01.5");
+        assert_eq!(fmt(Duration::new(0, 1_001)), "00:00:00.000001");
+        assert_eq!(fmt(Duration::new(0, 999)), "00:00:00");
+    }
+
+    #[test]
+    fn format_iso8601() {
+        let fmt = |d| format_duration_iso8601(d).to_string();
+        assert_eq!(fmt(Duration::from_secs(DAY)), "P1D");
+        assert_eq!(fmt(Duration::from_secs(DAY + 60)), "P1DT1M");
+        assert_eq!(fmt(Duration::from_secs(7200)), "PT2H");
+        assert_eq!(fmt(Duration::from_micros(250)), "PT0.00025S");
+    }
+
+    #[test]
+    fn round_trip() {
+        for val in &[
+            Duration::ZERO,
+            Duration::from_secs(59),
+            Duration::from_secs(90 * DAY + 3599),
+            Duration::new(DAY + 1, 123_456_000),
+        ] {
+            let pg = format_duration_postgres(*val).to_string();
+            assert_eq!(parse_duration_postgres(&pg), Ok(*val));
+            let iso = format_duration_iso8601(*val).to_string();
+            assert_eq!(parse_duration_postgres(&iso), Ok(*val));
+        }
+    }
+
+    #[test]
+    fn max_round_trip() {
+        // the largest values with microsecond precision
+        for &d in &[
+            Duration::new(u64::MAX, 999_999_000),
+            Duration::new(u64::MAX, 0),
+        ] {
+            let postgres = format_duration_postgres(d).to_string();
+            assert_eq!(parse_duration_postgres(&postgres), Ok(d), "{}", postgres);
+            let iso = format_duration_iso8601(d).to_string();
+            assert_eq!(parse_duration_postgres(&iso), Ok(d), "{}", iso);
+        }
+    }
+
+    #[test]
+    fn interval() {
+        let interval = |months, days, microseconds| PgInterval {
+            months,
+            days,
+            microseconds,
+        };
+        let val = Duration::new(93784, 5_000_999);
+        assert_eq!(
+            PgInterval::try_from(val),
+            Ok(interval(0, 0, 93_784_005_000))
+        );
+        assert_eq!(
+            PgInterval::try_from(Duration::MAX),
+            Err(Error::NumberOverflow)
+        );
+        assert_eq!(interval(0, 0, 1).duration(), Some(Duration::from_micros(1)));
+        assert_eq!(
+            interval(1, 1, 1_000_000).duration(),
+            Some(Duration::from_secs(31 * DAY + 1))
+        );
+        assert_eq!(
+            interval(i32::MAX, i32::MAX, i64::MAX).duration(),
+            Some(Duration::new(
+                i32::MAX as u64 * 31 * DAY + i64::MAX as u64 / 1_000_000,
+                (i64::MAX % 1_000_000) as u32 * 1000
+            ))
+        );
+        assert_eq!(interval(0, -1, 0).duration(), None);
+        assert_eq!(interval(-1, 31, 0).duration(), None);
+    }
+}
@@ -0,0 +1,442 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::duration::{self, format_duration, parse_duration_in};
+
+/// Error parsing a duration predicate
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// Predicate is neither a comparison nor a range
+    InvalidFormat,
+    /// The start of the range is after its end
+    InvalidRange,
+    /// Latency spec doesn't start with a percentile from `p0` to `p100`
+    InvalidPercentile,
+    /// The threshold is not a valid duration
+    Duration(duration::Error),
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Duration(e) => Some(e),
+            Error::InvalidFormat | Error::InvalidRange | Error::InvalidPercentile => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidFormat => write!(
+                f,
+                "predicate must start with `>`, `>=`, `<`, `<=` or `=`, \
+                or be a range like `1s..5s`"
+            ),
+            Error::InvalidRange => write!(f, "start of the range is after its end"),
+            Error::InvalidPercentile => write!(
+                f,
+                "latency spec must start with a percentile from `p0` to `p100`"
+            ),
+            Error::Duration(e) => write!(f, "invalid duration: {}", e),
+        }
+    }
+}
+
+impl From<duration::Error> for Error {
+    fn from(e: duration::Error) -> Error {
+        Error::Duration(e)
+    }
+}
+
+/// Comparison operator of a [`DurationPredicate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Comparison {
+    /// `<`
+    Less,
+    /// `<=`
+    LessOrEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterOrEqual,
+    /// `=` or `==`
+    Equal,
+}
+
+impl Comparison {
+    /// Compares `value` to `threshold`
+    pub fn compare(self, value: Duration, threshold: Duration) -> bool {
+        match self {
+            Comparison::Less => value < threshold,
+            Comparison::LessOrEqual => value <= threshold,
+            Comparison::Greater => value > threshold,
+            Comparison::GreaterOrEqual => value >= threshold,
+            Comparison::Equal => value == threshold,
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+            Comparison::Equal => "=",
+        }
+    }
+}
+
+/// Strips a comparison operator from the start of `s`
+pub(crate) fn strip_comparison(s: &str) -> Option<(Comparison, &str)> {
+    let operators = [
+        (">=", Comparison::GreaterOrEqual),
+        ("<=", Comparison::LessOrEqual),
+        ("==", Comparison::Equal),
+        (">", Comparison::Greater),
+        ("<", Comparison::Less),
+        ("=", Comparison::Equal),
+    ];
+    operators
+        .iter()
+        .find_map(|&(prefix, op)| s.strip_prefix(prefix).map(|rest| (op, rest)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Compare(Comparison, Duration),
+    Range {
+        start: Duration,
+        end: Duration,
+        inclusive: bool,
+    },
+}
+
+/// A condition on a duration, like `> 5m` or `100ms..1s`
+///
+/// Supported spellings:
+///
+/// * `> <duration>`, `>= <duration>`, `< <duration>`, `<= <duration>`
+/// * `= <duration>`, `== <duration>`
+/// * `<start>..<end>`, which doesn't include the end, and `<start>..=<end>`
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::parse_duration_predicate;
+///
+/// let slow = parse_duration_predicate("> 500ms").unwrap();
+/// assert!(slow.matches(Duration::from_millis(700)));
+/// assert!(!slow.matches(Duration::from_millis(500)));
+///
+/// let range = parse_duration_predicate("1s..=2s").unwrap();
+/// assert!(range.matches(Duration::from_secs(2)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DurationPredicate {
+    kind: Kind,
+}
+
+/// Parse a duration predicate, see [`DurationPredicate`] for the syntax
+pub fn parse_duration_predicate(input: &str) -> Result<DurationPredicate, Error> {
+    let s = input.trim();
+    if let Some((op, threshold)) = strip_comparison(s) {
+        let threshold = parse_duration_in(input, threshold)?;
+        return Ok(DurationPredicate {
+            kind: Kind::Compare(op, threshold),
+        });
+    }
+    let idx = s.find("..").ok_or(Error::InvalidFormat)?;
+    let (end, inclusive) = match s[idx + 2..].strip_prefix('=') {
+        Some(end) => (end, true),
+        None => (&s[idx + 2..], false),
+    };
+    let start = parse_duration_in(input, &s[..idx])?;
+    let end = parse_duration_in(input, end)?;
+    if start > end {
+        return Err(Error::InvalidRange);
+    }
+    Ok(DurationPredicate {
+        kind: Kind::Range {
+            start,
+            end,
+            inclusive,
+        },
+    })
+}
+
+impl DurationPredicate {
+    /// Checks whether `value` satisfies the predicate
+    pub fn matches(&self, value: Duration) -> bool {
+        match self.kind {
+            Kind::Compare(op, threshold) => op.compare(value, threshold),
+            Kind::Range {
+                start,
+                end,
+                inclusive: true,
+            } => start <= value && value <= end,
+            Kind::Range { start, end, .. } => start <= value && value < end,
+        }
+    }
+}
+
+impl FromStr for DurationPredicate {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<DurationPredicate, Error> {
+        parse_duration_predicate(s)
+    }
+}
+
+impl fmt::Display for DurationPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            Kind::Compare(op, threshold) => {
+                write!(f, "{} {}", op.symbol(), format_duration(threshold))
+            }
+            Kind::Range {
+                start,
+                end,
+                inclusive,
+            } => {
+                let sep = if inclusive { "..=" } else { ".." };
+                write!(
+                    f,
+                    "{}{}{}",
+                    format_duration(start),
+                    sep,
+                    format_duration(end)
+                )
+            }
+        }
+    }
+}
+
+/// A latency objective, like `p99 < 250ms`
+///
+/// The spec is a percentile from `p0` to `p100`, which may have a
+/// fraction like `p99.9`, a comparison operator as in
+/// [`DurationPredicate`], and a threshold. `p50` can be spelled `median`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::{parse_latency_spec, Comparison};
+///
+/// let spec = parse_latency_spec("p99.9 <= 250ms").unwrap();
+/// assert_eq!(spec.percentile(), 99.9);
+/// assert_eq!(spec.op(), Comparison::LessOrEqual);
+/// assert_eq!(spec.threshold(), Duration::from_millis(250));
+/// assert!(spec.matches(Duration::from_millis(180)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencySpec {
+    percentile: f64,
+    op: Comparison,
+    threshold: Duration,
+}
+
+/// Parse a latency spec, see [`LatencySpec`] for the syntax
+pub fn parse_latency_spec(input: &str) -> Result<LatencySpec, Error> {
+    let s = input.trim();
+    let (percentile, rest) = if let Some(rest) = s.strip_prefix("median") {
+        (50.0, rest)
+    } else {
+        let rest = s
+            .strip_prefix('p')
+            .or_else(|| s.strip_prefix('P'))
+            .ok_or(Error::InvalidPercentile)?;
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let digits = &rest[..end];
+        if !digits.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(Error::InvalidPercentile);
+        }
+        let value: f64 = digits.parse().map_err(|_| Error::InvalidPercentile)?;
+        if value > 100.0 {
+            return Err(Error::InvalidPercentile);
+        }
+        (value, &rest[end..])
+    };
+    let (op, threshold) = strip_comparison(rest.trim_start()).ok_or(Error::InvalidFormat)?;
+    Ok(LatencySpec {
+        percentile,
+        op,
+        threshold: parse_duration_in(input, threshold)?,
+    })
+}
+
+impl LatencySpec {
+    /// The percentile, from 0 to 100
+    pub fn percentile(&self) -> f64 {
+        self.percentile
+    }
+
+    /// The comparison operator
+    pub fn op(&self) -> Comparison {
+        self.op
+    }
+
+    /// The latency threshold
+    pub fn threshold(&self) -> Duration {
+        self.threshold
+    }
+
+    /// Checks whether the observed latency at the percentile meets the
+    /// objective
+    pub fn matches(&self, observed: Duration) -> bool {
+        self.op.compare(observed, self.threshold)
+    }
+}
+
+impl FromStr for LatencySpec {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<LatencySpec, Error> {
+        parse_latency_spec(s)
+    }
+}
+
+impl fmt::Display for LatencySpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "p{} {} {}",
+            self.percentile,
+            self.op.symbol(),
+            format_duration(self.threshold)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{parse_duration_predicate, parse_latency_spec, Comparison, Error};
+    use crate::duration::Error as DurationError;
+
+    fn check(predicate: &str, millis: u64) -> bool {
+        parse_duration_predicate(predicate)
+            .unwrap()
+            .matches(Duration::from_millis(millis))
+    }
+
+    #[test]
+    fn comparisons() {
+        assert!(check("> 5m", 300_001));
+        assert!(!check(">5m", 300_000));
+        assert!(check(">= 5m", 300_000));
+        assert!(check("<1h", 3_599_999));
+        assert!(!check("< 1h", 3_600_000));
+        assert!(check("<= 1h", 3_600_000));
+        assert!(check("= 1.5s", 1500));
+        assert!(!check("== 1.5s", 1501));
+    }
+
+    #[test]
+    fn ranges() {
+        assert!(check("100ms..1s", 100));
+        assert!(check("100ms..1s", 999));
+        assert!(!check("100ms..1s", 1000));
+        assert!(check("100ms..=1s", 1000));
+        assert!(check("1.5s .. 2.5s", 2000));
+        assert!(!check("1.5s .. 2.5s", 1499));
+        assert!(!check("1s..1s", 1000));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_duration_predicate("5m"), Err(Error::InvalidFormat));
+        assert_eq!(parse_duration_predicate("~5m"), Err(Error::InvalidFormat));
+        assert_eq!(parse_duration_predicate("2s..1s"), Err(Error::InvalidRange));
+        assert_eq!(
+            parse_duration_predicate(">"),
+            Err(Error::Duration(DurationError::Empty))
+        );
+        assert_eq!(
+            parse_duration_predicate("1s.."),
+            Err(Error::Duration(DurationError::Empty))
+        );
+        // offsets are in the whole input
+        let offset = |s| match parse_duration_predicate(s) {
+            Err(Error::Duration(e)) => e.span(),
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(offset(" >= 5m h"), Some(7..8));
+        assert_eq!(offset("1s .. 2x"), Some(7..8));
+        assert_eq!(offset("1!s..2s"), Some(1..1));
+        let err = parse_latency_spec("p99 < 1h 2").unwrap_err();
+        match err {
+            Error::Duration(e) => assert_eq!(e.span(), Some(10..10)),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn display() {
+        for input in &[
+            "> 5m",
+            ">= 1h 30m",
+            "< 500ms",
+            "<= 1s",
+            "= 2s",
+            "1s..5s",
+            "0s..=1day",
+        ] {
+            assert_eq!(parse_duration_predicate(input).unwrap().to_string(), *input);
+        }
+        assert_eq!(
+            parse_duration_predicate("==90s").unwrap().to_string(),
+            "= 1m 30s"
+        );
+    }
+
+    #[test]
+    fn latency_specs() {
+        let spec = parse_latency_spec("p99 < 250ms").unwrap();
+        assert_eq!(spec.percentile(), 99.0);
+        assert_eq!(spec.op(), Comparison::Less);
+        assert_eq!(spec.threshold(), Duration::from_millis(250));
+        assert!(spec.matches(Duration::from_millis(249)));
+        assert!(!spec.matches(Duration::from_millis(250)));
+
+        let spec = parse_latency_spec("P99.99>=1s").unwrap();
+        assert_eq!(spec.percentile(), 99.99);
+        assert_eq!(spec.op(), Comparison::GreaterOrEqual);
+        assert_eq!(
+            parse_latency_spec("median = 5ms").unwrap().percentile(),
+            50.0
+        );
+        assert_eq!(parse_latency_spec("p0 > 0s").unwrap().percentile(), 0.0);
+        assert_eq!(parse_latency_spec("p100 <1h").unwrap().percentile(), 100.0);
+    }
+
+    #[test]
+    fn latency_errors() {
+        let parse = parse_latency_spec;
+        assert_eq!(parse("< 250ms"), Err(Error::InvalidPercentile));
+        assert_eq!(parse("p < 250ms"), Err(Error::InvalidPercentile));
+        assert_eq!(parse("p.5 < 250ms"), Err(Error::InvalidPercentile));
+        assert_eq!(parse("p101 < 250ms"), Err(Error::InvalidPercentile));
+        assert_eq!(parse("p99.9.9 < 250ms"), Err(Error::InvalidPercentile));
+        assert_eq!(parse("p99 250ms"), Err(Error::InvalidFormat));
+        assert_eq!(parse("p99 100ms..1s"), Err(Error::InvalidFormat));
+        assert_eq!(parse("p99 <"), Err(Error::Duration(DurationError::Empty)));
+    }
+
+    #[test]
+    fn latency_display() {
+        for input in &["p99 < 250ms", "p99.9 >= 1s", "p50 = 1m 30s"] {
+            assert_eq!(parse_latency_spec(input).unwrap().to_string(), *input);
+        }
+        assert_eq!(
+            parse_latency_spec("median<=5ms").unwrap().to_string(),
+            "p50 <= 5ms"
+        );
+    }
+}
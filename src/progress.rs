@@ -0,0 +1,134 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::duration::format_duration;
+
+/// A wrapper type that allows you to Display an estimated time of arrival
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FormattedEta(Duration);
+
+/// Estimate the remaining time of a task from its progress so far
+///
+/// Assumes the rest of the work proceeds at the same average rate as the
+/// `done` units which took `elapsed` time. Returns `None` when no estimate
+/// can be made: nothing is done yet, `done` exceeds `total` or the result
+/// doesn't fit into a `Duration`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::estimate_eta;
+///
+/// let eta = estimate_eta(25, 100, Duration::from_secs(30));
+/// assert_eq!(eta, Some(Duration::from_secs(90)));
+/// assert_eq!(estimate_eta(0, 100, Duration::from_secs(30)), None);
+/// ```
+pub fn estimate_eta(done: u64, total: u64, elapsed: Duration) -> Option<Duration> {
+    if done == 0 || done > total {
+        return None;
+    }
+    let elapsed = elapsed.as_nanos();
+    let (done, remaining) = (u128::from(done), u128::from(total - done));
+    let nanos = match elapsed.checked_mul(remaining) {
+        Some(n) => n / done,
+        // precision doesn't matter much for values this large
+        None => (elapsed / done).checked_mul(remaining)?,
+    };
+    let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+    Some(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+}
+
+/// Formats an estimated remaining time for displaying in progress bars
+///
+/// The value is rounded, so that it doesn't flicker on every redraw:
+/// to whole seconds below an hour, to minutes below a day and to hours
+/// otherwise.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::format_eta;
+///
+/// let eta = Duration::new(251, 700_000_000);
+/// assert_eq!(format_eta(eta).to_string(), "ETA 4m 12s");
+/// let eta = Duration::from_secs(3 * 3600 + 1799);
+/// assert_eq!(format_eta(eta).to_string(), "ETA 3h 30m");
+/// ```
+pub fn format_eta(eta: Duration) -> FormattedEta {
+    FormattedEta(eta)
+}
+
+impl FormattedEta {
+    /// Returns a reference to the [`Duration`][] that is being formatted.
+    pub fn get_ref(&self) -> &Duration {
+        &self.0
+    }
+}
+
+impl fmt::Display for FormattedEta {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let step = match self.0.as_secs() {
+            0..=3599 => 1,
+            3600..=86399 => 60,
+            _ => 3600,
+        };
+        let secs = self.0.as_secs();
+        let mut rounded = secs - secs % step;
+        if secs % step * 1_000_000_000 + u64::from(self.0.subsec_nanos()) >= step * 500_000_000 {
+            rounded = rounded.saturating_add(step);
+        }
+        write!(f, "ETA {}", format_duration(Duration::from_secs(rounded)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{estimate_eta, format_eta};
+
+    #[test]
+    fn estimate() {
+        let elapsed = Duration::from_secs(10);
+        assert_eq!(estimate_eta(10, 10, elapsed), Some(Duration::ZERO));
+        assert_eq!(estimate_eta(1, 2, elapsed), Some(elapsed));
+        assert_eq!(
+            estimate_eta(3, 4, elapsed),
+            Some(Duration::new(3, 333_333_333))
+        );
+        assert_eq!(estimate_eta(11, 10, elapsed), None);
+        assert_eq!(estimate_eta(0, 0, elapsed), None);
+    }
+
+    #[test]
+    fn estimate_huge() {
+        assert_eq!(
+            estimate_eta(1, u64::MAX, Duration::from_secs(1)),
+            Some(Duration::from_secs(u64::MAX - 1))
+        );
+        assert_eq!(estimate_eta(1, u64::MAX, Duration::from_secs(2)), None);
+        assert_eq!(estimate_eta(1, 3, Duration::MAX), None);
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(format_eta(Duration::ZERO).to_string(), "ETA 0s");
+        assert_eq!(format_eta(Duration::from_millis(499)).to_string(), "ETA 0s");
+        assert_eq!(format_eta(Duration::from_millis(500)).to_string(), "ETA 1s");
+        assert_eq!(
+            format_eta(Duration::new(3599, 500_000_000)).to_string(),
+            "ETA 1h"
+        );
+        assert_eq!(
+            format_eta(Duration::from_secs(86399)).to_string(),
+            "ETA 1day"
+        );
+        assert_eq!(
+            format_eta(Duration::from_secs(86400 + 5399)).to_string(),
+            "ETA 1day 1h"
+        );
+        assert!(format_eta(Duration::MAX).to_string().starts_with("ETA "));
+    }
+}
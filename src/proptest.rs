@@ -0,0 +1,188 @@
+//! Strategies for property testing with [proptest]
+//!
+//! The strategies generate durations and timestamps along with strings
+//! accepted by [`parse_duration`](crate::parse_duration) and
+//! [`parse_rfc3339_weak`](crate::parse_rfc3339_weak), so crates which wrap
+//! them (config loaders, serde helpers, CLI arguments) can check that they
+//! accept the same inputs.
+//!
+//! # Example
+//!
+//! ```
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     fn parses(s in humantime::proptest::duration_string()) {
+//!         prop_assert!(humantime::parse_duration(&s).is_ok());
+//!     }
+//! }
+//! # parses();
+//! ```
+//!
+//! [proptest]: https://docs.rs/proptest
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use ::proptest::prelude::*;
+use ::proptest::sample::select;
+
+use crate::date::{
+    format_rfc3339, format_rfc3339_micros, format_rfc3339_millis, format_rfc3339_nanos,
+    format_rfc3339_seconds,
+};
+use crate::duration::Unit;
+use crate::grammar::grammar;
+
+/// Fractions which are exact for every unit, `0.016h` isn't accepted
+/// because it isn't a whole number of seconds
+const FRACTIONS: &[&str] = &[".5", ".25", ".75", ".125", ".0"];
+
+/// First second of year 10000, the last one `format_rfc3339` can write
+const MAX_TIMESTAMP_SECS: u64 = 253_402_300_800;
+
+/// Any duration
+///
+/// All of them can be formatted with
+/// [`format_duration`](crate::format_duration) and parsed back.
+pub fn duration() -> impl Strategy<Value = Duration> {
+    (any::<u64>(), 0..1_000_000_000u32).prop_map(|(secs, nanos)| Duration::new(secs, nanos))
+}
+
+/// Spellings of the units, either of nanoseconds (`nanos`) or of all the
+/// other units which can have a fraction
+fn aliases(nanos: bool) -> Vec<&'static str> {
+    grammar()
+        .units()
+        .iter()
+        .filter(|u| (u.unit() == Unit::Nanosecond) == nanos)
+        .flat_map(|u| u.aliases().iter().copied())
+        .collect()
+}
+
+fn component() -> impl Strategy<Value = String> {
+    prop_oneof![
+        (0..10_000u32, select(aliases(true))).prop_map(|(n, unit)| format!("{}{}", n, unit)),
+        (0..10_000u32, select(aliases(false))).prop_map(|(n, unit)| format!("{}{}", n, unit)),
+        (0..10_000u32, select(FRACTIONS), select(aliases(false)))
+            .prop_map(|(n, frac, unit)| format!("{}{}{}", n, frac, unit)),
+    ]
+}
+
+/// A string valid for [`parse_duration`](crate::parse_duration), like
+/// `3h 0.5min 12ns`
+///
+/// Components use all the unit spellings, optional fractions and spaces
+/// between components, numbers are small enough to never overflow.
+pub fn duration_string() -> impl Strategy<Value = String> {
+    prop::collection::vec((component(), select(&["", " ", "  "][..])), 1..6).prop_map(|items| {
+        let mut result = String::new();
+        for (item, sep) in items {
+            if !result.is_empty() {
+                result.push_str(sep);
+            }
+            result.push_str(&item);
+        }
+        result
+    })
+}
+
+/// Applies a small typo to `s`
+fn mutate(s: String, kind: u8, pos: prop::sample::Index, c: char) -> String {
+    let mut chars: Vec<char> = s.chars().collect();
+    let idx = pos.index(chars.len() + 1);
+    match kind {
+        0 if idx < chars.len() => {
+            chars.remove(idx);
+        }
+        1 if idx < chars.len() => chars[idx] = c,
+        2 if idx + 1 < chars.len() => chars.swap(idx, idx + 1),
+        _ => chars.insert(idx, c),
+    }
+    chars.into_iter().collect()
+}
+
+/// A [`duration_string`] with a typo: a character removed, replaced,
+/// inserted or two characters swapped
+///
+/// The result may or may not be a valid duration, it's meant to check that
+/// invalid input is rejected gracefully.
+pub fn near_valid_duration_string() -> impl Strategy<Value = String> {
+    let chars = select(&['0', '9', '.', ' ', '-', 's', 'm', 'x', 'µ', ','][..]);
+    (
+        duration_string(),
+        0..4u8,
+        any::<prop::sample::Index>(),
+        chars,
+    )
+        .prop_map(|(s, kind, pos, c)| mutate(s, kind, pos, c))
+}
+
+/// A timestamp between 1970 and year 9999
+pub fn timestamp() -> impl Strategy<Value = SystemTime> {
+    (0..MAX_TIMESTAMP_SECS, 0..1_000_000_000u32)
+        .prop_map(|(secs, nanos)| UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
+/// A string valid for [`parse_rfc3339_weak`](crate::parse_rfc3339_weak),
+/// like `2018-02-14 00:28:07.250`
+///
+/// Fractions have any of the precisions of `format_rfc3339_*`, both `T`
+/// and space separators are used and `Z` is optional.
+pub fn timestamp_string() -> impl Strategy<Value = String> {
+    (timestamp(), 0..5u8, any::<bool>(), any::<bool>()).prop_map(|(time, precision, t, z)| {
+        let mut s = match precision {
+            0 => format_rfc3339(time),
+            1 => format_rfc3339_seconds(time),
+            2 => format_rfc3339_millis(time),
+            3 => format_rfc3339_micros(time),
+            _ => format_rfc3339_nanos(time),
+        }
+        .to_string();
+        if !t {
+            s.replace_range(10..11, " ");
+        }
+        if !z {
+            s.pop();
+        }
+        s
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use ::proptest::prelude::*;
+
+    use super::timestamp_string;
+    use super::{duration, duration_string, near_valid_duration_string, timestamp};
+    use crate::date::{format_rfc3339_nanos, parse_rfc3339, parse_rfc3339_weak};
+    use crate::duration::{format_duration, parse_duration};
+
+    proptest! {
+        #[test]
+        fn duration_round_trip(d in duration()) {
+            prop_assert_eq!(parse_duration(&format_duration(d).to_string()), Ok(d));
+        }
+
+        #[test]
+        fn duration_strings_parse(s in duration_string()) {
+            prop_assert!(parse_duration(&s).is_ok(), "{:?}", s);
+        }
+
+        #[test]
+        fn near_valid_strings_dont_panic(s in near_valid_duration_string()) {
+            if let Ok(d) = parse_duration(&s) {
+                prop_assert_eq!(parse_duration(&format_duration(d).to_string()), Ok(d));
+            }
+        }
+
+        #[test]
+        fn timestamp_round_trip(t in timestamp()) {
+            prop_assert_eq!(parse_rfc3339(&format_rfc3339_nanos(t).to_string()), Ok(t));
+        }
+
+        #[test]
+        fn timestamp_strings_parse(s in timestamp_string()) {
+            prop_assert!(parse_rfc3339_weak(&s).is_ok(), "{:?}", s);
+        }
+    }
+}
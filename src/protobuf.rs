@@ -0,0 +1,301 @@
+use std::convert::TryFrom;
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::wrapper;
+
+const NANOS: i128 = 1_000_000_000;
+/// Bound of `google.protobuf.Duration` seconds, about 10,000 years
+const MAX_DURATION_SECS: i64 = 315_576_000_000;
+/// Seconds of `0001-01-01T00:00:00Z`, the earliest protobuf timestamp
+const MIN_TIMESTAMP_SECS: i64 = -62_135_596_800;
+/// Seconds of `9999-12-31T23:59:59Z`, the latest protobuf timestamp
+const MAX_TIMESTAMP_SECS: i64 = 253_402_300_799;
+
+/// Error converting from or to protobuf well-known types
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum Error {
+    /// The value is outside of the range of the protobuf type
+    OutOfRange,
+    /// The duration is negative and the target type is unsigned
+    Negative,
+    /// The nanoseconds are out of range or have another sign than seconds
+    InvalidNanos,
+}
+
+impl StdError for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::OutOfRange => write!(f, "value is out of range of the protobuf type"),
+            Error::Negative => write!(f, "duration is negative"),
+            Error::InvalidNanos => write!(f, "nanoseconds are invalid"),
+        }
+    }
+}
+
+/// The fields of a `google.protobuf.Duration` message
+///
+/// These are the fields of `prost_types::Duration` and of the types other
+/// protobuf libraries generate, so converting to the generated type is a
+/// struct literal. Protobuf durations are signed: negative durations have
+/// `seconds` and `nanos` both negative or zero.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use std::time::Duration;
+/// use humantime::{format_signed, parse_duration, ProtoDuration};
+///
+/// let timeout = ProtoDuration::try_from(parse_duration("1.5s").unwrap()).unwrap();
+/// assert_eq!(timeout, ProtoDuration { seconds: 1, nanos: 500_000_000 });
+/// let delta = ProtoDuration { seconds: -90, nanos: 0 };
+/// assert_eq!(format_signed(delta.as_nanos().unwrap()).to_string(), "-1m 30s");
+/// assert!(Duration::try_from(delta).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ProtoDuration {
+    /// Signed seconds
+    pub seconds: i64,
+    /// Signed fraction of a second in nanoseconds
+    pub nanos: i32,
+}
+
+impl ProtoDuration {
+    /// Creates a duration from signed nanoseconds, like those of
+    /// [`format_signed`](crate::format_signed)
+    pub fn from_nanos(nanos: i128) -> Result<ProtoDuration, Error> {
+        let seconds = i64::try_from(nanos / NANOS).map_err(|_| Error::OutOfRange)?;
+        let value = ProtoDuration {
+            seconds,
+            nanos: (nanos % NANOS) as i32,
+        };
+        value.as_nanos().map(|_| value)
+    }
+
+    /// Returns the signed nanoseconds of a valid duration
+    pub fn as_nanos(&self) -> Result<i128, Error> {
+        if self.seconds.unsigned_abs() > MAX_DURATION_SECS as u64 {
+            return Err(Error::OutOfRange);
+        }
+        let nanos = i128::from(self.nanos);
+        if nanos.abs() >= NANOS || (self.seconds.signum() * i64::from(self.nanos.signum()) < 0) {
+            return Err(Error::InvalidNanos);
+        }
+        Ok(i128::from(self.seconds) * NANOS + nanos)
+    }
+}
+
+impl TryFrom<Duration> for ProtoDuration {
+    type Error = Error;
+
+    fn try_from(val: Duration) -> Result<ProtoDuration, Error> {
+        ProtoDuration::from_nanos(val.as_nanos() as i128)
+    }
+}
+
+impl TryFrom<ProtoDuration> for Duration {
+    type Error = Error;
+
+    /// Fails with [`Error::Negative`] for negative durations
+    fn try_from(val: ProtoDuration) -> Result<Duration, Error> {
+        let nanos = val.as_nanos()?;
+        if nanos < 0 {
+            return Err(Error::Negative);
+        }
+        Ok(Duration::new(
+            (nanos / NANOS) as u64,
+            (nanos % NANOS) as u32,
+        ))
+    }
+}
+
+impl TryFrom<wrapper::Duration> for ProtoDuration {
+    type Error = Error;
+
+    fn try_from(val: wrapper::Duration) -> Result<ProtoDuration, Error> {
+        ProtoDuration::try_from(*val)
+    }
+}
+
+impl TryFrom<ProtoDuration> for wrapper::Duration {
+    type Error = Error;
+
+    fn try_from(val: ProtoDuration) -> Result<wrapper::Duration, Error> {
+        Duration::try_from(val).map(wrapper::Duration::from)
+    }
+}
+
+/// The fields of a `google.protobuf.Timestamp` message
+///
+/// Like [`ProtoDuration`], these are the fields of `prost_types::Timestamp`.
+/// Timestamps from `0001-01-01T00:00:00Z` to `9999-12-31T23:59:59.999999999Z`
+/// are valid, `nanos` is never negative, also before the epoch.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use std::time::SystemTime;
+/// use humantime::{format_rfc3339, parse_rfc3339, ProtoTimestamp};
+///
+/// let time = parse_rfc3339("2018-02-14T00:28:07.5Z").unwrap();
+/// let proto = ProtoTimestamp::try_from(time).unwrap();
+/// assert_eq!(proto, ProtoTimestamp { seconds: 1_518_568_087, nanos: 500_000_000 });
+/// let time = SystemTime::try_from(proto).unwrap();
+/// assert_eq!(format_rfc3339(time).to_string(), "2018-02-14T00:28:07.500000000Z");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ProtoTimestamp {
+    /// Seconds since the epoch, negative before it
+    pub seconds: i64,
+    /// Non-negative fraction of a second in nanoseconds
+    pub nanos: i32,
+}
+
+impl TryFrom<SystemTime> for ProtoTimestamp {
+    type Error = Error;
+
+    fn try_from(val: SystemTime) -> Result<ProtoTimestamp, Error> {
+        let nanos = match val.duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_nanos() as i128,
+            Err(e) => -(e.duration().as_nanos() as i128),
+        };
+        let seconds = i64::try_from(nanos.div_euclid(NANOS)).map_err(|_| Error::OutOfRange)?;
+        if !(MIN_TIMESTAMP_SECS..=MAX_TIMESTAMP_SECS).contains(&seconds) {
+            return Err(Error::OutOfRange);
+        }
+        Ok(ProtoTimestamp {
+            seconds,
+            nanos: nanos.rem_euclid(NANOS) as i32,
+        })
+    }
+}
+
+impl TryFrom<ProtoTimestamp> for SystemTime {
+    type Error = Error;
+
+    /// Fails with [`Error::OutOfRange`] also for valid timestamps which
+    /// the system time can't represent
+    fn try_from(val: ProtoTimestamp) -> Result<SystemTime, Error> {
+        if !(MIN_TIMESTAMP_SECS..=MAX_TIMESTAMP_SECS).contains(&val.seconds) {
+            return Err(Error::OutOfRange);
+        }
+        if !(0..NANOS as i32).contains(&val.nanos) {
+            return Err(Error::InvalidNanos);
+        }
+        let time = if val.seconds >= 0 {
+            UNIX_EPOCH.checked_add(Duration::from_secs(val.seconds as u64))
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(val.seconds.unsigned_abs()))
+        };
+        time.and_then(|t| t.checked_add(Duration::from_nanos(val.nanos as u64)))
+            .ok_or(Error::OutOfRange)
+    }
+}
+
+impl TryFrom<wrapper::Timestamp> for ProtoTimestamp {
+    type Error = Error;
+
+    fn try_from(val: wrapper::Timestamp) -> Result<ProtoTimestamp, Error> {
+        ProtoTimestamp::try_from(*val)
+    }
+}
+
+impl TryFrom<ProtoTimestamp> for wrapper::Timestamp {
+    type Error = Error;
+
+    fn try_from(val: ProtoTimestamp) -> Result<wrapper::Timestamp, Error> {
+        SystemTime::try_from(val).map(wrapper::Timestamp::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryFrom;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use super::{Error, ProtoDuration, ProtoTimestamp};
+    use crate::wrapper;
+
+    fn duration(seconds: i64, nanos: i32) -> ProtoDuration {
+        ProtoDuration { seconds, nanos }
+    }
+
+    fn timestamp(seconds: i64, nanos: i32) -> ProtoTimestamp {
+        ProtoTimestamp { seconds, nanos }
+    }
+
+    #[test]
+    fn durations() {
+        let val = Duration::new(90, 5);
+        assert_eq!(ProtoDuration::try_from(val), Ok(duration(90, 5)));
+        assert_eq!(Duration::try_from(duration(90, 5)), Ok(val));
+        assert_eq!(
+            wrapper::Duration::try_from(duration(90, 5)),
+            Ok(wrapper::Duration::from(val))
+        );
+        assert_eq!(
+            ProtoDuration::try_from(Duration::MAX),
+            Err(Error::OutOfRange)
+        );
+        assert_eq!(
+            ProtoDuration::try_from(Duration::from_secs(315_576_000_001)),
+            Err(Error::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn negative() {
+        assert_eq!(
+            ProtoDuration::from_nanos(-1_500_000_000),
+            Ok(duration(-1, -500_000_000))
+        );
+        assert_eq!(ProtoDuration::from_nanos(-5), Ok(duration(0, -5)));
+        assert_eq!(duration(-1, -500_000_000).as_nanos(), Ok(-1_500_000_000));
+        assert_eq!(Duration::try_from(duration(0, -5)), Err(Error::Negative));
+        assert_eq!(duration(-1, 5).as_nanos(), Err(Error::InvalidNanos));
+        assert_eq!(duration(1, -5).as_nanos(), Err(Error::InvalidNanos));
+        assert_eq!(
+            duration(0, 1_000_000_000).as_nanos(),
+            Err(Error::InvalidNanos)
+        );
+        assert_eq!(ProtoDuration::from_nanos(i128::MIN), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    fn timestamps() {
+        let time = UNIX_EPOCH + Duration::new(1_518_568_087, 5);
+        assert_eq!(
+            ProtoTimestamp::try_from(time),
+            Ok(timestamp(1_518_568_087, 5))
+        );
+        assert_eq!(SystemTime::try_from(timestamp(1_518_568_087, 5)), Ok(time));
+        assert_eq!(
+            wrapper::Timestamp::try_from(timestamp(0, 0)),
+            Ok(wrapper::Timestamp::from(UNIX_EPOCH))
+        );
+        let before = UNIX_EPOCH - Duration::from_millis(500);
+        assert_eq!(
+            ProtoTimestamp::try_from(before),
+            Ok(timestamp(-1, 500_000_000))
+        );
+        assert_eq!(SystemTime::try_from(timestamp(-1, 500_000_000)), Ok(before));
+        assert_eq!(
+            SystemTime::try_from(timestamp(253_402_300_800, 0)),
+            Err(Error::OutOfRange)
+        );
+        assert_eq!(
+            SystemTime::try_from(timestamp(0, -1)),
+            Err(Error::InvalidNanos)
+        );
+        assert_eq!(
+            ProtoTimestamp::try_from(UNIX_EPOCH + Duration::from_secs(253_402_300_800)),
+            Err(Error::OutOfRange)
+        );
+    }
+}
@@ -0,0 +1,263 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::duration::Unit;
+
+fn from_nanos(nanos: u128) -> Duration {
+    match u64::try_from(nanos / 1_000_000_000) {
+        Ok(secs) => Duration::new(secs, (nanos % 1_000_000_000) as u32),
+        Err(_) => Duration::MAX,
+    }
+}
+
+/// Rounds a duration to the nearest multiple of `step`
+///
+/// Values exactly halfway between two multiples are rounded up. A zero
+/// `step` leaves the value unchanged. The result saturates at
+/// `Duration::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::round_to;
+///
+/// let step = Duration::from_millis(250);
+/// assert_eq!(round_to(Duration::from_millis(1120), step), Duration::from_millis(1000));
+/// assert_eq!(round_to(Duration::from_millis(1125), step), Duration::from_millis(1250));
+/// ```
+pub fn round_to(value: Duration, step: Duration) -> Duration {
+    let step = step.as_nanos();
+    if step == 0 {
+        return value;
+    }
+    let nanos = value.as_nanos();
+    let floor = nanos - nanos % step;
+    if nanos % step >= step - step / 2 {
+        from_nanos(floor + step)
+    } else {
+        from_nanos(floor)
+    }
+}
+
+/// Rounds a duration down to a whole number of `unit`s
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::{floor_to, Unit};
+///
+/// assert_eq!(floor_to(Duration::from_secs(119), Unit::Minute), Duration::from_secs(60));
+/// ```
+pub fn floor_to(value: Duration, unit: Unit) -> Duration {
+    let step = u128::from(unit.nanos_per_unit());
+    let nanos = value.as_nanos();
+    from_nanos(nanos - nanos % step)
+}
+
+/// Rounds a duration up to a whole number of `unit`s
+///
+/// The result saturates at `Duration::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::{ceil_to, Unit};
+///
+/// assert_eq!(ceil_to(Duration::from_secs(61), Unit::Minute), Duration::from_secs(120));
+/// assert_eq!(ceil_to(Duration::from_secs(60), Unit::Minute), Duration::from_secs(60));
+/// ```
+pub fn ceil_to(value: Duration, unit: Unit) -> Duration {
+    let step = u128::from(unit.nanos_per_unit());
+    let nanos = value.as_nanos();
+    match nanos % step {
+        0 => value,
+        rem => from_nanos(nanos - rem + step),
+    }
+}
+
+/// Moves `time` to a multiple of `step` since the epoch, `up` or down
+fn align(time: SystemTime, step: u128, up: bool) -> SystemTime {
+    let (after_epoch, nanos) = match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => (true, d.as_nanos()),
+        Err(e) => (false, e.duration().as_nanos()),
+    };
+    let rem = nanos % step;
+    if rem == 0 {
+        return time;
+    }
+    // away from the epoch if rounding up after it or down before it
+    let offset = if up == after_epoch {
+        from_nanos(nanos - rem + step)
+    } else {
+        from_nanos(nanos - rem)
+    };
+    let aligned = if after_epoch {
+        UNIX_EPOCH.checked_add(offset)
+    } else {
+        UNIX_EPOCH.checked_sub(offset)
+    };
+    aligned.expect("overflow when aligning time")
+}
+
+/// Rounds a timestamp down to a whole number of `unit`s since the epoch
+///
+/// This floors a time to the start of its second, hour or UTC day. Weeks
+/// are counted from the epoch too, so they start on Thursday, and months
+/// and years are of the average length the parser uses, not calendar
+/// ones.
+///
+/// # Examples
+///
+/// ```
+/// use humantime::{parse_rfc3339, truncate_to, Unit};
+///
+/// let time = parse_rfc3339("2018-02-14T00:28:07.5Z").unwrap();
+/// let hour = parse_rfc3339("2018-02-14T00:00:00Z").unwrap();
+/// assert_eq!(truncate_to(time, Unit::Hour), hour);
+/// assert_eq!(truncate_to(time, Unit::Day), hour);
+/// ```
+pub fn truncate_to(time: SystemTime, unit: Unit) -> SystemTime {
+    align(time, u128::from(unit.nanos_per_unit()), false)
+}
+
+/// Rounds a timestamp up to a multiple of `step` since the epoch
+///
+/// This finds the next boundary of a schedule, like every 5 minutes.
+/// Times already on a boundary and a zero `step` leave the value
+/// unchanged.
+///
+/// # Panics
+///
+/// Panics if the result can't be represented by `SystemTime`, like
+/// `SystemTime + Duration` does.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::{align_up, parse_rfc3339};
+///
+/// let time = parse_rfc3339("2018-02-14T00:28:07Z").unwrap();
+/// let next = parse_rfc3339("2018-02-14T00:30:00Z").unwrap();
+/// assert_eq!(align_up(time, Duration::from_secs(300)), next);
+/// assert_eq!(align_up(next, Duration::from_secs(300)), next);
+/// ```
+pub fn align_up(time: SystemTime, step: Duration) -> SystemTime {
+    match step.as_nanos() {
+        0 => time,
+        step => align(time, step, true),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{align_up, ceil_to, floor_to, round_to, truncate_to};
+    use crate::duration::Unit;
+
+    #[test]
+    fn round() {
+        let step = Duration::from_secs(5);
+        assert_eq!(round_to(Duration::ZERO, step), Duration::ZERO);
+        assert_eq!(
+            round_to(Duration::new(2, 499_999_999), step),
+            Duration::ZERO
+        );
+        assert_eq!(round_to(Duration::new(2, 500_000_000), step), step);
+        assert_eq!(round_to(Duration::new(7, 1), step), step);
+        assert_eq!(
+            round_to(Duration::new(7, 1), Duration::ZERO),
+            Duration::new(7, 1)
+        );
+        // odd step, the half is not representable
+        let step = Duration::new(0, 3);
+        assert_eq!(round_to(Duration::new(0, 1), step), Duration::ZERO);
+        assert_eq!(round_to(Duration::new(0, 2), step), step);
+    }
+
+    #[test]
+    fn floor() {
+        let d = Duration::new(90061, 123_456_789);
+        assert_eq!(floor_to(d, Unit::Nanosecond), d);
+        assert_eq!(
+            floor_to(d, Unit::Microsecond),
+            Duration::new(90061, 123_456_000)
+        );
+        assert_eq!(
+            floor_to(d, Unit::Millisecond),
+            Duration::new(90061, 123_000_000)
+        );
+        assert_eq!(floor_to(d, Unit::Second), Duration::from_secs(90061));
+        assert_eq!(floor_to(d, Unit::Minute), Duration::from_secs(90060));
+        assert_eq!(floor_to(d, Unit::Hour), Duration::from_secs(90000));
+        assert_eq!(floor_to(d, Unit::Day), Duration::from_secs(86400));
+        assert_eq!(floor_to(d, Unit::Week), Duration::ZERO);
+    }
+
+    #[test]
+    fn ceil() {
+        let d = Duration::new(90061, 123_456_789);
+        assert_eq!(ceil_to(d, Unit::Nanosecond), d);
+        assert_eq!(ceil_to(d, Unit::Second), Duration::from_secs(90062));
+        assert_eq!(ceil_to(d, Unit::Day), Duration::from_secs(2 * 86400));
+        assert_eq!(ceil_to(d, Unit::Month), Duration::from_secs(2_630_016));
+        assert_eq!(ceil_to(d, Unit::Year), Duration::from_secs(31_557_600));
+        assert_eq!(ceil_to(Duration::ZERO, Unit::Year), Duration::ZERO);
+    }
+
+    #[test]
+    fn saturate() {
+        assert_eq!(ceil_to(Duration::MAX, Unit::Hour), Duration::MAX);
+        assert_eq!(
+            round_to(Duration::MAX, Duration::from_secs(2)),
+            Duration::MAX
+        );
+        assert_eq!(round_to(Duration::MAX, Duration::MAX), Duration::MAX);
+    }
+
+    #[test]
+    fn truncate() {
+        let t = UNIX_EPOCH + Duration::new(90061, 123_456_789);
+        assert_eq!(truncate_to(t, Unit::Nanosecond), t);
+        assert_eq!(
+            truncate_to(t, Unit::Millisecond),
+            UNIX_EPOCH + Duration::new(90061, 123_000_000)
+        );
+        assert_eq!(
+            truncate_to(t, Unit::Minute),
+            UNIX_EPOCH + Duration::from_secs(90060)
+        );
+        assert_eq!(
+            truncate_to(t, Unit::Day),
+            UNIX_EPOCH + Duration::from_secs(86400)
+        );
+        assert_eq!(truncate_to(t, Unit::Week), UNIX_EPOCH);
+        // before the epoch times are moved further from it
+        let t = UNIX_EPOCH - Duration::from_secs(90);
+        assert_eq!(
+            truncate_to(t, Unit::Minute),
+            UNIX_EPOCH - Duration::from_secs(120)
+        );
+        assert_eq!(truncate_to(t, Unit::Second), t);
+    }
+
+    #[test]
+    fn align() {
+        let step = Duration::from_secs(300);
+        let t = UNIX_EPOCH + Duration::new(601, 0);
+        assert_eq!(align_up(t, step), UNIX_EPOCH + Duration::from_secs(900));
+        assert_eq!(align_up(UNIX_EPOCH, step), UNIX_EPOCH);
+        assert_eq!(align_up(t, Duration::ZERO), t);
+        assert_eq!(
+            align_up(UNIX_EPOCH + Duration::new(0, 1), Duration::from_nanos(3)),
+            UNIX_EPOCH + Duration::from_nanos(3)
+        );
+        let t = UNIX_EPOCH - Duration::from_secs(301);
+        assert_eq!(align_up(t, step), UNIX_EPOCH - Duration::from_secs(300));
+        let t = UNIX_EPOCH - Duration::from_secs(1);
+        assert_eq!(align_up(t, step), UNIX_EPOCH);
+    }
+}
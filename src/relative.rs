@@ -0,0 +1,604 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::business::{weekday, BusinessCalendar, Weekday};
+use crate::civil::days_since_epoch;
+use crate::duration::{self, parse_duration, Unit};
+
+/// Error applying a human-friendly duration to a timestamp
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// The duration expression could not be parsed
+    Duration(duration::Error),
+    /// The resulting timestamp can't be represented by `SystemTime`
+    Overflow,
+    /// The calendar has no working days of the week
+    NoBusinessDays,
+    /// A word of a date phrase is not understood or repeats a part which
+    /// was already specified
+    UnknownWord(String),
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Duration(e) => Some(e),
+            Error::Overflow | Error::NoBusinessDays | Error::UnknownWord(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Duration(e) => write!(f, "invalid duration: {}", e),
+            Error::Overflow => write!(f, "resulting timestamp is out of range"),
+            Error::NoBusinessDays => write!(f, "calendar has no working days"),
+            Error::UnknownWord(word) => write!(f, "unexpected {:?} in date phrase", word),
+        }
+    }
+}
+
+impl From<duration::Error> for Error {
+    fn from(e: duration::Error) -> Error {
+        Error::Duration(e)
+    }
+}
+
+/// Add a human-friendly duration to a timestamp
+///
+/// The duration is parsed with [`parse_duration`](crate::parse_duration),
+/// the addition is checked, so the error is returned instead of a panic when
+/// the result is not representable on the current platform.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::add_human;
+///
+/// let start = UNIX_EPOCH + Duration::new(1_518_563_312, 0);
+/// assert_eq!(add_human(start, "2h"), Ok(start + Duration::new(7200, 0)));
+/// ```
+pub fn add_human(time: SystemTime, duration: &str) -> Result<SystemTime, Error> {
+    let duration = parse_duration(duration)?;
+    time.checked_add(duration).ok_or(Error::Overflow)
+}
+
+/// Subtract a human-friendly duration from a timestamp
+///
+/// Works the same way as [`add_human`], but moves the timestamp backwards.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::sub_human;
+///
+/// let start = UNIX_EPOCH + Duration::new(1_518_563_312, 0);
+/// assert_eq!(sub_human(start, "1day 30min"), Ok(start - Duration::new(88200, 0)));
+/// ```
+pub fn sub_human(time: SystemTime, duration: &str) -> Result<SystemTime, Error> {
+    let duration = parse_duration(duration)?;
+    time.checked_sub(duration).ok_or(Error::Overflow)
+}
+
+/// Advance a timestamp by working time, skipping weekends and holidays
+///
+/// The working time is split into business days using
+/// [`BusinessCalendar::span`]. The timestamp moves forward by that many
+/// business days keeping the time of day, then the rest of the working
+/// time is added. If that lands on a non-business day, the result is moved
+/// to the next business day (again keeping the time of day), so the result
+/// is always on a business day.
+///
+/// Working hours within a day are not modelled: the time of day is kept
+/// as is.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::{advance_business, parse_business_duration, BusinessCalendar};
+///
+/// let calendar = BusinessCalendar::new();
+/// // Friday, 2018-02-16 10:00:00 UTC
+/// let friday = UNIX_EPOCH + Duration::from_secs(1_518_775_200);
+/// let work = parse_business_duration("2 business days", &calendar).unwrap();
+/// // Tuesday, 2018-02-20 10:00:00 UTC
+/// assert_eq!(
+///     advance_business(friday, work, &calendar),
+///     Ok(friday + Duration::from_secs(4 * 86400)),
+/// );
+/// ```
+pub fn advance_business(
+    time: SystemTime,
+    work: Duration,
+    calendar: &BusinessCalendar,
+) -> Result<SystemTime, Error> {
+    let per_week = u64::from(calendar.workdays_per_week());
+    if per_week == 0 {
+        return Err(Error::NoBusinessDays);
+    }
+    let span = calendar.span(work);
+    let start = days_since_epoch(time);
+    let mut day = start;
+    let mut left = span.days;
+    // skip whole weeks, then correct for holidays within them
+    while left > per_week {
+        let weeks = (left - 1) / per_week;
+        let weeks = i64::try_from(weeks).map_err(|_| Error::Overflow)?;
+        let end = weeks
+            .checked_mul(7)
+            .and_then(|n| day.checked_add(n))
+            .ok_or(Error::Overflow)?;
+        left -= weeks as u64 * per_week - calendar.holidays_between(day, end);
+        day = end;
+    }
+    while left > 0 {
+        day = day.checked_add(1).ok_or(Error::Overflow)?;
+        if calendar.is_business_day_number(day) {
+            left -= 1;
+        }
+    }
+    let days = (day - start) as u64;
+    let mut result = days
+        .checked_mul(86400)
+        .and_then(|secs| time.checked_add(Duration::from_secs(secs)))
+        .and_then(|t| t.checked_add(span.time))
+        .ok_or(Error::Overflow)?;
+    while !calendar.is_business_day(result) {
+        result = result
+            .checked_add(Duration::from_secs(86400))
+            .ok_or(Error::Overflow)?;
+    }
+    Ok(result)
+}
+
+const NANOS_PER_DAY: i128 = 86_400_000_000_000;
+
+fn day_word(word: &str) -> Option<i64> {
+    match word.to_ascii_lowercase().as_str() {
+        "today" => Some(0),
+        "tomorrow" => Some(1),
+        "yesterday" => Some(-1),
+        _ => None,
+    }
+}
+
+fn named_time(word: &str) -> Option<u64> {
+    match word.to_ascii_lowercase().as_str() {
+        "midnight" => Some(0),
+        "noon" => Some(12 * 3600),
+        "teatime" => Some(16 * 3600),
+        _ => None,
+    }
+}
+
+pub(crate) fn weekday_word(word: &str) -> Option<Weekday> {
+    let day = match word.to_ascii_lowercase().as_str() {
+        "monday" | "mon" => Weekday::Monday,
+        "tuesday" | "tue" | "tues" => Weekday::Tuesday,
+        "wednesday" | "wed" => Weekday::Wednesday,
+        "thursday" | "thu" | "thurs" => Weekday::Thursday,
+        "friday" | "fri" => Weekday::Friday,
+        "saturday" | "sat" => Weekday::Saturday,
+        "sunday" | "sun" => Weekday::Sunday,
+        _ => return None,
+    };
+    Some(day)
+}
+
+/// Parses time of day like `09:00`, `9:30:15`, `9am`, `9 pm`
+///
+/// Returns seconds since midnight and the number of words used, or `None`
+/// if the word is not a time of day.
+fn parse_clock(word: &str, next: Option<&str>) -> Result<Option<(u64, usize)>, Error> {
+    let invalid = || Error::UnknownWord(word.to_owned());
+    let lower = word.to_ascii_lowercase();
+    let next = next.map(|w| w.to_ascii_lowercase());
+    let (core, pm, used) = if let Some(core) = lower.strip_suffix("am") {
+        (core, Some(false), 1)
+    } else if let Some(core) = lower.strip_suffix("pm") {
+        (core, Some(true), 1)
+    } else {
+        match next.as_deref() {
+            Some("am") => (lower.as_str(), Some(false), 2),
+            Some("pm") => (lower.as_str(), Some(true), 2),
+            _ => (lower.as_str(), None, 1),
+        }
+    };
+    if pm.is_none() && !core.contains(':') {
+        return Ok(None);
+    }
+    let mut fields = [0u64; 3];
+    for (idx, part) in core.split(':').enumerate() {
+        if idx == fields.len() || part.is_empty() || part.len() > 2 {
+            return Err(invalid());
+        }
+        fields[idx] = part.parse().map_err(|_| invalid())?;
+    }
+    let [mut hours, minutes, seconds] = fields;
+    if minutes > 59 || seconds > 59 {
+        return Err(invalid());
+    }
+    match pm {
+        Some(pm) => {
+            if hours == 0 || hours > 12 {
+                return Err(invalid());
+            }
+            hours = hours % 12 + if pm { 12 } else { 0 };
+        }
+        None if hours > 23 => return Err(invalid()),
+        None => {}
+    }
+    Ok(Some((hours * 3600 + minutes * 60 + seconds, used)))
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, word: &str) -> Result<(), Error> {
+    if slot.is_some() {
+        return Err(Error::UnknownWord(word.to_owned()));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn to_nanos(time: SystemTime) -> i128 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_nanos() as i128,
+        Err(e) => -(e.duration().as_nanos() as i128),
+    }
+}
+
+fn from_nanos(nanos: i128) -> Option<SystemTime> {
+    let duration = |n: u128| {
+        let secs = u64::try_from(n / 1_000_000_000).ok()?;
+        Some(Duration::new(secs, (n % 1_000_000_000) as u32))
+    };
+    if nanos >= 0 {
+        UNIX_EPOCH.checked_add(duration(nanos as u128)?)
+    } else {
+        UNIX_EPOCH.checked_sub(duration(nanos.unsigned_abs())?)
+    }
+}
+
+/// Resolve a date phrase of `at` and `date -d`, like `noon tomorrow`
+///
+/// A useful subset of the phrases is supported, in any order:
+///
+/// * `now`, `today`, `tomorrow`, `yesterday`
+/// * time of day: `09:00`, `21:30:15`, `9am`, `9:30 pm`, `noon`,
+///   `midnight`, `teatime` (16:00)
+/// * day of the week: `monday`, `mon`, ... -- the nearest such day,
+///   today included; with `next` or `last` -- strictly after or before
+///   today
+/// * `next <unit>`, `last <unit>`: `next week`, `last hour`
+/// * offsets: `2 hours`, `+2h`, `in 3 days`, `now + 1 hour`,
+///   `3 days ago`, `-1 week`
+///
+/// Words are case-insensitive. All computations are in UTC. Like in GNU
+/// `date`, the time of day is kept unless it is given explicitly or a day
+/// of the week is used (which means midnight). A time which has already
+/// passed today is not moved to tomorrow. Units are the same as in
+/// [`parse_duration`](crate::parse_duration), so `next month` is 30.44
+/// days.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::{parse_date_phrase, parse_rfc3339};
+///
+/// let now = parse_rfc3339("2018-02-14T09:15:00Z").unwrap();  // Wednesday
+/// let at = |phrase| parse_date_phrase(phrase, now).unwrap();
+/// assert_eq!(at("noon tomorrow"), parse_rfc3339("2018-02-15T12:00:00Z").unwrap());
+/// assert_eq!(at("next week"), parse_rfc3339("2018-02-21T09:15:00Z").unwrap());
+/// assert_eq!(at("09:00 Monday"), parse_rfc3339("2018-02-19T09:00:00Z").unwrap());
+/// assert_eq!(at("2 hours ago"), parse_rfc3339("2018-02-14T07:15:00Z").unwrap());
+/// ```
+pub fn parse_date_phrase(phrase: &str, now: SystemTime) -> Result<SystemTime, Error> {
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    if words.is_empty() {
+        return Err(Error::Duration(duration::Error::Empty));
+    }
+    let mut day_shift = None;
+    let mut day_of_week = None;
+    let mut time_of_day = None;
+    let mut offset = 0i128;
+    let mut negative = false;
+    let mut idx = 0;
+    while idx < words.len() {
+        let word = words[idx];
+        let is = |keyword: &str| word.eq_ignore_ascii_case(keyword);
+        idx += 1;
+        if is("now") || is("in") || is("+") {
+            continue;
+        } else if is("-") {
+            negative = true;
+        } else if let Some(shift) = day_word(word) {
+            set_once(&mut day_shift, shift, word)?;
+        } else if let Some(time) = named_time(word) {
+            set_once(&mut time_of_day, time, word)?;
+        } else if let Some(day) = weekday_word(word) {
+            set_once(&mut day_of_week, (day, 0), word)?;
+        } else if is("next") || is("last") || is("this") {
+            let direction = if is("next") {
+                1
+            } else if is("last") {
+                -1
+            } else {
+                0
+            };
+            let target = words.get(idx).copied().unwrap_or("");
+            idx += 1;
+            if let Some(day) = weekday_word(target) {
+                set_once(&mut day_of_week, (day, direction), target)?;
+            } else {
+                let unit: Unit = target
+                    .to_ascii_lowercase()
+                    .parse()
+                    .map_err(|_| Error::UnknownWord(target.to_owned()))?;
+                if direction == 0 {
+                    return Err(Error::UnknownWord(word.to_owned()));
+                }
+                offset += direction * i128::from(unit.nanos_per_unit());
+            }
+        } else {
+            let (sign, body) = match word.as_bytes()[0] {
+                b'+' => (false, &word[1..]),
+                b'-' => (true, &word[1..]),
+                _ => (negative, word),
+            };
+            negative = false;
+            if !body.starts_with(|c: char| c.is_ascii_digit()) {
+                return Err(Error::UnknownWord(word.to_owned()));
+            }
+            if body.len() == word.len() {
+                if let Some((time, used)) = parse_clock(word, words.get(idx).copied())? {
+                    set_once(&mut time_of_day, time, word)?;
+                    idx += used - 1;
+                    continue;
+                }
+            }
+            let mut value = parse_duration(body);
+            if body.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+                if let Some(unit) = words.get(idx) {
+                    value = parse_duration(&format!("{} {}", body, unit));
+                    idx += 1;
+                }
+            }
+            let value = value?.as_nanos() as i128;
+            let ago = words
+                .get(idx)
+                .map_or(false, |w| w.eq_ignore_ascii_case("ago"));
+            if ago {
+                idx += 1;
+            }
+            offset += if sign != ago { -value } else { value };
+        }
+    }
+    let now = to_nanos(now);
+    let mut day = now.div_euclid(NANOS_PER_DAY);
+    let mut time = now.rem_euclid(NANOS_PER_DAY);
+    day += i128::from(day_shift.unwrap_or(0));
+    if let Some((target, direction)) = day_of_week {
+        let current = weekday(day as i64) as i128;
+        let ahead = (target as i128 - current).rem_euclid(7);
+        day += match direction {
+            1 if ahead == 0 => 7,
+            -1 if ahead == 0 => -7,
+            -1 => ahead - 7,
+            _ => ahead,
+        };
+        time = 0;
+    }
+    if let Some(secs) = time_of_day {
+        time = i128::from(secs) * 1_000_000_000;
+    }
+    from_nanos(day * NANOS_PER_DAY + time + offset).ok_or(Error::Overflow)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::Error;
+    use super::{add_human, advance_business, parse_date_phrase, sub_human};
+    use crate::business::{BusinessCalendar, Weekday};
+    use crate::date::parse_rfc3339;
+    use crate::duration::Error as DurationError;
+
+    const DAY: u64 = 86400;
+
+    #[test]
+    fn add() {
+        let start = UNIX_EPOCH + Duration::new(1_000_000, 0);
+        assert_eq!(add_human(start, "0"), Ok(start));
+        assert_eq!(
+            add_human(start, "1h 15ms"),
+            Ok(start + Duration::new(3600, 15_000_000))
+        );
+    }
+
+    #[test]
+    fn sub() {
+        let start = UNIX_EPOCH + Duration::new(1_000_000, 0);
+        assert_eq!(sub_human(start, "1000000s"), Ok(UNIX_EPOCH));
+        assert_eq!(
+            sub_human(start, "2min 5ns"),
+            Ok(start - Duration::new(120, 5))
+        );
+    }
+
+    #[test]
+    fn bad_duration() {
+        assert_eq!(
+            add_human(UNIX_EPOCH, "1 parsec"),
+            Err(Error::Duration(DurationError::UnknownUnit {
+                start: 2,
+                end: 8,
+                unit: "parsec".to_owned(),
+                value: 1,
+            }))
+        );
+        assert_eq!(
+            sub_human(UNIX_EPOCH, ""),
+            Err(Error::Duration(DurationError::Empty))
+        );
+    }
+
+    #[test]
+    fn overflow() {
+        let max = format!("{}s", u64::MAX);
+        assert_eq!(add_human(UNIX_EPOCH, &max), Err(Error::Overflow));
+        assert_eq!(sub_human(UNIX_EPOCH, &max), Err(Error::Overflow));
+        assert_eq!(
+            add_human(UNIX_EPOCH, &max).unwrap_err().to_string(),
+            "resulting timestamp is out of range"
+        );
+    }
+
+    #[test]
+    fn business() {
+        let cal = BusinessCalendar::new();
+        // Wednesday, 2018-02-14 09:00:00 UTC
+        let wed = UNIX_EPOCH + Duration::new(1_518_598_800, 0);
+        let adv = |t, hours: u64, cal: &BusinessCalendar| {
+            advance_business(t, Duration::from_secs(hours * 3600), cal)
+        };
+        assert_eq!(adv(wed, 0, &cal), Ok(wed));
+        assert_eq!(adv(wed, 8, &cal), Ok(wed + Duration::from_secs(DAY)));
+        assert_eq!(adv(wed, 16, &cal), Ok(wed + Duration::from_secs(2 * DAY)));
+        assert_eq!(adv(wed, 24, &cal), Ok(wed + Duration::from_secs(5 * DAY)));
+        assert_eq!(
+            adv(wed, 12, &cal),
+            Ok(wed + Duration::from_secs(DAY + 4 * 3600))
+        );
+        assert_eq!(adv(wed, 40, &cal), Ok(wed + Duration::from_secs(7 * DAY)));
+        assert_eq!(
+            adv(wed, 800, &cal),
+            Ok(wed + Duration::from_secs(140 * DAY))
+        );
+        // Saturday moves to Monday
+        let sat = wed + Duration::from_secs(3 * DAY);
+        assert_eq!(adv(sat, 0, &cal), Ok(sat + Duration::from_secs(2 * DAY)));
+        assert_eq!(adv(sat, 8, &cal), Ok(sat + Duration::from_secs(2 * DAY)));
+        assert_eq!(adv(sat, 16, &cal), Ok(sat + Duration::from_secs(3 * DAY)));
+        // the rest of the work time crosses midnight into Saturday
+        let fri = wed + Duration::from_secs(2 * DAY + 12 * 3600);
+        assert_eq!(
+            adv(fri, 6, &cal),
+            Ok(fri + Duration::from_secs(2 * DAY + 6 * 3600))
+        );
+    }
+
+    #[test]
+    fn business_holidays() {
+        let wed = UNIX_EPOCH + Duration::new(1_518_598_800, 0);
+        let cal = BusinessCalendar::new()
+            .holiday(wed + Duration::from_secs(DAY))
+            .holiday(wed + Duration::from_secs(6 * DAY))
+            .holiday(wed + Duration::from_secs(4 * DAY));
+        let adv = |hours: u64| advance_business(wed, Duration::from_secs(hours * 3600), &cal);
+        assert_eq!(adv(8), Ok(wed + Duration::from_secs(2 * DAY)));
+        assert_eq!(adv(16), Ok(wed + Duration::from_secs(5 * DAY)));
+        assert_eq!(adv(40), Ok(wed + Duration::from_secs(9 * DAY)));
+        assert_eq!(adv(800), Ok(wed + Duration::from_secs(142 * DAY)));
+    }
+
+    #[test]
+    fn business_errors() {
+        let cal = BusinessCalendar::new().workdays(&[]);
+        assert_eq!(
+            advance_business(UNIX_EPOCH, Duration::ZERO, &cal),
+            Err(Error::NoBusinessDays)
+        );
+        let cal = BusinessCalendar::new().workdays(&[Weekday::Monday]);
+        assert_eq!(
+            advance_business(UNIX_EPOCH, Duration::MAX, &cal),
+            Err(Error::Overflow)
+        );
+    }
+
+    #[test]
+    fn phrases() {
+        // Wednesday
+        let now = parse_rfc3339("2018-02-14T09:15:30Z").unwrap();
+        let at = |phrase| parse_date_phrase(phrase, now);
+        let ts = |s| Ok(parse_rfc3339(s).unwrap());
+        assert_eq!(at("now"), Ok(now));
+        assert_eq!(at("today"), Ok(now));
+        assert_eq!(at("tomorrow"), ts("2018-02-15T09:15:30Z"));
+        assert_eq!(at("Yesterday"), ts("2018-02-13T09:15:30Z"));
+        assert_eq!(at("noon"), ts("2018-02-14T12:00:00Z"));
+        assert_eq!(at("midnight"), ts("2018-02-14T00:00:00Z"));
+        assert_eq!(at("teatime tomorrow"), ts("2018-02-15T16:00:00Z"));
+        assert_eq!(at("tomorrow 7:05"), ts("2018-02-15T07:05:00Z"));
+        assert_eq!(at("23:59:59"), ts("2018-02-14T23:59:59Z"));
+        assert_eq!(at("9pm"), ts("2018-02-14T21:00:00Z"));
+        assert_eq!(at("12am"), ts("2018-02-14T00:00:00Z"));
+        assert_eq!(at("12:30 PM"), ts("2018-02-14T12:30:00Z"));
+        assert_eq!(at("7:30am yesterday"), ts("2018-02-13T07:30:00Z"));
+    }
+
+    #[test]
+    fn phrase_weekdays() {
+        // Wednesday
+        let now = parse_rfc3339("2018-02-14T09:15:30Z").unwrap();
+        let at = |phrase| parse_date_phrase(phrase, now);
+        let ts = |s| Ok(parse_rfc3339(s).unwrap());
+        assert_eq!(at("wednesday"), ts("2018-02-14T00:00:00Z"));
+        assert_eq!(at("friday"), ts("2018-02-16T00:00:00Z"));
+        assert_eq!(at("Mon 09:00"), ts("2018-02-19T09:00:00Z"));
+        assert_eq!(at("next wed"), ts("2018-02-21T00:00:00Z"));
+        assert_eq!(at("next thursday"), ts("2018-02-15T00:00:00Z"));
+        assert_eq!(at("last wednesday"), ts("2018-02-07T00:00:00Z"));
+        assert_eq!(at("last tuesday noon"), ts("2018-02-13T12:00:00Z"));
+        assert_eq!(at("this sunday"), ts("2018-02-18T00:00:00Z"));
+    }
+
+    #[test]
+    fn phrase_offsets() {
+        let now = parse_rfc3339("2018-02-14T09:15:30Z").unwrap();
+        let at = |phrase| parse_date_phrase(phrase, now);
+        let ts = |s| Ok(parse_rfc3339(s).unwrap());
+        assert_eq!(at("next week"), ts("2018-02-21T09:15:30Z"));
+        assert_eq!(at("last hour"), ts("2018-02-14T08:15:30Z"));
+        assert_eq!(at("2 hours"), ts("2018-02-14T11:15:30Z"));
+        assert_eq!(at("in 30min"), ts("2018-02-14T09:45:30Z"));
+        assert_eq!(at("now + 1 hour"), ts("2018-02-14T10:15:30Z"));
+        assert_eq!(at("+1day"), ts("2018-02-15T09:15:30Z"));
+        assert_eq!(at("-1 week"), ts("2018-02-07T09:15:30Z"));
+        assert_eq!(at("now - 90s"), ts("2018-02-14T09:14:00Z"));
+        assert_eq!(at("3 days ago"), ts("2018-02-11T09:15:30Z"));
+        assert_eq!(at("noon tomorrow + 2h"), ts("2018-02-15T14:00:00Z"));
+        assert_eq!(
+            parse_date_phrase("yesterday", UNIX_EPOCH),
+            Ok(UNIX_EPOCH - Duration::from_secs(86400))
+        );
+        assert_eq!(
+            parse_date_phrase("noon", UNIX_EPOCH - Duration::from_secs(1)),
+            Ok(UNIX_EPOCH - Duration::from_secs(12 * 3600))
+        );
+    }
+
+    #[test]
+    fn phrase_errors() {
+        let now = parse_rfc3339("2018-02-14T09:15:30Z").unwrap();
+        let at = |phrase| parse_date_phrase(phrase, now);
+        let word = |w: &str| Err(Error::UnknownWord(w.to_owned()));
+        assert_eq!(at(""), Err(Error::Duration(DurationError::Empty)));
+        assert_eq!(at("soon"), word("soon"));
+        assert_eq!(at("tomorrow today"), word("today"));
+        assert_eq!(at("noon 13:00"), word("13:00"));
+        assert_eq!(at("25:00"), word("25:00"));
+        assert_eq!(at("10:60"), word("10:60"));
+        assert_eq!(at("13pm"), word("13pm"));
+        assert_eq!(at("next"), word(""));
+        assert_eq!(at("next fortnight"), word("fortnight"));
+        assert_eq!(at("this week"), word("this"));
+        assert!(matches!(at("2 parsecs"), Err(Error::Duration(_))));
+    }
+}
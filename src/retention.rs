@@ -0,0 +1,256 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+
+/// Error parsing a retention policy
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// Item is not in form of `<count> <period>`, e.g. `7 daily`
+    InvalidFormat,
+    /// Count is not a valid non-negative integer
+    InvalidNumber,
+    /// Period is not one of `hourly`, `daily`, `weekly`, `monthly`,
+    /// `yearly`
+    UnknownPeriod(String),
+    /// The same period is specified twice
+    DuplicatePeriod(Period),
+}
+
+impl StdError for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidFormat => write!(
+                f,
+                "retention policy format is invalid, \
+                expected e.g. `keep 7 daily, 4 weekly`"
+            ),
+            Error::InvalidNumber => write!(f, "invalid number of backups to keep"),
+            Error::UnknownPeriod(period) => write!(
+                f,
+                "unknown period {:?}, supported periods: \
+                hourly, daily, weekly, monthly, yearly",
+                period
+            ),
+            Error::DuplicatePeriod(period) => {
+                write!(f, "period {} is specified more than once", period)
+            }
+        }
+    }
+}
+
+/// Period of a retention policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Period {
+    /// Keep the last backup of each hour
+    Hourly,
+    /// Keep the last backup of each day
+    Daily,
+    /// Keep the last backup of each week
+    Weekly,
+    /// Keep the last backup of each month
+    Monthly,
+    /// Keep the last backup of each year
+    Yearly,
+}
+
+const PERIODS: [Period; 5] = [
+    Period::Hourly,
+    Period::Daily,
+    Period::Weekly,
+    Period::Monthly,
+    Period::Yearly,
+];
+
+impl Period {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Period::Hourly => "hourly",
+            Period::Daily => "daily",
+            Period::Weekly => "weekly",
+            Period::Monthly => "monthly",
+            Period::Yearly => "yearly",
+        }
+    }
+}
+
+impl FromStr for Period {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Period, Error> {
+        PERIODS
+            .iter()
+            .find(|p| p.as_str() == s)
+            .copied()
+            .ok_or_else(|| Error::UnknownPeriod(s.to_owned()))
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Grandfather-father-son retention policy, like
+/// `keep 7 daily, 4 weekly, 12 monthly`
+///
+/// The `keep` prefix is optional, items are separated by commas. Empty
+/// policy is written as `keep nothing`.
+///
+/// # Example
+///
+/// ```
+/// use humantime::{Period, RetentionPolicy};
+///
+/// let policy: RetentionPolicy = "keep 7 daily, 4 weekly, 12 monthly".parse().unwrap();
+/// assert_eq!(policy.count(Period::Daily), 7);
+/// assert_eq!(policy.count(Period::Yearly), 0);
+/// assert_eq!(policy, RetentionPolicy::new()
+///     .keep(Period::Daily, 7)
+///     .keep(Period::Weekly, 4)
+///     .keep(Period::Monthly, 12));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RetentionPolicy {
+    counts: [u32; 5],
+}
+
+impl RetentionPolicy {
+    /// Create a policy which keeps nothing
+    pub const fn new() -> RetentionPolicy {
+        RetentionPolicy { counts: [0; 5] }
+    }
+
+    /// Set the number of backups to keep for a period
+    pub fn keep(mut self, period: Period, count: u32) -> RetentionPolicy {
+        self.counts[period as usize] = count;
+        self
+    }
+
+    /// Returns the number of backups to keep for a period
+    pub fn count(&self, period: Period) -> u32 {
+        self.counts[period as usize]
+    }
+
+    /// Returns `true` if no backups are kept for any period
+    pub fn is_empty(&self) -> bool {
+        self.counts.iter().all(|&c| c == 0)
+    }
+
+    /// Iterates over periods with non-zero counts, shortest period first
+    pub fn iter(&self) -> impl Iterator<Item = (Period, u32)> + '_ {
+        PERIODS
+            .iter()
+            .map(move |&p| (p, self.count(p)))
+            .filter(|&(_, c)| c > 0)
+    }
+}
+
+impl FromStr for RetentionPolicy {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<RetentionPolicy, Error> {
+        let s = s.trim();
+        let s = match s.strip_prefix("keep") {
+            Some(rest) if rest.is_empty() || rest.starts_with(char::is_whitespace) => rest.trim(),
+            _ => s,
+        };
+        let mut policy = RetentionPolicy::new();
+        if s == "nothing" {
+            return Ok(policy);
+        }
+        let mut seen = [false; 5];
+        for item in s.split(',') {
+            let mut words = item.split_whitespace();
+            let (count, period) = match (words.next(), words.next(), words.next()) {
+                (Some(count), Some(period), None) => (count, period),
+                _ => return Err(Error::InvalidFormat),
+            };
+            if !count.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(Error::InvalidNumber);
+            }
+            let count = count.parse().map_err(|_| Error::InvalidNumber)?;
+            let period: Period = period.parse()?;
+            if seen[period as usize] {
+                return Err(Error::DuplicatePeriod(period));
+            }
+            seen[period as usize] = true;
+            policy = policy.keep(period, count);
+        }
+        Ok(policy)
+    }
+}
+
+impl fmt::Display for RetentionPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("keep nothing");
+        }
+        f.write_str("keep")?;
+        for (idx, (period, count)) in self.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, " {} {}", count, period)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Error, Period, RetentionPolicy};
+
+    #[test]
+    fn parse() {
+        let policy: RetentionPolicy = "keep 24 hourly,7 daily , 1 yearly".parse().unwrap();
+        assert_eq!(
+            policy.iter().collect::<Vec<_>>(),
+            vec![
+                (Period::Hourly, 24),
+                (Period::Daily, 7),
+                (Period::Yearly, 1)
+            ]
+        );
+        let policy: RetentionPolicy = "4 weekly".parse().unwrap();
+        assert_eq!(policy, RetentionPolicy::new().keep(Period::Weekly, 4));
+        let policy: RetentionPolicy = "keep nothing".parse().unwrap();
+        assert!(policy.is_empty());
+        assert_eq!("keep 0 daily".parse(), Ok(RetentionPolicy::new()));
+    }
+
+    #[test]
+    fn errors() {
+        let parse = |s: &str| s.parse::<RetentionPolicy>();
+        assert_eq!(parse(""), Err(Error::InvalidFormat));
+        assert_eq!(parse("keep"), Err(Error::InvalidFormat));
+        assert_eq!(parse("keep 7 daily,"), Err(Error::InvalidFormat));
+        assert_eq!(parse("keep daily"), Err(Error::InvalidFormat));
+        assert_eq!(parse("keep 7 daily 4 weekly"), Err(Error::InvalidFormat));
+        assert_eq!(parse("keep -7 daily"), Err(Error::InvalidNumber));
+        assert_eq!(parse("keep 99999999999 daily"), Err(Error::InvalidNumber));
+        assert_eq!(
+            parse("keep 7 days"),
+            Err(Error::UnknownPeriod("days".to_owned()))
+        );
+        assert_eq!(
+            parse("keep 7 daily, 8 daily"),
+            Err(Error::DuplicatePeriod(Period::Daily))
+        );
+        assert_eq!(
+            parse("keep 7 daily, 8 daily").unwrap_err().to_string(),
+            "period daily is specified more than once"
+        );
+    }
+
+    #[test]
+    fn display() {
+        for input in &["keep 7 daily, 4 weekly, 12 monthly", "keep nothing"] {
+            let policy: RetentionPolicy = input.parse().unwrap();
+            assert_eq!(policy.to_string(), *input);
+        }
+        let policy: RetentionPolicy = "1 yearly, 2 hourly".parse().unwrap();
+        assert_eq!(policy.to_string(), "keep 2 hourly, 1 yearly");
+    }
+}
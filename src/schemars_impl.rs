@@ -0,0 +1,233 @@
+use std::borrow::Cow;
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+use crate::grammar::grammar;
+use crate::wrapper::{Duration, Timeout, Timestamp};
+
+fn duration_pattern() -> String {
+    grammar().to_regex()
+}
+
+/// Pattern of the input accepted by `parse_rfc3339_weak`
+///
+/// The fields are checked for their ranges, but not the days against the
+/// month, so `2018-02-31 00:00:00` matches and is rejected by the parser.
+fn timestamp_pattern() -> String {
+    const DATE: &str = r"(19[7-9][0-9]|[2-9][0-9]{3})-(0[1-9]|1[0-2])-(0[1-9]|[12][0-9]|3[01])";
+    const TIME: &str = r"([01][0-9]|2[0-3]):[0-5][0-9]:([0-5][0-9]|60)";
+    // after a dot or a comma, only the first nine digits are significant
+    const FRACTION: &str = r"([.,][0-9]*)?";
+    // an offset or a single abbreviation after a space
+    const OFFSET: &str = r"(Z|\+00:00| UTC| GMT| Z)?";
+    format!("^{}[T ]{}{}{}$", DATE, TIME, FRACTION, OFFSET)
+}
+
+impl JsonSchema for Duration {
+    fn schema_name() -> Cow<'static, str> {
+        "HumanDuration".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "humantime::Duration".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "description": "Duration in free form, like `1h 30min` or `250ms`",
+            "pattern": duration_pattern(),
+            "examples": ["30s", "1h 30min", "250ms", "7days"],
+        })
+    }
+}
+
+impl JsonSchema for Timestamp {
+    fn schema_name() -> Cow<'static, str> {
+        "HumanTimestamp".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "humantime::Timestamp".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "string",
+            "description": "UTC timestamp in RFC 3339 format, like `2018-02-14 00:28:07 UTC`",
+            "pattern": timestamp_pattern(),
+            "examples": ["2018-02-14T00:28:07Z", "2018-02-14 00:28:07.250"],
+        })
+    }
+}
+
+impl JsonSchema for Timeout {
+    fn schema_name() -> Cow<'static, str> {
+        "HumanTimeout".into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        "humantime::Timeout".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let pattern = duration_pattern();
+        json_schema!({
+            "type": ["string", "null"],
+            "description": "Timeout like `30s`, `none`, `off` or null disable the timeout",
+            "pattern": format!("^(none|off)$|{}", pattern),
+            "examples": ["30s", "5min", "none"],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use schemars::{schema_for, JsonSchema};
+
+    use crate::grammar::regex_is_match;
+    use crate::wrapper::{Duration, Timeout, Timestamp};
+
+    fn examples<T: JsonSchema>() -> Vec<String> {
+        let schema = schema_for!(T);
+        schema
+            .get("examples")
+            .and_then(|e| e.as_array())
+            .expect("examples are present")
+            .iter()
+            .map(|e| e.as_str().unwrap().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn examples_parse() {
+        for e in examples::<Duration>() {
+            e.parse::<Duration>().unwrap();
+        }
+        for e in examples::<Timestamp>() {
+            e.parse::<Timestamp>().unwrap();
+        }
+        for e in examples::<Timeout>() {
+            e.parse::<Timeout>().unwrap();
+        }
+    }
+
+    fn pattern<T: JsonSchema>() -> String {
+        let schema = schema_for!(T);
+        schema.get("pattern").unwrap().as_str().unwrap().to_owned()
+    }
+
+    #[test]
+    fn pattern_matches_parser() {
+        let inputs = &[
+            "none",
+            " none",
+            "off ",
+            "None",
+            "0",
+            " 0",
+            "0 ",
+            "00",
+            "0s",
+            "30s",
+            " 30s ",
+            "1h 30min",
+            "1h30m",
+            "1.5h",
+            "1. 5h",
+            ".5h",
+            "5",
+            "5 s",
+            "7days",
+            "3 fortnights",
+            "1 2s",
+            "1h 30 m",
+            "",
+            " ",
+        ];
+        for input in inputs {
+            let matched = regex_is_match(&pattern::<Timeout>(), input);
+            assert_eq!(matched, input.parse::<Timeout>().is_ok(), "{:?}", input);
+            let matched = regex_is_match(&pattern::<Duration>(), input);
+            assert_eq!(matched, input.parse::<Duration>().is_ok(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn timestamp_pattern_matches_parser() {
+        let timestamp = pattern::<Timestamp>();
+        let accepted = &[
+            "2018-02-14T00:28:07Z",
+            "2018-02-14T00:28:07",
+            "2018-02-14 00:28:07",
+            "2018-02-14 00:28:07Z",
+            "2018-02-14T00:28:07+00:00",
+            "2018-02-14 00:28:07.250",
+            "2018-02-14T00:28:07.250Z",
+            "2018-02-14T00:28:07.250+00:00",
+            "2018-02-14 00:28:07.1234567891234",
+            "2018-02-14 00:28:07.",
+            "2018-02-14 00:28:07,250",
+            "2018-02-14T00:28:07,250Z",
+            "2018-02-14 00:28:07 UTC",
+            "2018-02-14T00:28:07.250 GMT",
+            "2018-02-14 00:28:07 Z",
+            "2018-02-14 00:28:60",
+            "1970-01-01T00:00:00Z",
+            "9999-12-31T23:59:59Z",
+        ];
+        let rejected = &[
+            "",
+            "2018-02-14",
+            " 2018-02-14 00:28:07",
+            "2018-02-14 00:28:07 ",
+            "2018-02-14t00:28:07",
+            "2018-02-14  00:28:07",
+            "2018-02-14 00:28:07z",
+            "2018-02-14 00:28:07+01:00",
+            "2018-02-14 00:28:07.1+05:30",
+            "2018-02-14 00:28:07Zjunk",
+            "2018-02-14 00:28:07Z+00:00",
+            "2018-02-14 00:28:07.12a",
+            "2018-02-14 00:28:07;250",
+            "2018-02-14 00:28:07,2,5",
+            "2018-02-14 00:28:07 CET",
+            "2018-02-14 00:28:07 utc",
+            "2018-02-14 00:28:07UTC",
+            "2018-02-14 00:28:07  UTC",
+            "2018-02-14 00:28:07 UTC UTC",
+            "2018-02-14 00:28:07 GMT Z",
+            "2018-02-14 00:28:07Z GMT",
+            "2018-02-14 00:28:7",
+            "1969-12-31 23:59:59",
+            "2018-13-14 00:28:07",
+            "2018-02-00 00:28:07",
+            "2018-02-14 24:28:07",
+            "2018-02-14 00:60:07",
+            "2018-02-14 00:28:61",
+        ];
+        for input in accepted {
+            assert!(regex_is_match(&timestamp, input), "{:?}", input);
+            input.parse::<Timestamp>().unwrap();
+        }
+        for input in rejected {
+            assert!(!regex_is_match(&timestamp, input), "{:?}", input);
+            assert!(input.parse::<Timestamp>().is_err(), "{:?}", input);
+        }
+        // days aren't checked against the month
+        assert!(regex_is_match(&timestamp, "2018-02-31 00:28:07"));
+        assert!("2018-02-31 00:28:07".parse::<Timestamp>().is_err());
+    }
+
+    #[test]
+    fn schema() {
+        let schema = schema_for!(Duration);
+        assert_eq!(schema.get("title").unwrap(), "HumanDuration");
+        assert_eq!(schema.get("type").unwrap(), "string");
+        assert!(schema
+            .get("pattern")
+            .and_then(|p| p.as_str())
+            .unwrap()
+            .contains("|months|"));
+    }
+}
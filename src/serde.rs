@@ -0,0 +1,373 @@
+//! Helpers for using human-friendly durations in serde
+//!
+//! The modules are meant for `#[serde(with = "...")]` on
+//! [`std::time::Duration`] fields. They serialize a duration as a string,
+//! like `1h 30m`, and deserialize either such a string or a bare number in
+//! the implied unit, so existing configs with numeric fields keep working
+//! while new ones can use human-friendly values.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Config {
+//!     #[serde(with = "humantime::serde::duration")]
+//!     timeout: Duration,
+//!     #[serde(with = "humantime::serde::duration_millis")]
+//!     poll_interval: Duration,
+//! }
+//!
+//! let config: Config =
+//!     serde_json::from_str(r#"{"timeout": 30, "poll_interval": "1s"}"#).unwrap();
+//! assert_eq!(config.timeout, Duration::from_secs(30));
+//! assert_eq!(config.poll_interval, Duration::from_secs(1));
+//!
+//! let config: Config =
+//!     serde_json::from_str(r#"{"timeout": "2min", "poll_interval": 250}"#).unwrap();
+//! assert_eq!(config.timeout, Duration::from_secs(120));
+//! assert_eq!(config.poll_interval, Duration::from_millis(250));
+//! ```
+//!
+//! Use [`deserialize_with_unit`] for other implied units.
+//!
+//! All modules also accept the `{"secs": 5400, "nanos": 0}` layout of the
+//! `Duration` implementation of serde, and [`duration_split`] writes
+//! durations in that layout, for data read by other programs.
+//!
+//! # Configuration libraries
+//!
+//! The wrapper types [`Duration`](crate::Duration),
+//! [`Timestamp`](crate::Timestamp) and [`Timeout`](crate::Timeout)
+//! implement `Serialize` and `Deserialize` when the `serde` feature is
+//! enabled, so they can be used directly as fields of configuration
+//! structures loaded by [config] or [figment] from TOML, YAML, JSON or
+//! environment variables:
+//!
+//! ```
+//! #[derive(serde::Deserialize)]
+//! struct Settings {
+//!     // `timeout = "90s"` in TOML, or `timeout = 90` for seconds
+//!     timeout: humantime::Duration,
+//!     // `retry = "none"` disables retries
+//!     retry: humantime::Timeout,
+//! }
+//!
+//! let settings: Settings =
+//!     serde_json::from_str(r#"{"timeout": 90, "retry": "none"}"#).unwrap();
+//! assert_eq!(*settings.timeout, std::time::Duration::from_secs(90));
+//! assert!(!settings.retry.is_enabled());
+//!
+//! let err = serde_json::from_str::<Settings>(r#"{"timeout": "90", "retry": "5s"}"#)
+//!     .err()
+//!     .unwrap();
+//! assert_eq!(
+//!     err.to_string(),
+//!     "time unit needed, for example 90sec or 90ms at line 1 column 16",
+//! );
+//! ```
+//!
+//! Parse errors are reported through `serde::de::Error` with the message of
+//! the parser, and the configuration library adds its own context to it,
+//! like the line above.
+//!
+//! [config]: https://docs.rs/config
+//! [figment]: https://docs.rs/figment
+
+use std::fmt;
+use std::time::Duration;
+
+use ::serde::de::{self, Deserializer, MapAccess, SeqAccess, Unexpected, Visitor};
+use ::serde::ser::{SerializeStruct, Serializer};
+
+use crate::duration::{format_duration_with, parse_duration, Style, Unit};
+use crate::float::duration_from_secs_f64;
+
+pub(crate) struct DurationVisitor(pub(crate) Unit);
+
+impl DurationVisitor {
+    fn to_duration<E: de::Error>(&self, value: u64) -> Result<Duration, E> {
+        let nanos = u128::from(value) * u128::from(self.0.nanos_per_unit());
+        u64::try_from(nanos / 1_000_000_000)
+            .map(|secs| Duration::new(secs, (nanos % 1_000_000_000) as u32))
+            .map_err(|_| E::invalid_value(Unexpected::Unsigned(value), self))
+    }
+
+    /// Builds a duration from the fields of the serde layout of std
+    fn from_fields<E: de::Error>(secs: Option<u64>, nanos: Option<u32>) -> Result<Duration, E> {
+        let secs = secs.ok_or_else(|| E::missing_field("secs"))?;
+        let nanos = nanos.ok_or_else(|| E::missing_field("nanos"))?;
+        secs.checked_add(u64::from(nanos / 1_000_000_000))
+            .map(|secs| Duration::new(secs, nanos % 1_000_000_000))
+            .ok_or_else(|| E::custom("overflow deserializing Duration"))
+    }
+}
+
+impl<'de> Visitor<'de> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit = match self.0 {
+            Unit::Nanosecond => "nanoseconds",
+            Unit::Microsecond => "microseconds",
+            Unit::Millisecond => "milliseconds",
+            Unit::Second => "seconds",
+            Unit::Minute => "minutes",
+            Unit::Hour => "hours",
+            Unit::Day => "days",
+            Unit::Week => "weeks",
+            Unit::Month => "months",
+            Unit::Year => "years",
+        };
+        write!(
+            f,
+            "a duration like `1h 30m`, or a non-negative number of {}",
+            unit
+        )
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Duration, E> {
+        parse_duration(v).map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Duration, E> {
+        self.to_duration(v)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Duration, E> {
+        match u64::try_from(v) {
+            Ok(v) => self.to_duration(v),
+            Err(_) => Err(E::invalid_value(Unexpected::Signed(v), &self)),
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Duration, E> {
+        // the factor is exact for seconds and larger units
+        let secs = v * (self.0.nanos_per_unit() as f64 / 1e9);
+        duration_from_secs_f64(secs).map_err(|_| E::invalid_value(Unexpected::Float(v), &self))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Duration, A::Error> {
+        let (mut secs, mut nanos) = (None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "secs" if secs.is_some() => return Err(de::Error::duplicate_field("secs")),
+                "nanos" if nanos.is_some() => return Err(de::Error::duplicate_field("nanos")),
+                "secs" => secs = Some(map.next_value()?),
+                "nanos" => nanos = Some(map.next_value()?),
+                _ => return Err(de::Error::unknown_field(&key, &["secs", "nanos"])),
+            }
+        }
+        Self::from_fields(secs, nanos)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Duration, A::Error> {
+        let secs = seq.next_element()?;
+        let nanos = seq.next_element()?;
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            return Err(de::Error::invalid_length(3, &"secs and nanos"));
+        }
+        Self::from_fields(secs, nanos)
+    }
+}
+
+/// Deserializes a duration from a string or a number in `unit`
+///
+/// To be used from a function in `#[serde(deserialize_with = "...")]`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use serde::{Deserialize, Deserializer};
+/// use humantime::Unit;
+///
+/// fn minutes<'de, D: Deserializer<'de>>(d: D) -> Result<Duration, D::Error> {
+///     humantime::serde::deserialize_with_unit(d, Unit::Minute)
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     #[serde(deserialize_with = "minutes")]
+///     retention: Duration,
+/// }
+///
+/// let config: Config = serde_json::from_str(r#"{"retention": 90}"#).unwrap();
+/// assert_eq!(config.retention, Duration::from_secs(90 * 60));
+/// ```
+pub fn deserialize_with_unit<'de, D>(deserializer: D, unit: Unit) -> Result<Duration, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_any(DurationVisitor(unit))
+}
+
+/// Serializes a duration as a string, like `1h 30m`
+///
+/// This is always [`Style::Human`], whatever the style of
+/// [`format_duration`](crate::format_duration) is, so the value deserializes
+/// back.
+pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_duration_with(*value, Style::Human))
+}
+
+/// Duration as a string, bare numbers are seconds
+pub mod duration {
+    pub use super::serialize;
+    use super::{deserialize_with_unit, Duration, Unit};
+    use ::serde::Deserializer;
+
+    /// Deserializes a duration from a string or a number of seconds
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        deserialize_with_unit(deserializer, Unit::Second)
+    }
+}
+
+/// Duration as `{"secs": 5400, "nanos": 0}`, as serde writes `Duration`
+///
+/// Strings like `1h 30m` and bare numbers of seconds are accepted on input
+/// too, so configs written by hand can use them while the output stays
+/// compatible with programs deserializing plain `Duration` fields.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Job {
+///     #[serde(with = "humantime::serde::duration_split")]
+///     timeout: Duration,
+/// }
+///
+/// let job: Job = serde_json::from_str(r#"{"timeout": "1m 30s"}"#).unwrap();
+/// assert_eq!(job.timeout, Duration::from_secs(90));
+/// let json = serde_json::to_string(&job).unwrap();
+/// assert_eq!(json, r#"{"timeout":{"secs":90,"nanos":0}}"#);
+/// ```
+pub mod duration_split {
+    use super::{deserialize_with_unit, Duration, SerializeStruct, Unit};
+    use ::serde::{Deserializer, Serializer};
+
+    /// Serializes a duration as its seconds and nanoseconds
+    pub fn serialize<S: Serializer>(value: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("Duration", 2)?;
+        s.serialize_field("secs", &value.as_secs())?;
+        s.serialize_field("nanos", &value.subsec_nanos())?;
+        s.end()
+    }
+
+    /// Deserializes a duration from its fields, a string, or a number of
+    /// seconds
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        deserialize_with_unit(deserializer, Unit::Second)
+    }
+}
+
+/// Duration as a string, bare numbers are milliseconds
+pub mod duration_millis {
+    pub use super::serialize;
+    use super::{deserialize_with_unit, Duration, Unit};
+    use ::serde::Deserializer;
+
+    /// Deserializes a duration from a string or a number of milliseconds
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        deserialize_with_unit(deserializer, Unit::Millisecond)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use ::serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Secs(#[serde(with = "super::duration")] Duration);
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Millis(#[serde(with = "super::duration_millis")] Duration);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Split(#[serde(with = "super::duration_split")] Duration);
+
+    #[test]
+    fn numbers() {
+        let secs = |s| serde_json::from_str::<Secs>(s).map(|v| v.0);
+        assert_eq!(secs("0").unwrap(), Duration::ZERO);
+        assert_eq!(secs("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(secs("1.5").unwrap(), Duration::from_millis(1500));
+        assert_eq!(secs("1.9999999999").unwrap(), Duration::from_secs(2));
+        assert!(secs("-1").is_err());
+        assert!(secs("-0.5").is_err());
+        assert!(secs("-0.0").is_err());
+        assert!(secs("1e300").is_err());
+        assert_eq!(
+            secs("18446744073709551615").unwrap(),
+            Duration::from_secs(u64::MAX)
+        );
+        let millis = |s| serde_json::from_str::<Millis>(s).map(|v| v.0);
+        assert_eq!(millis("250").unwrap(), Duration::from_millis(250));
+        assert_eq!(millis("0.0015").unwrap(), Duration::from_nanos(1500));
+        assert_eq!(
+            millis("18446744073709551615").unwrap(),
+            Duration::from_millis(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn strings() {
+        let secs = |s| serde_json::from_str::<Secs>(s).map(|v| v.0);
+        assert_eq!(secs(r#""1h 30m""#).unwrap(), Duration::from_secs(5400));
+        assert_eq!(
+            secs(r#""90""#).unwrap_err().to_string(),
+            "time unit needed, for example 90sec or 90ms at line 1 column 4"
+        );
+        assert_eq!(
+            secs("true").unwrap_err().to_string(),
+            "invalid type: boolean `true`, expected a duration like `1h 30m`, \
+            or a non-negative number of seconds at line 1 column 4"
+        );
+        assert_eq!(
+            serde_json::to_string(&Secs(Duration::from_secs(5400))).unwrap(),
+            r#""1h 30m""#
+        );
+    }
+
+    #[test]
+    fn split() {
+        let d = Duration::new(5400, 250);
+        let json = serde_json::to_string(&Split(d)).unwrap();
+        assert_eq!(json, r#"{"secs":5400,"nanos":250}"#);
+        // the layout of std
+        assert_eq!(json, serde_json::to_string(&d).unwrap());
+        assert_eq!(serde_json::from_str::<Duration>(&json).unwrap(), d);
+        let split = |s| serde_json::from_str::<Split>(s).map(|v| v.0);
+        assert_eq!(split(&json).unwrap(), d);
+        assert_eq!(split("[5400, 250]").unwrap(), d);
+        assert_eq!(split(r#""1h 30m""#).unwrap(), Duration::from_secs(5400));
+        assert_eq!(split("90").unwrap(), Duration::from_secs(90));
+        assert_eq!(
+            split(r#"{"secs": 1, "nanos": 1500000000}"#).unwrap(),
+            Duration::new(2, 500_000_000)
+        );
+        let secs = |s| serde_json::from_str::<Secs>(s).map(|v| v.0);
+        assert_eq!(secs(&json).unwrap(), d);
+        assert_eq!(
+            split(r#"{"secs": 1}"#).unwrap_err().to_string(),
+            "missing field `nanos` at line 1 column 11"
+        );
+        assert_eq!(
+            split(r#"{"secs": 1, "nanos": 0, "secs": 2}"#)
+                .unwrap_err()
+                .to_string(),
+            "duplicate field `secs` at line 1 column 30"
+        );
+        assert!(split(r#"{"seconds": 1}"#).is_err());
+        assert!(split("[1, 2, 3]").is_err());
+        assert!(split(r#"{"secs": 18446744073709551615, "nanos": 4000000000}"#).is_err());
+    }
+}
@@ -0,0 +1,385 @@
+use std::fmt;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::duration::{format_duration_with, Style, Unit};
+use crate::options::{style_by_name, style_name, FormatOptions, ParseOptions, PROFILES};
+use crate::serde::DurationVisitor;
+#[cfg(feature = "toml-datetime")]
+use crate::toml_datetime::{parse_toml_datetime, LocalCompletion, TOML_DATETIME_KEY};
+use crate::wrapper::{Duration, Timeout, Timestamp};
+
+// Durations are serialized in `Style::Human` whatever the style of
+// `format_duration` is, as other styles may not deserialize back.
+impl Serialize for Duration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_duration_with(**self, Style::Human))
+    }
+}
+
+impl<'de> Deserialize<'de> for Duration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        deserializer
+            .deserialize_any(DurationVisitor(Unit::Second))
+            .map(Duration::from)
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+struct TimestampVisitor;
+
+impl<'de> Visitor<'de> for TimestampVisitor {
+    type Value = Timestamp;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a timestamp like `2018-02-14T00:28:07Z`")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Timestamp, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    /// Reads TOML datetimes, which the `toml` crate passes as a map
+    #[cfg(feature = "toml-datetime")]
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Timestamp, A::Error> {
+        match map.next_key::<String>()? {
+            Some(key) if key == TOML_DATETIME_KEY => {}
+            _ => return Err(de::Error::invalid_type(de::Unexpected::Map, &self)),
+        }
+        let value: String = map.next_value()?;
+        parse_toml_datetime(&value, LocalCompletion::Utc)
+            .map(Timestamp::from)
+            .map_err(de::Error::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Timestamp, D::Error> {
+        deserializer.deserialize_str(TimestampVisitor)
+    }
+}
+
+impl Serialize for Timeout {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.get() {
+            Some(d) => serializer.collect_str(&format_duration_with(d, Style::Human)),
+            None => serializer.serialize_str("none"),
+        }
+    }
+}
+
+struct TimeoutVisitor;
+
+impl<'de> Visitor<'de> for TimeoutVisitor {
+    type Value = Timeout;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a duration like `30s`, or `none` to disable the timeout")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Timeout, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Timeout, E> {
+        Ok(Timeout::NONE)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Timeout, E> {
+        Ok(Timeout::NONE)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Timeout, D::Error> {
+        d.deserialize_any(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Timeout {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Timeout, D::Error> {
+        deserializer.deserialize_any(TimeoutVisitor)
+    }
+}
+
+impl Serialize for ParseOptions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("ParseOptions", 4)?;
+        s.serialize_field("calendar_units", &self.calendar_units())?;
+        s.serialize_field("concatenation", &self.concatenation())?;
+        s.serialize_field("lenient", &self.lenient())?;
+        s.serialize_field("m_as_minutes", &self.m_as_minutes())?;
+        s.end()
+    }
+}
+
+struct ParseOptionsVisitor;
+
+impl<'de> Visitor<'de> for ParseOptionsVisitor {
+    type Value = ParseOptions;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("parse options like `{\"calendar_units\": false}`")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<ParseOptions, A::Error> {
+        const FIELDS: &[&str] = &["calendar_units", "concatenation", "lenient", "m_as_minutes"];
+        let mut options = ParseOptions::new();
+        let mut seen = [false; 4];
+        while let Some(key) = map.next_key::<String>()? {
+            let idx = match FIELDS.iter().position(|&f| f == key) {
+                Some(idx) => idx,
+                None => return Err(de::Error::unknown_field(&key, FIELDS)),
+            };
+            if seen[idx] {
+                return Err(de::Error::duplicate_field(FIELDS[idx]));
+            }
+            seen[idx] = true;
+            let allow = map.next_value()?;
+            options = match idx {
+                0 => options.with_calendar_units(allow),
+                1 => options.with_concatenation(allow),
+                2 => options.with_lenient(allow),
+                _ => options.with_m_as_minutes(allow),
+            };
+        }
+        Ok(options)
+    }
+}
+
+impl<'de> Deserialize<'de> for ParseOptions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ParseOptions, D::Error> {
+        deserializer.deserialize_map(ParseOptionsVisitor)
+    }
+}
+
+impl Serialize for FormatOptions {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("FormatOptions", 1)?;
+        s.serialize_field("style", style_name(self.style()))?;
+        s.end()
+    }
+}
+
+struct FormatOptionsVisitor;
+
+impl<'de> Visitor<'de> for FormatOptionsVisitor {
+    type Value = FormatOptions;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a profile like `verbose`, or options like `{\"style\": \"verbose\"}`")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<FormatOptions, E> {
+        FormatOptions::profile(v).ok_or_else(|| {
+            let names: Vec<&str> = PROFILES.iter().map(|&(name, _)| name).collect();
+            E::custom(format_args!(
+                "unknown profile {:?}, expected one of {}",
+                v,
+                names.join(", ")
+            ))
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<FormatOptions, A::Error> {
+        const FIELDS: &[&str] = &["style"];
+        let mut style = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match &key[..] {
+                "style" if style.is_some() => return Err(de::Error::duplicate_field(FIELDS[0])),
+                "style" => {
+                    let name = map.next_value::<String>()?;
+                    style = Some(style_by_name(&name).ok_or_else(|| {
+                        de::Error::custom(format_args!("unknown style {:?}", name))
+                    })?);
+                }
+                _ => return Err(de::Error::unknown_field(&key, FIELDS)),
+            }
+        }
+        Ok(style.map_or_else(FormatOptions::new, |s| FormatOptions::new().with_style(s)))
+    }
+}
+
+impl<'de> Deserialize<'de> for FormatOptions {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<FormatOptions, D::Error> {
+        deserializer.deserialize_any(FormatOptionsVisitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use crate::duration::Style;
+    use crate::options::{with_format_style, FormatOptions, ParseOptions, STYLES};
+    use crate::wrapper::{Timeout, Timestamp};
+
+    #[test]
+    fn duration() {
+        let d: crate::wrapper::Duration = serde_json::from_str(r#""1m 30s""#).unwrap();
+        assert_eq!(*d, Duration::from_secs(90));
+        let d: crate::wrapper::Duration = serde_json::from_str("90").unwrap();
+        assert_eq!(*d, Duration::from_secs(90));
+        assert_eq!(serde_json::to_string(&d).unwrap(), r#""1m 30s""#);
+    }
+
+    #[test]
+    fn timestamp() {
+        let t: Timestamp = serde_json::from_str(r#""2018-02-14 00:28:07""#).unwrap();
+        assert_eq!(*t, UNIX_EPOCH + Duration::from_secs(1_518_568_087));
+        assert_eq!(
+            serde_json::to_string(&t).unwrap(),
+            r#""2018-02-14T00:28:07Z""#
+        );
+        assert_eq!(
+            serde_json::from_str::<Timestamp>("0")
+                .unwrap_err()
+                .to_string(),
+            "invalid type: integer `0`, expected a timestamp like \
+            `2018-02-14T00:28:07Z` at line 1 column 1"
+        );
+    }
+
+    #[cfg(feature = "toml-datetime")]
+    #[test]
+    fn toml_timestamp() {
+        use serde::de::value::{Error, MapDeserializer};
+        use serde::Deserialize;
+
+        let toml = |key: &str, value: &str| {
+            let entries = vec![(key.to_owned(), value.to_owned())];
+            Timestamp::deserialize(MapDeserializer::<_, Error>::new(entries.into_iter()))
+        };
+        let time = UNIX_EPOCH + Duration::from_secs(296_638_320);
+        let key = "$__toml_private_datetime";
+        assert_eq!(toml(key, "1979-05-27T00:32:00-07:00"), Ok(time.into()));
+        assert_eq!(toml(key, "1979-05-27T07:32:00"), Ok(time.into()));
+        assert!(toml(key, "07:32:00").is_err());
+        assert!(toml("when", "1979-05-27T07:32:00Z").is_err());
+    }
+
+    #[test]
+    fn deserialize_timeout() {
+        let t: Timeout = serde_json::from_str(r#""5s""#).unwrap();
+        assert_eq!(t, Duration::from_secs(5).into());
+        let t: Timeout = serde_json::from_str(r#""off""#).unwrap();
+        assert_eq!(t, Timeout::NONE);
+        let t: Timeout = serde_json::from_str("null").unwrap();
+        assert_eq!(t, Timeout::NONE);
+        let t: Option<Timeout> = serde_json::from_str(r#""1h""#).unwrap();
+        assert_eq!(t, Some(Duration::from_secs(3600).into()));
+        assert_eq!(
+            serde_json::from_str::<Timeout>(r#""5""#)
+                .unwrap_err()
+                .to_string(),
+            "time unit needed, for example 5sec or 5ms at line 1 column 3"
+        );
+        assert!(serde_json::from_str::<Timeout>("5").is_err());
+    }
+
+    #[test]
+    fn serialize_timeout() {
+        let t = Timeout::from(Duration::from_secs(90));
+        assert_eq!(serde_json::to_string(&t).unwrap(), r#""1m 30s""#);
+        assert_eq!(serde_json::to_string(&Timeout::NONE).unwrap(), r#""none""#);
+    }
+
+    #[test]
+    fn round_trip_in_every_style() {
+        let d = crate::wrapper::Duration::from(Duration::new(5400, 250_000_000));
+        let max = crate::wrapper::Duration::from(Duration::MAX);
+        let t = Timeout::from(Duration::from_micros(1500));
+        for &(style, name) in STYLES.iter() {
+            with_format_style(style, || {
+                for d in &[d, max] {
+                    let json = serde_json::to_string(d).unwrap();
+                    let back: crate::wrapper::Duration = serde_json::from_str(&json).unwrap();
+                    assert_eq!(back, *d, "{}", name);
+                }
+                let json = serde_json::to_string(&t).unwrap();
+                assert_eq!(
+                    serde_json::from_str::<Timeout>(&json).unwrap(),
+                    t,
+                    "{}",
+                    name
+                );
+            });
+        }
+    }
+
+    #[test]
+    fn scoped_style() {
+        let d = crate::wrapper::Duration::from(Duration::from_secs(5400));
+        let (text, json) = with_format_style(Style::Iso8601, || {
+            (d.to_string(), serde_json::to_string(&d).unwrap())
+        });
+        assert_eq!(text, "PT1H30M");
+        assert_eq!(json, r#""1h 30m""#);
+    }
+
+    #[test]
+    fn parse_options() {
+        let options = ParseOptions::new().with_calendar_units(false);
+        let json = serde_json::to_string(&options).unwrap();
+        assert_eq!(
+            json,
+            r#"{"calendar_units":false,"concatenation":true,"lenient":false,"m_as_minutes":false}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ParseOptions>(&json).unwrap(),
+            options
+        );
+        let options: ParseOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(options, ParseOptions::new());
+        assert_eq!(
+            serde_json::from_str::<ParseOptions>(r#"{"calendar": true}"#)
+                .unwrap_err()
+                .to_string(),
+            "unknown field `calendar`, expected one of `calendar_units`, `concatenation`, \
+            `lenient`, `m_as_minutes` at line 1 column 11"
+        );
+        let options: ParseOptions = serde_json::from_str(r#"{"concatenation": false}"#).unwrap();
+        assert!(!options.concatenation());
+        assert!(options.calendar_units());
+    }
+
+    #[test]
+    fn format_options() {
+        let options: FormatOptions = serde_json::from_str(r#""words""#).unwrap();
+        assert_eq!(options.style(), Style::Verbose);
+        let json = serde_json::to_string(&options).unwrap();
+        assert_eq!(json, r#"{"style":"verbose"}"#);
+        assert_eq!(
+            serde_json::from_str::<FormatOptions>(&json).unwrap(),
+            options
+        );
+        for &(style, name) in &STYLES {
+            let options = FormatOptions::new().with_style(style);
+            let json = serde_json::to_string(&options).unwrap();
+            assert_eq!(json, format!(r#"{{"style":"{}"}}"#, name));
+            assert_eq!(
+                serde_json::from_str::<FormatOptions>(&json).unwrap(),
+                options
+            );
+        }
+        let options: FormatOptions = serde_json::from_str("{}").unwrap();
+        assert_eq!(options, FormatOptions::new());
+        assert_eq!(
+            serde_json::from_str::<FormatOptions>(r#""fancy""#)
+                .unwrap_err()
+                .to_string(),
+            "unknown profile \"fancy\", expected one of compact, verbose, words, abbreviated, \
+            go, iso8601, machine at line 1 column 7"
+        );
+        assert_eq!(
+            serde_json::from_str::<FormatOptions>(r#"{"style":"fancy"}"#)
+                .unwrap_err()
+                .to_string(),
+            "unknown style \"fancy\" at line 1 column 17"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::time::Duration;
+
+use crate::duration::{format_duration_with, FormattedDuration, Style};
+use crate::options::{current_style, FormatOptions};
+
+/// A wrapper type that allows you to [`Display`](fmt::Display) a signed
+/// duration, like `+2m 10s` or `-340ms`
+///
+/// Returned by [`format_signed`] and [`FormatOptions::format_signed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FormattedSigned(Ordering, FormattedDuration);
+
+/// A wrapper type that allows you to [`Display`](fmt::Display) a change
+/// between two durations, like `-12.5% (-340ms)`
+///
+/// Returned by [`format_change`] and [`FormatOptions::format_change`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FormattedChange {
+    percent: f64,
+    precision: usize,
+    delta: FormattedSigned,
+}
+
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+fn signed(delta: i128, style: Style) -> FormattedSigned {
+    let abs = delta.unsigned_abs();
+    let secs = abs / NANOS_PER_SEC;
+    let duration = if secs > u128::from(u64::MAX) {
+        Duration::MAX
+    } else {
+        Duration::new(secs as u64, (abs % NANOS_PER_SEC) as u32)
+    };
+    FormattedSigned(delta.cmp(&0), format_duration_with(duration, style))
+}
+
+/// Formats a difference of durations in nanoseconds, with a sign
+///
+/// Positive values are prefixed with `+`, negative ones with `-`, and zero
+/// has no sign. Values larger than [`Duration::MAX`] are written as
+/// `Duration::MAX`. The style is the one [`format_duration`](crate::format_duration)
+/// uses.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::format_signed;
+///
+/// let before = Duration::from_millis(1_340);
+/// let after = Duration::from_millis(1_000);
+/// let delta = after.as_nanos() as i128 - before.as_nanos() as i128;
+/// assert_eq!(format_signed(delta).to_string(), "-340ms");
+/// assert_eq!(format_signed(130_000_000_000).to_string(), "+2m 10s");
+/// assert_eq!(format_signed(0).to_string(), "0s");
+/// ```
+pub fn format_signed(delta: i128) -> FormattedSigned {
+    signed(delta, current_style())
+}
+
+fn change(before: Duration, after: Duration, style: Style) -> FormattedChange {
+    let delta = after.as_nanos() as i128 - before.as_nanos() as i128;
+    let percent = if before == Duration::ZERO {
+        match delta.cmp(&0) {
+            Ordering::Less => f64::NEG_INFINITY,
+            Ordering::Equal => 0.0,
+            Ordering::Greater => f64::INFINITY,
+        }
+    } else {
+        delta as f64 / before.as_nanos() as f64 * 100.0
+    };
+    FormattedChange {
+        percent,
+        precision: 1,
+        delta: signed(delta, style),
+    }
+}
+
+/// Formats the change from `before` to `after`, like `-12.5% (-340ms)`
+///
+/// The percentage is relative to `before` and has one decimal place by
+/// default, see [`FormattedChange::with_precision`]. A change from zero is
+/// written as `+inf%`. The difference is written as [`format_signed`] does.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::format_change;
+///
+/// let before = Duration::from_millis(2720);
+/// let after = Duration::from_millis(2380);
+/// assert_eq!(format_change(before, after).to_string(), "-12.5% (-340ms)");
+/// let change = format_change(after, before).with_precision(2);
+/// assert_eq!(change.to_string(), "+14.29% (+340ms)");
+/// ```
+pub fn format_change(before: Duration, after: Duration) -> FormattedChange {
+    change(before, after, current_style())
+}
+
+impl FormatOptions {
+    /// Formats the change from `before` to `after` with these options
+    ///
+    /// See [`format_change`] for the details.
+    pub fn format_change(&self, before: Duration, after: Duration) -> FormattedChange {
+        change(before, after, self.style())
+    }
+
+    /// Formats a difference of durations in nanoseconds with these options
+    ///
+    /// See [`format_signed`] for the details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use humantime::{FormatOptions, Style};
+    ///
+    /// let options = FormatOptions::new().with_style(Style::Verbose);
+    /// assert_eq!(options.format_signed(-2_000_000).to_string(), "-2 milliseconds");
+    /// ```
+    pub fn format_signed(&self, delta: i128) -> FormattedSigned {
+        signed(delta, self.style())
+    }
+}
+
+impl FormattedSigned {
+    /// Returns the sign of the value, `Less` for negative values
+    pub fn sign(&self) -> Ordering {
+        self.0
+    }
+
+    /// Returns a reference to the [`Duration`] that is being formatted,
+    /// which is the absolute value
+    pub fn get_ref(&self) -> &Duration {
+        self.1.get_ref()
+    }
+}
+
+impl FormattedChange {
+    /// Sets the number of decimal places of the percentage
+    pub fn with_precision(self, precision: usize) -> FormattedChange {
+        FormattedChange { precision, ..self }
+    }
+
+    /// Returns the change relative to `before`, in percents
+    pub fn percent(&self) -> f64 {
+        self.percent
+    }
+
+    /// Returns the difference between the durations
+    pub fn delta(&self) -> &FormattedSigned {
+        &self.delta
+    }
+}
+
+impl fmt::Display for FormattedChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.percent.is_infinite() {
+            let sign = if self.percent > 0.0 { "+" } else { "-" };
+            write!(f, "{}inf%", sign)?;
+        } else {
+            let abs = format!("{:.*}", self.precision, self.percent.abs());
+            // no sign if the value rounds to zero
+            if abs.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+                f.write_str(if self.percent > 0.0 { "+" } else { "-" })?;
+            }
+            write!(f, "{}%", abs)?;
+        }
+        write!(f, " (")?;
+        fmt::Display::fmt(&self.delta, f)?;
+        write!(f, ")")
+    }
+}
+
+impl fmt::Display for FormattedSigned {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Ordering::Less => f.write_str("-")?,
+            Ordering::Greater => f.write_str("+")?,
+            Ordering::Equal => {}
+        }
+        fmt::Display::fmt(&self.1, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+    use std::time::Duration;
+
+    use super::{format_change, format_signed};
+    use crate::duration::Style;
+    use crate::options::{with_format_style, FormatOptions};
+
+    #[test]
+    fn signs() {
+        assert_eq!(format_signed(1).to_string(), "+1ns");
+        assert_eq!(format_signed(-1).to_string(), "-1ns");
+        assert_eq!(format_signed(-90_000_000_000).to_string(), "-1m 30s");
+        assert_eq!(format_signed(0).sign(), Ordering::Equal);
+        assert_eq!(format_signed(-5).sign(), Ordering::Less);
+        assert_eq!(*format_signed(-5).get_ref(), Duration::from_nanos(5));
+    }
+
+    #[test]
+    fn saturated() {
+        assert_eq!(*format_signed(i128::MIN).get_ref(), Duration::MAX);
+        assert_eq!(*format_signed(i128::MAX).get_ref(), Duration::MAX);
+        let max = Duration::MAX.as_nanos() as i128;
+        assert_eq!(*format_signed(-max).get_ref(), Duration::MAX);
+    }
+
+    #[test]
+    fn styles() {
+        let delta = 5_400_250_000_000;
+        let options = FormatOptions::new().with_style(Style::Prometheus);
+        assert_eq!(options.format_signed(delta).to_string(), "+1h30m250ms");
+        assert_eq!(
+            format!("{:#}", format_signed(-delta)),
+            "-1 hour 30 minutes 250 milliseconds"
+        );
+        let text = with_format_style(Style::Iso8601, || format_signed(-delta).to_string());
+        assert_eq!(text, "-PT1H30M0.25S");
+    }
+
+    #[test]
+    fn changes() {
+        let ms = Duration::from_millis;
+        assert_eq!(
+            format_change(ms(100), ms(150)).to_string(),
+            "+50.0% (+50ms)"
+        );
+        assert_eq!(format_change(ms(100), ms(100)).to_string(), "0.0% (0s)");
+        assert_eq!(
+            format_change(ms(100_000), ms(100_001)).to_string(),
+            "0.0% (+1ms)"
+        );
+        assert_eq!(
+            format_change(ms(100_000), ms(99_999))
+                .with_precision(3)
+                .to_string(),
+            "-0.001% (-1ms)"
+        );
+        assert_eq!(
+            format_change(ms(3), ms(1)).with_precision(0).to_string(),
+            "-67% (-2ms)"
+        );
+        assert_eq!(format_change(ms(0), ms(5)).to_string(), "+inf% (+5ms)");
+        assert_eq!(format_change(ms(0), ms(0)).to_string(), "0.0% (0s)");
+        assert_eq!(format_change(ms(4), ms(1)).percent(), -75.0);
+        assert_eq!(format_change(ms(4), ms(1)).delta().sign(), Ordering::Less);
+    }
+
+    #[test]
+    fn change_styles() {
+        let options = FormatOptions::new().with_style(Style::Verbose);
+        let change = options.format_change(Duration::from_secs(60), Duration::from_secs(90));
+        assert_eq!(change.to_string(), "+50.0% (+30 seconds)");
+    }
+}
@@ -0,0 +1,67 @@
+use std::cmp::Ordering;
+
+use crate::duration::{parse_duration_nanos, Error};
+
+/// Parses a duration into the total number of nanoseconds, for sorting
+///
+/// This is [`parse_duration_nanos`](crate::parse_duration_nanos), so the
+/// values larger than `std::time::Duration` are sorted too.
+///
+/// # Example
+///
+/// ```
+/// let mut cells = vec!["1h", "90s", "2min"];
+/// cells.sort_by_key(|s| humantime::sort_key(s).unwrap());
+/// assert_eq!(cells, ["90s", "2min", "1h"]);
+/// ```
+pub fn sort_key(s: &str) -> Result<u128, Error> {
+    parse_duration_nanos(s)
+}
+
+/// Compares two cells containing durations
+///
+/// Durations are compared by value and come before the cells which can't
+/// be parsed, like `n/a` or empty ones, which are compared as strings.
+///
+/// # Example
+///
+/// ```
+/// let mut cells = vec!["n/a", "1h", "", "90s", "2 min"];
+/// cells.sort_by(|a, b| humantime::compare_durations(a, b));
+/// assert_eq!(cells, ["90s", "2 min", "1h", "", "n/a"]);
+/// ```
+pub fn compare_durations(a: &str, b: &str) -> Ordering {
+    match (sort_key(a), sort_key(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.cmp(b),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cmp::Ordering;
+
+    use super::{compare_durations, sort_key};
+    use crate::duration::Error;
+
+    #[test]
+    fn keys() {
+        assert_eq!(sort_key("1ns"), Ok(1));
+        assert_eq!(sort_key("1.5s"), Ok(1_500_000_000));
+        assert_eq!(
+            sort_key("18446744073709551615s 999999999ns"),
+            Ok(u64::MAX as u128 * 1_000_000_000 + 999_999_999)
+        );
+        assert_eq!(sort_key(""), Err(Error::Empty));
+    }
+
+    #[test]
+    fn compare() {
+        assert_eq!(compare_durations("60s", "1min"), Ordering::Equal);
+        assert_eq!(compare_durations("1h", "59min"), Ordering::Greater);
+        assert_eq!(compare_durations("1x", "1h"), Ordering::Greater);
+        assert_eq!(compare_durations("a", "b"), Ordering::Less);
+    }
+}
@@ -0,0 +1,236 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::civil::civil_from_days;
+use crate::date::{parse_rfc3339_weak, Error};
+
+/// A wrapper type that allows you to Display a SystemTime as a sortable
+/// timestamp, like `2024-06-01_13-45-07`
+///
+/// Returned by [`format_sortable`]. The timestamps contain no colons or
+/// spaces, so they can be used in file names, and sort lexicographically
+/// in the order of time, as long as the separator and precision are the
+/// same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortableTimestamp {
+    time: SystemTime,
+    separator: char,
+    digits: u8,
+}
+
+/// Formats a timestamp like `2024-06-01_13-45-07`, for naming files
+///
+/// The separator between the date and the time is `_` and there are no
+/// fractional seconds by default, see [`SortableTimestamp::with_separator`]
+/// and [`SortableTimestamp::with_precision`]. [`parse_sortable`] parses the
+/// timestamp back.
+///
+/// The value is always UTC and ignores system timezone.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::format_sortable;
+///
+/// let time = UNIX_EPOCH + Duration::new(1_717_249_507, 250_000_000);
+/// assert_eq!(format_sortable(time).to_string(), "2024-06-01_13-45-07");
+/// let name = format!("backup-{}.tar", format_sortable(time).with_precision(3));
+/// assert_eq!(name, "backup-2024-06-01_13-45-07.250.tar");
+/// ```
+pub fn format_sortable(time: SystemTime) -> SortableTimestamp {
+    SortableTimestamp {
+        time,
+        separator: '_',
+        digits: 0,
+    }
+}
+
+/// Parses a timestamp written by [`format_sortable`]
+///
+/// Any separator except digits is accepted between the date and the time,
+/// and any number of fractional digits after a `.`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use humantime::parse_sortable;
+///
+/// let time = UNIX_EPOCH + Duration::new(1_717_249_507, 0);
+/// assert_eq!(parse_sortable("2024-06-01_13-45-07"), Ok(time));
+/// assert_eq!(parse_sortable("2024-06-01T13-45-07"), Ok(time));
+/// assert!(parse_sortable("2024-06-01 13:45:07").is_err());
+/// ```
+pub fn parse_sortable(s: &str) -> Result<SystemTime, Error> {
+    let separator = match s.get(10..).and_then(|rest| rest.chars().next()) {
+        Some(c) if !c.is_ascii_digit() => c,
+        _ => return Err(Error::InvalidFormat),
+    };
+    let (date, time) = (&s[..10], &s[10 + separator.len_utf8()..]);
+    let b = time.as_bytes();
+    if b.len() < 8 || b[2] != b'-' || b[5] != b'-' || b.get(8).map_or(false, |&c| c != b'.') {
+        return Err(Error::InvalidFormat);
+    }
+    if !b.iter().skip(9).all(u8::is_ascii_digit) {
+        return Err(Error::InvalidDigit);
+    }
+    parse_rfc3339_weak(&format!(
+        "{}T{}:{}:{}",
+        date,
+        &time[..2],
+        &time[3..5],
+        &time[6..]
+    ))
+}
+
+impl SortableTimestamp {
+    /// Sets the separator between the date and the time, `_` by default
+    ///
+    /// [`parse_sortable`] accepts any separator which is not a digit.
+    pub fn with_separator(self, separator: char) -> SortableTimestamp {
+        SortableTimestamp { separator, ..self }
+    }
+
+    /// Sets the number of fractional digits of seconds, zero by default
+    ///
+    /// The fraction is truncated, so that the timestamp is never later than
+    /// the time. At most nine digits are written.
+    pub fn with_precision(self, digits: u8) -> SortableTimestamp {
+        SortableTimestamp {
+            digits: digits.min(9),
+            ..self
+        }
+    }
+
+    /// Returns a reference to the [`SystemTime`][] that is being formatted.
+    pub fn get_ref(&self) -> &SystemTime {
+        &self.time
+    }
+}
+
+impl fmt::Display for SortableTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let dur = self
+            .time
+            .duration_since(UNIX_EPOCH)
+            .expect("all times should be after the epoch");
+        let secs = dur.as_secs();
+        if secs >= 253_402_300_800 {
+            // year 9999
+            return Err(fmt::Error);
+        }
+        let (year, month, day) = civil_from_days((secs / 86400) as i64);
+        write!(
+            f,
+            "{:04}-{:02}-{:02}{}{:02}-{:02}-{:02}",
+            year,
+            month,
+            day,
+            self.separator,
+            secs / 3600 % 24,
+            secs / 60 % 60,
+            secs % 60
+        )?;
+        if self.digits > 0 {
+            let fraction = format!("{:09}", dur.subsec_nanos());
+            write!(f, ".{}", &fraction[..usize::from(self.digits)])?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{format_sortable, parse_sortable};
+    use crate::date::Error;
+    use crate::test_support::random_timestamp;
+
+    #[test]
+    fn format() {
+        let time = UNIX_EPOCH + Duration::new(1_518_568_087, 5_000);
+        assert_eq!(
+            format_sortable(UNIX_EPOCH).to_string(),
+            "1970-01-01_00-00-00"
+        );
+        assert_eq!(
+            format_sortable(time).with_precision(6).to_string(),
+            "2018-02-14_00-28-07.000005"
+        );
+        assert_eq!(
+            format_sortable(time).with_precision(20).to_string(),
+            "2018-02-14_00-28-07.000005000"
+        );
+        assert_eq!(
+            format_sortable(time).with_separator('T').to_string(),
+            "2018-02-14T00-28-07"
+        );
+        assert_eq!(
+            format_sortable(time).with_separator('·').to_string(),
+            "2018-02-14·00-28-07"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = rand::rng();
+        for _ in 0..10000 {
+            let time = random_timestamp(&mut rng);
+            let text = format_sortable(time).with_precision(9).to_string();
+            assert_eq!(parse_sortable(&text), Ok(time));
+        }
+    }
+
+    #[test]
+    fn sorted() {
+        let mut rng = rand::rng();
+        let mut times: Vec<_> = (0..1000).map(|_| random_timestamp(&mut rng)).collect();
+        let mut names: Vec<String> = times
+            .iter()
+            .map(|&t| format_sortable(t).with_precision(9).to_string())
+            .collect();
+        times.sort();
+        names.sort();
+        let parsed: Vec<_> = names.iter().map(|n| parse_sortable(n).unwrap()).collect();
+        assert_eq!(parsed, times);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_sortable(""), Err(Error::InvalidFormat));
+        assert_eq!(
+            parse_sortable("2018-02-14000-28-07"),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            parse_sortable("2018-02-14_00:28:07"),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            parse_sortable("2018-02-14_00-28-07Z"),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            parse_sortable("2018-02-14_00-28-0x"),
+            Err(Error::InvalidDigit)
+        );
+        assert_eq!(
+            parse_sortable("2018-02-14_00-28-0µ"),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            parse_sortable("2018-02-14_00-28-07.5Z"),
+            Err(Error::InvalidDigit)
+        );
+        assert_eq!(
+            parse_sortable("2018-02-30_00-28-07"),
+            Err(Error::OutOfRange)
+        );
+        assert_eq!(
+            parse_sortable("2018-02-14_00-28-07.5"),
+            Ok(UNIX_EPOCH + Duration::new(1_518_568_087, 500_000_000))
+        );
+    }
+}
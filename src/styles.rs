@@ -0,0 +1,115 @@
+//! Marker types picking a duration [`Style`] at compile time
+//!
+//! [`format_with_style`] takes the style as a type parameter, so the
+//! choice is made at compile time. The output never depends on
+//! [`set_default_options`](crate::set_default_options) or
+//! [`with_format_style`](crate::with_format_style), and the style of a
+//! generic type or function is fixed by its signature.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::Duration;
+//! use humantime::format_with_style;
+//! use humantime::styles::{Compact, Machine, Verbose};
+//!
+//! let val = Duration::from_secs(5400);
+//! assert_eq!(format_with_style::<Compact>(val).to_string(), "1h 30m");
+//! assert_eq!(format_with_style::<Machine>(val).to_string(), "1h30m");
+//! assert_eq!(format_with_style::<Verbose>(val).to_string(), "1 hour 30 minutes");
+//! ```
+
+use std::time::Duration;
+
+use crate::duration::{format_duration_with, FormattedDuration, Style};
+
+/// A type standing for one [`Style`]
+pub trait StaticStyle {
+    /// The style used by [`format_with_style`]
+    const STYLE: Style;
+}
+
+macro_rules! markers {
+    ($($(#[$meta:meta])* $name:ident,)*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+            pub struct $name;
+
+            impl StaticStyle for $name {
+                const STYLE: Style = Style::$name;
+            }
+        )*
+    };
+}
+
+markers! {
+    /// [`Style::Human`], like `1h 30m 250ms`
+    Human,
+    /// [`Style::Verbose`], like `1 hour 30 minutes 250 milliseconds`
+    Verbose,
+    /// [`Style::Prometheus`], like `1h30m`
+    Prometheus,
+    /// [`Style::Kubernetes`], like `1h30m`
+    Kubernetes,
+    /// [`Style::Postgres`], like `01:30:00`
+    Postgres,
+    /// [`Style::Iso8601`], like `PT1H30M`
+    Iso8601,
+    /// [`Style::Ffmpeg`], like `01:30:00.000`
+    Ffmpeg,
+    /// [`Style::Machine`], like `1h30m250ms`
+    Machine,
+    /// [`Style::Abbreviated`], like `2y 3mo 1d 1h 30m`
+    Abbreviated,
+    /// [`Style::Go`], like `1h30m250ms`
+    Go,
+}
+
+/// [`Style::Human`], the `compact` profile of
+/// [`FormatOptions::profile`](crate::FormatOptions::profile)
+pub type Compact = Human;
+
+/// Formats a duration in the style `S`, ignoring the default options
+///
+/// This is [`FormatOptions::format`](crate::FormatOptions::format) with
+/// the style known at compile time, see the [module documentation](self).
+pub fn format_with_style<S: StaticStyle>(val: Duration) -> FormattedDuration {
+    format_duration_with(val, S::STYLE)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{format_with_style, Compact, Human, Iso8601, StaticStyle, Verbose};
+    use crate::duration::Style;
+    use crate::options::{with_format_style, FormatOptions};
+
+    #[test]
+    fn styles() {
+        let val = Duration::new(5400, 250_000_000);
+        with_format_style(Style::Verbose, || {
+            assert_eq!(format_with_style::<Human>(val).to_string(), "1h 30m 250ms");
+        });
+        assert_eq!(
+            format_with_style::<Verbose>(val).to_string(),
+            "1 hour 30 minutes 250 milliseconds"
+        );
+        assert_eq!(
+            format_with_style::<Compact>(val).to_string(),
+            "1h 30m 250ms"
+        );
+        assert_eq!(
+            Some(Compact::STYLE),
+            FormatOptions::profile("compact").map(|o| o.style())
+        );
+        assert_eq!(
+            format_with_style::<Iso8601>(val).to_string(),
+            FormatOptions::new()
+                .with_style(Style::Iso8601)
+                .format(val)
+                .to_string()
+        );
+    }
+}
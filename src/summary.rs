@@ -0,0 +1,220 @@
+use std::fmt;
+use std::time::Duration;
+
+use crate::duration::format_duration_with;
+use crate::options::{current_style, FormatOptions};
+
+/// Number of bars in the sparkline
+const BINS: usize = 16;
+/// Bars of the sparkline, from an empty bin to the fullest one
+const BARS: &[u8] = b"_.:-=+*#";
+
+/// Statistics of a set of durations, like benchmark timings
+///
+/// Displayed as `min 1ms, avg 2ms, p50 2ms, p95 4ms, max 5ms |#=_.|`, the
+/// durations written as [`format_duration`](crate::format_duration) does,
+/// followed by a sparkline of the distribution between the minimum and the
+/// maximum.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::summarize;
+///
+/// let timings: Vec<Duration> = [12, 15, 11, 14, 38].iter()
+///     .map(|&ms| Duration::from_millis(ms))
+///     .collect();
+/// let summary = summarize(&timings).unwrap();
+/// assert_eq!(summary.median(), Duration::from_millis(14));
+/// assert_eq!(
+///     summary.to_string(),
+///     "min 11ms, avg 18ms, p50 14ms, p95 38ms, max 38ms |#==____________=|"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DurationSummary {
+    count: usize,
+    min: Duration,
+    mean: Duration,
+    p50: Duration,
+    p95: Duration,
+    max: Duration,
+    bins: Vec<usize>,
+    options: Option<FormatOptions>,
+}
+
+/// Computes statistics of `values`, or `None` if there are none
+///
+/// Percentiles use the nearest-rank method, so they are always one of the
+/// values.
+pub fn summarize(values: &[Duration]) -> Option<DurationSummary> {
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let (&min, &max) = (sorted.first()?, sorted.last()?);
+    let count = sorted.len();
+    let total: u128 = sorted.iter().map(|d| d.as_nanos()).sum();
+    let mean = total / count as u128;
+    let rank = |p: usize| sorted[(count * p + 99) / 100 - 1];
+    let bins = match (max - min).as_nanos() {
+        0 => vec![count],
+        range => {
+            let mut bins = vec![0; BINS];
+            for value in &sorted {
+                let idx = (value.as_nanos() - min.as_nanos()) * BINS as u128 / range;
+                bins[(idx as usize).min(BINS - 1)] += 1;
+            }
+            bins
+        }
+    };
+    Some(DurationSummary {
+        count,
+        min,
+        // the mean is between the minimum and the maximum, so it fits
+        mean: Duration::new((mean / 1_000_000_000) as u64, (mean % 1_000_000_000) as u32),
+        p50: rank(50),
+        p95: rank(95),
+        max,
+        bins,
+        options: None,
+    })
+}
+
+impl DurationSummary {
+    /// Formats the durations with `options`, instead of the default style
+    pub fn with_format(self, options: FormatOptions) -> DurationSummary {
+        DurationSummary {
+            options: Some(options),
+            ..self
+        }
+    }
+
+    /// Number of values
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The smallest value
+    pub fn min(&self) -> Duration {
+        self.min
+    }
+
+    /// The arithmetic mean, truncated to nanoseconds
+    pub fn mean(&self) -> Duration {
+        self.mean
+    }
+
+    /// The 50th percentile
+    pub fn median(&self) -> Duration {
+        self.p50
+    }
+
+    /// The 95th percentile
+    pub fn p95(&self) -> Duration {
+        self.p95
+    }
+
+    /// The largest value
+    pub fn max(&self) -> Duration {
+        self.max
+    }
+
+    /// The sparkline of the distribution, without the delimiters
+    pub fn sparkline(&self) -> String {
+        let fullest = self.bins.iter().copied().max().unwrap_or(0);
+        self.bins
+            .iter()
+            .map(|&n| match n {
+                0 => BARS[0] as char,
+                // non-empty bins always get a visible bar
+                n => BARS[1 + (n * (BARS.len() - 1) - 1) / fullest] as char,
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for DurationSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let style = self.options.map_or_else(current_style, |o| o.style());
+        let stats = [
+            ("min", self.min),
+            ("avg", self.mean),
+            ("p50", self.p50),
+            ("p95", self.p95),
+            ("max", self.max),
+        ];
+        for (idx, &(name, value)) in stats.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{} {}", name, format_duration_with(value, style))?;
+        }
+        write!(f, " |{}|", self.sparkline())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::summarize;
+    use crate::duration::Style;
+    use crate::options::FormatOptions;
+
+    fn millis(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|&ms| Duration::from_millis(ms)).collect()
+    }
+
+    #[test]
+    fn statistics() {
+        assert_eq!(summarize(&[]), None);
+        let values: Vec<u64> = (1..=100).rev().collect();
+        let summary = summarize(&millis(&values)).unwrap();
+        assert_eq!(summary.count(), 100);
+        assert_eq!(summary.min(), Duration::from_millis(1));
+        assert_eq!(summary.mean(), Duration::from_micros(50_500));
+        assert_eq!(summary.median(), Duration::from_millis(50));
+        assert_eq!(summary.p95(), Duration::from_millis(95));
+        assert_eq!(summary.max(), Duration::from_millis(100));
+        let values: Vec<u64> = (0..16).collect();
+        let summary = summarize(&millis(&values)).unwrap();
+        assert_eq!(summary.sparkline(), "################");
+
+        let summary = summarize(&[Duration::new(0, 1), Duration::new(0, 2)]).unwrap();
+        assert_eq!(summary.mean(), Duration::new(0, 1));
+    }
+
+    #[test]
+    fn single_value() {
+        let summary = summarize(&millis(&[7])).unwrap();
+        assert_eq!(summary.median(), Duration::from_millis(7));
+        assert_eq!(summary.p95(), Duration::from_millis(7));
+        assert_eq!(
+            summary.to_string(),
+            "min 7ms, avg 7ms, p50 7ms, p95 7ms, max 7ms |#|"
+        );
+    }
+
+    #[test]
+    fn sparkline() {
+        let mut values = vec![0; 14];
+        values.extend(&[8, 15, 15]);
+        let summary = summarize(&millis(&values)).unwrap();
+        assert_eq!(summary.sparkline(), "#_______.______.");
+        let summary = summarize(&[Duration::ZERO, Duration::MAX]).unwrap();
+        assert_eq!(summary.sparkline(), "#______________#");
+    }
+
+    #[test]
+    fn styles() {
+        let options = FormatOptions::new().with_style(Style::Verbose);
+        let summary = summarize(&millis(&[1000, 3000]))
+            .unwrap()
+            .with_format(options);
+        assert_eq!(
+            summary.to_string(),
+            "min 1 second, avg 2 seconds, p50 1 second, \
+            p95 3 seconds, max 3 seconds |#______________#|"
+        );
+    }
+}
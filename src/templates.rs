@@ -0,0 +1,162 @@
+//! Template filters, independent of the template engine
+//!
+//! This is not an integration with Tera or Handlebars: the crate depends on
+//! neither, and nothing here registers a filter or a helper. The functions
+//! are the bodies of such filters, and each application registers them
+//! with its engine. Register them as `humantime_duration`,
+//! `humantime_rfc3339` and `humantime_relative`, so that templates are
+//! portable between applications.
+//!
+//! Template engines pass numbers from the context as floating point, so
+//! these functions take seconds as `f64` and return the formatted text.
+//!
+//! # Example
+//!
+//! ```
+//! use std::time::{Duration, UNIX_EPOCH};
+//! use humantime::templates;
+//!
+//! assert_eq!(templates::duration(5400.0, None).unwrap(), "1h 30m");
+//! assert_eq!(templates::duration(5400.0, Some("verbose")).unwrap(), "1 hour 30 minutes");
+//! assert_eq!(templates::rfc3339(1_518_568_087.0).unwrap(), "2018-02-14T00:28:07Z");
+//! let now = UNIX_EPOCH + Duration::from_secs(1_518_568_087);
+//! assert_eq!(templates::relative(1_518_564_487.0, now).unwrap(), "1h ago");
+//! ```
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::date::{format_rfc3339, max};
+use crate::duration::format_duration;
+use crate::float::{self, duration_from_secs_f64};
+use crate::options::FormatOptions;
+
+/// Error of a template filter
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// The number of seconds can't be converted
+    Value(float::Error),
+    /// The style is not a profile of [`FormatOptions::profile`]
+    UnknownStyle(String),
+    /// The timestamp is later than the system or the formatter supports,
+    /// the end of the year 9999 on most platforms
+    OutOfRange,
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Value(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Value(e) => e.fmt(f),
+            Error::UnknownStyle(name) => write!(f, "unknown duration style {:?}", name),
+            Error::OutOfRange => write!(f, "timestamp is out of range"),
+        }
+    }
+}
+
+impl From<float::Error> for Error {
+    fn from(e: float::Error) -> Error {
+        Error::Value(e)
+    }
+}
+
+/// Formats seconds as a duration, in the style of the named profile
+///
+/// Without a style the default style of [`format_duration`] is used.
+pub fn duration(secs: f64, style: Option<&str>) -> Result<String, Error> {
+    let value = duration_from_secs_f64(secs)?;
+    match style {
+        Some(name) => FormatOptions::profile(name)
+            .map(|options| options.format(value).to_string())
+            .ok_or_else(|| Error::UnknownStyle(name.to_owned())),
+        None => Ok(format_duration(value).to_string()),
+    }
+}
+
+/// Formats seconds since the epoch as an RFC 3339 timestamp
+pub fn rfc3339(epoch_secs: f64) -> Result<String, Error> {
+    let since_epoch = duration_from_secs_f64(epoch_secs)?;
+    if since_epoch.as_secs() > max::SECONDS {
+        return Err(Error::OutOfRange);
+    }
+    Ok(format_rfc3339(UNIX_EPOCH + since_epoch).to_string())
+}
+
+/// Formats seconds since the epoch relative to `now`, like `5m ago`
+///
+/// Times after `now` are written like `in 5m`. The difference is rounded
+/// to whole seconds, a difference below half a second is `now`.
+pub fn relative(epoch_secs: f64, now: SystemTime) -> Result<String, Error> {
+    let time = UNIX_EPOCH
+        .checked_add(duration_from_secs_f64(epoch_secs)?)
+        .ok_or(Error::OutOfRange)?;
+    let (delta, future) = match time.duration_since(now) {
+        Ok(d) => (d, true),
+        Err(e) => (e.duration(), false),
+    };
+    let secs = delta.as_secs() + u64::from(delta.subsec_nanos() >= 500_000_000);
+    let text = format_duration(Duration::from_secs(secs));
+    Ok(match (secs, future) {
+        (0, _) => "now".to_owned(),
+        (_, true) => format!("in {}", text),
+        (_, false) => format!("{} ago", text),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{duration, relative, rfc3339, Error};
+    use crate::float;
+
+    #[test]
+    fn durations() {
+        assert_eq!(duration(0.25, None).unwrap(), "250ms");
+        assert_eq!(duration(5400.0, Some("go")).unwrap(), "1h30m");
+        assert_eq!(
+            duration(1.0, Some("fancy")),
+            Err(Error::UnknownStyle("fancy".into()))
+        );
+        assert_eq!(
+            duration(-1.0, None),
+            Err(Error::Value(float::Error::Negative))
+        );
+        assert_eq!(
+            duration(f64::NAN, None).unwrap_err().to_string(),
+            "number of seconds is not finite"
+        );
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(rfc3339(0.0).unwrap(), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339(0.5).unwrap(), "1970-01-01T00:00:00.500000000Z");
+        assert_eq!(rfc3339(1e12), Err(Error::OutOfRange));
+        assert_eq!(rfc3339(-1.0), Err(Error::Value(float::Error::Negative)));
+    }
+
+    #[test]
+    fn relative_times() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        assert_eq!(relative(1_000_000.0, now).unwrap(), "now");
+        assert_eq!(relative(1_000_000.4, now).unwrap(), "now");
+        assert_eq!(relative(999_999.5, now).unwrap(), "1s ago");
+        assert_eq!(relative(1_000_090.0, now).unwrap(), "in 1m 30s");
+        assert_eq!(relative(913_600.0, now).unwrap(), "1day ago");
+        assert_eq!(
+            relative(1e30, now),
+            Err(Error::Value(float::Error::Overflow))
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Random values and round-trip checks for tests
+//!
+//! These are the generators used by the tests of this crate. Crates which
+//! wrap the parsers and formatters (serde helpers, config loaders, other
+//! formats) can use them to assert that they stay compatible. Enabled by
+//! the `test-support` feature.
+//!
+//! # Example
+//!
+//! ```
+//! use humantime::test_support::check_duration_round_trip;
+//!
+//! // a wrapper which must accept everything `format_duration` writes
+//! fn parse_config_value(s: &str) -> Result<std::time::Duration, String> {
+//!     humantime::parse_duration(s.trim()).map_err(|e| e.to_string())
+//! }
+//!
+//! check_duration_round_trip(1000, |d| format!(" {} ", humantime::format_duration(d)), parse_config_value);
+//! ```
+
+use std::fmt::Debug;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rand::Rng;
+
+use crate::date::max;
+
+/// First second of year 9999, durations around it are still readable
+const MAX_DURATION_SECS: u64 = 253_370_764_800;
+
+/// A random duration of whole seconds up to about 8000 years
+pub fn random_seconds<R: Rng + ?Sized>(rng: &mut R) -> Duration {
+    Duration::from_secs(rng.random_range(0..MAX_DURATION_SECS))
+}
+
+/// A random duration up to about 8000 years with nanoseconds
+pub fn random_duration<R: Rng + ?Sized>(rng: &mut R) -> Duration {
+    Duration::new(
+        rng.random_range(0..MAX_DURATION_SECS),
+        rng.random_range(0..1_000_000_000),
+    )
+}
+
+/// A random timestamp of whole seconds which can be formatted as RFC3339
+///
+/// The range is 1970 to year 9999, or to 2038 on platforms with 32-bit
+/// `SystemTime`.
+pub fn random_timestamp_seconds<R: Rng + ?Sized>(rng: &mut R) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(rng.random_range(0..=max::SECONDS))
+}
+
+/// A random timestamp with nanoseconds which can be formatted as RFC3339
+pub fn random_timestamp<R: Rng + ?Sized>(rng: &mut R) -> SystemTime {
+    random_timestamp_seconds(rng) + Duration::new(0, rng.random_range(0..1_000_000_000))
+}
+
+/// Checks that `parse(format(d)) == d` for `iterations` random durations
+///
+/// # Panics
+///
+/// Panics with the duration and the formatted string if the check fails.
+pub fn check_duration_round_trip<F, P, E>(iterations: usize, format: F, parse: P)
+where
+    F: Fn(Duration) -> String,
+    P: Fn(&str) -> Result<Duration, E>,
+    E: Debug,
+{
+    let mut rng = rand::rng();
+    for _ in 0..iterations {
+        let value = random_duration(&mut rng);
+        let formatted = format(value);
+        let parsed = parse(&formatted);
+        assert!(
+            matches!(parsed, Ok(v) if v == value),
+            "round trip of {:?} failed: formatted as {:?}, parsed as {:?}",
+            value,
+            formatted,
+            parsed,
+        );
+    }
+}
+
+/// Checks that `parse(format(t)) == t` for `iterations` random timestamps
+///
+/// # Panics
+///
+/// Panics with the timestamp and the formatted string if the check fails.
+pub fn check_timestamp_round_trip<F, P, E>(iterations: usize, format: F, parse: P)
+where
+    F: Fn(SystemTime) -> String,
+    P: Fn(&str) -> Result<SystemTime, E>,
+    E: Debug,
+{
+    let mut rng = rand::rng();
+    for _ in 0..iterations {
+        let value = random_timestamp(&mut rng);
+        let formatted = format(value);
+        let parsed = parse(&formatted);
+        assert!(
+            matches!(parsed, Ok(v) if v == value),
+            "round trip of {:?} failed: formatted as {:?}, parsed as {:?}",
+            value,
+            formatted,
+            parsed,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_duration_round_trip, check_timestamp_round_trip};
+    use crate::date::{format_rfc3339_nanos, format_rfc3339_seconds, parse_rfc3339};
+    use crate::duration::{format_duration, parse_duration};
+
+    #[test]
+    fn round_trips() {
+        check_duration_round_trip(100, |d| format_duration(d).to_string(), parse_duration);
+        check_timestamp_round_trip(100, |t| format_rfc3339_nanos(t).to_string(), parse_rfc3339);
+    }
+
+    #[test]
+    #[should_panic(expected = "round trip of")]
+    fn failure() {
+        // seconds precision loses the fraction
+        check_timestamp_round_trip(
+            100,
+            |t| format_rfc3339_seconds(t).to_string(),
+            parse_rfc3339,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "parsed as Err")]
+    fn parse_error() {
+        check_duration_round_trip(1, |d| format!("{}!", format_duration(d)), parse_duration);
+    }
+}
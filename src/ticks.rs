@@ -0,0 +1,223 @@
+use std::time::Duration;
+
+use crate::duration::format_duration;
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Steps that look natural on a time axis, in nanoseconds
+const STEPS: &[u64] = &[
+    1,
+    2,
+    5,
+    10,
+    20,
+    50,
+    100,
+    200,
+    500,
+    1_000,
+    2_000,
+    5_000,
+    10_000,
+    20_000,
+    50_000,
+    100_000,
+    200_000,
+    500_000,
+    1_000_000,
+    2_000_000,
+    5_000_000,
+    10_000_000,
+    20_000_000,
+    50_000_000,
+    100_000_000,
+    200_000_000,
+    500_000_000,
+    NANOS_PER_SEC,
+    2 * NANOS_PER_SEC,
+    5 * NANOS_PER_SEC,
+    10 * NANOS_PER_SEC,
+    15 * NANOS_PER_SEC,
+    30 * NANOS_PER_SEC,
+    60 * NANOS_PER_SEC,
+    2 * 60 * NANOS_PER_SEC,
+    5 * 60 * NANOS_PER_SEC,
+    10 * 60 * NANOS_PER_SEC,
+    15 * 60 * NANOS_PER_SEC,
+    30 * 60 * NANOS_PER_SEC,
+    3600 * NANOS_PER_SEC,
+    2 * 3600 * NANOS_PER_SEC,
+    3 * 3600 * NANOS_PER_SEC,
+    6 * 3600 * NANOS_PER_SEC,
+    12 * 3600 * NANOS_PER_SEC,
+    86400 * NANOS_PER_SEC,
+    2 * 86400 * NANOS_PER_SEC,
+    7 * 86400 * NANOS_PER_SEC,
+    14 * 86400 * NANOS_PER_SEC,
+    2_630_016 * NANOS_PER_SEC,     // 1 month
+    3 * 2_630_016 * NANOS_PER_SEC, // 3 months
+    6 * 2_630_016 * NANOS_PER_SEC, // 6 months
+];
+
+/// One year, the table continues as multiples of it with 1-2-5 steps
+const YEAR: u128 = 31_557_600 * NANOS_PER_SEC as u128;
+
+fn nice_step(range: u128, target_count: u128) -> u128 {
+    for &step in STEPS {
+        if range / u128::from(step) <= target_count {
+            return u128::from(step);
+        }
+    }
+    let mut years = 1;
+    loop {
+        for mult in &[1, 2, 5] {
+            let step = YEAR * years * mult;
+            if range / step <= target_count {
+                return step;
+            }
+        }
+        years *= 10;
+    }
+}
+
+/// Generates human-friendly axis ticks for a time range `0..=range`
+///
+/// The step between ticks is chosen from values that look natural to a
+/// human (`1s`, `5s`, `15s`, `1m`, `5m`, ...), so that the range is split
+/// into at most `target_count` intervals. The returned ticks start at zero
+/// and never exceed `range`.
+///
+/// Use [`format_duration`](crate::format_duration) or
+/// [`nice_tick_labels`] to get the labels.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::nice_ticks;
+///
+/// let ticks = nice_ticks(Duration::from_secs(50), 4);
+/// assert_eq!(ticks, vec![
+///     Duration::from_secs(0),
+///     Duration::from_secs(15),
+///     Duration::from_secs(30),
+///     Duration::from_secs(45),
+/// ]);
+/// ```
+pub fn nice_ticks(range: Duration, target_count: usize) -> Vec<Duration> {
+    let range = range.as_nanos();
+    let target_count = target_count.max(1) as u128;
+    let step = nice_step(range, target_count);
+    (0..=range / step)
+        .map(|i| {
+            let nanos = i * step;
+            Duration::new(
+                (nanos / u128::from(NANOS_PER_SEC)) as u64,
+                (nanos % u128::from(NANOS_PER_SEC)) as u32,
+            )
+        })
+        .collect()
+}
+
+/// Generates ticks like [`nice_ticks`] paired with their formatted labels
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::nice_tick_labels;
+///
+/// let labels: Vec<String> = nice_tick_labels(Duration::from_secs(7200), 3)
+///     .into_iter()
+///     .map(|(_, label)| label)
+///     .collect();
+/// assert_eq!(labels, vec!["0s", "1h", "2h"]);
+/// ```
+pub fn nice_tick_labels(range: Duration, target_count: usize) -> Vec<(Duration, String)> {
+    nice_ticks(range, target_count)
+        .into_iter()
+        .map(|tick| (tick, format_duration(tick).to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{nice_tick_labels, nice_ticks};
+
+    fn labels(range: Duration, count: usize) -> Vec<String> {
+        nice_tick_labels(range, count)
+            .into_iter()
+            .map(|(_, label)| label)
+            .collect()
+    }
+
+    #[test]
+    fn zero_range() {
+        assert_eq!(nice_ticks(Duration::ZERO, 5), vec![Duration::ZERO]);
+        assert_eq!(nice_ticks(Duration::ZERO, 0), vec![Duration::ZERO]);
+    }
+
+    #[test]
+    fn subsecond() {
+        assert_eq!(
+            labels(Duration::from_millis(3), 3),
+            vec!["0s", "1ms", "2ms", "3ms"]
+        );
+        #[cfg(not(feature = "mu"))]
+        assert_eq!(
+            labels(Duration::from_micros(900), 5),
+            vec!["0s", "200us", "400us", "600us", "800us"]
+        );
+        #[cfg(feature = "mu")]
+        assert_eq!(
+            labels(Duration::from_micros(900), 5),
+            vec!["0s", "200µs", "400µs", "600µs", "800µs"]
+        );
+        assert_eq!(labels(Duration::new(0, 7), 1), vec!["0s", "5ns"]);
+    }
+
+    #[test]
+    fn clock() {
+        assert_eq!(
+            labels(Duration::from_secs(90), 6),
+            vec!["0s", "15s", "30s", "45s", "1m", "1m 15s", "1m 30s"]
+        );
+        assert_eq!(
+            labels(Duration::from_secs(3600), 4),
+            vec!["0s", "15m", "30m", "45m", "1h"]
+        );
+        assert_eq!(
+            labels(Duration::from_secs(86400), 5),
+            vec!["0s", "6h", "12h", "18h", "1day"]
+        );
+    }
+
+    #[test]
+    fn calendar() {
+        assert_eq!(
+            labels(Duration::from_secs(30 * 86400), 5),
+            vec!["0s", "7days", "14days", "21days", "28days"]
+        );
+        assert_eq!(
+            labels(Duration::from_secs(31_557_600), 4),
+            vec!["0s", "3months", "6months", "9months"]
+        );
+        assert_eq!(
+            labels(Duration::from_secs(100 * 31_557_600), 4),
+            vec!["0s", "50years", "100years"]
+        );
+    }
+
+    #[test]
+    fn count_is_respected() {
+        for secs in &[1, 59, 61, 1000, 100_000, 10_000_000, u64::MAX] {
+            for count in 1..12 {
+                let ticks = nice_ticks(Duration::from_secs(*secs), count);
+                assert!(ticks.len() <= count + 1, "{} {}", secs, count);
+                assert!(*ticks.last().unwrap() <= Duration::from_secs(*secs));
+            }
+        }
+    }
+}
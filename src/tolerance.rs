@@ -0,0 +1,295 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::duration::{self, parse_duration};
+
+/// Maximum number of fractional digits in a percentage
+const MAX_PERCENT_DIGITS: u32 = 6;
+
+/// Error parsing a tolerance
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// Absolute tolerance is not a valid duration
+    Duration(duration::Error),
+    /// Percentage is not a non-negative decimal number
+    ///
+    /// At most six fractional digits are supported.
+    InvalidPercentage,
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Duration(e) => Some(e),
+            Error::InvalidPercentage => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Duration(e) => write!(f, "invalid tolerance: {}", e),
+            Error::InvalidPercentage => write!(f, "invalid percentage"),
+        }
+    }
+}
+
+impl From<duration::Error> for Error {
+    fn from(e: duration::Error) -> Error {
+        Error::Duration(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Absolute(Duration),
+    /// Percentage as `numerator / 10^MAX_PERCENT_DIGITS`
+    Relative(u64),
+}
+
+/// Allowed difference between two durations
+///
+/// Parsed either from a duration like `100ms`, or from a percentage
+/// like `5%` or `0.5%`.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::Tolerance;
+///
+/// let tolerance: Tolerance = "10%".parse().unwrap();
+/// assert!(tolerance.within(Duration::from_millis(1090), Duration::from_secs(1)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Tolerance(Kind);
+
+fn parse_percentage(s: &str) -> Result<u64, Error> {
+    let (int, frac) = match s.find('.') {
+        Some(idx) => (&s[..idx], &s[idx + 1..]),
+        None => (s, ""),
+    };
+    let all_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    if int.is_empty()
+        || !all_digits(int)
+        || !all_digits(frac)
+        || frac.len() > MAX_PERCENT_DIGITS as usize
+        || (s.contains('.') && frac.is_empty())
+    {
+        return Err(Error::InvalidPercentage);
+    }
+    let mut value = 0u64;
+    for b in int.bytes().chain(frac.bytes()) {
+        value = value
+            .checked_mul(10)
+            .and_then(|v| v.checked_add(u64::from(b - b'0')))
+            .ok_or(Error::InvalidPercentage)?;
+    }
+    value
+        .checked_mul(10u64.pow(MAX_PERCENT_DIGITS - frac.len() as u32))
+        .ok_or(Error::InvalidPercentage)
+}
+
+impl Tolerance {
+    /// Tolerance allowing a fixed difference
+    pub const fn absolute(value: Duration) -> Tolerance {
+        Tolerance(Kind::Absolute(value))
+    }
+
+    /// Checks whether `a` and `b` differ by no more than the tolerance
+    ///
+    /// Percentage is taken of the larger of two values, so the check is
+    /// symmetric.
+    pub fn approx_eq(&self, a: Duration, b: Duration) -> bool {
+        self.allows(a, b, a.max(b))
+    }
+
+    /// Checks whether `value` is no further from `reference` than the
+    /// tolerance
+    ///
+    /// Percentage is taken of the `reference`.
+    pub fn within(&self, value: Duration, reference: Duration) -> bool {
+        self.allows(value, reference, reference)
+    }
+
+    /// Absolute difference allowed when compared against `base`
+    pub(crate) fn amount(&self, base: Duration) -> Duration {
+        match self.0 {
+            Kind::Absolute(max) => max,
+            Kind::Relative(percent) => {
+                let scale = 100 * 10u128.pow(MAX_PERCENT_DIGITS);
+                let nanos = base
+                    .as_nanos()
+                    .checked_mul(u128::from(percent))
+                    .map_or(u128::MAX, |n| n / scale);
+                match u64::try_from(nanos / 1_000_000_000) {
+                    Ok(secs) => Duration::new(secs, (nanos % 1_000_000_000) as u32),
+                    Err(_) => Duration::MAX,
+                }
+            }
+        }
+    }
+
+    fn allows(&self, a: Duration, b: Duration, base: Duration) -> bool {
+        let diff = a.max(b) - a.min(b);
+        match self.0 {
+            Kind::Absolute(max) => diff <= max,
+            Kind::Relative(percent) => {
+                // diff / base <= percent / (100 * 10^digits)
+                let scale = 100 * 10u128.pow(MAX_PERCENT_DIGITS);
+                match base.as_nanos().checked_mul(u128::from(percent)) {
+                    Some(allowed) => diff.as_nanos() * scale <= allowed,
+                    None => true,
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Tolerance {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Tolerance, Error> {
+        let s = s.trim();
+        match s.strip_suffix('%') {
+            Some(percent) => {
+                parse_percentage(percent.trim_end()).map(|p| Tolerance(Kind::Relative(p)))
+            }
+            None => Ok(Tolerance::absolute(parse_duration(s)?)),
+        }
+    }
+}
+
+impl fmt::Display for Tolerance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Kind::Absolute(value) => crate::format_duration(value).fmt(f),
+            Kind::Relative(percent) => {
+                let scale = 10u64.pow(MAX_PERCENT_DIGITS);
+                write!(f, "{}", percent / scale)?;
+                let mut frac = percent % scale;
+                if frac != 0 {
+                    let mut digits = MAX_PERCENT_DIGITS as usize;
+                    while frac % 10 == 0 {
+                        frac /= 10;
+                        digits -= 1;
+                    }
+                    write!(f, ".{:0width$}", frac, width = digits)?;
+                }
+                f.write_str("%")
+            }
+        }
+    }
+}
+
+/// Checks whether two durations are equal within a human-written tolerance
+///
+/// The tolerance is either an absolute duration (`100ms`) or a
+/// percentage of the larger value (`5%`).
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::approx_eq;
+///
+/// let a = Duration::from_millis(1000);
+/// let b = Duration::from_millis(1040);
+/// assert_eq!(approx_eq(a, b, "50ms"), Ok(true));
+/// assert_eq!(approx_eq(a, b, "3%"), Ok(false));
+/// ```
+pub fn approx_eq(a: Duration, b: Duration, tolerance: &str) -> Result<bool, Error> {
+    Ok(tolerance.parse::<Tolerance>()?.approx_eq(a, b))
+}
+
+/// Checks whether `value` is within a human-written tolerance of
+/// `reference`
+///
+/// The tolerance is either an absolute duration (`100ms`) or a
+/// percentage of the `reference` (`5%`).
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::within;
+///
+/// let expected = Duration::from_millis(200);
+/// assert_eq!(within(Duration::from_millis(210), expected, "5%"), Ok(true));
+/// assert_eq!(within(Duration::from_millis(211), expected, "5%"), Ok(false));
+/// ```
+pub fn within(value: Duration, reference: Duration, tolerance: &str) -> Result<bool, Error> {
+    Ok(tolerance.parse::<Tolerance>()?.within(value, reference))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::{approx_eq, within, Error, Tolerance};
+    use crate::duration::Error as DurationError;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn absolute() {
+        assert_eq!(approx_eq(ms(100), ms(150), "50ms"), Ok(true));
+        assert_eq!(approx_eq(ms(150), ms(100), "50ms"), Ok(true));
+        assert_eq!(approx_eq(ms(100), ms(151), "50ms"), Ok(false));
+        assert_eq!(within(ms(100), ms(100), "0s"), Ok(true));
+        assert_eq!(within(ms(100), ms(101), "0"), Ok(false));
+    }
+
+    #[test]
+    fn relative() {
+        // 10% of the larger value
+        assert_eq!(approx_eq(ms(900), ms(1000), "10%"), Ok(true));
+        assert_eq!(approx_eq(ms(1000), ms(900), "10%"), Ok(true));
+        assert_eq!(approx_eq(ms(899), ms(1000), "10%"), Ok(false));
+        // 10% of the reference
+        assert_eq!(within(ms(1100), ms(1000), "10%"), Ok(true));
+        assert_eq!(within(ms(1000), ms(1100), "10%"), Ok(true));
+        assert_eq!(within(ms(1000), ms(1112), "10%"), Ok(false));
+        assert_eq!(within(ms(1001), ms(1000), "0.1%"), Ok(true));
+        assert_eq!(within(ms(1002), ms(1000), "0.1 %"), Ok(false));
+        assert_eq!(within(Duration::MAX, ms(1), "1000000%"), Ok(false));
+        assert_eq!(within(Duration::MAX, Duration::ZERO, "100%"), Ok(false));
+        assert_eq!(approx_eq(Duration::MAX, ms(1), "100%"), Ok(true));
+    }
+
+    #[test]
+    fn bad_tolerance() {
+        for input in &["%", "5.%", ".5%", "-5%", "1.0000001%", "5x%", "5%%"] {
+            assert_eq!(
+                input.parse::<Tolerance>(),
+                Err(Error::InvalidPercentage),
+                "{:?}",
+                input
+            );
+        }
+        assert_eq!(
+            approx_eq(ms(1), ms(1), "5"),
+            Err(Error::Duration(DurationError::UnknownUnit {
+                start: 1,
+                end: 1,
+                unit: String::new(),
+                value: 5,
+            }))
+        );
+    }
+
+    #[test]
+    fn display() {
+        for input in &["5%", "0.5%", "12.000001%", "100ms", "0%"] {
+            let tolerance: Tolerance = input.parse().unwrap();
+            assert_eq!(tolerance.to_string(), *input);
+        }
+        let tolerance: Tolerance = "1.50%".parse().unwrap();
+        assert_eq!(tolerance.to_string(), "1.5%");
+    }
+}
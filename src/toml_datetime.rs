@@ -0,0 +1,175 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::date::{max, parse_rfc3339_weak, Error};
+
+/// The key under which the `toml` crate passes datetimes to serde
+pub(crate) const TOML_DATETIME_KEY: &str = "$__toml_private_datetime";
+
+/// How [`parse_toml_datetime`] completes values without an offset
+///
+/// TOML has local date-times like `1979-05-27T07:32:00` and local dates
+/// like `1979-05-27`, which are not points in time until the application
+/// picks a time zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LocalCompletion {
+    /// Rejects local values with [`Error::InvalidFormat`]
+    Reject,
+    /// Reads local values as UTC, like [`parse_rfc3339_weak`] does
+    Utc,
+    /// Reads local values at a fixed offset east of UTC, in seconds
+    Offset(i32),
+}
+
+/// Parses a TOML datetime, like `1979-05-27T07:32:00-07:00`
+///
+/// Offset date-times may have any offset, `T` may be a space and the
+/// fraction of seconds is optional, as in TOML. Local date-times and
+/// local dates are completed with `completion`, a local date being the
+/// midnight at its start. Local times without a date are always rejected.
+///
+/// With the `serde` feature, the [`Timestamp`](crate::Timestamp) wrapper
+/// deserializes TOML datetimes as well as strings, completing local values
+/// as UTC.
+///
+/// # Example
+///
+/// ```
+/// use humantime::{parse_rfc3339, parse_toml_datetime, LocalCompletion};
+///
+/// let time = parse_rfc3339("1979-05-27T07:32:00Z").unwrap();
+/// let parse = |s| parse_toml_datetime(s, LocalCompletion::Reject);
+/// assert_eq!(parse("1979-05-27T00:32:00-07:00"), Ok(time));
+/// assert_eq!(parse("1979-05-27 07:32:00Z"), Ok(time));
+/// assert!(parse("1979-05-27T07:32:00").is_err());
+/// let local = parse_toml_datetime("1979-05-27T09:32:00", LocalCompletion::Offset(7200));
+/// assert_eq!(local, Ok(time));
+/// ```
+pub fn parse_toml_datetime(s: &str, completion: LocalCompletion) -> Result<SystemTime, Error> {
+    let b = s.as_bytes();
+    if b.len() == 10 {
+        // local date
+        return complete(parse_rfc3339_weak(&format!("{}T00:00:00", s))?, completion);
+    }
+    if b.len() < 19 || b[4] != b'-' || !matches!(b[10], b'T' | b't' | b' ') {
+        return Err(Error::InvalidFormat);
+    }
+    let datetime = &s[..10];
+    let rest = &s[11..];
+    let (time, offset) = match rest.find(['Z', 'z', '+', '-']) {
+        Some(idx) => (&rest[..idx], Some(&rest[idx..])),
+        None => (rest, None),
+    };
+    let utc = parse_rfc3339_weak(&format!("{}T{}Z", datetime, time))?;
+    match offset {
+        None => complete(utc, completion),
+        Some("Z" | "z") => Ok(utc),
+        Some(offset) => shift(utc, parse_offset(offset)?),
+    }
+}
+
+/// Parses `+HH:MM` into seconds east of UTC
+fn parse_offset(s: &str) -> Result<i32, Error> {
+    let b = s.as_bytes();
+    if b.len() != 6 || b[3] != b':' {
+        return Err(Error::InvalidFormat);
+    }
+    let digit = |c: u8| match c {
+        b'0'..=b'9' => Ok(i32::from(c - b'0')),
+        _ => Err(Error::InvalidDigit),
+    };
+    let hours = digit(b[1])? * 10 + digit(b[2])?;
+    let minutes = digit(b[4])? * 10 + digit(b[5])?;
+    if hours > 23 || minutes > 59 {
+        return Err(Error::OutOfRange);
+    }
+    let secs = hours * 3600 + minutes * 60;
+    Ok(if b[0] == b'-' { -secs } else { secs })
+}
+
+fn complete(utc: SystemTime, completion: LocalCompletion) -> Result<SystemTime, Error> {
+    match completion {
+        LocalCompletion::Reject => Err(Error::InvalidFormat),
+        LocalCompletion::Utc => Ok(utc),
+        LocalCompletion::Offset(secs) => shift(utc, secs),
+    }
+}
+
+/// Converts a wall clock time at `offset` east of UTC, read as UTC, to UTC
+///
+/// The result stays in the range that [`parse_rfc3339`] accepts.
+///
+/// [`parse_rfc3339`]: crate::parse_rfc3339
+fn shift(wall: SystemTime, offset: i32) -> Result<SystemTime, Error> {
+    let since_epoch = wall
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| Error::OutOfRange)?;
+    let secs = since_epoch.as_secs() as i64 - i64::from(offset);
+    if secs < 0 || secs as u64 > max::SECONDS {
+        return Err(Error::OutOfRange);
+    }
+    Ok(UNIX_EPOCH + Duration::new(secs as u64, since_epoch.subsec_nanos()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_toml_datetime, LocalCompletion};
+    use crate::date::{parse_rfc3339, Error};
+
+    #[test]
+    fn offsets() {
+        let time = parse_rfc3339("1979-05-27T07:32:00Z").unwrap();
+        let parse = |s| parse_toml_datetime(s, LocalCompletion::Reject);
+        assert_eq!(parse("1979-05-27T07:32:00Z"), Ok(time));
+        assert_eq!(parse("1979-05-27t07:32:00z"), Ok(time));
+        assert_eq!(parse("1979-05-27T07:32:00+00:00"), Ok(time));
+        assert_eq!(parse("1979-05-27T00:32:00-07:00"), Ok(time));
+        assert_eq!(parse("1979-05-27T13:02:00+05:30"), Ok(time));
+        assert_eq!(
+            parse("1979-05-27T00:32:00.999999-07:00"),
+            parse_rfc3339("1979-05-27T07:32:00.999999Z")
+        );
+        assert_eq!(parse("1970-01-01T00:00:00+01:00"), Err(Error::OutOfRange));
+        assert_eq!(parse("9999-12-31T23:59:59-01:00"), Err(Error::OutOfRange));
+    }
+
+    #[test]
+    fn local() {
+        let parse = |s, c| parse_toml_datetime(s, c);
+        assert_eq!(
+            parse("1979-05-27T07:32:00", LocalCompletion::Reject),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            parse("1979-05-27T07:32:00", LocalCompletion::Utc),
+            parse_rfc3339("1979-05-27T07:32:00Z")
+        );
+        assert_eq!(
+            parse("1979-05-27", LocalCompletion::Utc),
+            parse_rfc3339("1979-05-27T00:00:00Z")
+        );
+        assert_eq!(
+            parse("1979-05-27", LocalCompletion::Offset(-3600)),
+            parse_rfc3339("1979-05-27T01:00:00Z")
+        );
+        assert_eq!(
+            parse("1979-05-27", LocalCompletion::Reject),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(
+            parse("07:32:00", LocalCompletion::Utc),
+            Err(Error::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let parse = |s| parse_toml_datetime(s, LocalCompletion::Utc);
+        assert_eq!(parse(""), Err(Error::InvalidFormat));
+        assert_eq!(parse("1979-05-27X07:32:00Z"), Err(Error::InvalidFormat));
+        assert_eq!(parse("1979-05-27T07:32:00+7:00"), Err(Error::InvalidFormat));
+        assert_eq!(parse("1979-05-27T07:32:00+0x:00"), Err(Error::InvalidDigit));
+        assert_eq!(parse("1979-05-27T07:32:00+24:00"), Err(Error::OutOfRange));
+        assert_eq!(parse("1979-13-27"), Err(Error::OutOfRange));
+    }
+}
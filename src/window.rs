@@ -0,0 +1,339 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::business::{weekday, Weekday};
+use crate::relative::weekday_word;
+
+const SECS_PER_DAY: i64 = 86400;
+const ALL_DAYS: u8 = 0b0111_1111;
+const DAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Error parsing a time window
+#[derive(Debug, PartialEq, Clone)]
+#[non_exhaustive]
+pub enum Error {
+    /// Window is not a time range, optionally preceded by days
+    InvalidFormat,
+    /// The word is not a day of the week
+    UnknownWeekday(String),
+    /// Time of day is not `HH:MM` or `HH:MM:SS` from `00:00` to `24:00`
+    InvalidTime,
+}
+
+impl StdError for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidFormat => write!(
+                f,
+                "time window must look like `09:00-17:00` or `Mon-Fri 08:00-18:00`"
+            ),
+            Error::UnknownWeekday(day) => write!(f, "unknown day of the week {:?}", day),
+            Error::InvalidTime => write!(
+                f,
+                "time of day must be `HH:MM` or `HH:MM:SS` from 00:00 to 24:00"
+            ),
+        }
+    }
+}
+
+/// A daily or weekly time window, like `09:00-17:00` or `Mon-Fri 08:00-18:00`
+///
+/// The window is a range of time of day, which doesn't include the end,
+/// optionally preceded by days of the week: single days, ranges like
+/// `Mon-Fri` or `Fri-Mon`, or lists like `Mon,Wed,Fri-Sun`. Without days,
+/// the window repeats every day. Without a time range, like `Sat-Sun`, it
+/// covers the whole days.
+///
+/// When the end is not after the start, like `Fri 22:00-06:00`, the
+/// window goes past midnight and the days are those when it starts.
+///
+/// # Example
+///
+/// ```
+/// use humantime::{parse_rfc3339, TimeWindow};
+///
+/// let window: TimeWindow = "Mon-Fri 08:00-18:00".parse().unwrap();
+/// // Wednesday
+/// let time = parse_rfc3339("2018-02-14T07:30:00Z").unwrap();
+/// assert!(!window.contains(time, 0));
+/// assert!(window.contains(time, 3600));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeWindow {
+    days: u8,
+    start: u32,
+    end: u32,
+}
+
+/// Parse a time window, see [`TimeWindow`] for the syntax
+pub fn parse_time_window(s: &str) -> Result<TimeWindow, Error> {
+    let mut words = s.split_whitespace();
+    let (days, times) = match (words.next(), words.next(), words.next()) {
+        (Some(first), None, None) if first.starts_with(|c: char| c.is_ascii_digit()) => {
+            (None, first)
+        }
+        (Some(days), None, None) => (Some(days), "00:00-24:00"),
+        (Some(days), Some(times), None) => (Some(days), times),
+        _ => return Err(Error::InvalidFormat),
+    };
+    let days = match days {
+        Some(days) => parse_days(days)?,
+        None => ALL_DAYS,
+    };
+    let mut times = times.splitn(2, '-');
+    let start = parse_time(times.next().unwrap_or(""))?;
+    let end = parse_time(times.next().ok_or(Error::InvalidFormat)?)?;
+    let start = if start == SECS_PER_DAY as u32 {
+        0
+    } else {
+        start
+    };
+    Ok(TimeWindow { days, start, end })
+}
+
+fn parse_day(word: &str) -> Result<Weekday, Error> {
+    weekday_word(word).ok_or_else(|| Error::UnknownWeekday(word.to_owned()))
+}
+
+fn parse_days(s: &str) -> Result<u8, Error> {
+    let mut mask = 0;
+    for item in s.split(',') {
+        let mut ends = item.splitn(2, '-');
+        let first = parse_day(ends.next().unwrap_or(""))? as u8;
+        let last = match ends.next() {
+            Some(last) => parse_day(last)? as u8,
+            None => first,
+        };
+        let mut day = first;
+        loop {
+            mask |= 1 << day;
+            if day == last {
+                break;
+            }
+            day = (day + 1) % 7;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_time(s: &str) -> Result<u32, Error> {
+    let mut secs = 0;
+    let mut parts = 0;
+    for part in s.split(':') {
+        if part.len() != 2 || !part.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(Error::InvalidTime);
+        }
+        let value: u32 = part.parse().map_err(|_| Error::InvalidTime)?;
+        let max = if parts == 0 { 24 } else { 59 };
+        if value > max {
+            return Err(Error::InvalidTime);
+        }
+        secs = secs * 60 + value;
+        parts += 1;
+    }
+    if !(2..=3).contains(&parts) {
+        return Err(Error::InvalidTime);
+    }
+    if parts == 2 {
+        secs *= 60;
+    }
+    if secs > SECS_PER_DAY as u32 {
+        return Err(Error::InvalidTime);
+    }
+    Ok(secs)
+}
+
+impl TimeWindow {
+    /// Returns `true` if the window includes the day of the week
+    ///
+    /// For windows going past midnight, this is the day the window starts.
+    pub fn includes_day(&self, day: Weekday) -> bool {
+        self.days & 1 << day as u8 != 0
+    }
+
+    /// Checks whether `time` is inside the window
+    ///
+    /// The window is in local time, `offset` is the number of seconds the
+    /// local time is ahead of UTC, like `3600` for `+01:00`.
+    pub fn contains(&self, time: SystemTime, offset: i32) -> bool {
+        let secs = match time.duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_secs() as i64,
+            Err(e) => {
+                let d = e.duration();
+                -(d.as_secs() as i64) - i64::from(d.subsec_nanos() > 0)
+            }
+        };
+        let local = secs + i64::from(offset);
+        let day = local.div_euclid(SECS_PER_DAY);
+        let time = local.rem_euclid(SECS_PER_DAY) as u32;
+        let on = |day: i64| self.includes_day(weekday(day));
+        if self.start < self.end {
+            on(day) && self.start <= time && time < self.end
+        } else {
+            on(day) && time >= self.start || on(day - 1) && time < self.end
+        }
+    }
+}
+
+impl FromStr for TimeWindow {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<TimeWindow, Error> {
+        parse_time_window(s)
+    }
+}
+
+fn fmt_time(secs: u32, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{:02}:{:02}", secs / 3600, secs / 60 % 60)?;
+    if secs % 60 != 0 {
+        write!(f, ":{:02}", secs % 60)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.days != ALL_DAYS {
+            let mut day = 0;
+            let mut first = true;
+            while day < 7 {
+                if self.days & 1 << day == 0 {
+                    day += 1;
+                    continue;
+                }
+                let start = day;
+                while day < 6 && self.days & 1 << (day + 1) != 0 {
+                    day += 1;
+                }
+                if !first {
+                    f.write_str(",")?;
+                }
+                first = false;
+                f.write_str(DAY_NAMES[start])?;
+                if day > start {
+                    write!(f, "-{}", DAY_NAMES[day])?;
+                }
+                day += 1;
+            }
+            f.write_str(" ")?;
+        }
+        fmt_time(self.start, f)?;
+        f.write_str("-")?;
+        fmt_time(self.end, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{parse_time_window, Error};
+    use crate::business::Weekday;
+
+    // 2018-02-12 00:00:00 UTC, Monday
+    const MONDAY: u64 = 1_518_393_600;
+
+    fn check(window: &str, day: u64, hh: u64, mm: u64) -> bool {
+        let secs = MONDAY + day * 86400 + hh * 3600 + mm * 60;
+        parse_time_window(window)
+            .unwrap()
+            .contains(UNIX_EPOCH + Duration::from_secs(secs), 0)
+    }
+
+    #[test]
+    fn daily() {
+        assert!(check("09:00-17:00", 0, 9, 0));
+        assert!(check("09:00-17:00", 6, 16, 59));
+        assert!(!check("09:00-17:00", 2, 17, 0));
+        assert!(!check("09:00-17:00", 2, 8, 59));
+        assert!(check("00:00-24:00", 3, 23, 59));
+        assert!(check("22:00-06:00", 0, 23, 0));
+        assert!(check("22:00-06:00", 0, 5, 59));
+        assert!(!check("22:00-06:00", 0, 6, 0));
+    }
+
+    #[test]
+    fn weekly() {
+        assert!(check("Mon-Fri 08:00-18:00", 4, 8, 0));
+        assert!(!check("Mon-Fri 08:00-18:00", 5, 12, 0));
+        assert!(check("Sat-Sun", 6, 23, 59));
+        assert!(!check("Sat-Sun", 0, 0, 0));
+        assert!(check("Fri-Mon 12:00-13:00", 0, 12, 30));
+        assert!(!check("Fri-Mon 12:00-13:00", 1, 12, 30));
+        assert!(check("mon,wed,fri 10:00-11:00", 2, 10, 0));
+        assert!(!check("mon,wed,fri 10:00-11:00", 3, 10, 0));
+        // the night from Sunday belongs to Sunday
+        assert!(check("Sun 22:00-06:00", 0, 1, 0));
+        assert!(!check("Sun 22:00-06:00", 0, 23, 0));
+    }
+
+    #[test]
+    fn offsets() {
+        let window = parse_time_window("Mon 09:00-10:00").unwrap();
+        let time = UNIX_EPOCH + Duration::from_secs(MONDAY + 8 * 3600);
+        assert!(!window.contains(time, 0));
+        assert!(window.contains(time, 3600));
+        assert!(!window.contains(time, -3600));
+        // 23:30 on Sunday in UTC is Monday in +01:00
+        let time = UNIX_EPOCH + Duration::from_secs(MONDAY - 1800);
+        let window = parse_time_window("Mon").unwrap();
+        assert!(window.contains(time, 3600));
+        assert!(!window.contains(time, 0));
+        assert!(window.includes_day(Weekday::Monday));
+        assert!(!window.includes_day(Weekday::Sunday));
+    }
+
+    #[test]
+    fn before_epoch() {
+        // 1969-12-31 was Wednesday
+        let window = parse_time_window("Wed 23:00-24:00").unwrap();
+        assert!(window.contains(UNIX_EPOCH - Duration::new(0, 1), 0));
+        assert!(!window.contains(UNIX_EPOCH, 0));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_time_window(""), Err(Error::InvalidFormat));
+        assert_eq!(
+            parse_time_window("Mon 09:00-10:00 x"),
+            Err(Error::InvalidFormat)
+        );
+        assert_eq!(parse_time_window("09:00"), Err(Error::InvalidFormat));
+        assert_eq!(parse_time_window("9:00-10:00"), Err(Error::InvalidTime));
+        assert_eq!(parse_time_window("09:60-10:00"), Err(Error::InvalidTime));
+        assert_eq!(parse_time_window("09:00-24:01"), Err(Error::InvalidTime));
+        assert_eq!(parse_time_window("09:00-25:00"), Err(Error::InvalidTime));
+        assert_eq!(parse_time_window("09-10"), Err(Error::InvalidTime));
+        assert_eq!(
+            parse_time_window("Mon-Fry 09:00-10:00"),
+            Err(Error::UnknownWeekday("Fry".to_owned()))
+        );
+    }
+
+    #[test]
+    fn display() {
+        for input in &[
+            "09:00-17:00",
+            "Mon-Fri 08:00-18:00",
+            "Mon,Wed,Fri-Sun 22:00-06:30:15",
+            "Sat-Sun 00:00-24:00",
+        ] {
+            assert_eq!(parse_time_window(input).unwrap().to_string(), *input);
+        }
+        assert_eq!(
+            parse_time_window("Fri-Mon 12:00-13:00")
+                .unwrap()
+                .to_string(),
+            "Mon,Fri-Sun 12:00-13:00"
+        );
+        assert_eq!(
+            parse_time_window("sun,sat 24:00-01:00")
+                .unwrap()
+                .to_string(),
+            "Sat-Sun 00:00-01:00"
+        );
+    }
+}
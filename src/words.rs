@@ -0,0 +1,173 @@
+/// Numbers below twenty, by their value
+const SMALL: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+/// Tens from twenty, by their value divided by ten minus two
+const TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Word {
+    Digit(u64),
+    Teen(u64),
+    Tens(u64),
+    Hundred,
+    Thousand,
+    And,
+}
+
+fn word(w: &str) -> Option<Word> {
+    let eq = |name: &&str| name.eq_ignore_ascii_case(w);
+    if let Some(n) = SMALL.iter().position(eq) {
+        let n = n as u64;
+        return Some(if n < 10 {
+            Word::Digit(n)
+        } else {
+            Word::Teen(n)
+        });
+    }
+    if let Some(n) = TENS.iter().position(eq) {
+        return Some(Word::Tens((n as u64 + 2) * 10));
+    }
+    match w.to_ascii_lowercase().as_str() {
+        "hundred" => Some(Word::Hundred),
+        "thousand" => Some(Word::Thousand),
+        "and" => Some(Word::And),
+        _ => None,
+    }
+}
+
+/// Parses a spelled-out number below a million at the start of `s`, like
+/// `forty-five` or `two hundred and one`
+///
+/// Returns the value and the length of the number in bytes. The words
+/// after the number are left alone, so `and` ends the number unless
+/// another word of the number follows.
+pub(crate) fn parse_number(s: &str) -> Option<(u64, usize)> {
+    use self::Word::*;
+
+    let mut total = 0; // thousands
+    let mut group = 0; // below a thousand
+    let mut last = None;
+    let mut end = 0;
+    let mut pos = 0;
+    loop {
+        let rest = &s[pos..];
+        let len = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let next = match word(&rest[..len]) {
+            Some(next) => next,
+            None => break,
+        };
+        let valid = match (last, next) {
+            // `zero` is a number on its own
+            (None, Digit(0)) => true,
+            (_, Digit(0)) => false,
+            (None | Some(Tens(_) | Hundred | Thousand | And), Digit(n))
+            | (None | Some(Hundred | Thousand | And), Teen(n) | Tens(n)) => {
+                group += n;
+                true
+            }
+            (Some(Digit(_)), Hundred) if group < 10 => {
+                group *= 100;
+                true
+            }
+            (Some(Digit(_) | Teen(_) | Tens(_) | Hundred), Thousand) if total == 0 => {
+                total = group * 1000;
+                group = 0;
+                true
+            }
+            // `and` is kept only if the number continues
+            (Some(Hundred | Thousand), And) => true,
+            _ => false,
+        };
+        if !valid {
+            break;
+        }
+        last = Some(next);
+        pos += len;
+        if next != And {
+            end = pos;
+        }
+        // words are separated by whitespace or a hyphen
+        let rest = &s[pos..];
+        let sep = match rest.strip_prefix('-') {
+            Some(after) => after,
+            None => rest.trim_start(),
+        };
+        if sep.len() == rest.len() {
+            break;
+        }
+        pos += rest.len() - sep.len();
+    }
+    last.map(|_| (total + group, end))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_number;
+
+    fn number(s: &str) -> Option<u64> {
+        parse_number(s).map(|(n, _)| n)
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(number("zero"), Some(0));
+        assert_eq!(number("Two"), Some(2));
+        assert_eq!(number("fifteen"), Some(15));
+        assert_eq!(number("forty five"), Some(45));
+        assert_eq!(number("forty-five"), Some(45));
+        assert_eq!(number("ninety"), Some(90));
+        assert_eq!(number("one hundred"), Some(100));
+        assert_eq!(number("two hundred and one"), Some(201));
+        assert_eq!(number("three thousand"), Some(3000));
+        assert_eq!(number("twelve thousand and five"), Some(12005));
+        assert_eq!(
+            number("nine hundred ninety-nine thousand nine hundred ninety-nine"),
+            Some(999_999)
+        );
+        assert_eq!(number("minutes"), None);
+        assert_eq!(number("and five"), None);
+        assert_eq!(number(""), None);
+    }
+
+    #[test]
+    fn ends() {
+        assert_eq!(parse_number("forty five minutes"), Some((45, 10)));
+        assert_eq!(parse_number("two hours"), Some((2, 3)));
+        assert_eq!(parse_number("one hundred and ten"), Some((110, 19)));
+        // doesn't continue the number, so `and` is not a part of it
+        assert_eq!(parse_number("one hundred and a half"), Some((100, 11)));
+        // every word once
+        assert_eq!(parse_number("one two"), Some((1, 3)));
+        assert_eq!(parse_number("twenty thirty"), Some((20, 6)));
+        assert_eq!(parse_number("five zero"), Some((5, 4)));
+        assert_eq!(parse_number("twenty hundred"), Some((20, 6)));
+        assert_eq!(parse_number("one thousand two thousand"), Some((1002, 16)));
+        assert_eq!(parse_number("twenty-"), Some((20, 6)));
+        assert_eq!(parse_number("twenty -five"), Some((20, 6)));
+    }
+}
@@ -1,10 +1,13 @@
+use std::convert::TryFrom;
 use std::fmt;
 use std::ops::Deref;
 use std::str::FromStr;
-use std::time::{Duration as StdDuration, SystemTime};
+use std::time::{Duration as StdDuration, Instant, SystemTime};
 
 use crate::date::{self, format_rfc3339, parse_rfc3339_weak};
-use crate::duration::{self, format_duration, parse_duration};
+use crate::duration::{
+    self, format_duration, format_duration_with, parse_duration, FormattedDuration, Style,
+};
 
 /// A wrapper for duration that has `FromStr` implementation
 ///
@@ -22,6 +25,19 @@ use crate::duration::{self, format_duration, parse_duration};
 /// assert_eq!(x, Duration::new(12*3600 + 5*60, 2))
 /// ```
 ///
+/// It also converts from and into strings, for conversion frameworks which
+/// use `TryFrom<String>` and `Into<String>`. The string is always in
+/// [`Style::Human`], whatever the style of `Display` is, so it converts
+/// back:
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use humantime::Duration;
+///
+/// let x = Duration::try_from("90s").unwrap();
+/// assert_eq!(String::from(x), "1m 30s");
+/// ```
+///
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub struct Duration(StdDuration);
 
@@ -46,6 +62,62 @@ pub struct Duration(StdDuration);
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Timestamp(SystemTime);
 
+/// A wrapper for Instant that displays the time elapsed since it was taken
+///
+/// This is useful for quick measurements in scripts and benchmarks, where
+/// the elapsed time should be printed in a human-friendly form.
+///
+/// # Example
+///
+/// ```
+/// let start = humantime::HumanInstant::now();
+/// // ... do some work ...
+/// println!("done in {}", start);
+/// assert!(start.elapsed_human().get_ref() <= &start.elapsed());
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HumanInstant(Instant);
+
+/// A timeout which can be disabled, that has `FromStr` implementation
+///
+/// Values `0`, `none` and `off` (and any other zero duration) disable the
+/// timeout, everything else is parsed by `parse_duration`. This is the
+/// usual expectation for timeouts in config files and command-line
+/// options.
+///
+/// With the `serde` feature `Timeout` is (de)serialized as a string, and
+/// `null` deserializes into a disabled timeout. Like for [`Duration`],
+/// `String::from` always uses [`Style::Human`].
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use humantime::Timeout;
+///
+/// let t: Timeout = "30s".parse().unwrap();
+/// assert_eq!(t.get(), Some(Duration::from_secs(30)));
+/// let t: Timeout = "off".parse().unwrap();
+/// assert_eq!(Option::<Duration>::from(t), None);
+/// ```
+///
+/// The type works out of the box as a [clap] argument:
+///
+/// ```
+/// use clap::{value_parser, Arg, Command};
+/// use humantime::Timeout;
+///
+/// let matches = Command::new("server")
+///     .arg(Arg::new("timeout").long("timeout").value_parser(value_parser!(Timeout)))
+///     .get_matches_from(["server", "--timeout", "none"]);
+/// assert_eq!(matches.get_one::<Timeout>("timeout"), Some(&Timeout::NONE));
+/// ```
+///
+/// [clap]: https://docs.rs/clap
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Timeout(Option<StdDuration>);
+
 impl Duration {
     /// Create a new instance from a [`StdDuration`]. This can be used in a `const` context.
     ///
@@ -56,6 +128,34 @@ impl Duration {
     pub const fn new(value: StdDuration) -> Self {
         Self(value)
     }
+
+    /// Total number of nanoseconds
+    ///
+    /// ```rust
+    /// let d: humantime::Duration = "1s 5ns".parse().unwrap();
+    /// assert_eq!(d.as_nanos_u128(), 1_000_000_005);
+    /// ```
+    pub fn as_nanos_u128(&self) -> u128 {
+        self.0.as_nanos()
+    }
+
+    /// Creates a duration from nanoseconds, `None` if it's larger than
+    /// [`StdDuration::MAX`]
+    ///
+    /// Use [`parse_duration_nanos`](crate::parse_duration_nanos) to parse
+    /// the total number of nanoseconds directly.
+    ///
+    /// ```rust
+    /// use humantime::Duration;
+    ///
+    /// let d = Duration::from_nanos_u128(1_000_000_005).unwrap();
+    /// assert_eq!(d.to_string(), "1s 5ns");
+    /// assert_eq!(Duration::from_nanos_u128(u128::MAX), None);
+    /// ```
+    pub fn from_nanos_u128(nanos: u128) -> Option<Self> {
+        let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+        Some(Self(StdDuration::new(secs, (nanos % 1_000_000_000) as u32)))
+    }
 }
 
 impl AsRef<StdDuration> for Duration {
@@ -96,6 +196,26 @@ impl fmt::Display for Duration {
     }
 }
 
+impl TryFrom<&str> for Duration {
+    type Error = duration::Error;
+    fn try_from(s: &str) -> Result<Duration, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for Duration {
+    type Error = duration::Error;
+    fn try_from(s: String) -> Result<Duration, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Duration> for String {
+    fn from(val: Duration) -> String {
+        format_duration_with(val.0, Style::Human).to_string()
+    }
+}
+
 impl AsRef<SystemTime> for Timestamp {
     fn as_ref(&self) -> &SystemTime {
         &self.0
@@ -133,3 +253,184 @@ impl fmt::Display for Timestamp {
         format_rfc3339(self.0).fmt(f)
     }
 }
+
+impl TryFrom<&str> for Timestamp {
+    type Error = date::Error;
+    fn try_from(s: &str) -> Result<Timestamp, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for Timestamp {
+    type Error = date::Error;
+    fn try_from(s: String) -> Result<Timestamp, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Timestamp> for String {
+    fn from(val: Timestamp) -> String {
+        val.to_string()
+    }
+}
+
+impl HumanInstant {
+    /// Returns an instant corresponding to "now"
+    pub fn now() -> HumanInstant {
+        HumanInstant(Instant::now())
+    }
+
+    /// Returns the time elapsed since this instant, ready to be displayed
+    pub fn elapsed_human(&self) -> FormattedDuration {
+        format_duration(self.0.elapsed())
+    }
+}
+
+impl AsRef<Instant> for HumanInstant {
+    fn as_ref(&self) -> &Instant {
+        &self.0
+    }
+}
+
+impl Deref for HumanInstant {
+    type Target = Instant;
+    fn deref(&self) -> &Instant {
+        &self.0
+    }
+}
+
+impl From<HumanInstant> for Instant {
+    fn from(val: HumanInstant) -> Self {
+        val.0
+    }
+}
+
+impl From<Instant> for HumanInstant {
+    fn from(instant: Instant) -> HumanInstant {
+        HumanInstant(instant)
+    }
+}
+
+impl fmt::Display for HumanInstant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.elapsed_human().fmt(f)
+    }
+}
+
+impl Timeout {
+    /// Disabled timeout
+    pub const NONE: Timeout = Timeout(None);
+
+    /// Create a new instance from an optional [`StdDuration`]
+    ///
+    /// Zero duration is the same as `None`.
+    pub const fn new(value: Option<StdDuration>) -> Self {
+        match value {
+            Some(d) if d.as_nanos() == 0 => Timeout(None),
+            _ => Timeout(value),
+        }
+    }
+
+    /// Returns the timeout duration, or `None` if disabled
+    pub fn get(&self) -> Option<StdDuration> {
+        self.0
+    }
+
+    /// Returns `true` if the timeout is not disabled
+    pub fn is_enabled(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+impl From<Timeout> for Option<StdDuration> {
+    fn from(val: Timeout) -> Self {
+        val.0
+    }
+}
+
+impl From<Option<StdDuration>> for Timeout {
+    fn from(dur: Option<StdDuration>) -> Timeout {
+        Timeout::new(dur)
+    }
+}
+
+impl From<StdDuration> for Timeout {
+    fn from(dur: StdDuration) -> Timeout {
+        Timeout::new(Some(dur))
+    }
+}
+
+impl FromStr for Timeout {
+    type Err = duration::Error;
+    fn from_str(s: &str) -> Result<Timeout, Self::Err> {
+        match s {
+            "none" | "off" => Ok(Timeout::NONE),
+            _ => parse_duration(s).map(|d| Timeout::new(Some(d))),
+        }
+    }
+}
+
+impl fmt::Display for Timeout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(d) => format_duration(d).fmt(f),
+            None => f.write_str("none"),
+        }
+    }
+}
+
+impl TryFrom<&str> for Timeout {
+    type Error = duration::Error;
+    fn try_from(s: &str) -> Result<Timeout, Self::Error> {
+        s.parse()
+    }
+}
+
+impl TryFrom<String> for Timeout {
+    type Error = duration::Error;
+    fn try_from(s: String) -> Result<Timeout, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Timeout> for String {
+    fn from(val: Timeout) -> String {
+        match val.0 {
+            Some(d) => format_duration_with(d, Style::Human).to_string(),
+            None => "none".to_owned(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryFrom;
+    use std::time::Duration as StdDuration;
+
+    use super::{Duration, Timeout};
+    use crate::duration::Style;
+    use crate::options::with_format_style;
+
+    #[test]
+    fn string_round_trip() {
+        let d = Duration::from(StdDuration::from_secs(90));
+        let t = Timeout::from(Some(StdDuration::from_secs(90)));
+        for &style in &[
+            Style::Human,
+            Style::Iso8601,
+            Style::Postgres,
+            Style::Verbose,
+        ] {
+            with_format_style(style, || {
+                assert_eq!(String::from(d), "1m 30s");
+                assert_eq!(Duration::try_from(String::from(d)), Ok(d));
+                assert_eq!(String::from(t), "1m 30s");
+                assert_eq!(Timeout::try_from(String::from(t)), Ok(t));
+                assert_eq!(String::from(Timeout::NONE), "none");
+            });
+        }
+        with_format_style(Style::Postgres, || {
+            assert_eq!(t.to_string(), "00:01:30");
+        });
+    }
+}
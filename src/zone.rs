@@ -0,0 +1,840 @@
+use std::convert::TryFrom;
+use std::error::Error as StdError;
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::civil::{civil_from_days, days_from_civil, CivilTime};
+#[cfg(feature = "local-offset")]
+use crate::date::{format_rfc3339, Rfc3339Timestamp};
+
+/// Error reading a time zone
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[non_exhaustive]
+pub enum Error {
+    /// The data is not a valid TZif file
+    InvalidTzif,
+    /// The string is not a valid POSIX `TZ` rule
+    InvalidRule,
+}
+
+impl StdError for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidTzif => write!(f, "time zone file is invalid"),
+            Error::InvalidRule => write!(f, "time zone rule is invalid"),
+        }
+    }
+}
+
+/// A time zone, as a history of UTC offsets
+///
+/// Zones are read from the TZif files of the tz database, like
+/// `/usr/share/zoneinfo/Europe/Berlin`, or from POSIX `TZ` rules like
+/// `CET-1CEST,M3.5.0,M10.5.0/3`. Leap seconds are ignored, like everywhere
+/// else in this crate.
+///
+/// # Example
+///
+/// ```
+/// use humantime::{format_rfc3339, parse_rfc3339, Zone};
+///
+/// let berlin = Zone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+/// let time = parse_rfc3339("2024-06-01T12:00:00Z").unwrap();
+/// assert_eq!(berlin.offset_at(time), 7200);
+/// let text = format_rfc3339(time).with_offset(berlin.offset_at(time));
+/// assert_eq!(text.to_string(), "2024-06-01T14:00:00+02:00");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Zone {
+    /// Offset before the first transition
+    initial: i32,
+    /// Transitions in UTC seconds, in order, with the offset after them
+    transitions: Vec<(i64, i32)>,
+    /// Rule for the times after the last transition
+    rule: Option<Rule>,
+}
+
+/// How [`next_wall_clock`] resolves local times which happen twice or not at all
+///
+/// When the clocks go back, like from 03:00 to 02:00, the times from 02:00
+/// to 03:00 happen twice. When they go forward, like from 02:00 to 03:00,
+/// the times in between don't happen: with [`Ambiguity::Earlier`] and
+/// [`Ambiguity::Later`] they are moved forward by the length of the gap,
+/// so 02:30 is 03:30, as RFC 5545 does for calendar events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Ambiguity {
+    /// The first of two times, and times in gaps moved forward
+    Earlier,
+    /// The second of two times, and times in gaps moved forward
+    Later,
+    /// Skips the days when the time happens twice or not at all
+    Skip,
+}
+
+/// Instants when a local time happens
+enum Local {
+    Unique(i64),
+    /// Earlier and later time in UTC seconds
+    Twice(i64, i64),
+    /// In a gap, the time moved forward
+    Gap(i64),
+}
+
+/// POSIX `TZ` rule, like `CET-1CEST,M3.5.0,M10.5.0/3`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Rule {
+    std: i32,
+    dst: Option<Dst>,
+}
+
+/// Daylight saving time of a [`Rule`], with the local time of day of the
+/// changes in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Dst {
+    offset: i32,
+    start: (Day, i32),
+    end: (Day, i32),
+}
+
+/// Day of the year in a [`Rule`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Day {
+    /// `Jn`, from 1 to 365, never counting February 29
+    Julian(u16),
+    /// `n`, from 0 to 365, counting February 29
+    Ordinal(u16),
+    /// `Mm.w.d`, the day `d` (0 is Sunday) of the week `w` of the month
+    /// `m`, week 5 being the last one
+    Month { month: u32, week: u32, weekday: u32 },
+}
+
+impl Day {
+    /// Returns the day in `year`, in days since the epoch
+    fn in_year(self, year: i64) -> i64 {
+        let new_year = days_from_civil(year, 1, 1);
+        match self {
+            Day::Julian(n) => {
+                let leap = days_from_civil(year, 3, 1) - days_from_civil(year, 2, 1) == 29;
+                new_year + i64::from(n) - 1 + i64::from(leap && n >= 60)
+            }
+            Day::Ordinal(n) => new_year + i64::from(n),
+            Day::Month {
+                month,
+                week,
+                weekday,
+            } => {
+                let first = days_from_civil(year, month, 1);
+                let next_month = match month {
+                    12 => days_from_civil(year + 1, 1, 1),
+                    _ => days_from_civil(year, month + 1, 1),
+                };
+                // the epoch is a Thursday
+                let first_weekday = (first + 4).rem_euclid(7);
+                let mut day = first
+                    + (i64::from(weekday) - first_weekday).rem_euclid(7)
+                    + 7 * (i64::from(week) - 1);
+                while day >= next_month {
+                    day -= 7;
+                }
+                day
+            }
+        }
+    }
+}
+
+impl Rule {
+    fn offset_at(&self, secs: i64) -> i32 {
+        let dst = match self.dst {
+            Some(dst) => dst,
+            None => return self.std,
+        };
+        // near the ends of the i64 range there is no year to change in
+        let local = match secs.checked_add(i64::from(self.std)) {
+            Some(local) => local,
+            None => return self.std,
+        };
+        let year = civil_from_days(local.div_euclid(86400)).0;
+        let change = |(day, time): (Day, i32), offset: i32| {
+            day.in_year(year)
+                .checked_mul(86400)?
+                .checked_add(i64::from(time) - i64::from(offset))
+        };
+        let (start, end) = match (change(dst.start, self.std), change(dst.end, dst.offset)) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return self.std,
+        };
+        let summer = if start < end {
+            start <= secs && secs < end
+        } else {
+            // southern hemisphere
+            !(end <= secs && secs < start)
+        };
+        if summer {
+            dst.offset
+        } else {
+            self.std
+        }
+    }
+}
+
+struct RuleParser<'a> {
+    s: &'a [u8],
+    pos: usize,
+}
+
+impl RuleParser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.s.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: u8) -> bool {
+        let found = self.peek() == Some(c);
+        self.pos += usize::from(found);
+        found
+    }
+
+    /// Abbreviation like `CET` or `<+0545>`
+    fn name(&mut self) -> Result<(), Error> {
+        let start = self.pos;
+        let len = if self.eat(b'<') {
+            while !matches!(self.peek(), Some(b'>') | None) {
+                self.pos += 1;
+            }
+            if !self.eat(b'>') {
+                return Err(Error::InvalidRule);
+            }
+            self.pos - start - 2
+        } else {
+            while self.peek().map_or(false, |c| c.is_ascii_alphabetic()) {
+                self.pos += 1;
+            }
+            self.pos - start
+        };
+        if len < 3 {
+            return Err(Error::InvalidRule);
+        }
+        Ok(())
+    }
+
+    fn number(&mut self, max: u32) -> Result<u32, Error> {
+        let start = self.pos;
+        let mut value = 0u32;
+        while let Some(c @ b'0'..=b'9') = self.peek() {
+            value = value * 10 + u32::from(c - b'0');
+            if value > max {
+                return Err(Error::InvalidRule);
+            }
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(Error::InvalidRule);
+        }
+        Ok(value)
+    }
+
+    /// `[+-]hh[:mm[:ss]]` in seconds
+    fn time(&mut self, max_hours: u32) -> Result<i32, Error> {
+        let negative = self.eat(b'-');
+        if !negative {
+            self.eat(b'+');
+        }
+        let mut secs = self.number(max_hours)? * 3600;
+        if self.eat(b':') {
+            secs += self.number(59)? * 60;
+            if self.eat(b':') {
+                secs += self.number(59)?;
+            }
+        }
+        let secs = secs as i32;
+        Ok(if negative { -secs } else { secs })
+    }
+
+    /// `Jn`, `n` or `Mm.w.d`, with an optional `/time` defaulting to
+    /// 02:00
+    fn change(&mut self) -> Result<(Day, i32), Error> {
+        let day = if self.eat(b'J') {
+            match self.number(365)? {
+                0 => return Err(Error::InvalidRule),
+                n => Day::Julian(n as u16),
+            }
+        } else if self.eat(b'M') {
+            let month = self.number(12)?;
+            let week = if self.eat(b'.') { self.number(5)? } else { 0 };
+            let weekday = if self.eat(b'.') { self.number(6)? } else { 7 };
+            if month == 0 || week == 0 || weekday == 7 {
+                return Err(Error::InvalidRule);
+            }
+            Day::Month {
+                month,
+                week,
+                weekday,
+            }
+        } else {
+            Day::Ordinal(self.number(365)? as u16)
+        };
+        let time = if self.eat(b'/') {
+            self.time(167)?
+        } else {
+            7200
+        };
+        Ok((day, time))
+    }
+
+    fn rule(&mut self) -> Result<Rule, Error> {
+        self.name()?;
+        // POSIX offsets are west of UTC
+        let std = -self.time(24)?;
+        if self.peek().is_none() {
+            return Ok(Rule { std, dst: None });
+        }
+        self.name()?;
+        let offset = match self.peek() {
+            Some(b',') | None => std + 3600,
+            Some(_) => -self.time(24)?,
+        };
+        let (start, end) = if self.eat(b',') {
+            let start = self.change()?;
+            if !self.eat(b',') {
+                return Err(Error::InvalidRule);
+            }
+            (start, self.change()?)
+        } else {
+            // the rules of the United States, as in glibc
+            let day = |month, week| Day::Month {
+                month,
+                week,
+                weekday: 0,
+            };
+            ((day(3, 2), 7200), (day(11, 1), 7200))
+        };
+        if self.peek().is_some() {
+            return Err(Error::InvalidRule);
+        }
+        Ok(Rule {
+            std,
+            dst: Some(Dst { offset, start, end }),
+        })
+    }
+}
+
+fn parse_rule(s: &str) -> Result<Rule, Error> {
+    RuleParser {
+        s: s.as_bytes(),
+        pos: 0,
+    }
+    .rule()
+}
+
+/// Counts of a TZif header, from `isutcnt` to `charcnt`
+fn tzif_counts(data: &[u8]) -> Result<[usize; 6], Error> {
+    if data.len() < 44 || &data[..4] != b"TZif" {
+        return Err(Error::InvalidTzif);
+    }
+    let mut counts = [0; 6];
+    for (idx, count) in counts.iter_mut().enumerate() {
+        let start = 20 + idx * 4;
+        let bytes = [
+            data[start],
+            data[start + 1],
+            data[start + 2],
+            data[start + 3],
+        ];
+        *count = u32::from_be_bytes(bytes) as usize;
+    }
+    Ok(counts)
+}
+
+/// Length of the data block after a TZif header
+fn tzif_block_len(counts: [usize; 6], time_size: usize) -> usize {
+    let [isutcnt, isstdcnt, leapcnt, timecnt, typecnt, charcnt] = counts;
+    timecnt * (time_size + 1)
+        + typecnt * 6
+        + charcnt
+        + leapcnt * (time_size + 4)
+        + isstdcnt
+        + isutcnt
+}
+
+impl Zone {
+    /// Returns the UTC zone
+    pub fn utc() -> Zone {
+        Zone::fixed(0)
+    }
+
+    /// Returns a zone with a fixed offset, in seconds east of UTC
+    pub fn fixed(offset: i32) -> Zone {
+        Zone {
+            initial: offset,
+            transitions: Vec::new(),
+            rule: None,
+        }
+    }
+
+    /// Reads a zone from a POSIX `TZ` rule, like `EST5EDT,M3.2.0,M11.1.0`
+    ///
+    /// Offsets in these rules are west of UTC, so `EST5` is five hours
+    /// behind UTC. Without the dates of the changes, the daylight saving
+    /// time rules of the United States are used, as glibc does.
+    pub fn from_posix_tz(s: &str) -> Result<Zone, Error> {
+        let rule = parse_rule(s)?;
+        Ok(Zone {
+            initial: rule.std,
+            transitions: Vec::new(),
+            rule: Some(rule),
+        })
+    }
+
+    /// Reads a zone from the contents of a TZif file (RFC 8536)
+    ///
+    /// These are the files of the tz database, like `/etc/localtime`.
+    /// Times after the last transition of the file use the `TZ` rule at
+    /// its end.
+    pub fn from_tzif(data: &[u8]) -> Result<Zone, Error> {
+        let mut counts = tzif_counts(data)?;
+        let mut block = &data[44..];
+        let mut time_size = 4;
+        let mut footer = None;
+        if data[4] >= b'2' {
+            let v2 = block
+                .get(tzif_block_len(counts, 4)..)
+                .ok_or(Error::InvalidTzif)?;
+            counts = tzif_counts(v2)?;
+            block = &v2[44..];
+            time_size = 8;
+            footer = Some(
+                block
+                    .get(tzif_block_len(counts, 8)..)
+                    .ok_or(Error::InvalidTzif)?,
+            );
+        }
+        let [_, _, _, timecnt, typecnt, _] = counts;
+        if typecnt == 0 || block.len() < tzif_block_len(counts, time_size) {
+            return Err(Error::InvalidTzif);
+        }
+        let (times, rest) = block.split_at(timecnt * time_size);
+        let (indices, rest) = rest.split_at(timecnt);
+        let offsets = rest[..typecnt * 6]
+            .chunks(6)
+            .map(|t| i32::from_be_bytes([t[0], t[1], t[2], t[3]]))
+            .collect::<Vec<_>>();
+        let mut transitions = Vec::with_capacity(timecnt);
+        for (time, &index) in times.chunks(time_size).zip(indices) {
+            let at = match *time {
+                [a, b, c, d] => i64::from(i32::from_be_bytes([a, b, c, d])),
+                _ => i64::from_be_bytes(<[u8; 8]>::try_from(time).unwrap()),
+            };
+            let offset = *offsets.get(usize::from(index)).ok_or(Error::InvalidTzif)?;
+            transitions.push((at, offset));
+        }
+        let rule = match footer {
+            Some(footer) => {
+                let text = footer
+                    .strip_prefix(b"\n")
+                    .and_then(|f| f.split(|&c| c == b'\n').next())
+                    .and_then(|f| std::str::from_utf8(f).ok())
+                    .ok_or(Error::InvalidTzif)?;
+                match text {
+                    "" => None,
+                    _ => Some(parse_rule(text).map_err(|_| Error::InvalidTzif)?),
+                }
+            }
+            None => None,
+        };
+        Ok(Zone {
+            initial: offsets[0],
+            transitions,
+            rule,
+        })
+    }
+
+    /// Reads the time zone of the system
+    ///
+    /// This is the zone of the `TZ` environment variable, a zone name like
+    /// `Europe/Berlin`, a path or a POSIX rule, or `/etc/localtime` without
+    /// it, as the C library reads them. Returns `None` if the zone can't be
+    /// read, which is always the case on systems without these files, like
+    /// Windows.
+    #[cfg(feature = "local-offset")]
+    pub fn local() -> Option<Zone> {
+        use std::fs;
+        use std::path::Path;
+
+        let tz = match std::env::var("TZ") {
+            Ok(tz) => tz,
+            Err(_) => return Zone::from_tzif(&fs::read("/etc/localtime").ok()?).ok(),
+        };
+        let tz = tz.strip_prefix(':').unwrap_or(&tz);
+        if tz.is_empty() {
+            return Some(Zone::utc());
+        }
+        match fs::read(Path::new("/usr/share/zoneinfo").join(tz)) {
+            Ok(data) => Zone::from_tzif(&data).ok(),
+            Err(_) => Zone::from_posix_tz(tz).ok(),
+        }
+    }
+
+    /// Returns the offset at `time`, in seconds east of UTC
+    pub fn offset_at(&self, time: SystemTime) -> i32 {
+        self.offset_at_secs(epoch_secs(time).0)
+    }
+
+    /// Returns the instants of a local time, given as seconds since the
+    /// epoch in the local time, assuming offsets change at most once in a
+    /// day
+    ///
+    /// Returns `None` if an instant doesn't fit in an `i64`.
+    fn resolve(&self, local: i64) -> Option<Local> {
+        let before = self.offset_at_secs(local.saturating_sub(86400));
+        let after = self.offset_at_secs(local.saturating_add(86400));
+        let valid = |offset: i32| {
+            let utc = local.checked_sub(i64::from(offset))?;
+            Some(Some(utc).filter(|&utc| self.offset_at_secs(utc) == offset))
+        };
+        Some(match (valid(before)?, valid(after)?) {
+            (Some(a), Some(b)) if a != b => Local::Twice(a.min(b), a.max(b)),
+            (Some(utc), _) | (_, Some(utc)) => Local::Unique(utc),
+            (None, None) => Local::Gap(local.checked_sub(i64::from(before))?),
+        })
+    }
+
+    pub(crate) fn offset_at_secs(&self, secs: i64) -> i32 {
+        let idx = self.transitions.partition_point(|&(at, _)| at <= secs);
+        match (idx, &self.rule) {
+            (0, Some(rule)) if self.transitions.is_empty() => rule.offset_at(secs),
+            (0, _) => self.initial,
+            (_, Some(rule)) if idx == self.transitions.len() => rule.offset_at(secs),
+            _ => self.transitions[idx - 1].1,
+        }
+    }
+}
+
+/// Returns the whole seconds since the epoch, rounded down, and the
+/// nanoseconds after them
+///
+/// Times beyond the `i64` range are clamped to it.
+fn epoch_secs(time: SystemTime) -> (i64, u32) {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => (
+            i64::try_from(d.as_secs()).unwrap_or(i64::MAX),
+            d.subsec_nanos(),
+        ),
+        Err(e) => {
+            let d = e.duration();
+            let secs = i64::try_from(d.as_secs()).map_or(i64::MIN, |s| -s);
+            match d.subsec_nanos() {
+                0 => (secs, 0),
+                n => (secs.saturating_sub(1), 1_000_000_000 - n),
+            }
+        }
+    }
+}
+
+/// Returns the first time after `after` when the wall clock in `zone` shows `time`
+///
+/// This is the next occurrence of a daily time, like 09:00 every day,
+/// which stays at 09:00 local time across daylight saving time changes,
+/// unlike adding 24 hours. Local times which happen twice or not at all on
+/// a day are resolved with `ambiguity`. Returns `None` if the result would
+/// overflow the system time.
+///
+/// # Example
+///
+/// ```
+/// use humantime::{format_rfc3339, parse_rfc3339, Ambiguity, CivilTime, Zone};
+/// use humantime::next_wall_clock;
+///
+/// let berlin = Zone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+/// let nine = CivilTime::new(9, 0, 0, 0).unwrap();
+/// // Saturday before the clocks go forward
+/// let now = parse_rfc3339("2024-03-30T08:00:00Z").unwrap();
+/// let next = next_wall_clock(now, nine, &berlin, Ambiguity::Earlier).unwrap();
+/// assert_eq!(format_rfc3339(next).to_string(), "2024-03-31T07:00:00Z");
+/// ```
+pub fn next_wall_clock(
+    after: SystemTime,
+    time: CivilTime,
+    zone: &Zone,
+    ambiguity: Ambiguity,
+) -> Option<SystemTime> {
+    let (secs, nanos) = epoch_secs(after);
+    let after = (i128::from(secs), nanos);
+    let today = secs
+        .checked_add(i64::from(zone.offset_at_secs(secs)))?
+        .div_euclid(86400);
+    // a day can be skipped, on the day of `after` the time can be past
+    for day in today - 1..today + 4 {
+        let local = day
+            .checked_mul(86400)?
+            .checked_add(i64::from(time.seconds_of_day()))?;
+        let utc = match (zone.resolve(local)?, ambiguity) {
+            (Local::Unique(utc), _) => utc,
+            (Local::Twice(earlier, _), Ambiguity::Earlier) => earlier,
+            (Local::Twice(_, later), Ambiguity::Later) => later,
+            (Local::Gap(utc), Ambiguity::Earlier | Ambiguity::Later) => utc,
+            (_, Ambiguity::Skip) => continue,
+        };
+        if (i128::from(utc), time.nanosecond()) > after {
+            let since_epoch = Duration::new(utc.unsigned_abs(), 0);
+            let whole = if utc >= 0 {
+                UNIX_EPOCH.checked_add(since_epoch)
+            } else {
+                UNIX_EPOCH.checked_sub(since_epoch)
+            };
+            return whole?.checked_add(Duration::from_nanos(u64::from(time.nanosecond())));
+        }
+    }
+    None
+}
+
+/// Formats an RFC 3339 timestamp in the local time of the system
+///
+/// The time is followed by the offset of the system time zone at that
+/// time, like `2018-02-14T01:28:07+01:00`, as [`Zone::local`] reads it.
+/// UTC is used when the zone can't be read.
+///
+/// The zone is read on every call, use [`Zone::local`] and
+/// [`Rfc3339Timestamp::with_offset`] when formatting many timestamps.
+#[cfg(feature = "local-offset")]
+pub fn format_rfc3339_local(time: SystemTime) -> Rfc3339Timestamp {
+    let offset = Zone::local().map_or(0, |zone| zone.offset_at(time));
+    format_rfc3339(time).with_offset(offset)
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{next_wall_clock, Ambiguity, Error, Zone};
+    use crate::civil::CivilTime;
+    use crate::date::{format_rfc3339, parse_rfc3339};
+
+    fn offset(zone: &Zone, time: &str) -> i32 {
+        zone.offset_at(parse_rfc3339(time).unwrap())
+    }
+
+    /// TZif version 2 file with the transitions and the footer
+    fn tzif(initial: i32, transitions: &[(i64, i32)], footer: &str) -> Vec<u8> {
+        let header = |data: &mut Vec<u8>| {
+            data.extend_from_slice(b"TZif2");
+            data.extend_from_slice(&[0; 15]);
+            let typecnt = transitions.len() as u32 + 1;
+            for &count in &[0, 0, 0, transitions.len() as u32, typecnt, 4] {
+                data.extend_from_slice(&u32::to_be_bytes(count));
+            }
+        };
+        let mut data = Vec::new();
+        header(&mut data);
+        for &(at, _) in transitions {
+            data.extend_from_slice(&(at as i32).to_be_bytes());
+        }
+        let block = |data: &mut Vec<u8>| {
+            data.extend((1..=transitions.len()).map(|idx| idx as u8));
+            let offsets = Some(initial).into_iter();
+            for offset in offsets.chain(transitions.iter().map(|&(_, o)| o)) {
+                data.extend_from_slice(&offset.to_be_bytes());
+                data.extend_from_slice(&[0, 0]);
+            }
+            data.extend_from_slice(b"ABC\0");
+        };
+        block(&mut data);
+        header(&mut data);
+        for &(at, _) in transitions {
+            data.extend_from_slice(&at.to_be_bytes());
+        }
+        block(&mut data);
+        data.extend_from_slice(format!("\n{}\n", footer).as_bytes());
+        data
+    }
+
+    #[test]
+    fn posix_rules() {
+        let berlin = Zone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        assert_eq!(offset(&berlin, "2024-01-15T12:00:00Z"), 3600);
+        assert_eq!(offset(&berlin, "2024-03-31T00:59:59Z"), 3600);
+        assert_eq!(offset(&berlin, "2024-03-31T01:00:00Z"), 7200);
+        assert_eq!(offset(&berlin, "2024-10-27T00:59:59Z"), 7200);
+        assert_eq!(offset(&berlin, "2024-10-27T01:00:00Z"), 3600);
+        let new_york = Zone::from_posix_tz("EST5EDT").unwrap();
+        assert_eq!(offset(&new_york, "2024-03-10T06:59:59Z"), -18000);
+        assert_eq!(offset(&new_york, "2024-03-10T07:00:00Z"), -14400);
+        assert_eq!(offset(&new_york, "2024-11-03T06:00:00Z"), -18000);
+        let sydney = Zone::from_posix_tz("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert_eq!(offset(&sydney, "2024-01-15T12:00:00Z"), 39600);
+        assert_eq!(offset(&sydney, "2024-04-06T16:00:00Z"), 36000);
+        assert_eq!(offset(&sydney, "2024-10-05T16:00:00Z"), 39600);
+        let kathmandu = Zone::from_posix_tz("<+0545>-5:45").unwrap();
+        assert_eq!(offset(&kathmandu, "2024-06-01T00:00:00Z"), 20700);
+        let julian = Zone::from_posix_tz("XST0XDT,J60/0,300").unwrap();
+        assert_eq!(offset(&julian, "2024-02-29T12:00:00Z"), 0);
+        assert_eq!(offset(&julian, "2024-03-01T00:00:00Z"), 3600);
+        assert_eq!(offset(&julian, "2024-10-27T00:00:00Z"), 3600);
+        assert_eq!(offset(&julian, "2024-10-28T00:00:00Z"), 0);
+    }
+
+    #[test]
+    fn invalid_rules() {
+        for rule in &[
+            "",
+            "UTC",
+            "CE-1",
+            "<+01-1",
+            "CET-1CEST,M3.5.0",
+            "CET-1CEST,M13.5.0,M10.5.0",
+        ] {
+            assert_eq!(
+                Zone::from_posix_tz(rule),
+                Err(Error::InvalidRule),
+                "{}",
+                rule
+            );
+        }
+        assert_eq!(
+            Zone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3 "),
+            Err(Error::InvalidRule)
+        );
+        assert_eq!(
+            Zone::from_posix_tz("UTC0"),
+            Ok(Zone::from_posix_tz("GMT+0").unwrap())
+        );
+    }
+
+    #[test]
+    fn tzif_files() {
+        let start = parse_rfc3339("2000-01-01T00:00:00Z").unwrap();
+        let start = start
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        let zone = tzif(
+            1800,
+            &[(start, 7200), (start + 86400, 3600)],
+            "CET-1CEST,M3.5.0,M10.5.0/3",
+        );
+        let zone = Zone::from_tzif(&zone).unwrap();
+        assert_eq!(offset(&zone, "1999-12-31T23:59:59Z"), 1800);
+        assert_eq!(offset(&zone, "2000-01-01T00:00:00Z"), 7200);
+        assert_eq!(offset(&zone, "2000-01-02T00:00:00Z"), 3600);
+        // the footer rule after the last transition
+        assert_eq!(offset(&zone, "2000-01-03T00:00:00Z"), 3600);
+        assert_eq!(offset(&zone, "2024-06-01T00:00:00Z"), 7200);
+        let utc = Zone::from_tzif(&tzif(0, &[], "UTC0")).unwrap();
+        assert_eq!(offset(&utc, "2024-06-01T00:00:00Z"), 0);
+        let fixed = Zone::from_tzif(&tzif(-3600, &[], "")).unwrap();
+        assert_eq!(fixed, Zone::fixed(-3600));
+    }
+
+    #[test]
+    fn wall_clock() {
+        let berlin = Zone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let next = |after: &str, h, m, ambiguity| {
+            let time = CivilTime::new(h, m, 0, 0).unwrap();
+            let after = parse_rfc3339(after).unwrap();
+            next_wall_clock(after, time, &berlin, ambiguity).map(|t| format_rfc3339(t).to_string())
+        };
+        let some = |s: &str| Some(s.to_owned());
+        let earlier = Ambiguity::Earlier;
+        assert_eq!(
+            next("2024-06-01T06:00:00Z", 9, 0, earlier),
+            some("2024-06-01T07:00:00Z")
+        );
+        assert_eq!(
+            next("2024-06-01T07:00:00Z", 9, 0, earlier),
+            some("2024-06-02T07:00:00Z")
+        );
+        assert_eq!(
+            next("2024-01-01T00:00:00Z", 0, 30, earlier),
+            some("2024-01-01T23:30:00Z")
+        );
+        // 02:30 doesn't happen on March 31, it is 03:30 CEST
+        let spring = "2024-03-30T12:00:00Z";
+        assert_eq!(next(spring, 2, 30, earlier), some("2024-03-31T01:30:00Z"));
+        assert_eq!(
+            next(spring, 2, 30, Ambiguity::Later),
+            some("2024-03-31T01:30:00Z")
+        );
+        assert_eq!(
+            next(spring, 2, 30, Ambiguity::Skip),
+            some("2024-04-01T00:30:00Z")
+        );
+        // 02:30 happens at 00:30 UTC in CEST and at 01:30 UTC in CET
+        let autumn = "2024-10-26T12:00:00Z";
+        assert_eq!(next(autumn, 2, 30, earlier), some("2024-10-27T00:30:00Z"));
+        assert_eq!(
+            next(autumn, 2, 30, Ambiguity::Later),
+            some("2024-10-27T01:30:00Z")
+        );
+        assert_eq!(
+            next(autumn, 2, 30, Ambiguity::Skip),
+            some("2024-10-28T01:30:00Z")
+        );
+        // between the two, the earlier one is past
+        let between = "2024-10-27T01:00:00Z";
+        assert_eq!(next(between, 2, 30, earlier), some("2024-10-28T01:30:00Z"));
+        assert_eq!(
+            next(between, 2, 30, Ambiguity::Later),
+            some("2024-10-27T01:30:00Z")
+        );
+        let fixed = Zone::fixed(-3600);
+        let time = CivilTime::new(23, 0, 0, 5).unwrap();
+        let after = parse_rfc3339("1970-01-01T00:00:00Z").unwrap();
+        let next = next_wall_clock(after, time, &fixed, Ambiguity::Skip).unwrap();
+        assert_eq!(
+            format_rfc3339(next).to_string(),
+            "1970-01-01T00:00:00.000000005Z"
+        );
+    }
+
+    #[test]
+    fn far_times() {
+        let berlin = Zone::from_posix_tz("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        let east = Zone::fixed(3600);
+        let nine = CivilTime::new(9, 0, 0, 0).unwrap();
+        let last = UNIX_EPOCH.checked_add(Duration::from_secs(i64::MAX as u64));
+        let first = UNIX_EPOCH.checked_sub(Duration::new(i64::MAX as u64, 999_999_999));
+        for &time in last.iter().chain(first.iter()) {
+            for zone in &[&berlin, &east] {
+                assert!(zone.offset_at(time) == 3600 || zone.offset_at(time) == 7200);
+                for &ambiguity in &[Ambiguity::Earlier, Ambiguity::Later, Ambiguity::Skip] {
+                    let next = next_wall_clock(time, nine, zone, ambiguity);
+                    assert!(next.map_or(true, |next| next > time));
+                }
+            }
+        }
+        if let Some(last) = last {
+            assert_eq!(
+                next_wall_clock(last, nine, &berlin, Ambiguity::Earlier),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_tzif() {
+        let data = tzif(0, &[(0, 3600)], "UTC0");
+        assert_eq!(Zone::from_tzif(b"TZif"), Err(Error::InvalidTzif));
+        assert_eq!(
+            Zone::from_tzif(&data[..data.len() - 8]),
+            Err(Error::InvalidTzif)
+        );
+        let mut bad_magic = data.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(Zone::from_tzif(&bad_magic), Err(Error::InvalidTzif));
+        let bad_rule = tzif(0, &[(0, 3600)], "nonsense");
+        assert_eq!(Zone::from_tzif(&bad_rule), Err(Error::InvalidTzif));
+    }
+
+    #[cfg(feature = "local-offset")]
+    #[test]
+    fn local() {
+        use super::format_rfc3339_local;
+
+        let time = parse_rfc3339("2024-06-01T12:00:00Z").unwrap();
+        let text = format_rfc3339_local(time).to_string();
+        let zone = Zone::local().unwrap_or_else(Zone::utc);
+        let expected = crate::date::format_rfc3339(time).with_offset(zone.offset_at(time));
+        assert_eq!(text, expected.to_string());
+    }
+}
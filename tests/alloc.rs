@@ -0,0 +1,68 @@
+//! Checks that parsing and formatting don't allocate
+//!
+//! This is a separate test binary because it replaces the global allocator.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::io::Write;
+use std::time::{Duration, UNIX_EPOCH};
+
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn allocations<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+#[test]
+fn no_allocations() {
+    let inputs = [
+        "0",
+        "1h 30min",
+        "2years 3months 1d 4h 5m 6s 7ms 8us 9ns",
+        "1.5h 0.25s 3.001us",
+        " 12 hours ",
+    ];
+    let mut buf = Vec::with_capacity(200);
+    let count = allocations(|| {
+        for input in &inputs {
+            let duration = humantime::parse_duration(input).unwrap();
+            buf.clear();
+            write!(buf, "{}", humantime::format_duration(duration)).unwrap();
+        }
+        let time = humantime::parse_rfc3339("2018-02-14T00:28:07.250Z").unwrap();
+        buf.clear();
+        write!(buf, "{}", humantime::format_rfc3339_nanos(time)).unwrap();
+        buf.clear();
+        let duration = Duration::new(123_456_789, 987_654_321);
+        write!(buf, "{}", humantime::format_rfc3339(UNIX_EPOCH + duration)).unwrap();
+        // errors carrying no unit name don't allocate either
+        assert!(humantime::parse_duration("1h!").is_err());
+        assert!(humantime::parse_duration("").is_err());
+    });
+    assert_eq!(count, 0);
+    // the counter works
+    assert_eq!(
+        allocations(|| drop(humantime::parse_duration("1 fortnight"))),
+        1
+    );
+}